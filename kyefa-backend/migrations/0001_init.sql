@@ -0,0 +1,141 @@
+-- Base schema. Every record belongs to a school; requests are scoped to one.
+
+CREATE TYPE user_role AS ENUM ('admin', 'committee_member', 'headteacher', 'data_entry', 'staff', 'teacher');
+CREATE TYPE gender AS ENUM ('male', 'female');
+CREATE TYPE class_level AS ENUM (
+    'lower_secondary_year8', 'lower_secondary_year9',
+    'igcse1', 'igcse2',
+    'wassce1', 'wassce2', 'wassce3',
+    'a_level1', 'a_level2'
+);
+CREATE TYPE stream AS ENUM ('cambridge_igcse', 'cambridge_lower_secondary', 'cambridge_a_level', 'wassce');
+CREATE TYPE participation_status AS ENUM ('participating', 'not_participating');
+CREATE TYPE subject_name AS ENUM (
+    'english', 'mathematics', 'science', 'ict', 'social_studies', 'french',
+    'physics', 'chemistry', 'biology', 'economics', 'business', 'geography',
+    'core_math', 'elective_math', 'further_math'
+);
+CREATE TYPE academic_year AS ENUM ('year2024_2025', 'year2025_2026', 'year2026_2027');
+CREATE TYPE support_staff_role AS ENUM ('janitor', 'administrator', 'headteacher');
+
+CREATE TABLE schools (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE TABLE users (
+    id UUID PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    role user_role NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    first_name TEXT NOT NULL,
+    surname TEXT NOT NULL,
+    other_names TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE students (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    first_name TEXT NOT NULL,
+    surname TEXT NOT NULL,
+    other_names TEXT,
+    gender gender NOT NULL,
+    class_level class_level NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    merged_into UUID REFERENCES students(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX students_school_idx ON students (school_id);
+
+CREATE TABLE teachers (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    first_name TEXT NOT NULL,
+    surname TEXT NOT NULL,
+    other_names TEXT,
+    status participation_status NOT NULL DEFAULT 'participating',
+    is_active BOOLEAN NOT NULL DEFAULT TRUE
+);
+CREATE INDEX teachers_school_idx ON teachers (school_id);
+
+CREATE TABLE support_staff (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    first_name TEXT NOT NULL,
+    surname TEXT NOT NULL,
+    other_names TEXT,
+    role support_staff_role NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE TABLE subjects (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    name subject_name NOT NULL,
+    stream stream NOT NULL,
+    UNIQUE (school_id, name, stream)
+);
+
+CREATE TABLE terms (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    name TEXT NOT NULL,
+    academic_year academic_year NOT NULL,
+    start_date TIMESTAMP NOT NULL,
+    end_date TIMESTAMP NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+    CHECK (start_date < end_date)
+);
+-- At most one active term per school
+CREATE UNIQUE INDEX terms_one_active_idx ON terms (school_id) WHERE is_active;
+
+-- Fee for each class in a term
+CREATE TABLE class_fees (
+    term_id UUID NOT NULL REFERENCES terms(id),
+    class_level class_level NOT NULL,
+    amount DOUBLE PRECISION NOT NULL CHECK (amount >= 0),
+    PRIMARY KEY (term_id, class_level)
+);
+
+CREATE TABLE conducted_periods (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    date TIMESTAMP NOT NULL,
+    class_level class_level NOT NULL,
+    subject_id UUID NOT NULL REFERENCES subjects(id),
+    teacher_id UUID NOT NULL REFERENCES teachers(id),
+    term_id UUID NOT NULL REFERENCES terms(id),
+    week_of_term INTEGER NOT NULL CHECK (week_of_term > 0),
+    was_conducted BOOLEAN NOT NULL,
+    notes TEXT
+);
+CREATE INDEX conducted_periods_term_idx ON conducted_periods (term_id, teacher_id);
+
+CREATE TABLE payments (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    student_id UUID NOT NULL REFERENCES students(id),
+    term_id UUID NOT NULL REFERENCES terms(id),
+    amount_paid DOUBLE PRECISION NOT NULL CHECK (amount_paid > 0),
+    method TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    date_paid TIMESTAMP NOT NULL DEFAULT LOCALTIMESTAMP,
+    recorded_by UUID NOT NULL REFERENCES users(id)
+);
+CREATE INDEX payments_term_idx ON payments (term_id, student_id);
+
+-- Revenue-split rules; the newest one for a school is in force
+CREATE TABLE constraint_configs (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    name TEXT NOT NULL,
+    base_percentage DOUBLE PRECISION NOT NULL,
+    admin_percentage DOUBLE PRECISION NOT NULL,
+    support_staff_percentage DOUBLE PRECISION NOT NULL,
+    max_periods_paid INTEGER CHECK (max_periods_paid > 0),
+    max_ratio DOUBLE PRECISION CHECK (max_ratio > 0 AND max_ratio <= 1),
+    created_at TIMESTAMP NOT NULL DEFAULT LOCALTIMESTAMP
+);
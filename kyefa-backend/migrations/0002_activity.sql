@@ -0,0 +1,20 @@
+-- Audit log behind the home dashboard's activity feed, and the link from a
+-- teacher record to the account they sign in with
+
+CREATE TYPE activity_type AS ENUM (
+    'payment_received', 'student_added', 'teaching_period_created',
+    'user_created', 'students_merged', 'exemption_granted'
+);
+
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    -- Unset for changes made before the acting user was known
+    user_id UUID REFERENCES users(id),
+    activity_type activity_type NOT NULL,
+    description TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX audit_log_school_idx ON audit_log (school_id, created_at DESC);
+
+ALTER TABLE teachers ADD COLUMN user_id UUID UNIQUE REFERENCES users(id);
@@ -0,0 +1,20 @@
+-- Academic years become plain start years, so any year can be set up, and
+-- each student's class is kept per year for historical reports
+
+ALTER TABLE terms ALTER COLUMN academic_year TYPE INTEGER
+    USING substring(academic_year::text FROM 5 FOR 4)::integer;
+DROP TYPE academic_year;
+
+ALTER TYPE activity_type ADD VALUE 'students_promoted';
+
+CREATE TYPE promotion_outcome AS ENUM ('promoted', 'repeated', 'graduated', 'left');
+
+CREATE TABLE class_history (
+    student_id UUID NOT NULL REFERENCES students(id),
+    school_id UUID NOT NULL REFERENCES schools(id),
+    academic_year INTEGER NOT NULL,
+    class_level class_level NOT NULL,
+    -- Unset while the year is open
+    outcome promotion_outcome,
+    PRIMARY KEY (student_id, academic_year)
+);
@@ -0,0 +1,25 @@
+-- Enrolment in the tuition programme, per term. Fees are only expected for
+-- enrolled terms, at the class fee in force when the student joined.
+
+CREATE TABLE enrolments (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    student_id UUID NOT NULL REFERENCES students(id),
+    term_id UUID NOT NULL REFERENCES terms(id),
+    joined_on DATE NOT NULL,
+    -- Set when the student withdraws; they still owe the term's fee
+    left_on DATE CHECK (left_on >= joined_on),
+    fee_amount DOUBLE PRECISION NOT NULL CHECK (fee_amount >= 0),
+    UNIQUE (student_id, term_id)
+);
+CREATE INDEX enrolments_term_idx ON enrolments (term_id);
+
+-- Until now every active student was charged their class fee in every term,
+-- so existing terms start out with everyone enrolled from the first day
+INSERT INTO enrolments (id, school_id, student_id, term_id, joined_on, fee_amount)
+SELECT gen_random_uuid(), s.school_id, s.id, t.id, t.start_date::date, cf.amount
+FROM students s
+JOIN terms t ON t.school_id = s.school_id
+LEFT JOIN class_history ch ON ch.student_id = s.id AND ch.academic_year = t.academic_year
+JOIN class_fees cf ON cf.term_id = t.id AND cf.class_level = COALESCE(ch.class_level, s.class_level)
+WHERE s.merged_into IS NULL AND (s.is_active OR ch.student_id IS NOT NULL);
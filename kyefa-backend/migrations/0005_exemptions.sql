@@ -0,0 +1,19 @@
+-- Approved reductions of a student's fee for one term. A full exemption
+-- waives the whole enrolment fee; `amount` records what it was at approval.
+
+CREATE TYPE exemption_reason AS ENUM ('staff_child', 'hardship', 'scholarship');
+
+CREATE TABLE exemptions (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    student_id UUID NOT NULL REFERENCES students(id),
+    term_id UUID NOT NULL REFERENCES terms(id),
+    is_full BOOLEAN NOT NULL,
+    amount DOUBLE PRECISION NOT NULL CHECK (amount >= 0),
+    reason exemption_reason NOT NULL,
+    approved_by UUID NOT NULL REFERENCES users(id),
+    notes TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT LOCALTIMESTAMP,
+    UNIQUE (student_id, term_id)
+);
+CREATE INDEX exemptions_term_idx ON exemptions (term_id);
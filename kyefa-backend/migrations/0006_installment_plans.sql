@@ -0,0 +1,19 @@
+-- Agreed schedules for paying a term's fee in parts. A student has at most
+-- one plan per term; saving a new schedule replaces the installments.
+
+CREATE TABLE installment_plans (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    student_id UUID NOT NULL REFERENCES students(id),
+    term_id UUID NOT NULL REFERENCES terms(id),
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMP NOT NULL DEFAULT LOCALTIMESTAMP,
+    UNIQUE (student_id, term_id)
+);
+
+CREATE TABLE installments (
+    plan_id UUID NOT NULL REFERENCES installment_plans(id) ON DELETE CASCADE,
+    due_date DATE NOT NULL,
+    amount DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+    PRIMARY KEY (plan_id, due_date)
+);
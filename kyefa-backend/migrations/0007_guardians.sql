@@ -0,0 +1,24 @@
+-- Parents and guardians to call about fees. Children linked to the same
+-- guardian are one family.
+
+CREATE TYPE guardian_relationship AS ENUM ('mother', 'father', 'guardian', 'grandparent', 'sibling', 'other');
+
+CREATE TABLE guardians (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    first_name TEXT NOT NULL,
+    surname TEXT NOT NULL,
+    other_names TEXT,
+    relationship guardian_relationship NOT NULL,
+    phone_number TEXT NOT NULL,
+    alternate_phone TEXT,
+    is_primary_contact BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX guardians_school_idx ON guardians (school_id);
+
+CREATE TABLE guardian_students (
+    guardian_id UUID NOT NULL REFERENCES guardians(id) ON DELETE CASCADE,
+    student_id UUID NOT NULL REFERENCES students(id),
+    PRIMARY KEY (guardian_id, student_id)
+);
+CREATE INDEX guardian_students_student_idx ON guardian_students (student_id);
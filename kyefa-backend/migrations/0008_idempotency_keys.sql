@@ -0,0 +1,15 @@
+-- Responses to writes sent with an Idempotency-Key, so a retried request is
+-- answered with the first outcome instead of being applied twice. A row with
+-- no status is still being handled.
+
+CREATE TABLE idempotency_keys (
+    key UUID PRIMARY KEY,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    request_hash BYTEA NOT NULL,
+    status SMALLINT,
+    content_type TEXT,
+    body BYTEA,
+    created_at TIMESTAMP NOT NULL DEFAULT LOCALTIMESTAMP
+);
+CREATE INDEX idempotency_keys_created_idx ON idempotency_keys (created_at);
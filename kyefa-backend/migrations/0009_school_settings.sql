@@ -0,0 +1,19 @@
+-- Settings shared by every desktop in a school, edited by its admins. A
+-- school without a row uses the defaults in `SchoolSettings::default`.
+
+CREATE TABLE school_settings (
+    school_id UUID PRIMARY KEY REFERENCES schools(id),
+    currency_symbol TEXT NOT NULL,
+    date_format TEXT NOT NULL,
+    default_payment_method TEXT NOT NULL,
+    admin_share_percentage DOUBLE PRECISION NOT NULL,
+    staff_share_percentage DOUBLE PRECISION NOT NULL,
+    teacher_share_percentage DOUBLE PRECISION NOT NULL,
+    password_min_length INTEGER NOT NULL,
+    password_history_count INTEGER NOT NULL,
+    max_failed_attempts INTEGER NOT NULL,
+    lockout_minutes INTEGER NOT NULL,
+    sibling_discount_percentage DOUBLE PRECISION NOT NULL DEFAULT 0,
+    updated_by UUID NOT NULL REFERENCES users(id),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,4 @@
+-- Accounts created by an admin, or whose password an admin reset, carry a
+-- temporary password the user has to replace at their next login
+
+ALTER TABLE users ADD COLUMN must_change_password BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,13 @@
+-- Passwords users have replaced, so the school's policy can stop them
+-- choosing a recent one again, and audit entries for password changes
+
+ALTER TYPE activity_type ADD VALUE 'password_changed';
+ALTER TYPE activity_type ADD VALUE 'password_change_failed';
+
+CREATE TABLE password_history (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    password_hash TEXT NOT NULL,
+    replaced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX password_history_user_idx ON password_history (user_id, replaced_at DESC);
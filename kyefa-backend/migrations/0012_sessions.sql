@@ -0,0 +1,26 @@
+-- Sessions started at login, lockouts after repeated failed logins, and the
+-- login history admins review on shared office PCs
+
+ALTER TABLE users ADD COLUMN failed_login_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN locked_until TIMESTAMPTZ;
+
+CREATE TABLE sessions (
+    -- SHA-256 of the token the desktop holds, so the table alone can't be
+    -- used to act as anyone
+    token_hash BYTEA PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX sessions_user_idx ON sessions (user_id);
+
+CREATE TABLE login_attempts (
+    id UUID PRIMARY KEY,
+    -- Unset when the username didn't match an account
+    user_id UUID REFERENCES users(id),
+    username TEXT NOT NULL,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    machine_name TEXT NOT NULL,
+    succeeded BOOLEAN NOT NULL
+);
+CREATE INDEX login_attempts_time_idx ON login_attempts (attempted_at DESC);
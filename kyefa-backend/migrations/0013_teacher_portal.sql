@@ -0,0 +1,50 @@
+-- The committee's verification of logged periods, teachers' disputes of
+-- rejected ones, and the payouts teachers see as payslips in their portal
+
+CREATE TYPE period_verification AS ENUM ('pending', 'verified', 'rejected', 'disputed');
+
+ALTER TYPE activity_type ADD VALUE 'period_reviewed';
+ALTER TYPE activity_type ADD VALUE 'period_disputed';
+
+ALTER TABLE conducted_periods ADD COLUMN verification period_verification NOT NULL DEFAULT 'pending';
+ALTER TABLE conducted_periods ADD COLUMN rejection_reason TEXT;
+ALTER TABLE conducted_periods ADD COLUMN dispute_reason TEXT;
+ALTER TABLE conducted_periods ADD COLUMN reviewed_by UUID REFERENCES users(id);
+ALTER TABLE conducted_periods ADD COLUMN reviewed_at TIMESTAMPTZ;
+-- Periods logged before verification existed were already being paid on
+UPDATE conducted_periods SET verification = 'verified';
+
+-- One run of the revenue split for a term
+CREATE TABLE disbursements (
+    id UUID PRIMARY KEY,
+    school_id UUID NOT NULL REFERENCES schools(id),
+    term_id UUID NOT NULL REFERENCES terms(id),
+    total_revenue DOUBLE PRECISION NOT NULL,
+    admin_share DOUBLE PRECISION NOT NULL,
+    support_staff_share DOUBLE PRECISION NOT NULL,
+    teacher_base_share DOUBLE PRECISION NOT NULL,
+    teacher_period_share DOUBLE PRECISION NOT NULL,
+    constraint_id UUID NOT NULL REFERENCES constraint_configs(id),
+    calculated_on TIMESTAMP NOT NULL DEFAULT LOCALTIMESTAMP
+);
+CREATE INDEX disbursements_term_idx ON disbursements (term_id);
+
+CREATE TABLE teacher_payouts (
+    id UUID PRIMARY KEY,
+    disbursement_id UUID NOT NULL REFERENCES disbursements(id),
+    teacher_id UUID NOT NULL REFERENCES teachers(id),
+    base_share DOUBLE PRECISION NOT NULL,
+    period_share DOUBLE PRECISION NOT NULL,
+    capped BOOLEAN NOT NULL,
+    UNIQUE (disbursement_id, teacher_id)
+);
+CREATE INDEX teacher_payouts_teacher_idx ON teacher_payouts (teacher_id);
+
+-- The periods a payout paid for
+CREATE TABLE teacher_payout_items (
+    id UUID PRIMARY KEY,
+    payout_id UUID NOT NULL REFERENCES teacher_payouts(id),
+    conducted_period_id UUID NOT NULL REFERENCES conducted_periods(id),
+    paid_amount DOUBLE PRECISION NOT NULL,
+    UNIQUE (payout_id, conducted_period_id)
+);
@@ -0,0 +1,13 @@
+-- Which schools each account can work in. A request is only served for a
+-- school the session's user belongs to.
+
+CREATE TABLE user_schools (
+    user_id UUID NOT NULL REFERENCES users(id),
+    school_id UUID NOT NULL REFERENCES schools(id),
+    PRIMARY KEY (user_id, school_id)
+);
+CREATE INDEX user_schools_school_idx ON user_schools (school_id);
+
+-- Until now every account could work in every school
+INSERT INTO user_schools (user_id, school_id)
+SELECT u.id, s.id FROM users u CROSS JOIN schools s;
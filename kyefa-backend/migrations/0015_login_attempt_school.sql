@@ -0,0 +1,5 @@
+-- The school the login screen was set to, so an attempt with a username
+-- that matched no account is only shown to that school's admins. Unset when
+-- the computer had no school chosen yet.
+
+ALTER TABLE login_attempts ADD COLUMN school_id UUID REFERENCES schools(id);
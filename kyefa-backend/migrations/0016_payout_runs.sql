@@ -0,0 +1,8 @@
+-- Payout runs from the committee's review screen. A school without a
+-- ConstraintConfig splits by its settings alone, and each term is paid once.
+
+ALTER TYPE activity_type ADD VALUE 'payout_run';
+
+ALTER TABLE disbursements ALTER COLUMN constraint_id DROP NOT NULL;
+DROP INDEX disbursements_term_idx;
+CREATE UNIQUE INDEX disbursements_term_idx ON disbursements (term_id);
@@ -0,0 +1,26 @@
+use kyefa_models::ActivityType;
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+/// Adds an entry to the school's audit log. Pass the transaction making the
+/// change so the entry is only kept if the change is.
+pub async fn record(
+    executor: impl PgExecutor<'_>,
+    school_id: Uuid,
+    user_id: Option<Uuid>,
+    activity_type: ActivityType,
+    description: impl Into<String>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO audit_log (id, school_id, user_id, activity_type, description)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(school_id)
+    .bind(user_id)
+    .bind(activity_type)
+    .bind(description.into())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
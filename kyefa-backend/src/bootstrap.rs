@@ -0,0 +1,55 @@
+use kyefa_models::UserRole;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::passwords;
+
+/// Prepares an empty database: creates the first school, named by
+/// `KYEFA_SCHOOL_NAME`, and an admin account for every school from
+/// `KYEFA_ADMIN_USERNAME` and `KYEFA_ADMIN_PASSWORD`. Does nothing once
+/// either exists.
+pub async fn run(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let has_school: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM schools)")
+        .fetch_one(pool)
+        .await?;
+    if !has_school {
+        let name = std::env::var("KYEFA_SCHOOL_NAME").unwrap_or_else(|_| "Kyefa School".to_string());
+        sqlx::query("INSERT INTO schools (id, name) VALUES ($1, $2)")
+            .bind(Uuid::new_v4())
+            .bind(&name)
+            .execute(pool)
+            .await?;
+        tracing::info!("Created school '{}'", name);
+    }
+
+    let has_user: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users)")
+        .fetch_one(pool)
+        .await?;
+    if has_user {
+        return Ok(());
+    }
+    let Ok(password) = std::env::var("KYEFA_ADMIN_PASSWORD") else {
+        tracing::warn!("There are no user accounts. Set KYEFA_ADMIN_PASSWORD to create the first admin.");
+        return Ok(());
+    };
+    let username = std::env::var("KYEFA_ADMIN_USERNAME").unwrap_or_else(|_| "admin".to_string());
+    let id = Uuid::new_v4();
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "INSERT INTO users (id, username, password_hash, role, first_name, surname)
+         VALUES ($1, $2, $3, $4, 'School', 'Administrator')",
+    )
+    .bind(id)
+    .bind(&username)
+    .bind(passwords::hash(&password)?)
+    .bind(UserRole::Admin)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("INSERT INTO user_schools (user_id, school_id) SELECT $1, id FROM schools")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    tracing::info!("Created admin account '{}'", username);
+    Ok(())
+}
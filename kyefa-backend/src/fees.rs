@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use kyefa_models::{
+    ClassLevel, Enrolment, Installment, InstallmentPlan, PaymentStatus, PaymentSummary, Student, StudentRow,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Differences below half a pesewa are rounding, not money owed
+pub const TOLERANCE: f64 = 0.005;
+
+/// What a student owes for one term and how much of it has come in
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeePosition {
+    /// The fee after any exemption; `None` when nothing is expected from the
+    /// student this term, because they weren't enrolled in it
+    pub fee: Option<f64>,
+    /// How much of the enrolment fee an exemption waived
+    pub exempted: f64,
+    pub paid: f64,
+    pub status: PaymentStatus,
+}
+
+impl FeePosition {
+    pub fn due(&self) -> f64 {
+        self.fee.unwrap_or(0.0)
+    }
+
+    pub fn outstanding(&self) -> f64 {
+        (self.due() - self.paid).max(0.0)
+    }
+
+    pub fn is_expected(&self) -> bool {
+        self.fee.is_some()
+    }
+
+    /// Marks a part-paid term overdue once an installment of `plan` has
+    /// fallen due before `today` without the payments covering it
+    pub fn against_plan(mut self, plan: Option<&InstallmentPlan>, today: NaiveDate) -> Self {
+        if self.status == PaymentStatus::PartialOnTrack
+            && plan.is_some_and(|plan| plan.days_overdue(self.paid, today).is_some())
+        {
+            self.status = PaymentStatus::PartialOverdue;
+        }
+        self
+    }
+}
+
+/// Classifies a student's term from the fee charged, the part of it
+/// exempted and the payments made. A student exempted from the whole fee is
+/// `Exempt` rather than paid.
+pub fn position(fee: Option<f64>, exempted: f64, paid: f64) -> FeePosition {
+    let exempted = fee.map_or(0.0, |fee| exempted.clamp(0.0, fee));
+    let due = fee.map(|fee| fee - exempted);
+    let status = match (fee, due) {
+        (Some(fee), _) if exempted > 0.0 && exempted + TOLERANCE >= fee => PaymentStatus::Exempt,
+        (_, Some(due)) if paid + TOLERANCE >= due => PaymentStatus::Paid,
+        (_, Some(_)) if paid <= TOLERANCE => PaymentStatus::NotPaid,
+        (_, Some(_)) => PaymentStatus::PartialOnTrack,
+        _ => PaymentStatus::NotEnrolled,
+    };
+    FeePosition { fee: due, exempted, paid, status }
+}
+
+/// A student and their position for the term the list was loaded for
+#[derive(Debug, Clone)]
+pub struct StudentFees {
+    pub row: StudentRow,
+    /// The class the student was in during the term's academic year, which
+    /// the fee is charged for; `row.class_level` is their class now
+    pub class_in_term: ClassLevel,
+    pub enrolment: Option<Enrolment>,
+    pub plan: Option<InstallmentPlan>,
+    pub position: FeePosition,
+}
+
+impl StudentFees {
+    pub fn name(&self) -> String {
+        format!("{} {}", self.row.first_name, self.row.surname)
+    }
+
+    pub fn into_student(self) -> Student {
+        let mut student = Student::from(self.row);
+        student.fee_amount = self.position.due();
+        student.total_paid = self.position.paid;
+        student.payment_status = self.position.status;
+        student.enrolment = self.enrolment;
+        student.installment_plan = self.plan;
+        student
+    }
+}
+
+/// Totals and status counts over the students something is expected from
+pub fn summarise<'a>(positions: impl IntoIterator<Item = &'a FeePosition>) -> PaymentSummary {
+    let mut summary = PaymentSummary {
+        total_expected: 0.0,
+        total_exempted: 0.0,
+        total_received: 0.0,
+        total_pending: 0.0,
+        paid_count: 0,
+        partial_on_track_count: 0,
+        partial_overdue_count: 0,
+        unpaid_count: 0,
+        exempt_count: 0,
+    };
+    for position in positions.into_iter().filter(|position| position.is_expected()) {
+        summary.total_expected += position.due();
+        summary.total_exempted += position.exempted;
+        summary.total_received += position.paid;
+        summary.total_pending += position.outstanding();
+        match position.status {
+            PaymentStatus::Paid => summary.paid_count += 1,
+            PaymentStatus::PartialOnTrack => summary.partial_on_track_count += 1,
+            PaymentStatus::PartialOverdue => summary.partial_overdue_count += 1,
+            PaymentStatus::NotPaid => summary.unpaid_count += 1,
+            PaymentStatus::Exempt => summary.exempt_count += 1,
+            PaymentStatus::NotEnrolled => {}
+        }
+    }
+    summary
+}
+
+#[derive(sqlx::FromRow)]
+struct StudentFeesRow {
+    #[sqlx(flatten)]
+    row: StudentRow,
+    class_in_term: ClassLevel,
+    enrolment_id: Option<Uuid>,
+    joined_on: Option<NaiveDate>,
+    left_on: Option<NaiveDate>,
+    enrolment_fee: Option<f64>,
+    exemption_is_full: Option<bool>,
+    exemption_amount: Option<f64>,
+    total_paid: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct InstallmentRow {
+    plan_id: Uuid,
+    student_id: Uuid,
+    created_by: Uuid,
+    created_at: NaiveDateTime,
+    due_date: NaiveDate,
+    amount: f64,
+}
+
+/// Installment plans for `term_id`, keyed by student. `student_ids` narrows
+/// them when given.
+pub async fn plans(
+    pool: &PgPool,
+    school_id: Uuid,
+    term_id: Uuid,
+    student_ids: Option<&[Uuid]>,
+) -> Result<HashMap<Uuid, InstallmentPlan>, sqlx::Error> {
+    let rows: Vec<InstallmentRow> = sqlx::query_as(
+        "SELECT p.id AS plan_id, p.student_id, p.created_by, p.created_at, i.due_date, i.amount
+         FROM installment_plans p JOIN installments i ON i.plan_id = p.id
+         WHERE p.school_id = $1 AND p.term_id = $2 AND ($3::uuid[] IS NULL OR p.student_id = ANY($3))
+         ORDER BY p.student_id, i.due_date",
+    )
+    .bind(school_id)
+    .bind(term_id)
+    .bind(student_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut plans: HashMap<Uuid, InstallmentPlan> = HashMap::new();
+    for row in rows {
+        plans
+            .entry(row.student_id)
+            .or_insert_with(|| InstallmentPlan {
+                id: row.plan_id,
+                student_id: row.student_id,
+                term_id,
+                installments: Vec::new(),
+                created_by: row.created_by,
+                created_at: row.created_at,
+            })
+            .installments
+            .push(Installment { due_date: row.due_date, amount: row.amount });
+    }
+    Ok(plans)
+}
+
+/// Every student in the school with their position for `term_id`. Only
+/// students enrolled in the term owe anything, at the fee set when they
+/// enrolled and less any exemption; one who withdrew still owes it. A student
+/// behind on their installment plan is overdue. Without a term nobody owes
+/// anything. `student_ids` narrows the list when given.
+pub async fn load(
+    pool: &PgPool,
+    school_id: Uuid,
+    term_id: Option<Uuid>,
+    student_ids: Option<&[Uuid]>,
+) -> Result<Vec<StudentFees>, sqlx::Error> {
+    let rows: Vec<StudentFeesRow> = sqlx::query_as(
+        "SELECT s.id, s.school_id, s.first_name, s.surname, s.other_names, s.gender, s.class_level,
+                s.is_active, s.merged_into,
+                COALESCE(ch.class_level, s.class_level) AS class_in_term,
+                e.id AS enrolment_id, e.joined_on, e.left_on,
+                CASE WHEN s.merged_into IS NULL THEN e.fee_amount END AS enrolment_fee,
+                x.is_full AS exemption_is_full, x.amount AS exemption_amount,
+                COALESCE((SELECT SUM(p.amount_paid) FROM payments p
+                          WHERE p.student_id = s.id AND p.term_id = $2), 0) AS total_paid
+         FROM students s
+         LEFT JOIN terms t ON t.id = $2
+         LEFT JOIN class_history ch ON ch.student_id = s.id AND ch.academic_year = t.academic_year
+         LEFT JOIN enrolments e ON e.student_id = s.id AND e.term_id = $2
+         LEFT JOIN exemptions x ON x.student_id = s.id AND x.term_id = $2
+         WHERE s.school_id = $1 AND ($3::uuid[] IS NULL OR s.id = ANY($3))
+         ORDER BY s.surname, s.first_name",
+    )
+    .bind(school_id)
+    .bind(term_id)
+    .bind(student_ids)
+    .fetch_all(pool)
+    .await?;
+    let mut plans = match term_id {
+        Some(term_id) => plans(pool, school_id, term_id, student_ids).await?,
+        None => HashMap::new(),
+    };
+    let today = Local::now().date_naive();
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let enrolment = match (row.enrolment_id, row.joined_on, term_id) {
+                (Some(id), Some(joined_on), Some(term_id)) => Some(Enrolment {
+                    id,
+                    student_id: row.row.id,
+                    term_id,
+                    joined_on,
+                    left_on: row.left_on,
+                    fee_amount: row.enrolment_fee.unwrap_or(0.0),
+                }),
+                _ => None,
+            };
+            let exempted = match (row.exemption_is_full, row.enrolment_fee) {
+                (Some(true), Some(fee)) => fee,
+                (Some(false), _) => row.exemption_amount.unwrap_or(0.0),
+                _ => 0.0,
+            };
+            let plan = plans.remove(&row.row.id);
+            StudentFees {
+                position: position(row.enrolment_fee, exempted, row.total_paid).against_plan(plan.as_ref(), today),
+                plan,
+                class_in_term: row.class_in_term,
+                enrolment,
+                row: row.row,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_expected_without_a_fee() {
+        assert_eq!(position(None, 0.0, 0.0).status, PaymentStatus::NotEnrolled);
+        assert_eq!(position(None, 0.0, 50.0).outstanding(), 0.0);
+    }
+
+    #[test]
+    fn classifies_by_amount_paid() {
+        assert_eq!(position(Some(300.0), 0.0, 0.0).status, PaymentStatus::NotPaid);
+        assert_eq!(position(Some(300.0), 0.0, 100.0).status, PaymentStatus::PartialOnTrack);
+        assert_eq!(position(Some(300.0), 0.0, 300.0).status, PaymentStatus::Paid);
+        assert_eq!(position(Some(300.0), 0.0, 350.0).status, PaymentStatus::Paid);
+    }
+
+    #[test]
+    fn rounding_differences_count_as_paid() {
+        assert_eq!(position(Some(100.0), 0.0, 99.999).status, PaymentStatus::Paid);
+        assert_eq!(position(Some(100.0), 0.0, 99.99).status, PaymentStatus::PartialOnTrack);
+    }
+
+    #[test]
+    fn summary_skips_students_not_expected_to_pay() {
+        let positions = [
+            position(Some(300.0), 0.0, 300.0),
+            position(Some(300.0), 0.0, 100.0),
+            position(Some(300.0), 0.0, 0.0),
+            position(None, 0.0, 0.0),
+        ];
+        let summary = summarise(&positions);
+        assert_eq!(summary.total_expected, 900.0);
+        assert_eq!(summary.total_received, 400.0);
+        assert_eq!(summary.total_pending, 500.0);
+        assert_eq!((summary.paid_count, summary.partial_on_track_count, summary.unpaid_count), (1, 1, 1));
+    }
+
+    #[test]
+    fn exemptions_reduce_what_is_due() {
+        let partial = position(Some(300.0), 100.0, 200.0);
+        assert_eq!(partial.due(), 200.0);
+        assert_eq!(partial.status, PaymentStatus::Paid);
+        assert_eq!(position(Some(300.0), 100.0, 50.0).status, PaymentStatus::PartialOnTrack);
+        let full = position(Some(300.0), 300.0, 0.0);
+        assert_eq!((full.due(), full.outstanding()), (0.0, 0.0));
+        assert_eq!(full.status, PaymentStatus::Exempt);
+        // Never more than the fee, and nothing without an enrolment
+        assert_eq!(position(Some(300.0), 500.0, 0.0).exempted, 300.0);
+        assert_eq!(position(None, 100.0, 0.0).exempted, 0.0);
+    }
+
+    #[test]
+    fn summary_counts_exemptions() {
+        let positions = [position(Some(300.0), 300.0, 0.0), position(Some(300.0), 100.0, 0.0)];
+        let summary = summarise(&positions);
+        assert_eq!(summary.total_exempted, 400.0);
+        assert_eq!(summary.total_expected, 200.0);
+        assert_eq!((summary.exempt_count, summary.unpaid_count), (1, 1));
+    }
+
+    #[test]
+    fn part_paid_terms_fall_overdue_with_their_plan() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let plan = InstallmentPlan {
+            id: Uuid::nil(),
+            student_id: Uuid::nil(),
+            term_id: Uuid::nil(),
+            installments: vec![
+                Installment { due_date: day(1), amount: 100.0 },
+                Installment { due_date: day(15), amount: 200.0 },
+            ],
+            created_by: Uuid::nil(),
+            created_at: day(1).and_hms_opt(0, 0, 0).unwrap(),
+        };
+        let status = |paid, today| position(Some(300.0), 0.0, paid).against_plan(Some(&plan), today).status;
+        assert_eq!(status(100.0, day(20)), PaymentStatus::PartialOverdue);
+        assert_eq!(status(100.0, day(15)), PaymentStatus::PartialOnTrack);
+        assert_eq!(status(300.0, day(20)), PaymentStatus::Paid);
+        // Nothing paid stays unpaid, plan or not
+        assert_eq!(status(0.0, day(20)), PaymentStatus::NotPaid);
+        assert_eq!(position(Some(300.0), 0.0, 100.0).against_plan(None, day(20)).status, PaymentStatus::PartialOnTrack);
+    }
+
+    #[test]
+    fn outstanding_never_negative() {
+        assert_eq!(position(Some(200.0), 0.0, 50.0).outstanding(), 150.0);
+        assert_eq!(position(Some(200.0), 0.0, 250.0).outstanding(), 0.0);
+    }
+}
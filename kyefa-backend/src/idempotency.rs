@@ -0,0 +1,250 @@
+use axum::body::{to_bytes, Body, Bytes};
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use kyefa_models::{IDEMPOTENCY_KEY_HEADER, SCHOOL_ID_HEADER};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::routes::backups;
+use crate::session::CurrentUser;
+use crate::AppState;
+
+/// Largest request or response body kept for a replay, the same as axum's
+/// default limit on request bodies
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// How long a key can stay pending before its request is taken to have been
+/// abandoned, e.g. by a handler that panicked, and is let through again
+const PENDING_TIMEOUT_MINUTES: i32 = 15;
+
+/// How long outcomes are kept. The desktop replays its queued writes well
+/// within this.
+const RETENTION_DAYS: i32 = 7;
+
+#[derive(sqlx::FromRow)]
+struct StoredKey {
+    method: String,
+    path: String,
+    request_hash: Vec<u8>,
+    status: Option<i16>,
+    content_type: Option<String>,
+    body: Option<Vec<u8>>,
+    is_stale: bool,
+}
+
+/// Runs a POST or PUT that carries `IDEMPOTENCY_KEY_HEADER` at most once.
+/// A repeat of a request that succeeded gets the first response back; one
+/// still being handled gets 409, and a different request under the same key
+/// gets 422. Failed requests aren't kept, so they can be corrected and resent
+/// under the same key. Only a logged-in user's requests are kept, each under
+/// that user, so a key can't be claimed without a session or replay another
+/// user's response.
+pub async fn layer(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    match handle(&state, request, next).await {
+        Ok(response) => response,
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Only a restore is allowed a body the size of a whole dump
+fn body_limit(path: &str) -> usize {
+    match path {
+        backups::RESTORE_PATH => backups::MAX_DUMP_BYTES,
+        _ => MAX_BODY_BYTES,
+    }
+}
+
+async fn handle(state: &AppState, request: Request, next: Next) -> ApiResult<Response> {
+    if !matches!(*request.method(), Method::POST | Method::PUT) {
+        return Ok(next.run(request).await);
+    }
+    let Some(value) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    let key = value
+        .to_str()
+        .ok()
+        .and_then(|value| Uuid::parse_str(value).ok())
+        .ok_or_else(|| ApiError::bad_request("The request's idempotency key isn't a valid id."))?;
+
+    let (mut parts, body) = request.into_parts();
+    // Left to the handler, which turns it away the same way
+    let Ok(user) = CurrentUser::from_request_parts(&mut parts, state).await else {
+        return Ok(next.run(Request::from_parts(parts, body)).await);
+    };
+    let pool = &state.pool;
+    let limit = body_limit(parts.uri.path());
+    let body = to_bytes(Body::new(body), limit)
+        .await
+        .map_err(|_| ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "The request is too large to send."))?;
+    let method = parts.method.to_string();
+    let path = parts.uri.path_and_query().map_or(parts.uri.path(), |path| path.as_str()).to_string();
+    let school = parts.headers.get(SCHOOL_ID_HEADER).map(|value| value.as_bytes()).unwrap_or_default();
+    let hash = request_hash(&method, &path, user.id, school, &body);
+
+    if let Some(replay) = claim(pool, key, &method, &path, &hash).await? {
+        return Ok(replay);
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, limit).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Couldn't read the response for idempotency key {}: {}", key, e);
+            release(pool, key).await;
+            return Err(ApiError::internal("The server couldn't finish the request. Try again shortly."));
+        }
+    };
+
+    if parts.status.is_success() {
+        let content_type = parts.headers.get(header::CONTENT_TYPE).and_then(|value| value.to_str().ok());
+        let stored = sqlx::query("UPDATE idempotency_keys SET status = $2, content_type = $3, body = $4 WHERE key = $1")
+            .bind(key)
+            .bind(parts.status.as_u16() as i16)
+            .bind(content_type)
+            .bind(&body[..])
+            .execute(pool)
+            .await;
+        // The write has been made, so its response goes back regardless
+        if let Err(e) = stored {
+            tracing::error!("Couldn't store the response for idempotency key {}: {}", key, e);
+        }
+    } else {
+        release(pool, key).await;
+    }
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// Takes `key` for this request. Returns the response to replay when the
+/// key has already been used, or `None` when the request should go ahead.
+async fn claim(pool: &PgPool, key: Uuid, method: &str, path: &str, hash: &[u8]) -> ApiResult<Option<Response>> {
+    sqlx::query("DELETE FROM idempotency_keys WHERE created_at < LOCALTIMESTAMP - make_interval(days => $1)")
+        .bind(RETENTION_DAYS)
+        .execute(pool)
+        .await?;
+
+    loop {
+        let claimed = sqlx::query(
+            "INSERT INTO idempotency_keys (key, method, path, request_hash) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (key) DO NOTHING",
+        )
+        .bind(key)
+        .bind(method)
+        .bind(path)
+        .bind(hash)
+        .execute(pool)
+        .await?;
+        if claimed.rows_affected() == 1 {
+            return Ok(None);
+        }
+
+        let stored: Option<StoredKey> = sqlx::query_as(
+            "SELECT method, path, request_hash, status, content_type, body,
+                    created_at < LOCALTIMESTAMP - make_interval(mins => $2) AS is_stale
+             FROM idempotency_keys WHERE key = $1",
+        )
+        .bind(key)
+        .bind(PENDING_TIMEOUT_MINUTES)
+        .fetch_optional(pool)
+        .await?;
+        // The request holding the key failed and let it go in the meantime
+        let Some(stored) = stored else {
+            continue;
+        };
+
+        if stored.method != method || stored.path != path || stored.request_hash != hash {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "This request reuses the key of a different one. Start the change again.",
+            ));
+        }
+        if let Some(status) = stored.status {
+            let status = StatusCode::from_u16(status as u16).unwrap_or(StatusCode::OK);
+            let mut response = (status, Bytes::from(stored.body.unwrap_or_default())).into_response();
+            if let Some(content_type) = stored.content_type.and_then(|value| value.parse().ok()) {
+                response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+            }
+            return Ok(Some(response));
+        }
+        if stored.is_stale {
+            let retaken = sqlx::query(
+                "UPDATE idempotency_keys SET created_at = LOCALTIMESTAMP
+                 WHERE key = $1 AND status IS NULL AND created_at < LOCALTIMESTAMP - make_interval(mins => $2)",
+            )
+            .bind(key)
+            .bind(PENDING_TIMEOUT_MINUTES)
+            .execute(pool)
+            .await?;
+            if retaken.rows_affected() == 1 {
+                return Ok(None);
+            }
+        }
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "This change is still being saved. Try again in a moment.",
+        ));
+    }
+}
+
+/// Lets go of a key whose request failed, so it can be sent again
+async fn release(pool: &PgPool, key: Uuid) {
+    let released = sqlx::query("DELETE FROM idempotency_keys WHERE key = $1 AND status IS NULL")
+        .bind(key)
+        .execute(pool)
+        .await;
+    if let Err(e) = released {
+        tracing::error!("Couldn't release idempotency key {}: {}", key, e);
+    }
+}
+
+/// Identifies a request by what it asks for, who asks and which school it's
+/// for. Each part is length-prefixed so moving bytes between parts changes
+/// the hash.
+fn request_hash(method: &str, path: &str, user_id: Uuid, school: &[u8], body: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    for part in [method.as_bytes(), path.as_bytes(), user_id.as_bytes(), school, body] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: Uuid = Uuid::from_u128(1);
+
+    #[test]
+    fn same_request_hashes_the_same() {
+        let first = request_hash("POST", "/payments", USER, b"school", br#"{"amount":100}"#);
+        let second = request_hash("POST", "/payments", USER, b"school", br#"{"amount":100}"#);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn any_difference_changes_the_hash() {
+        let base = request_hash("POST", "/payments", USER, b"school", br#"{"amount":100}"#);
+        assert_ne!(base, request_hash("PUT", "/payments", USER, b"school", br#"{"amount":100}"#));
+        assert_ne!(base, request_hash("POST", "/students", USER, b"school", br#"{"amount":100}"#));
+        assert_ne!(base, request_hash("POST", "/payments", USER, b"other", br#"{"amount":100}"#));
+        assert_ne!(base, request_hash("POST", "/payments", USER, b"school", br#"{"amount":200}"#));
+        assert_ne!(base, request_hash("POST", "/payments", Uuid::from_u128(2), b"school", br#"{"amount":100}"#));
+    }
+
+    #[test]
+    fn parts_do_not_run_together() {
+        assert_ne!(request_hash("POST", "/a", USER, b"bc", b""), request_hash("POST", "/ab", USER, b"c", b""));
+    }
+
+    #[test]
+    fn only_a_restore_takes_a_whole_dump() {
+        assert_eq!(body_limit(backups::RESTORE_PATH), backups::MAX_DUMP_BYTES);
+        assert_eq!(body_limit("/payments"), MAX_BODY_BYTES);
+    }
+}
@@ -0,0 +1,53 @@
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use tracing_subscriber::EnvFilter;
+
+mod audit;
+mod bootstrap;
+mod error;
+mod fees;
+mod idempotency;
+mod passwords;
+mod routes;
+mod session;
+mod settings;
+mod shares;
+mod tenant;
+mod terms;
+
+/// Shared by every handler
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: PgPool,
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    let database_url = std::env::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set in .env or environment variables");
+    let bind_address = std::env::var("KYEFA_BIND_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+
+    let pool = PgPoolOptions::new()
+        .max_connections(10)
+        .connect(&database_url)
+        .await
+        .expect("Failed to connect to the database");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to run database migrations");
+    bootstrap::run(&pool)
+        .await
+        .expect("Failed to prepare the first school and admin account");
+
+    let app = routes::router(AppState { pool });
+    let listener = tokio::net::TcpListener::bind(&bind_address)
+        .await
+        .unwrap_or_else(|e| panic!("Failed to bind {}: {}", bind_address, e));
+    tracing::info!("Listening on {}", bind_address);
+    axum::serve(listener, app).await.expect("Server error");
+}
@@ -0,0 +1,43 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use std::sync::OnceLock;
+
+/// Argon2id hash in PHC string form, salt included
+pub fn hash(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string())
+}
+
+/// False for a wrong password and for a hash that can't be parsed
+pub fn verify(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+/// Takes as long as `verify` does against a real account, so a login with an
+/// unknown username isn't answered noticeably faster than a wrong password
+pub fn verify_nothing(password: &str) {
+    static DECOY: OnceLock<String> = OnceLock::new();
+    let decoy = DECOY.get_or_init(|| hash("decoy password").unwrap_or_default());
+    verify(password, decoy);
+}
+
+/// Whether `password` is the one behind any of `hashes`
+pub fn matches_any<'a>(password: &str, hashes: impl IntoIterator<Item = &'a str>) -> bool {
+    hashes.into_iter().any(|hash| verify(password, hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_a_reused_password() {
+        let hashes = [hash("first password").unwrap(), hash("second password").unwrap()];
+        assert!(matches_any("second password", hashes.iter().map(String::as_str)));
+        assert!(!matches_any("third password", hashes.iter().map(String::as_str)));
+        assert!(!matches_any("first password", []));
+    }
+}
@@ -0,0 +1,307 @@
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::Json;
+use kyefa_models::{
+    LockoutPolicy, LoginAttempt, LoginHistoryQuery, LoginPayload, LoginResponse, School, UnlockPayload, UserResponse,
+    UserRole, SCHOOL_ID_HEADER,
+};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::session::{self, CurrentUser};
+use crate::tenant::Tenant;
+use crate::{passwords, settings, AppState};
+
+/// Given for an unknown username, a wrong password, a deactivated account and
+/// a locked one alike, so the login screen can't be used to find usernames
+const LOGIN_FAILED: &str = "Incorrect username or password.";
+
+/// Most login attempts one request for the history returns
+const MAX_HISTORY_ROWS: u32 = 1000;
+
+#[derive(sqlx::FromRow)]
+struct LoginRow {
+    id: Uuid,
+    username: String,
+    password_hash: String,
+    role: UserRole,
+    is_active: bool,
+    first_name: String,
+    surname: String,
+    other_names: Option<String>,
+    must_change_password: bool,
+    is_locked: bool,
+}
+
+/// The computer name for the login history, or "Unknown" when none was sent
+fn machine_name(name: &str) -> &str {
+    Some(name.trim()).filter(|name| !name.is_empty()).unwrap_or("Unknown")
+}
+
+/// The school the login screen was set to. Nothing is checked against it;
+/// it only decides whose history an attempt shows up in.
+fn login_school(headers: &HeaderMap) -> Option<Uuid> {
+    headers
+        .get(SCHOOL_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok())
+}
+
+/// Adds an attempt to the login history. A `school_id` naming no school is
+/// stored as unset.
+async fn record_attempt(
+    executor: impl PgExecutor<'_>,
+    user_id: Option<Uuid>,
+    school_id: Option<Uuid>,
+    username: &str,
+    machine_name: &str,
+    succeeded: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO login_attempts (id, user_id, school_id, username, machine_name, succeeded)
+         VALUES ($1, $2, (SELECT id FROM schools WHERE id = $3), $4, $5, $6)",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(school_id)
+    .bind(username)
+    .bind(machine_name)
+    .bind(succeeded)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// The strictest of the schools' lockout policies: the fewest attempts
+/// allowed and the longest lockout. An account can belong to several
+/// schools, so no single school's policy can be picked.
+fn strictest(policies: impl IntoIterator<Item = LockoutPolicy>) -> LockoutPolicy {
+    policies
+        .into_iter()
+        .reduce(|a, b| LockoutPolicy {
+            max_failed_attempts: a.max_failed_attempts.min(b.max_failed_attempts),
+            lockout_minutes: a.lockout_minutes.max(b.lockout_minutes),
+        })
+        .unwrap_or_default()
+}
+
+/// The policy for an account, from the schools it belongs to
+async fn lockout_policy(pool: &PgPool, user_id: Uuid) -> Result<LockoutPolicy, sqlx::Error> {
+    let schools: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT s.id FROM schools s JOIN user_schools us ON us.school_id = s.id
+         WHERE us.user_id = $1 AND s.is_active",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    let mut policies = Vec::with_capacity(schools.len());
+    for school_id in schools {
+        policies.push(settings::load(pool, school_id).await?.lockout_policy);
+    }
+    Ok(strictest(policies))
+}
+
+/// Counts a wrong password against the account, locking it once the policy's
+/// limit is reached. The count starts again after a lockout.
+pub(crate) async fn count_failure(pool: &PgPool, user_id: Uuid, username: &str) -> Result<(), sqlx::Error> {
+    let policy = lockout_policy(pool, user_id).await?;
+    let locked: bool = sqlx::query_scalar(
+        "UPDATE users SET
+             locked_until = CASE WHEN failed_login_count + 1 >= $2
+                                 THEN NOW() + make_interval(mins => $3) ELSE locked_until END,
+             failed_login_count = CASE WHEN failed_login_count + 1 >= $2 THEN 0 ELSE failed_login_count + 1 END
+         WHERE id = $1
+         RETURNING COALESCE(locked_until > NOW(), FALSE)",
+    )
+    .bind(user_id)
+    .bind(policy.max_failed_attempts as i32)
+    .bind(policy.lockout_minutes as i32)
+    .fetch_one(pool)
+    .await?;
+    if locked {
+        tracing::warn!(
+            "Locked '{}' for {} minutes after {} failed logins",
+            username,
+            policy.lockout_minutes,
+            policy.max_failed_attempts
+        );
+    }
+    Ok(())
+}
+
+/// Starts a session for the user. Every attempt, successful or not, goes in
+/// the login history with the computer it was made from and the school the
+/// login screen was set to.
+pub async fn login(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<LoginPayload>,
+) -> ApiResult<Json<LoginResponse>> {
+    let username = payload.username.trim();
+    let machine_name = machine_name(&payload.machine_name);
+    let school_id = login_school(&headers);
+
+    let user: Option<LoginRow> = sqlx::query_as(
+        "SELECT id, username, password_hash, role, is_active, first_name, surname, other_names,
+                must_change_password, COALESCE(locked_until > NOW(), FALSE) AS is_locked
+         FROM users WHERE username = $1",
+    )
+    .bind(username)
+    .fetch_optional(&state.pool)
+    .await?;
+    let Some(user) = user else {
+        passwords::verify_nothing(&payload.password);
+        record_attempt(&state.pool, None, school_id, username, machine_name, false).await?;
+        return Err(ApiError::unauthorized(LOGIN_FAILED));
+    };
+
+    // Checked even for a locked account, so it isn't answered any faster
+    let is_correct = passwords::verify(&payload.password, &user.password_hash);
+    if !is_correct || !user.is_active || user.is_locked {
+        if !is_correct && user.is_active && !user.is_locked {
+            count_failure(&state.pool, user.id, &user.username).await?;
+        }
+        record_attempt(&state.pool, Some(user.id), school_id, username, machine_name, false).await?;
+        return Err(ApiError::unauthorized(LOGIN_FAILED));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    sqlx::query("UPDATE users SET failed_login_count = 0, locked_until = NULL WHERE id = $1")
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    record_attempt(&mut *tx, Some(user.id), school_id, username, machine_name, true).await?;
+    session::prune(&mut *tx).await?;
+    let token = session::start(&mut *tx, user.id).await?;
+    tx.commit().await?;
+
+    let schools: Vec<School> = sqlx::query_as(
+        "SELECT s.id, s.name, s.is_active FROM schools s JOIN user_schools us ON us.school_id = s.id
+         WHERE us.user_id = $1 AND s.is_active
+         ORDER BY s.name",
+    )
+    .bind(user.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(LoginResponse {
+        token,
+        user: UserResponse {
+            id: user.id.to_string(),
+            username: user.username,
+            role: user.role,
+            is_active: true,
+            first_name: user.first_name,
+            surname: user.surname,
+            other_names: user.other_names,
+            must_change_password: user.must_change_password,
+            locked_until: None,
+            schools,
+        },
+    }))
+}
+
+/// Ends the session the request was made with. Other computers the user is
+/// logged in on stay logged in.
+pub async fn logout(State(state): State<AppState>, user: CurrentUser) -> ApiResult<StatusCode> {
+    session::end(&state.pool, &user).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Checks the password of the user whose screen locked, keeping their session.
+/// Wrong passwords count towards the lockout and go in the login history, as
+/// they would at the login screen. The user is already known here, so unlike
+/// a login the answer can say the account is locked.
+pub async fn unlock(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(payload): Json<UnlockPayload>,
+) -> ApiResult<StatusCode> {
+    let machine_name = machine_name(&payload.machine_name);
+    let (username, password_hash, locked_minutes): (String, String, i32) = sqlx::query_as(
+        "SELECT username, password_hash,
+                COALESCE(CEIL(EXTRACT(EPOCH FROM locked_until - NOW()) / 60), 0)::INT
+         FROM users WHERE id = $1",
+    )
+    .bind(user.id)
+    .fetch_one(&state.pool)
+    .await?;
+    if locked_minutes > 0 {
+        return Err(ApiError::unauthorized(format!(
+            "Too many wrong passwords. Try again in {} minute{}.",
+            locked_minutes,
+            if locked_minutes == 1 { "" } else { "s" }
+        )));
+    }
+
+    if !passwords::verify(&payload.password, &password_hash) {
+        count_failure(&state.pool, user.id, &username).await?;
+        record_attempt(&state.pool, Some(user.id), None, &username, machine_name, false).await?;
+        return Err(ApiError::unauthorized("Incorrect password."));
+    }
+    let mut tx = state.pool.begin().await?;
+    sqlx::query("UPDATE users SET failed_login_count = 0 WHERE id = $1")
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    record_attempt(&mut *tx, Some(user.id), None, &username, machine_name, true).await?;
+    tx.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Login attempts, newest first, narrowed to usernames containing
+/// `query.username` and to failures when asked. Only admins can see them, and
+/// only for the school's accounts and for usernames that matched no account
+/// typed on a login screen set to the school.
+pub async fn login_history(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(query): Query<LoginHistoryQuery>,
+) -> ApiResult<Json<Vec<LoginAttempt>>> {
+    if !user.is_admin() {
+        return Err(ApiError::forbidden("Only an admin can see the login history."));
+    }
+    let limit = match query.limit {
+        0 => 100,
+        limit => limit.min(MAX_HISTORY_ROWS),
+    };
+    let username = query.username.as_deref().map(str::trim).filter(|name| !name.is_empty());
+    Ok(Json(
+        sqlx::query_as(
+            "SELECT id, user_id, username, attempted_at, machine_name, succeeded
+             FROM login_attempts
+             WHERE ($1::text IS NULL OR strpos(lower(username), lower($1)) > 0) AND (NOT $2 OR NOT succeeded)
+               AND (user_id IN (SELECT user_id FROM user_schools WHERE school_id = $4)
+                    OR (user_id IS NULL AND school_id = $4))
+             ORDER BY attempted_at DESC
+             LIMIT $3",
+        )
+        .bind(username)
+        .bind(query.failed_only)
+        .bind(limit as i64)
+        .bind(tenant.school_id)
+        .fetch_all(&state.pool)
+        .await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strictest_policy_wins() {
+        let policy = strictest([
+            LockoutPolicy { max_failed_attempts: 5, lockout_minutes: 30 },
+            LockoutPolicy { max_failed_attempts: 3, lockout_minutes: 10 },
+        ]);
+        assert_eq!(policy, LockoutPolicy { max_failed_attempts: 3, lockout_minutes: 30 });
+    }
+
+    #[test]
+    fn no_schools_falls_back_to_the_default() {
+        assert_eq!(strictest([]), LockoutPolicy::default());
+    }
+}
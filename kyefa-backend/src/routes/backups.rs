@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::AppState;
+
+/// Where a restore is sent, the one route allowed a body as large as a dump
+pub const RESTORE_PATH: &str = "/backups/restore";
+
+/// Largest dump accepted for a restore
+pub const MAX_DUMP_BYTES: usize = 64 * 1024 * 1024;
+
+/// Tables in a dump, parents before the tables that reference them, each
+/// with the condition picking out the school's rows given its id as `$1`.
+/// User accounts and the school record itself are shared with other schools
+/// and left alone, as are idempotency keys and the migration history.
+const TABLES: &[(&str, &str)] = &[
+    ("students", "school_id = $1"),
+    ("teachers", "school_id = $1"),
+    ("support_staff", "school_id = $1"),
+    ("subjects", "school_id = $1"),
+    ("terms", "school_id = $1"),
+    ("class_fees", "term_id IN (SELECT id FROM terms WHERE school_id = $1)"),
+    ("conducted_periods", "school_id = $1"),
+    ("payments", "school_id = $1"),
+    ("constraint_configs", "school_id = $1"),
+    ("disbursements", "school_id = $1"),
+    ("teacher_payouts", "disbursement_id IN (SELECT id FROM disbursements WHERE school_id = $1)"),
+    (
+        "teacher_payout_items",
+        "payout_id IN (SELECT p.id FROM teacher_payouts p JOIN disbursements d ON d.id = p.disbursement_id
+                       WHERE d.school_id = $1)",
+    ),
+    ("audit_log", "school_id = $1"),
+    ("class_history", "school_id = $1"),
+    ("enrolments", "school_id = $1"),
+    ("exemptions", "school_id = $1"),
+    ("installment_plans", "school_id = $1"),
+    ("installments", "plan_id IN (SELECT id FROM installment_plans WHERE school_id = $1)"),
+    ("guardians", "school_id = $1"),
+    ("guardian_students", "guardian_id IN (SELECT id FROM guardians WHERE school_id = $1)"),
+    ("school_settings", "school_id = $1"),
+];
+
+/// Every row belonging to one school, tagged with the migration the schema was at
+#[derive(Serialize, Deserialize)]
+pub struct Dump {
+    pub school_id: Uuid,
+    pub schema_version: i64,
+    pub exported_at: DateTime<Utc>,
+    pub tables: BTreeMap<String, Value>,
+}
+
+async fn schema_version(conn: &mut PgConnection) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success")
+        .fetch_one(conn)
+        .await
+}
+
+/// A consistent snapshot of the school's data, for the desktop to archive.
+/// It holds guardians' contacts, payments and accounts, so only an admin gets it.
+pub async fn export(State(state): State<AppState>, user: CurrentUser, tenant: Tenant) -> ApiResult<Json<Dump>> {
+    if !user.is_admin() {
+        return Err(ApiError::forbidden("Only an admin can back up the school's data."));
+    }
+    let mut tx = state.pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    let mut tables = BTreeMap::new();
+    for (table, belongs) in TABLES {
+        let rows: String =
+            sqlx::query_scalar(&format!("SELECT COALESCE(json_agg(t), '[]')::text FROM {} t WHERE {}", table, belongs))
+                .bind(tenant.school_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        let rows = serde_json::from_str(&rows)
+            .map_err(|e| ApiError::internal(format!("Couldn't export {}: {}", table, e)))?;
+        tables.insert(table.to_string(), rows);
+    }
+    let schema_version = schema_version(&mut tx).await?;
+    tx.commit().await?;
+
+    Ok(Json(Dump { school_id: tenant.school_id, schema_version, exported_at: Utc::now(), tables }))
+}
+
+/// Columns of `rows` that `table` still has. Columns added since the dump was
+/// taken are left to their defaults; ones since dropped are skipped.
+fn restorable_columns(rows: &[Value], table_columns: &[String]) -> Vec<String> {
+    let Some(Value::Object(first)) = rows.first() else {
+        return Vec::new();
+    };
+    table_columns.iter().filter(|column| first.contains_key(column.as_str())).cloned().collect()
+}
+
+/// Replaces the school's data with a dump `export` made of it. It all
+/// happens in one transaction, so a dump that fails part way, or holds a row
+/// of another school's, leaves the data as it was. Only an admin can restore.
+pub async fn restore(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    body: Bytes,
+) -> ApiResult<StatusCode> {
+    if !user.is_admin() {
+        return Err(ApiError::forbidden("Only an admin can restore a backup."));
+    }
+    let dump: Dump = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::bad_request(format!("The backup isn't a Kyefa database dump: {}", e)))?;
+    if dump.school_id != tenant.school_id {
+        return Err(ApiError::bad_request("The backup belongs to another school."));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let current = schema_version(&mut tx).await?;
+    if dump.schema_version > current {
+        return Err(ApiError::bad_request(
+            "The backup was made by a newer version of the server. Update the server before restoring it.",
+        ));
+    }
+
+    for (table, belongs) in TABLES.iter().rev() {
+        sqlx::query(&format!("DELETE FROM {} WHERE {}", table, belongs))
+            .bind(tenant.school_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    for (table, belongs) in TABLES {
+        let Some(Value::Array(rows)) = dump.tables.get(*table) else {
+            continue;
+        };
+        let table_columns: Vec<String> = sqlx::query_scalar(
+            "SELECT column_name::text FROM information_schema.columns
+             WHERE table_schema = current_schema() AND table_name = $1
+             ORDER BY ordinal_position",
+        )
+        .bind(table)
+        .fetch_all(&mut *tx)
+        .await?;
+        let columns = restorable_columns(rows, &table_columns);
+        if columns.is_empty() {
+            continue;
+        }
+
+        let columns = columns.iter().map(|column| format!("\"{}\"", column)).collect::<Vec<_>>().join(", ");
+        let json = serde_json::to_string(rows).map_err(|e| ApiError::internal(e.to_string()))?;
+        sqlx::query(&format!(
+            "INSERT INTO {table} ({columns}) SELECT {columns} FROM json_populate_recordset(NULL::{table}, $1::json)",
+        ))
+        .bind(json)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Restoring {} failed: {}", table, e);
+            ApiError::bad_request(format!("The backup's {} couldn't be restored, so nothing was changed.", table.replace('_', " ")))
+        })?;
+
+        let restored: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE {}", table, belongs))
+            .bind(tenant.school_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if restored as usize != rows.len() {
+            return Err(ApiError::bad_request(format!(
+                "The backup's {} include another school's records, so nothing was changed.",
+                table.replace('_', " ")
+            )));
+        }
+    }
+    tx.commit().await?;
+    tracing::info!("Restored school {} from a dump taken at {}", tenant.school_id, dump.exported_at);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn restores_columns_the_table_still_has() {
+        let rows = [json!({ "id": 1, "name": "Accra Academy", "dropped": true })];
+        let table = columns(&["id", "name", "is_active"]);
+        assert_eq!(restorable_columns(&rows, &table), columns(&["id", "name"]));
+    }
+
+    #[test]
+    fn nothing_to_restore_from_an_empty_table() {
+        assert!(restorable_columns(&[], &columns(&["id"])).is_empty());
+    }
+}
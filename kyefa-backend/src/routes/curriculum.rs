@@ -0,0 +1,29 @@
+use axum::extract::State;
+use axum::Json;
+use kyefa_models::{Subject, SubjectRow, Term, TermRow};
+
+use crate::error::ApiResult;
+use crate::tenant::Tenant;
+use crate::AppState;
+
+/// Every term, newest first
+pub async fn terms(State(state): State<AppState>, tenant: Tenant) -> ApiResult<Json<Vec<Term>>> {
+    let rows: Vec<TermRow> = sqlx::query_as(
+        "SELECT id, school_id, name, academic_year, start_date, end_date, is_active
+         FROM terms WHERE school_id = $1 ORDER BY start_date DESC",
+    )
+    .bind(tenant.school_id)
+    .fetch_all(&state.pool)
+    .await?;
+    Ok(Json(rows.into_iter().map(Term::from).collect()))
+}
+
+pub async fn subjects(State(state): State<AppState>, tenant: Tenant) -> ApiResult<Json<Vec<Subject>>> {
+    let rows: Vec<SubjectRow> = sqlx::query_as(
+        "SELECT id, school_id, name, stream FROM subjects WHERE school_id = $1 ORDER BY stream, name",
+    )
+    .bind(tenant.school_id)
+    .fetch_all(&state.pool)
+    .await?;
+    Ok(Json(rows.into_iter().map(Subject::from).collect()))
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use axum::extract::State;
+use axum::Json;
+use chrono::NaiveDateTime;
+use kyefa_models::{format_money, ActivityType, Disbursement, PeriodVerification, RunPayoutPayload};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::{audit, settings, shares, AppState};
+
+/// Pays a term out: splits the fees collected for it over the periods the
+/// committee verified and writes each teacher's payout with the periods it
+/// paid for, which their payslips are read from. A term is paid once, and
+/// only after none of its periods await review, since a paid period's
+/// verification can't change.
+pub async fn run(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<RunPayoutPayload>,
+) -> ApiResult<Json<Disbursement>> {
+    if !user.role.can_run_payouts() {
+        return Err(ApiError::forbidden("Only an admin or headteacher can pay out a term."));
+    }
+    let currency = settings::load(&state.pool, tenant.school_id).await?.currency_symbol;
+    let rules = shares::rules(&state.pool, tenant.school_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+    // Locked so two runs for the term can't both get past the check below
+    let term_name: Option<String> =
+        sqlx::query_scalar("SELECT name FROM terms WHERE id = $1 AND school_id = $2 FOR UPDATE")
+            .bind(payload.term_id)
+            .bind(tenant.school_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    let Some(term_name) = term_name else {
+        return Err(ApiError::not_found("That term doesn't exist."));
+    };
+    let paid: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM disbursements WHERE term_id = $1)")
+        .bind(payload.term_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if paid {
+        return Err(ApiError::bad_request(format!("{} has already been paid out.", term_name)));
+    }
+
+    // Locked so the committee can't change a period while it is being paid
+    let periods: Vec<(Uuid, Uuid, PeriodVerification)> = sqlx::query_as(
+        "SELECT id, teacher_id, verification FROM conducted_periods
+         WHERE school_id = $1 AND term_id = $2 AND was_conducted
+         ORDER BY date, id
+         FOR UPDATE",
+    )
+    .bind(tenant.school_id)
+    .bind(payload.term_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let awaiting = periods
+        .iter()
+        .filter(|(_, _, verification)| matches!(verification, PeriodVerification::Pending | PeriodVerification::Disputed))
+        .count();
+    if awaiting > 0 {
+        return Err(ApiError::bad_request(format!(
+            "Review the {} pending or disputed period{} of {} before paying it out.",
+            awaiting,
+            if awaiting == 1 { "" } else { "s" },
+            term_name
+        )));
+    }
+    let mut verified: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (id, teacher_id, verification) in &periods {
+        if *verification == PeriodVerification::Verified {
+            verified.entry(*teacher_id).or_default().push(*id);
+        }
+    }
+
+    let revenue: f64 =
+        sqlx::query_scalar("SELECT COALESCE(SUM(amount_paid), 0) FROM payments WHERE school_id = $1 AND term_id = $2")
+            .bind(tenant.school_id)
+            .bind(payload.term_id)
+            .fetch_one(&mut *tx)
+            .await?;
+    if revenue <= 0.0 {
+        return Err(ApiError::bad_request(format!("No fees have been collected for {} yet.", term_name)));
+    }
+    let split = shares::split(revenue, &rules, &shares::verified_periods(&mut *tx, tenant.school_id, payload.term_id).await?);
+    let base_share: f64 = split.teachers.iter().map(|teacher| teacher.base_share).sum();
+    let period_share: f64 = split.teachers.iter().map(|teacher| teacher.period_share).sum();
+
+    let id = Uuid::new_v4();
+    let calculated_on: NaiveDateTime = sqlx::query_scalar(
+        "INSERT INTO disbursements
+             (id, school_id, term_id, total_revenue, admin_share, support_staff_share,
+              teacher_base_share, teacher_period_share, constraint_id)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         RETURNING calculated_on",
+    )
+    .bind(id)
+    .bind(tenant.school_id)
+    .bind(payload.term_id)
+    .bind(revenue)
+    .bind(split.admin_share)
+    .bind(split.support_staff_share)
+    .bind(base_share)
+    .bind(period_share)
+    .bind(rules.constraint_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for teacher in &split.teachers {
+        let payout_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO teacher_payouts (id, disbursement_id, teacher_id, base_share, period_share, capped)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(payout_id)
+        .bind(id)
+        .bind(teacher.teacher_id)
+        .bind(teacher.base_share)
+        .bind(teacher.period_share)
+        .bind(teacher.capped)
+        .execute(&mut *tx)
+        .await?;
+        let periods = verified.get(&teacher.teacher_id).map(Vec::as_slice).unwrap_or_default();
+        for (period_id, amount) in shares::period_payments(teacher, periods) {
+            sqlx::query(
+                "INSERT INTO teacher_payout_items (id, payout_id, conducted_period_id, paid_amount)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(payout_id)
+            .bind(period_id)
+            .bind(amount)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    audit::record(
+        &mut *tx,
+        tenant.school_id,
+        Some(user.id),
+        ActivityType::PayoutRun,
+        format!(
+            "Paid out {}: {} to {} teachers from {} collected",
+            term_name,
+            format_money(base_share + period_share, &currency),
+            split.teachers.len(),
+            format_money(revenue, &currency)
+        ),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(Disbursement {
+        id,
+        school_id: tenant.school_id,
+        term_id: payload.term_id,
+        total_revenue: revenue,
+        admin_share: split.admin_share,
+        support_staff_share: split.support_staff_share,
+        teacher_base_share: base_share,
+        teacher_period_share: period_share,
+        constraint_id: rules.constraint_id,
+        calculated_on,
+    }))
+}
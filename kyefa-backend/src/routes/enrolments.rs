@@ -0,0 +1,165 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use chrono::{NaiveDate, NaiveDateTime};
+use kyefa_models::{EnrolStudentsPayload, Enrolment, TermRow, WithdrawEnrolmentPayload};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::settings;
+use crate::tenant::Tenant;
+use crate::AppState;
+
+const ENROLMENT_COLUMNS: &str = "id, student_id, term_id, joined_on, left_on, fee_amount";
+
+#[derive(Deserialize)]
+pub struct EnrolmentQuery {
+    term_id: Uuid,
+}
+
+/// Every enrolment in the term, withdrawn ones included
+pub async fn list(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Query(query): Query<EnrolmentQuery>,
+) -> ApiResult<Json<Vec<Enrolment>>> {
+    Ok(Json(
+        sqlx::query_as(&format!(
+            "SELECT {} FROM enrolments WHERE school_id = $1 AND term_id = $2 ORDER BY joined_on",
+            ENROLMENT_COLUMNS
+        ))
+        .bind(tenant.school_id)
+        .bind(query.term_id)
+        .fetch_all(&state.pool)
+        .await?,
+    ))
+}
+
+/// Enrols the students from `joined_on` at their class fee for the term, less
+/// the school's sibling discount for a student whose brother or sister is
+/// already enrolled. A student who withdrew earlier in the term is re-enrolled
+/// on the same record rather than charged twice.
+pub async fn enrol(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Json(payload): Json<EnrolStudentsPayload>,
+) -> ApiResult<Json<Vec<Enrolment>>> {
+    let term: Option<TermRow> = sqlx::query_as(
+        "SELECT id, school_id, name, academic_year, start_date, end_date, is_active
+         FROM terms WHERE id = $1 AND school_id = $2",
+    )
+    .bind(payload.term_id)
+    .bind(tenant.school_id)
+    .fetch_optional(&state.pool)
+    .await?;
+    let Some(term) = term else {
+        return Err(ApiError::not_found("That term doesn't exist."));
+    };
+    if payload.joined_on < term.start_date.date() || payload.joined_on > term.end_date.date() {
+        return Err(ApiError::bad_request(format!("The join date must fall within {}.", term.name)));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let sibling_discount = settings::load(&mut *tx, tenant.school_id).await?.sibling_discount_percentage;
+    let mut enrolments = Vec::with_capacity(payload.student_ids.len());
+    for student_id in &payload.student_ids {
+        let student: Option<(String, Option<f64>)> = sqlx::query_as(
+            "SELECT s.first_name || ' ' || s.surname, cf.amount
+             FROM students s
+             LEFT JOIN class_history ch ON ch.student_id = s.id AND ch.academic_year = $3
+             LEFT JOIN class_fees cf ON cf.term_id = $4 AND cf.class_level = COALESCE(ch.class_level, s.class_level)
+             WHERE s.id = $1 AND s.school_id = $2 AND s.is_active AND s.merged_into IS NULL",
+        )
+        .bind(student_id)
+        .bind(tenant.school_id)
+        .bind(term.academic_year)
+        .bind(term.id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (name, fee) = match student {
+            Some((name, Some(fee))) => (name, fee),
+            Some((name, None)) => {
+                return Err(ApiError::bad_request(format!("No fee is set for {}'s class in {}.", name, term.name)));
+            }
+            None => return Err(ApiError::not_found("A student being enrolled doesn't exist or is inactive.")),
+        };
+        // Siblings enrolled earlier in this same request count too
+        let has_sibling: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM guardian_students mine
+                 JOIN guardian_students theirs ON theirs.guardian_id = mine.guardian_id
+                                              AND theirs.student_id <> mine.student_id
+                 JOIN enrolments e ON e.student_id = theirs.student_id AND e.term_id = $2 AND e.left_on IS NULL
+                 WHERE mine.student_id = $1)",
+        )
+        .bind(student_id)
+        .bind(term.id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let fee = match has_sibling && sibling_discount > 0.0 {
+            true => (fee * (100.0 - sibling_discount)).round() / 100.0,
+            false => fee,
+        };
+
+        let enrolment: Option<Enrolment> = sqlx::query_as(&format!(
+            "INSERT INTO enrolments (id, school_id, student_id, term_id, joined_on, fee_amount)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (student_id, term_id) DO UPDATE SET left_on = NULL
+                 WHERE enrolments.left_on IS NOT NULL
+             RETURNING {}",
+            ENROLMENT_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(tenant.school_id)
+        .bind(student_id)
+        .bind(term.id)
+        .bind(payload.joined_on)
+        .bind(fee)
+        .fetch_optional(&mut *tx)
+        .await?;
+        match enrolment {
+            Some(enrolment) => enrolments.push(enrolment),
+            None => return Err(ApiError::bad_request(format!("{} is already enrolled in {}.", name, term.name))),
+        }
+    }
+    tx.commit().await?;
+
+    Ok(Json(enrolments))
+}
+
+/// Marks the enrolment as left on `left_on`. The term's fee is still owed.
+pub async fn withdraw(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Json(payload): Json<WithdrawEnrolmentPayload>,
+) -> ApiResult<Json<Enrolment>> {
+    let enrolment: Option<(NaiveDateTime, Option<NaiveDate>, NaiveDate)> = sqlx::query_as(
+        "SELECT t.end_date, e.left_on, e.joined_on FROM enrolments e JOIN terms t ON t.id = e.term_id
+         WHERE e.id = $1 AND e.school_id = $2",
+    )
+    .bind(payload.enrolment_id)
+    .bind(tenant.school_id)
+    .fetch_optional(&state.pool)
+    .await?;
+    let Some((end_date, left_on, joined_on)) = enrolment else {
+        return Err(ApiError::not_found("That enrolment doesn't exist."));
+    };
+    if left_on.is_some() {
+        return Err(ApiError::bad_request("The student has already withdrawn."));
+    }
+    if payload.left_on < joined_on || payload.left_on > end_date.date() {
+        return Err(ApiError::bad_request("The leave date must fall between the join date and the end of the term."));
+    }
+
+    Ok(Json(
+        sqlx::query_as(&format!(
+            "UPDATE enrolments SET left_on = $3 WHERE id = $1 AND school_id = $2 RETURNING {}",
+            ENROLMENT_COLUMNS
+        ))
+        .bind(payload.enrolment_id)
+        .bind(tenant.school_id)
+        .bind(payload.left_on)
+        .fetch_one(&state.pool)
+        .await?,
+    ))
+}
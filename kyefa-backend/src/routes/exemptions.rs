@@ -0,0 +1,105 @@
+use axum::extract::State;
+use axum::Json;
+use kyefa_models::{format_money, ActivityType, CreateExemptionPayload, Exemption};
+use uuid::Uuid;
+
+use crate::{audit, settings};
+use crate::error::{ApiError, ApiResult};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::AppState;
+
+pub const EXEMPTION_COLUMNS: &str = "id, student_id, term_id, is_full, amount, reason, approved_by, notes, created_at";
+
+/// Exempts an enrolled student from all or part of the term's fee. A student
+/// has at most one exemption per term; a partial one must leave something to
+/// pay, otherwise it should be full.
+pub async fn create(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<CreateExemptionPayload>,
+) -> ApiResult<Json<Exemption>> {
+    if !user.role.can_approve_exemptions() {
+        return Err(ApiError::forbidden("Only an admin or headteacher can approve exemptions."));
+    }
+
+    let currency = settings::load(&state.pool, tenant.school_id).await?.currency_symbol;
+    let mut tx = state.pool.begin().await?;
+    let enrolment: Option<(String, String, f64)> = sqlx::query_as(
+        "SELECT s.first_name || ' ' || s.surname, t.name, e.fee_amount
+         FROM enrolments e
+         JOIN students s ON s.id = e.student_id
+         JOIN terms t ON t.id = e.term_id
+         WHERE e.student_id = $1 AND e.term_id = $2 AND e.school_id = $3 AND s.merged_into IS NULL
+         FOR UPDATE OF e",
+    )
+    .bind(payload.student_id)
+    .bind(payload.term_id)
+    .bind(tenant.school_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((student_name, term_name, fee)) = enrolment else {
+        return Err(ApiError::bad_request("The student isn't enrolled in that term."));
+    };
+
+    let amount = if payload.is_full {
+        fee
+    } else {
+        let amount = payload.amount.unwrap_or(0.0);
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(ApiError::bad_request("The exempted amount must be more than zero."));
+        }
+        if amount >= fee {
+            return Err(ApiError::bad_request(format!(
+                "{} covers {}'s whole fee of {}. Record a full exemption instead.",
+                format_money(amount, &currency),
+                student_name,
+                format_money(fee, &currency)
+            )));
+        }
+        amount
+    };
+
+    let exemption: Option<Exemption> = sqlx::query_as(&format!(
+        "INSERT INTO exemptions (id, school_id, student_id, term_id, is_full, amount, reason, approved_by, notes)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         ON CONFLICT (student_id, term_id) DO NOTHING
+         RETURNING {}",
+        EXEMPTION_COLUMNS
+    ))
+    .bind(Uuid::new_v4())
+    .bind(tenant.school_id)
+    .bind(payload.student_id)
+    .bind(payload.term_id)
+    .bind(payload.is_full)
+    .bind(amount)
+    .bind(&payload.reason)
+    .bind(user.id)
+    .bind(payload.notes.as_deref().map(str::trim).filter(|notes| !notes.is_empty()))
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(exemption) = exemption else {
+        return Err(ApiError::bad_request(format!("{} already has an exemption for {}.", student_name, term_name)));
+    };
+    audit::record(
+        &mut *tx,
+        tenant.school_id,
+        Some(user.id),
+        ActivityType::ExemptionGranted,
+        match exemption.is_full {
+            true => format!("{} exempted from the {} fee ({})", student_name, term_name, payload.reason),
+            false => format!(
+                "{} exempted from {} of the {} fee ({})",
+                student_name,
+                format_money(amount, &currency),
+                term_name,
+                payload.reason
+            ),
+        },
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(exemption))
+}
@@ -0,0 +1,187 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use kyefa_models::{Guardian, GuardianRelationship, PersonName, SaveGuardianPayload};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::tenant::Tenant;
+use crate::AppState;
+
+#[derive(sqlx::FromRow)]
+struct GuardianRow {
+    id: Uuid,
+    first_name: String,
+    surname: String,
+    other_names: Option<String>,
+    relationship: GuardianRelationship,
+    phone_number: String,
+    alternate_phone: Option<String>,
+    is_primary_contact: bool,
+    student_ids: Vec<Uuid>,
+}
+
+impl From<GuardianRow> for Guardian {
+    fn from(row: GuardianRow) -> Self {
+        Guardian {
+            id: row.id,
+            name: PersonName { first_name: row.first_name, surname: row.surname, other_names: row.other_names },
+            relationship: row.relationship,
+            phone_number: row.phone_number,
+            alternate_phone: row.alternate_phone,
+            is_primary_contact: row.is_primary_contact,
+            student_ids: row.student_ids,
+        }
+    }
+}
+
+/// The school's guardians with the students linked to them, narrowed to
+/// `id` when given
+async fn load(pool: &PgPool, school_id: Uuid, id: Option<Uuid>) -> Result<Vec<Guardian>, sqlx::Error> {
+    let rows: Vec<GuardianRow> = sqlx::query_as(
+        "SELECT g.id, g.first_name, g.surname, g.other_names, g.relationship, g.phone_number,
+                g.alternate_phone, g.is_primary_contact,
+                ARRAY(SELECT gs.student_id FROM guardian_students gs WHERE gs.guardian_id = g.id) AS student_ids
+         FROM guardians g
+         WHERE g.school_id = $1 AND ($2::uuid IS NULL OR g.id = $2)
+         ORDER BY g.surname, g.first_name",
+    )
+    .bind(school_id)
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(Guardian::from).collect())
+}
+
+pub async fn list(State(state): State<AppState>, tenant: Tenant) -> ApiResult<Json<Vec<Guardian>>> {
+    Ok(Json(load(&state.pool, tenant.school_id, None).await?))
+}
+
+fn check(payload: &SaveGuardianPayload) -> ApiResult<()> {
+    if payload.first_name.trim().is_empty() || payload.surname.trim().is_empty() {
+        return Err(ApiError::bad_request("First name and surname are required."));
+    }
+    if payload.phone_number.trim().is_empty() {
+        return Err(ApiError::bad_request("Enter a phone number to reach the guardian on."));
+    }
+    if payload.student_ids.is_empty() {
+        return Err(ApiError::bad_request("Link the guardian to at least one student."));
+    }
+    Ok(())
+}
+
+/// Links the guardian to exactly `student_ids`. A primary contact takes over
+/// from any other guardian of the same children, so a family has one.
+async fn link(
+    tx: &mut PgConnection,
+    school_id: Uuid,
+    guardian_id: Uuid,
+    payload: &SaveGuardianPayload,
+) -> ApiResult<()> {
+    let found: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM students WHERE school_id = $1 AND id = ANY($2) AND merged_into IS NULL",
+    )
+    .bind(school_id)
+    .bind(&payload.student_ids)
+    .fetch_one(&mut *tx)
+    .await?;
+    let mut requested = payload.student_ids.clone();
+    requested.sort();
+    requested.dedup();
+    if found as usize != requested.len() {
+        return Err(ApiError::not_found("A student linked to the guardian doesn't exist."));
+    }
+
+    sqlx::query("DELETE FROM guardian_students WHERE guardian_id = $1")
+        .bind(guardian_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("INSERT INTO guardian_students (guardian_id, student_id) SELECT $1, UNNEST($2::uuid[])")
+        .bind(guardian_id)
+        .bind(&requested)
+        .execute(&mut *tx)
+        .await?;
+    if payload.is_primary_contact {
+        sqlx::query(
+            "UPDATE guardians SET is_primary_contact = FALSE
+             WHERE school_id = $1 AND id <> $2
+               AND id IN (SELECT guardian_id FROM guardian_students WHERE student_id = ANY($3))",
+        )
+        .bind(school_id)
+        .bind(guardian_id)
+        .bind(&requested)
+        .execute(&mut *tx)
+        .await?;
+    }
+    Ok(())
+}
+
+pub async fn create(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Json(payload): Json<SaveGuardianPayload>,
+) -> ApiResult<Json<Guardian>> {
+    check(&payload)?;
+    let id = Uuid::new_v4();
+    let mut tx = state.pool.begin().await?;
+    sqlx::query(
+        "INSERT INTO guardians (id, school_id, first_name, surname, other_names, relationship, phone_number,
+                                alternate_phone, is_primary_contact)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+    )
+    .bind(id)
+    .bind(tenant.school_id)
+    .bind(payload.first_name.trim())
+    .bind(payload.surname.trim())
+    .bind(payload.other_names.as_deref().map(str::trim).filter(|names| !names.is_empty()))
+    .bind(&payload.relationship)
+    .bind(payload.phone_number.trim())
+    .bind(payload.alternate_phone.as_deref().map(str::trim).filter(|phone| !phone.is_empty()))
+    .bind(payload.is_primary_contact)
+    .execute(&mut *tx)
+    .await?;
+    link(&mut tx, tenant.school_id, id, &payload).await?;
+    tx.commit().await?;
+
+    fetch_one(&state.pool, tenant.school_id, id).await.map(Json)
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SaveGuardianPayload>,
+) -> ApiResult<Json<Guardian>> {
+    check(&payload)?;
+    let mut tx = state.pool.begin().await?;
+    let updated = sqlx::query(
+        "UPDATE guardians SET first_name = $3, surname = $4, other_names = $5, relationship = $6,
+                              phone_number = $7, alternate_phone = $8, is_primary_contact = $9
+         WHERE id = $1 AND school_id = $2",
+    )
+    .bind(id)
+    .bind(tenant.school_id)
+    .bind(payload.first_name.trim())
+    .bind(payload.surname.trim())
+    .bind(payload.other_names.as_deref().map(str::trim).filter(|names| !names.is_empty()))
+    .bind(&payload.relationship)
+    .bind(payload.phone_number.trim())
+    .bind(payload.alternate_phone.as_deref().map(str::trim).filter(|phone| !phone.is_empty()))
+    .bind(payload.is_primary_contact)
+    .execute(&mut *tx)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::not_found("That guardian doesn't exist."));
+    }
+    link(&mut tx, tenant.school_id, id, &payload).await?;
+    tx.commit().await?;
+
+    fetch_one(&state.pool, tenant.school_id, id).await.map(Json)
+}
+
+async fn fetch_one(pool: &PgPool, school_id: Uuid, id: Uuid) -> ApiResult<Guardian> {
+    load(pool, school_id, Some(id))
+        .await?
+        .pop()
+        .ok_or_else(|| ApiError::not_found("That guardian doesn't exist."))
+}
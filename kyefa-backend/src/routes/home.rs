@@ -0,0 +1,174 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+use kyefa_models::{ActivityType, HomeQuery, HomeSummary, RecentActivity, TeacherHomeSummary, TermRow};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::ApiResult;
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::{fees, shares, terms, AppState};
+
+const DEFAULT_ACTIVITY_LIMIT: u32 = 20;
+const MAX_ACTIVITY_LIMIT: u32 = 100;
+
+/// Monday and Sunday of the week containing `today`
+fn week_of(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    (monday, monday + Duration::days(6))
+}
+
+/// Periods conducted and periods logged (conducted or not) between the dates,
+/// for one teacher when given
+async fn period_counts(
+    pool: &PgPool,
+    school_id: Uuid,
+    term_id: Option<Uuid>,
+    teacher_id: Option<Uuid>,
+    (from, to): (Option<NaiveDate>, Option<NaiveDate>),
+) -> Result<(usize, usize), sqlx::Error> {
+    let (conducted, logged): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*) FILTER (WHERE was_conducted), COUNT(*)
+         FROM conducted_periods
+         WHERE school_id = $1
+           AND ($2::uuid IS NULL OR term_id = $2)
+           AND ($3::uuid IS NULL OR teacher_id = $3)
+           AND ($4::date IS NULL OR date::date >= $4)
+           AND ($5::date IS NULL OR date::date <= $5)",
+    )
+    .bind(school_id)
+    .bind(term_id)
+    .bind(teacher_id)
+    .bind(from)
+    .bind(to)
+    .fetch_one(pool)
+    .await?;
+    Ok((conducted as usize, logged as usize))
+}
+
+/// The requesting user's own figures, when they are linked to a teacher
+async fn teacher_summary(
+    pool: &PgPool,
+    school_id: Uuid,
+    user_id: Uuid,
+    term: &TermRow,
+    expected_revenue: f64,
+    week: (NaiveDate, NaiveDate),
+) -> Result<Option<TeacherHomeSummary>, sqlx::Error> {
+    let teacher_id: Option<Uuid> = sqlx::query_scalar("SELECT id FROM teachers WHERE school_id = $1 AND user_id = $2")
+        .bind(school_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    let Some(teacher_id) = teacher_id else {
+        return Ok(None);
+    };
+
+    let (periods_this_term, _) = period_counts(pool, school_id, Some(term.id), Some(teacher_id), (None, None)).await?;
+    let (periods_this_week, _) =
+        period_counts(pool, school_id, Some(term.id), Some(teacher_id), (Some(week.0), Some(week.1))).await?;
+    let rules = shares::rules(pool, school_id).await?;
+    let periods = shares::verified_periods(pool, school_id, term.id).await?;
+    let projected_earnings = shares::split(expected_revenue, &rules, &periods)
+        .teachers
+        .iter()
+        .find(|teacher| teacher.teacher_id == teacher_id)
+        .map(|teacher| teacher.total())
+        .unwrap_or(0.0);
+
+    Ok(Some(TeacherHomeSummary { teacher_id, periods_this_term, periods_this_week, projected_earnings }))
+}
+
+/// Counts, collection and this week's periods for the active term, with the
+/// user's own figures when they teach
+pub async fn summary(State(state): State<AppState>, user: CurrentUser, tenant: Tenant) -> ApiResult<Json<HomeSummary>> {
+    let term = terms::active(&state.pool, tenant.school_id).await?;
+    let students = fees::load(&state.pool, tenant.school_id, term.as_ref().map(|term| term.id), None).await?;
+    let expected = || students.iter().filter(|student| student.position.is_expected());
+    let expected_revenue: f64 = expected().map(|student| student.position.due()).sum();
+    let total_revenue: f64 = expected().map(|student| student.position.paid).sum();
+
+    let active_teachers: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM teachers WHERE school_id = $1 AND is_active")
+        .bind(tenant.school_id)
+        .fetch_one(&state.pool)
+        .await?;
+    let week = week_of(Local::now().date_naive());
+    let (periods_this_week, logged_periods_this_week) =
+        period_counts(&state.pool, tenant.school_id, None, None, (Some(week.0), Some(week.1))).await?;
+    let teacher_summary = match &term {
+        Some(term) => {
+            teacher_summary(&state.pool, tenant.school_id, user.id, term, expected_revenue, week).await?
+        }
+        None => None,
+    };
+
+    Ok(Json(HomeSummary {
+        active_term_name: term.map(|term| term.name),
+        total_students: students.iter().filter(|student| student.row.is_active).count(),
+        active_teachers: active_teachers as usize,
+        expected_revenue,
+        total_revenue,
+        collection_rate: if expected_revenue > 0.0 { total_revenue / expected_revenue * 100.0 } else { 0.0 },
+        periods_this_week,
+        logged_periods_this_week,
+        teacher_summary,
+    }))
+}
+
+#[derive(sqlx::FromRow)]
+struct ActivityRow {
+    id: Uuid,
+    activity_type: ActivityType,
+    description: String,
+    created_at: DateTime<Utc>,
+    user_name: Option<String>,
+}
+
+/// The newest audit log entries for the school
+pub async fn activity(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Query(query): Query<HomeQuery>,
+) -> ApiResult<Json<Vec<RecentActivity>>> {
+    let limit = query.limit.unwrap_or(DEFAULT_ACTIVITY_LIMIT).clamp(1, MAX_ACTIVITY_LIMIT);
+    let rows: Vec<ActivityRow> = sqlx::query_as(
+        "SELECT a.id, a.activity_type, a.description, a.created_at, u.first_name || ' ' || u.surname AS user_name
+         FROM audit_log a
+         LEFT JOIN users u ON u.id = a.user_id
+         WHERE a.school_id = $1
+         ORDER BY a.created_at DESC
+         LIMIT $2",
+    )
+    .bind(tenant.school_id)
+    .bind(limit as i64)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|row| RecentActivity {
+                id: row.id.to_string(),
+                activity_type: row.activity_type,
+                description: row.description,
+                timestamp: row.created_at,
+                user_name: row.user_name.unwrap_or_else(|| "System".to_string()),
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn week_runs_monday_to_sunday() {
+        let wednesday = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let (monday, sunday) = week_of(wednesday);
+        assert_eq!(monday, NaiveDate::from_ymd_opt(2026, 10, 12).unwrap());
+        assert_eq!(sunday, NaiveDate::from_ymd_opt(2026, 10, 18).unwrap());
+        assert_eq!(week_of(monday), (monday, sunday));
+        assert_eq!(week_of(sunday), (monday, sunday));
+    }
+}
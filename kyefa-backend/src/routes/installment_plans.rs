@@ -0,0 +1,96 @@
+use axum::extract::State;
+use axum::Json;
+use kyefa_models::{format_money, InstallmentPlan, SaveInstallmentPlanPayload};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::fees::{self, TOLERANCE};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::{settings, AppState};
+
+/// Replaces the student's plan for the term with the installments given, or
+/// removes it when there are none. The installments must fall due in order
+/// and add up to what the student owes for the term after any exemption.
+pub async fn save(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<SaveInstallmentPlanPayload>,
+) -> ApiResult<Json<Option<InstallmentPlan>>> {
+    for (index, installment) in payload.installments.iter().enumerate() {
+        let number = index + 1;
+        if !installment.amount.is_finite() || installment.amount <= 0.0 {
+            return Err(ApiError::bad_request(format!("Installment {} must be more than zero.", number)));
+        }
+        if index > 0 && payload.installments[index - 1].due_date >= installment.due_date {
+            return Err(ApiError::bad_request(format!("Installment {} must fall due after the one before it.", number)));
+        }
+    }
+
+    let student = fees::load(&state.pool, tenant.school_id, Some(payload.term_id), Some(&[payload.student_id]))
+        .await?
+        .pop();
+    let Some(student) = student else {
+        return Err(ApiError::not_found("That student doesn't exist."));
+    };
+    if !student.position.is_expected() {
+        return Err(ApiError::bad_request(format!("{} isn't enrolled in that term.", student.name())));
+    }
+    let total: f64 = payload.installments.iter().map(|installment| installment.amount).sum();
+    let due = student.position.due();
+    if !payload.installments.is_empty() && (total - due).abs() > TOLERANCE {
+        let currency = settings::load(&state.pool, tenant.school_id).await?.currency_symbol;
+        return Err(ApiError::bad_request(format!(
+            "Installments add up to {} but {} owes {} for the term.",
+            format_money(total, &currency),
+            student.name(),
+            format_money(due, &currency)
+        )));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    if payload.installments.is_empty() {
+        sqlx::query("DELETE FROM installment_plans WHERE student_id = $1 AND term_id = $2 AND school_id = $3")
+            .bind(payload.student_id)
+            .bind(payload.term_id)
+            .bind(tenant.school_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        return Ok(Json(None));
+    }
+
+    let plan_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO installment_plans (id, school_id, student_id, term_id, created_by)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (student_id, term_id) DO UPDATE SET created_by = $5, created_at = LOCALTIMESTAMP
+         RETURNING id",
+    )
+    .bind(Uuid::new_v4())
+    .bind(tenant.school_id)
+    .bind(payload.student_id)
+    .bind(payload.term_id)
+    .bind(user.id)
+    .fetch_one(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM installments WHERE plan_id = $1")
+        .bind(plan_id)
+        .execute(&mut *tx)
+        .await?;
+    for installment in &payload.installments {
+        sqlx::query("INSERT INTO installments (plan_id, due_date, amount) VALUES ($1, $2, $3)")
+            .bind(plan_id)
+            .bind(installment.due_date)
+            .bind(installment.amount)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(Json(
+        fees::plans(&state.pool, tenant.school_id, payload.term_id, Some(&[payload.student_id]))
+            .await?
+            .remove(&payload.student_id),
+    ))
+}
@@ -0,0 +1,77 @@
+use axum::extract::DefaultBodyLimit;
+use axum::middleware;
+use axum::routing::{get, post, put};
+use axum::Router;
+
+use crate::idempotency;
+use crate::AppState;
+
+mod auth;
+pub(crate) mod backups;
+mod curriculum;
+mod disbursements;
+mod enrolments;
+pub(crate) mod exemptions;
+mod guardians;
+mod home;
+mod installment_plans;
+mod payments;
+mod periods;
+mod portal;
+mod reports;
+mod settings;
+mod staff;
+mod students;
+mod users;
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/login", post(auth::login))
+        .route("/logout", post(auth::logout))
+        .route("/students", get(students::list).post(students::create).put(students::update))
+        .route("/students/import", post(students::import))
+        .route("/students/merge", post(students::merge))
+        .route("/students/promote", post(students::promote))
+        .route("/students/{id}/class-history", get(students::class_history))
+        .route("/teachers", get(staff::teachers))
+        .route("/terms", get(curriculum::terms))
+        .route("/subjects", get(curriculum::subjects))
+        .route("/periods", get(periods::list))
+        .route("/periods/import", post(periods::import))
+        .route("/periods/{id}/verification", put(periods::review))
+        .route("/periods/{id}/dispute", post(portal::dispute))
+        .route("/portal/teacher", get(portal::teacher))
+        .route("/enrolments", get(enrolments::list).post(enrolments::enrol))
+        .route("/enrolments/withdraw", post(enrolments::withdraw))
+        .route("/exemptions", post(exemptions::create))
+        .route("/guardians", get(guardians::list).post(guardians::create))
+        .route("/guardians/{id}", put(guardians::update))
+        .route("/installment-plans", put(installment_plans::save))
+        .route("/payments", get(payments::list).post(payments::record))
+        .route("/disbursements", post(disbursements::run))
+        .route("/dashboard/summary", get(home::summary))
+        .route("/activity", get(home::activity))
+        .route("/reports/overview", get(reports::overview))
+        .route("/reports/projected-income", get(reports::projected_income))
+        .route("/reports/collection-status", get(reports::collection_status))
+        .route("/reports/teacher-earnings", get(reports::teacher_earnings))
+        .route("/reports/student-payments", get(reports::student_payments))
+        .route("/reports/exemptions", get(reports::exemptions))
+        .route("/reports/overdue-aging", get(reports::overdue_aging))
+        .route("/reports/collection-trend", get(reports::collection_trend))
+        .route("/reports/term-comparison", get(reports::term_comparison))
+        .route("/users", get(users::list).post(users::create))
+        .route("/users/{id}", put(users::update))
+        .route("/users/{id}/reset-password", post(users::reset_password))
+        .route("/account/change-password", post(users::change_password))
+        .route("/account/unlock", post(auth::unlock))
+        .route("/login-history", get(auth::login_history))
+        .route("/settings", get(settings::get).put(settings::update))
+        .route("/backups/export", get(backups::export))
+        .route(
+            backups::RESTORE_PATH,
+            post(backups::restore).layer(DefaultBodyLimit::max(backups::MAX_DUMP_BYTES)),
+        )
+        .layer(middleware::from_fn_with_state(state.clone(), idempotency::layer))
+        .with_state(state)
+}
@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::{Query, State};
+use axum::Json;
+use kyefa_models::{format_money, ActivityType, Payment, PaymentFilter, PaymentStatus, RecordPaymentPayload};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::{audit, fees, settings, AppState};
+
+#[derive(sqlx::FromRow)]
+struct PaymentRow {
+    id: Uuid,
+    student_id: Uuid,
+    term_id: Uuid,
+    amount_paid: f64,
+    method: String,
+    description: String,
+}
+
+/// Payments matching the filter, each with where the student stands for the
+/// term it was paid against
+async fn load(pool: &PgPool, school_id: Uuid, filter: &PaymentFilter, id: Option<Uuid>) -> ApiResult<Vec<Payment>> {
+    let rows: Vec<PaymentRow> = sqlx::query_as(
+        "SELECT id, student_id, term_id, amount_paid, method, description
+         FROM payments
+         WHERE school_id = $1
+           AND ($2::date IS NULL OR date_paid::date >= $2)
+           AND ($3::date IS NULL OR date_paid::date <= $3)
+           AND ($4::uuid IS NULL OR id = $4)
+         ORDER BY date_paid DESC",
+    )
+    .bind(school_id)
+    .bind(filter.date_from)
+    .bind(filter.date_to)
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut statuses: HashMap<(Uuid, Uuid), PaymentStatus> = HashMap::new();
+    let term_ids: HashSet<Uuid> = rows.iter().map(|row| row.term_id).collect();
+    for term_id in term_ids {
+        let student_ids: Vec<Uuid> = rows.iter().filter(|row| row.term_id == term_id).map(|row| row.student_id).collect();
+        for student in fees::load(pool, school_id, Some(term_id), Some(&student_ids)).await? {
+            statuses.insert((term_id, student.row.id), student.position.status);
+        }
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| Payment {
+            id: row.id.to_string(),
+            student_id: row.student_id.to_string(),
+            amount: row.amount_paid,
+            method: row.method,
+            description: row.description,
+            status: statuses.remove(&(row.term_id, row.student_id)).unwrap_or(PaymentStatus::NotEnrolled),
+        })
+        .collect())
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Query(filter): Query<PaymentFilter>,
+) -> ApiResult<Json<Vec<Payment>>> {
+    load(&state.pool, tenant.school_id, &filter, None).await.map(Json)
+}
+
+/// Records the payment under the client's id. A replay with an id that
+/// already exists returns that payment instead of recording it twice.
+pub async fn record(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<RecordPaymentPayload>,
+) -> ApiResult<Json<Payment>> {
+    if !payload.amount_paid.is_finite() || payload.amount_paid <= 0.0 {
+        return Err(ApiError::bad_request("Enter an amount greater than zero."));
+    }
+    if payload.method.trim().is_empty() {
+        return Err(ApiError::bad_request("Choose how the payment was made."));
+    }
+    let student: Option<String> = sqlx::query_scalar(
+        "SELECT first_name || ' ' || surname FROM students WHERE id = $1 AND school_id = $2 AND is_active",
+    )
+    .bind(payload.student_id)
+    .bind(tenant.school_id)
+    .fetch_optional(&state.pool)
+    .await?;
+    let Some(student_name) = student else {
+        return Err(ApiError::not_found("That student doesn't exist."));
+    };
+    let term_exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM terms WHERE id = $1 AND school_id = $2)")
+        .bind(payload.term_id)
+        .bind(tenant.school_id)
+        .fetch_one(&state.pool)
+        .await?;
+    if !term_exists {
+        return Err(ApiError::not_found("That term doesn't exist."));
+    }
+
+    let currency = settings::load(&state.pool, tenant.school_id).await?.currency_symbol;
+    let mut tx = state.pool.begin().await?;
+    let inserted = sqlx::query(
+        "INSERT INTO payments (id, school_id, student_id, term_id, amount_paid, method, description, recorded_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         ON CONFLICT (id) DO NOTHING",
+    )
+    .bind(payload.id)
+    .bind(tenant.school_id)
+    .bind(payload.student_id)
+    .bind(payload.term_id)
+    .bind(payload.amount_paid)
+    .bind(payload.method.trim())
+    .bind(payload.description.trim())
+    .bind(user.id)
+    .execute(&mut *tx)
+    .await?;
+    if inserted.rows_affected() == 1 {
+        audit::record(
+            &mut *tx,
+            tenant.school_id,
+            Some(user.id),
+            ActivityType::PaymentReceived,
+            format!("{} received from {}", format_money(payload.amount_paid, &currency), student_name),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    load(&state.pool, tenant.school_id, &PaymentFilter::default(), Some(payload.id))
+        .await?
+        .pop()
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("That payment doesn't exist."))
+}
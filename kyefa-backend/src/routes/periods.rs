@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use kyefa_models::{
+    ActivityType, ImportRowError, ImportSummary, PeriodForReview, PeriodImportPayload, PeriodReviewQuery,
+    PeriodVerification, ReviewPeriodPayload, TeacherPeriod, TermRow,
+};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use super::portal::{self, PeriodRow};
+use crate::{audit, terms, AppState};
+
+/// Records the register's periods against `term_id`, rejecting rows whose
+/// teacher, subject or date the school doesn't recognise. Rows replayed with
+/// an id already stored are skipped rather than recorded twice. The periods
+/// feed the payout split, so only data entry and the committee log them for
+/// anyone; a teacher logs their own.
+pub async fn import(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<PeriodImportPayload>,
+) -> ApiResult<Json<ImportSummary>> {
+    let term: Option<TermRow> = sqlx::query_as(
+        "SELECT id, school_id, name, academic_year, start_date, end_date, is_active
+         FROM terms WHERE id = $1 AND school_id = $2",
+    )
+    .bind(payload.term_id)
+    .bind(tenant.school_id)
+    .fetch_optional(&state.pool)
+    .await?;
+    let Some(term) = term else {
+        return Err(ApiError::not_found("That term doesn't exist."));
+    };
+    let teachers: HashSet<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM teachers WHERE school_id = $1 AND is_active AND ($2 OR user_id = $3)",
+    )
+    .bind(tenant.school_id)
+    .bind(user.role.can_log_any_periods())
+    .bind(user.id)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .collect();
+    if teachers.is_empty() && !user.role.can_log_any_periods() {
+        return Err(ApiError::forbidden("Only data entry or the committee can log periods for other teachers."));
+    }
+    let subjects: HashSet<Uuid> = sqlx::query_scalar("SELECT id FROM subjects WHERE school_id = $1")
+        .bind(tenant.school_id)
+        .fetch_all(&state.pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut rejected = Vec::new();
+    let mut created = 0;
+    let mut tx = state.pool.begin().await?;
+    for row in &payload.rows {
+        let period = &row.period;
+        let problem = if period.term_id != term.id {
+            Some(format!("This period isn't part of {}.", term.name))
+        } else if period.date.date() < term.start_date.date() || period.date.date() > term.end_date.date() {
+            Some(format!("The date is outside {}.", term.name))
+        } else if !teachers.contains(&period.teacher_id) {
+            Some(match user.role.can_log_any_periods() {
+                true => "The teacher isn't active at this school.".to_string(),
+                false => "You can only log your own periods.".to_string(),
+            })
+        } else if !subjects.contains(&period.subject_id) {
+            Some("The subject isn't offered at this school.".to_string())
+        } else if period.week_of_term == 0 {
+            Some("The week of term must be 1 or later.".to_string())
+        } else {
+            None
+        };
+        if let Some(message) = problem {
+            rejected.push(ImportRowError { row_number: row.row_number, message });
+            continue;
+        }
+
+        let inserted = sqlx::query(
+            "INSERT INTO conducted_periods
+                 (id, school_id, date, class_level, subject_id, teacher_id, term_id, week_of_term, was_conducted, notes)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(period.id)
+        .bind(tenant.school_id)
+        .bind(period.date)
+        .bind(&period.class_level)
+        .bind(period.subject_id)
+        .bind(period.teacher_id)
+        .bind(term.id)
+        .bind(period.week_of_term as i32)
+        .bind(period.was_conducted)
+        .bind(&period.notes)
+        .execute(&mut *tx)
+        .await?;
+        created += inserted.rows_affected() as usize;
+    }
+    if created > 0 {
+        audit::record(
+            &mut *tx,
+            tenant.school_id,
+            Some(user.id),
+            ActivityType::TeachingPeriodCreated,
+            match created {
+                1 => format!("1 period imported for {}", term.name),
+                count => format!("{} periods imported for {}", count, term.name),
+            },
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(Json(ImportSummary { created, rejected }))
+}
+
+#[derive(sqlx::FromRow)]
+struct ReviewRow {
+    teacher_id: Uuid,
+    teacher_name: String,
+    #[sqlx(flatten)]
+    period: PeriodRow,
+}
+
+/// The term's periods for the committee to review, oldest first, narrowed to
+/// one verification state when asked. The reviewer's own periods are left
+/// out, since someone else has to review them.
+pub async fn list(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(query): Query<PeriodReviewQuery>,
+) -> ApiResult<Json<Vec<PeriodForReview>>> {
+    if !user.role.can_review_periods() {
+        return Err(ApiError::forbidden("Only the committee can review periods."));
+    }
+    let term_id = match query.term_id {
+        Some(term_id) => term_id,
+        None => match terms::active(&state.pool, tenant.school_id).await? {
+            Some(term) => term.id,
+            None => return Ok(Json(Vec::new())),
+        },
+    };
+    let rows: Vec<ReviewRow> = sqlx::query_as(
+        "SELECT t.id AS teacher_id, t.first_name || ' ' || t.surname AS teacher_name,
+                cp.id, cp.date, cp.class_level, s.name AS subject, cp.week_of_term,
+                cp.verification, cp.rejection_reason, cp.dispute_reason
+         FROM conducted_periods cp
+         JOIN subjects s ON s.id = cp.subject_id
+         JOIN teachers t ON t.id = cp.teacher_id
+         WHERE cp.school_id = $1 AND cp.term_id = $2 AND cp.was_conducted
+           AND ($3::period_verification IS NULL OR cp.verification = $3)
+           AND t.user_id IS DISTINCT FROM $4
+         ORDER BY cp.date, t.surname, t.first_name",
+    )
+    .bind(tenant.school_id)
+    .bind(term_id)
+    .bind(query.verification)
+    .bind(user.id)
+    .fetch_all(&state.pool)
+    .await?;
+    Ok(Json(
+        rows.into_iter()
+            .map(|row| PeriodForReview {
+                teacher_id: row.teacher_id,
+                teacher_name: row.teacher_name,
+                period: TeacherPeriod::from(row.period),
+            })
+            .collect(),
+    ))
+}
+
+/// Records the committee's decision on a period: verified periods count
+/// towards payouts, rejected ones go back to the teacher with the reason.
+/// Nobody reviews their own periods, and a period already paid can't change.
+pub async fn review(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReviewPeriodPayload>,
+) -> ApiResult<Json<TeacherPeriod>> {
+    if !user.role.can_review_periods() {
+        return Err(ApiError::forbidden("Only the committee can verify periods."));
+    }
+    let reason = payload.rejection_reason.as_deref().map(str::trim).filter(|reason| !reason.is_empty());
+    if !payload.verified && reason.is_none() {
+        return Err(ApiError::bad_request("Give a reason for the rejection, so the teacher knows what to fix."));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let period: Option<(bool, bool)> = sqlx::query_as(
+        "SELECT COALESCE(t.user_id = $3, FALSE),
+                EXISTS (SELECT 1 FROM teacher_payout_items i WHERE i.conducted_period_id = cp.id)
+         FROM conducted_periods cp JOIN teachers t ON t.id = cp.teacher_id
+         WHERE cp.id = $1 AND cp.school_id = $2
+         FOR UPDATE OF cp",
+    )
+    .bind(id)
+    .bind(tenant.school_id)
+    .bind(user.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    match period {
+        None => return Err(ApiError::not_found("That period doesn't exist.")),
+        Some((true, _)) => return Err(ApiError::forbidden("Someone else on the committee has to review your own periods.")),
+        Some((_, true)) => return Err(ApiError::bad_request("This period has been paid, so its verification can't change.")),
+        Some(_) => {}
+    }
+
+    let verification = if payload.verified { PeriodVerification::Verified } else { PeriodVerification::Rejected };
+    sqlx::query(
+        "UPDATE conducted_periods
+         SET verification = $2, rejection_reason = $3, reviewed_by = $4, reviewed_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(&verification)
+    .bind(reason.filter(|_| !payload.verified))
+    .bind(user.id)
+    .execute(&mut *tx)
+    .await?;
+    let Some(period) = portal::period(&mut *tx, tenant.school_id, id).await? else {
+        return Err(ApiError::not_found("That period doesn't exist."));
+    };
+    let description = match reason.filter(|_| !payload.verified) {
+        Some(reason) => format!("Rejected the {} period of {} ({})", period.subject, period.date, reason),
+        None => format!("Verified the {} period of {}", period.subject, period.date),
+    };
+    audit::record(&mut *tx, tenant.school_id, Some(user.id), ActivityType::PeriodReviewed, description).await?;
+    tx.commit().await?;
+    Ok(Json(period))
+}
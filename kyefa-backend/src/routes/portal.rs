@@ -0,0 +1,224 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use chrono::NaiveDateTime;
+use kyefa_models::{
+    ActivityType, ClassLevel, DisputePeriodPayload, Payslip, PeriodVerification, ProjectedShare, SubjectName,
+    TeacherPeriod, TeacherPortal, TermRow,
+};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::{audit, fees, shares, terms, AppState};
+
+/// Longest dispute reason accepted, enough for a paragraph to the committee
+const MAX_REASON_CHARS: usize = 1000;
+
+#[derive(sqlx::FromRow)]
+pub(super) struct PeriodRow {
+    id: Uuid,
+    date: NaiveDateTime,
+    class_level: ClassLevel,
+    subject: SubjectName,
+    week_of_term: i32,
+    verification: PeriodVerification,
+    rejection_reason: Option<String>,
+    dispute_reason: Option<String>,
+}
+
+impl From<PeriodRow> for TeacherPeriod {
+    fn from(row: PeriodRow) -> Self {
+        Self {
+            id: row.id,
+            date: row.date.date(),
+            class_level: row.class_level,
+            subject: row.subject.to_string(),
+            week_of_term: row.week_of_term.clamp(0, u8::MAX as i32) as u8,
+            verification: row.verification,
+            rejection_reason: row.rejection_reason,
+            dispute_reason: row.dispute_reason,
+        }
+    }
+}
+
+const PERIOD_QUERY: &str = "SELECT cp.id, cp.date, cp.class_level, s.name AS subject, cp.week_of_term,
+                                   cp.verification, cp.rejection_reason, cp.dispute_reason
+                            FROM conducted_periods cp JOIN subjects s ON s.id = cp.subject_id";
+
+/// One of the school's periods as the portal shows it
+pub(super) async fn period(
+    executor: impl PgExecutor<'_>,
+    school_id: Uuid,
+    id: Uuid,
+) -> Result<Option<TeacherPeriod>, sqlx::Error> {
+    let row: Option<PeriodRow> = sqlx::query_as(&format!("{} WHERE cp.id = $1 AND cp.school_id = $2", PERIOD_QUERY))
+        .bind(id)
+        .bind(school_id)
+        .fetch_optional(executor)
+        .await?;
+    Ok(row.map(TeacherPeriod::from))
+}
+
+/// The school's teacher record linked to `user_id`, with the teacher's name
+async fn linked_teacher(pool: &PgPool, school_id: Uuid, user_id: Uuid) -> ApiResult<(Uuid, String)> {
+    let teacher: Option<(Uuid, String)> =
+        sqlx::query_as("SELECT id, first_name || ' ' || surname FROM teachers WHERE school_id = $1 AND user_id = $2")
+            .bind(school_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    teacher.ok_or_else(|| ApiError::not_found("Your account isn't linked to a teacher at this school."))
+}
+
+/// The teacher's share of the term's expected fees, counting only the
+/// periods the committee verified. Nothing when they aren't participating.
+async fn projected_share(
+    pool: &PgPool,
+    school_id: Uuid,
+    term: &TermRow,
+    teacher_id: Uuid,
+) -> Result<Option<ProjectedShare>, sqlx::Error> {
+    let students = fees::load(pool, school_id, Some(term.id), None).await?;
+    let expected_revenue = fees::summarise(students.iter().map(|student| &student.position)).total_expected;
+    let rules = shares::rules(pool, school_id).await?;
+    let periods = shares::verified_periods(pool, school_id, term.id).await?;
+    let split = shares::split(expected_revenue, &rules, &periods);
+
+    Ok(split.teachers.into_iter().find(|teacher| teacher.teacher_id == teacher_id).map(|teacher| {
+        ProjectedShare {
+            constraint_name: rules.name.clone(),
+            base_share: teacher.base_share,
+            period_share: teacher.period_share,
+            verified_periods: teacher.periods,
+            max_periods_paid: rules.max_periods_paid,
+            capped: teacher.capped,
+        }
+    }))
+}
+
+#[derive(sqlx::FromRow)]
+struct PayslipRow {
+    payout_id: Uuid,
+    term_name: String,
+    calculated_on: NaiveDateTime,
+    periods_paid: i64,
+    base_share: f64,
+    period_share: f64,
+    capped: bool,
+}
+
+/// The requesting teacher's periods in the active term, projected share and
+/// payslips. Only users linked to a teacher at the school have a portal.
+pub async fn teacher(State(state): State<AppState>, user: CurrentUser, tenant: Tenant) -> ApiResult<Json<TeacherPortal>> {
+    let (teacher_id, teacher_name) = linked_teacher(&state.pool, tenant.school_id, user.id).await?;
+    let term = terms::active(&state.pool, tenant.school_id).await?;
+    let (periods, projected_share) = match &term {
+        Some(term) => {
+            let periods: Vec<PeriodRow> = sqlx::query_as(&format!(
+                "{} WHERE cp.teacher_id = $1 AND cp.term_id = $2 ORDER BY cp.date DESC",
+                PERIOD_QUERY
+            ))
+            .bind(teacher_id)
+            .bind(term.id)
+            .fetch_all(&state.pool)
+            .await?;
+            let share = projected_share(&state.pool, tenant.school_id, term, teacher_id).await?;
+            (periods.into_iter().map(TeacherPeriod::from).collect(), share)
+        }
+        None => (Vec::new(), None),
+    };
+
+    let payslips: Vec<PayslipRow> = sqlx::query_as(
+        "SELECT p.id AS payout_id, t.name AS term_name, d.calculated_on,
+                (SELECT COUNT(*) FROM teacher_payout_items i WHERE i.payout_id = p.id) AS periods_paid,
+                p.base_share, p.period_share, p.capped
+         FROM teacher_payouts p
+         JOIN disbursements d ON d.id = p.disbursement_id
+         JOIN terms t ON t.id = d.term_id
+         WHERE p.teacher_id = $1 AND d.school_id = $2
+         ORDER BY d.calculated_on DESC",
+    )
+    .bind(teacher_id)
+    .bind(tenant.school_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(TeacherPortal {
+        teacher_id,
+        teacher_name,
+        term_name: term.map(|term| term.name),
+        periods,
+        projected_share,
+        payslips: payslips
+            .into_iter()
+            .map(|row| Payslip {
+                payout_id: row.payout_id,
+                term_name: row.term_name,
+                calculated_on: row.calculated_on,
+                periods_paid: row.periods_paid as usize,
+                base_share: row.base_share,
+                period_share: row.period_share,
+                capped: row.capped,
+            })
+            .collect(),
+    }))
+}
+
+/// Asks the committee to look at a rejected period again. Only the period's
+/// own teacher can dispute it.
+pub async fn dispute(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<DisputePeriodPayload>,
+) -> ApiResult<Json<TeacherPeriod>> {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::bad_request("Say why the period should be looked at again."));
+    }
+    if reason.chars().count() > MAX_REASON_CHARS {
+        return Err(ApiError::bad_request(format!(
+            "Keep the reason under {} characters.",
+            MAX_REASON_CHARS
+        )));
+    }
+    let (teacher_id, _) = linked_teacher(&state.pool, tenant.school_id, user.id).await?;
+
+    let mut tx = state.pool.begin().await?;
+    // Locked so the committee can't review it between the check and the update
+    let verification: Option<PeriodVerification> = sqlx::query_scalar(
+        "SELECT verification FROM conducted_periods
+         WHERE id = $1 AND school_id = $2 AND teacher_id = $3 FOR UPDATE",
+    )
+    .bind(id)
+    .bind(tenant.school_id)
+    .bind(teacher_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    match verification {
+        None => return Err(ApiError::not_found("That period isn't one of yours.")),
+        Some(PeriodVerification::Rejected) => {}
+        Some(_) => return Err(ApiError::bad_request("Only a rejected period can be disputed.")),
+    }
+    sqlx::query("UPDATE conducted_periods SET verification = 'disputed', dispute_reason = $2 WHERE id = $1")
+        .bind(id)
+        .bind(reason)
+        .execute(&mut *tx)
+        .await?;
+    let Some(period) = period(&mut *tx, tenant.school_id, id).await? else {
+        return Err(ApiError::not_found("That period isn't one of yours."));
+    };
+    audit::record(
+        &mut *tx,
+        tenant.school_id,
+        Some(user.id),
+        ActivityType::PeriodDisputed,
+        format!("Disputed the rejected {} period of {}", period.subject, period.date),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(Json(period))
+}
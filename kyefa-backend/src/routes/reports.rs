@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use chrono::{Local, NaiveDate};
+use kyefa_models::{
+    DbPayment, Exemption, ExemptionReportRow, OverdueAgingRow, PaymentSummary, ProjectedIncomeReport, ReportFilter, ReportOverview, StudentPaymentHistory,
+    TeacherEarnings, TermComparison, TermRow, WeeklyCollection,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::fees::{self, StudentFees};
+use crate::routes::exemptions::EXEMPTION_COLUMNS;
+use crate::session::CurrentUser;
+use crate::shares::{self, RevenueSplit};
+use crate::tenant::Tenant;
+use crate::{terms, AppState};
+
+/// How many terms the term-over-term comparison goes back, the chosen one included
+const COMPARED_TERMS: i64 = 6;
+
+/// Reports show every teacher's earnings and every family's balance, so only
+/// the school's leadership reads them
+fn require_reader(user: &CurrentUser) -> ApiResult<()> {
+    match user.role.can_view_reports() {
+        true => Ok(()),
+        false => Err(ApiError::forbidden("Only an admin, headteacher or committee member can see reports.")),
+    }
+}
+
+/// What every report is computed over: the term containing `date_from` (or the
+/// active term) and each student's position in it
+struct ReportScope {
+    term: Option<TermRow>,
+    students: Vec<StudentFees>,
+}
+
+impl ReportScope {
+    async fn load(pool: &PgPool, school_id: Uuid, filter: &ReportFilter) -> ApiResult<Self> {
+        let term = terms::containing(pool, school_id, filter.date_from).await?;
+        let students = fees::load(pool, school_id, term.as_ref().map(|term| term.id), None).await?;
+        Ok(Self { term, students })
+    }
+
+    fn expected_revenue(&self) -> f64 {
+        self.students.iter().map(|student| student.position.due()).sum()
+    }
+
+    fn exempted(&self) -> f64 {
+        self.students.iter().map(|student| student.position.exempted).sum()
+    }
+
+    fn expected_students(&self) -> impl Iterator<Item = &StudentFees> {
+        self.students.iter().filter(|student| student.position.is_expected())
+    }
+}
+
+/// Payments made against `term_id` between the filter's dates
+async fn payments(pool: &PgPool, school_id: Uuid, term_id: Uuid, filter: &ReportFilter) -> ApiResult<Vec<DbPayment>> {
+    Ok(sqlx::query_as(
+        "SELECT id, school_id, student_id, term_id, amount_paid, date_paid, recorded_by
+         FROM payments
+         WHERE school_id = $1 AND term_id = $2
+           AND ($3::date IS NULL OR date_paid::date >= $3)
+           AND ($4::date IS NULL OR date_paid::date <= $4)
+         ORDER BY date_paid",
+    )
+    .bind(school_id)
+    .bind(term_id)
+    .bind(filter.date_from)
+    .bind(filter.date_to)
+    .fetch_all(pool)
+    .await?)
+}
+
+async fn collected(pool: &PgPool, school_id: Uuid, term: Option<&TermRow>, filter: &ReportFilter) -> ApiResult<f64> {
+    let Some(term) = term else {
+        return Ok(0.0);
+    };
+    Ok(payments(pool, school_id, term.id, filter).await?.iter().map(|payment| payment.amount_paid).sum())
+}
+
+/// Splits `revenue` over the term's teachers and lists each teacher's part,
+/// narrowed to `filter.teacher_id` when set
+async fn earnings(
+    pool: &PgPool,
+    school_id: Uuid,
+    term: Option<&TermRow>,
+    filter: &ReportFilter,
+    revenue: f64,
+) -> ApiResult<(RevenueSplit, Vec<TeacherEarnings>)> {
+    let rules = shares::rules(pool, school_id).await?;
+    let teachers = match term {
+        Some(term) => shares::teacher_periods(pool, school_id, term.id, filter.date_from, filter.date_to).await?,
+        None => Vec::new(),
+    };
+    let periods: Vec<(Uuid, usize)> = teachers.iter().map(|(id, _, count)| (*id, *count)).collect();
+    let names: HashMap<Uuid, &String> = teachers.iter().map(|(id, name, _)| (*id, name)).collect();
+
+    let split = shares::split(revenue, &rules, &periods);
+    let earnings = split
+        .teachers
+        .iter()
+        .filter(|teacher| filter.teacher_id.is_none_or(|id| id == teacher.teacher_id))
+        .map(|teacher| TeacherEarnings {
+            teacher_id: teacher.teacher_id.to_string(),
+            teacher_name: names.get(&teacher.teacher_id).map(|name| name.to_string()).unwrap_or_default(),
+            total_periods: teacher.periods,
+            total_earnings: teacher.total(),
+            share_percentage: split.percentage_of_pool(teacher),
+        })
+        .collect();
+    Ok((split, earnings))
+}
+
+pub async fn overview(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<ReportOverview>> {
+    require_reader(&user)?;
+    let school_name: String = sqlx::query_scalar("SELECT name FROM schools WHERE id = $1")
+        .bind(tenant.school_id)
+        .fetch_one(&state.pool)
+        .await?;
+    let scope = ReportScope::load(&state.pool, tenant.school_id, &filter).await?;
+    let expected_revenue = scope.expected_revenue();
+    let total_revenue = collected(&state.pool, tenant.school_id, scope.term.as_ref(), &filter).await?;
+
+    Ok(Json(ReportOverview {
+        school_name,
+        term_name: scope.term.as_ref().map(|term| term.name.clone()),
+        total_students: scope.expected_students().count(),
+        expected_revenue,
+        total_exempted: scope.exempted(),
+        total_revenue,
+        collection_rate: if expected_revenue > 0.0 { total_revenue / expected_revenue * 100.0 } else { 0.0 },
+    }))
+}
+
+pub async fn projected_income(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<ProjectedIncomeReport>> {
+    require_reader(&user)?;
+    let scope = ReportScope::load(&state.pool, tenant.school_id, &filter).await?;
+    let expected_revenue = scope.expected_revenue();
+    let rules = shares::rules(&state.pool, tenant.school_id).await?;
+    let (split, teacher_earnings) =
+        earnings(&state.pool, tenant.school_id, scope.term.as_ref(), &filter, expected_revenue).await?;
+
+    Ok(Json(ProjectedIncomeReport {
+        expected_revenue,
+        admin_percentage: rules.admin_percentage,
+        support_staff_percentage: rules.support_staff_percentage,
+        teacher_percentage: rules.teacher_percentage(),
+        admin_share: split.admin_share,
+        support_staff_share: split.support_staff_share,
+        teacher_share: split.teacher_share,
+        teacher_earnings,
+    }))
+}
+
+pub async fn collection_status(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<PaymentSummary>> {
+    require_reader(&user)?;
+    let scope = ReportScope::load(&state.pool, tenant.school_id, &filter).await?;
+    Ok(Json(fees::summarise(scope.students.iter().map(|student| &student.position))))
+}
+
+/// Each teacher's part of the revenue collected between the filter's dates
+pub async fn teacher_earnings(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<Vec<TeacherEarnings>>> {
+    require_reader(&user)?;
+    let term = terms::containing(&state.pool, tenant.school_id, filter.date_from).await?;
+    let revenue = collected(&state.pool, tenant.school_id, term.as_ref(), &filter).await?;
+    let (_, earnings) = earnings(&state.pool, tenant.school_id, term.as_ref(), &filter, revenue).await?;
+    Ok(Json(earnings))
+}
+
+pub async fn student_payments(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<Vec<StudentPaymentHistory>>> {
+    require_reader(&user)?;
+    let scope = ReportScope::load(&state.pool, tenant.school_id, &filter).await?;
+    let mut by_student: HashMap<Uuid, Vec<DbPayment>> = HashMap::new();
+    if let Some(term) = &scope.term {
+        for payment in payments(&state.pool, tenant.school_id, term.id, &filter).await? {
+            by_student.entry(payment.student_id).or_default().push(payment);
+        }
+    }
+
+    let histories = scope
+        .expected_students()
+        .map(|student| StudentPaymentHistory {
+            student_id: student.row.id,
+            student_name: student.name(),
+            class_level: student.class_in_term.clone(),
+            fee_amount: student.position.due(),
+            total_paid: student.position.paid,
+            balance: student.position.outstanding(),
+            status: student.position.status.clone(),
+            payments: by_student.remove(&student.row.id).unwrap_or_default(),
+        })
+        .collect();
+    Ok(Json(histories))
+}
+
+#[derive(sqlx::FromRow)]
+struct ExemptionRow {
+    #[sqlx(flatten)]
+    exemption: Exemption,
+    approved_by_name: String,
+}
+
+/// Exemptions granted for the term, largest first, with the fee each one reduced
+pub async fn exemptions(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<Vec<ExemptionReportRow>>> {
+    require_reader(&user)?;
+    let scope = ReportScope::load(&state.pool, tenant.school_id, &filter).await?;
+    let Some(term) = &scope.term else {
+        return Ok(Json(Vec::new()));
+    };
+    let granted: Vec<ExemptionRow> = sqlx::query_as(&format!(
+        "SELECT {}, (SELECT u.first_name || ' ' || u.surname FROM users u WHERE u.id = x.approved_by) AS approved_by_name
+         FROM exemptions x WHERE x.school_id = $1 AND x.term_id = $2
+         ORDER BY x.amount DESC, x.created_at",
+        EXEMPTION_COLUMNS
+    ))
+    .bind(tenant.school_id)
+    .bind(term.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let students: HashMap<Uuid, &StudentFees> = scope.students.iter().map(|student| (student.row.id, student)).collect();
+    Ok(Json(
+        granted
+            .into_iter()
+            .filter_map(|row| {
+                let student = students.get(&row.exemption.student_id)?;
+                Some(ExemptionReportRow {
+                    student_name: student.name(),
+                    class_level: student.class_in_term.clone(),
+                    term_name: term.name.clone(),
+                    approved_by_name: row.approved_by_name,
+                    fee_amount: student.enrolment.as_ref().map_or(0.0, |enrolment| enrolment.fee_amount),
+                    exemption: row.exemption,
+                })
+            })
+            .collect(),
+    ))
+}
+
+/// Students behind on their installment plan, longest overdue first. An
+/// installment counts once its due date has passed.
+pub async fn overdue_aging(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<Vec<OverdueAgingRow>>> {
+    require_reader(&user)?;
+    let scope = ReportScope::load(&state.pool, tenant.school_id, &filter).await?;
+    // Whom to call about each student: their primary contact, otherwise
+    // their first guardian by name
+    let contacts: HashMap<Uuid, (String, String)> = sqlx::query_as::<_, (Uuid, String, String)>(
+        "SELECT DISTINCT ON (gs.student_id) gs.student_id, g.first_name || ' ' || g.surname, g.phone_number
+         FROM guardian_students gs JOIN guardians g ON g.id = gs.guardian_id
+         WHERE g.school_id = $1
+         ORDER BY gs.student_id, g.is_primary_contact DESC, g.surname, g.first_name",
+    )
+    .bind(tenant.school_id)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .map(|(student_id, name, phone)| (student_id, (name, phone)))
+    .collect();
+    let today = Local::now().date_naive();
+    let mut rows: Vec<OverdueAgingRow> = scope
+        .expected_students()
+        .filter_map(|student| {
+            let plan = student.plan.as_ref()?;
+            let installment = plan.next_unpaid(student.position.paid).filter(|installment| installment.due_date < today)?;
+            let fallen_due = plan.amount_due_by(today.pred_opt()?);
+            let contact = contacts.get(&student.row.id);
+            Some(OverdueAgingRow {
+                student_id: student.row.id,
+                student_name: student.name(),
+                class_level: student.class_in_term.clone(),
+                amount_overdue: (fallen_due - student.position.paid).max(0.0),
+                days_overdue: (today - installment.due_date).num_days(),
+                oldest_due_date: installment.due_date,
+                contact_name: contact.map(|(name, _)| name.clone()),
+                contact_phone: contact.map(|(_, phone)| phone.clone()),
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| b.days_overdue.cmp(&a.days_overdue).then_with(|| a.student_name.cmp(&b.student_name)));
+    Ok(Json(rows))
+}
+
+/// Buckets payments into the weeks of a term starting on `start` and lasting
+/// `weeks` weeks. Payments before the start count in the first week and
+/// those after the end in the last. Expected revenue accrues evenly, so the
+/// expected line reaches the full amount in the final week.
+fn weekly_collections(start: NaiveDate, weeks: u8, expected: f64, payments: &[(NaiveDate, f64)]) -> Vec<WeeklyCollection> {
+    let weeks = weeks.max(1);
+    let mut collected = vec![0.0; weeks as usize];
+    for (date, amount) in payments {
+        let week = ((*date - start).num_days().max(0) / 7).min(weeks as i64 - 1);
+        collected[week as usize] += amount;
+    }
+
+    let mut cumulative_collected = 0.0;
+    collected
+        .into_iter()
+        .enumerate()
+        .map(|(index, collected)| {
+            cumulative_collected += collected;
+            let week_of_term = index as u8 + 1;
+            WeeklyCollection {
+                week_of_term,
+                collected,
+                cumulative_collected,
+                cumulative_expected: expected * week_of_term as f64 / weeks as f64,
+            }
+        })
+        .collect()
+}
+
+/// Weeks in the term, counting a part week at the end as a whole one
+fn weeks_in(term: &TermRow) -> u8 {
+    let days = (term.end_date.date() - term.start_date.date()).num_days() + 1;
+    (days.max(1) as u64).div_ceil(7).min(u8::MAX as u64) as u8
+}
+
+/// Cumulative collections against expected revenue, week by week through the term
+pub async fn collection_trend(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<Vec<WeeklyCollection>>> {
+    require_reader(&user)?;
+    let scope = ReportScope::load(&state.pool, tenant.school_id, &filter).await?;
+    let Some(term) = &scope.term else {
+        return Ok(Json(Vec::new()));
+    };
+    let paid: Vec<(NaiveDate, f64)> = payments(&state.pool, tenant.school_id, term.id, &filter)
+        .await?
+        .iter()
+        .map(|payment| (payment.date_paid.date(), payment.amount_paid))
+        .collect();
+    Ok(Json(weekly_collections(term.start_date.date(), weeks_in(term), scope.expected_revenue(), &paid)))
+}
+
+/// Headline figures for the chosen term and the ones before it, oldest first
+pub async fn term_comparison(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Query(filter): Query<ReportFilter>,
+) -> ApiResult<Json<Vec<TermComparison>>> {
+    require_reader(&user)?;
+    let Some(chosen) = terms::containing(&state.pool, tenant.school_id, filter.date_from).await? else {
+        return Ok(Json(Vec::new()));
+    };
+    let mut compared: Vec<TermRow> = sqlx::query_as(
+        "SELECT id, school_id, name, academic_year, start_date, end_date, is_active
+         FROM terms WHERE school_id = $1 AND start_date <= $2
+         ORDER BY start_date DESC LIMIT $3",
+    )
+    .bind(tenant.school_id)
+    .bind(chosen.start_date)
+    .bind(COMPARED_TERMS)
+    .fetch_all(&state.pool)
+    .await?;
+    compared.reverse();
+
+    let mut comparison = Vec::with_capacity(compared.len());
+    for term in compared {
+        let students = fees::load(&state.pool, tenant.school_id, Some(term.id), None).await?;
+        let total_revenue = collected(&state.pool, tenant.school_id, Some(&term), &ReportFilter::default()).await?;
+        let total_periods: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM conducted_periods WHERE school_id = $1 AND term_id = $2 AND was_conducted",
+        )
+        .bind(tenant.school_id)
+        .bind(term.id)
+        .fetch_one(&state.pool)
+        .await?;
+        comparison.push(TermComparison {
+            term_id: term.id,
+            term_name: term.name,
+            expected_revenue: students.iter().map(|student| student.position.due()).sum(),
+            total_revenue,
+            total_periods: total_periods as usize,
+        });
+    }
+    Ok(Json(comparison))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 9, d).unwrap()
+    }
+
+    #[test]
+    fn collections_accumulate_week_by_week() {
+        let weeks = weekly_collections(day(7), 3, 300.0, &[(day(8), 50.0), (day(15), 20.0), (day(16), 30.0)]);
+        assert_eq!(weeks.len(), 3);
+        assert_eq!(weeks[0].collected, 50.0);
+        assert_eq!(weeks[1].collected, 50.0);
+        assert_eq!(weeks[1].cumulative_collected, 100.0);
+        assert_eq!(weeks[2].cumulative_collected, 100.0);
+        assert_eq!(weeks[0].cumulative_expected, 100.0);
+        assert_eq!(weeks[2].cumulative_expected, 300.0);
+    }
+
+    #[test]
+    fn payments_outside_the_term_land_in_the_first_or_last_week() {
+        let weeks = weekly_collections(day(7), 2, 0.0, &[(day(1), 10.0), (day(30), 5.0)]);
+        assert_eq!(weeks[0].collected, 10.0);
+        assert_eq!(weeks[1].collected, 5.0);
+    }
+}
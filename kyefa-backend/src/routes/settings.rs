@@ -0,0 +1,60 @@
+use axum::extract::State;
+use axum::Json;
+use kyefa_models::{SchoolSettings, UpdateSchoolSettingsPayload};
+
+use crate::error::{ApiError, ApiResult};
+use crate::settings::{self, SettingsRow, SETTINGS_COLUMNS};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::AppState;
+
+pub async fn get(State(state): State<AppState>, tenant: Tenant) -> ApiResult<Json<SchoolSettings>> {
+    Ok(Json(settings::load(&state.pool, tenant.school_id).await?))
+}
+
+/// Replaces the school's settings after the same checks the desktop makes.
+/// Only an admin can change them.
+pub async fn update(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<UpdateSchoolSettingsPayload>,
+) -> ApiResult<Json<SchoolSettings>> {
+    if !user.is_admin() {
+        return Err(ApiError::forbidden("Only an admin can change the school's settings."));
+    }
+    let new = payload.settings;
+    new.validate().map_err(ApiError::bad_request)?;
+
+    let row: SettingsRow = sqlx::query_as(&format!(
+        "INSERT INTO school_settings (school_id, currency_symbol, date_format, default_payment_method,
+                                      admin_share_percentage, staff_share_percentage, teacher_share_percentage,
+                                      password_min_length, password_history_count, max_failed_attempts,
+                                      lockout_minutes, sibling_discount_percentage, updated_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+         ON CONFLICT (school_id) DO UPDATE SET
+             currency_symbol = $2, date_format = $3, default_payment_method = $4,
+             admin_share_percentage = $5, staff_share_percentage = $6, teacher_share_percentage = $7,
+             password_min_length = $8, password_history_count = $9, max_failed_attempts = $10,
+             lockout_minutes = $11, sibling_discount_percentage = $12, updated_by = $13, updated_at = NOW()
+         RETURNING {}",
+        SETTINGS_COLUMNS
+    ))
+    .bind(tenant.school_id)
+    .bind(new.currency_symbol.trim())
+    .bind(&new.date_format)
+    .bind(new.default_payment_method.trim())
+    .bind(new.admin_share_percentage)
+    .bind(new.staff_share_percentage)
+    .bind(new.teacher_share_percentage)
+    .bind(new.password_policy.min_length as i32)
+    .bind(new.password_policy.history_count as i32)
+    .bind(new.lockout_policy.max_failed_attempts as i32)
+    .bind(new.lockout_policy.lockout_minutes as i32)
+    .bind(new.sibling_discount_percentage)
+    .bind(user.id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(row.into()))
+}
@@ -0,0 +1,18 @@
+use axum::extract::State;
+use axum::Json;
+use kyefa_models::{Teacher, TeacherRow};
+
+use crate::error::ApiResult;
+use crate::tenant::Tenant;
+use crate::AppState;
+
+pub async fn teachers(State(state): State<AppState>, tenant: Tenant) -> ApiResult<Json<Vec<Teacher>>> {
+    let rows: Vec<TeacherRow> = sqlx::query_as(
+        "SELECT id, school_id, first_name, surname, other_names, status, is_active
+         FROM teachers WHERE school_id = $1 ORDER BY surname, first_name",
+    )
+    .bind(tenant.school_id)
+    .fetch_all(&state.pool)
+    .await?;
+    Ok(Json(rows.into_iter().map(Teacher::from).collect()))
+}
@@ -0,0 +1,408 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use kyefa_models::{
+    AcademicYear, ActivityType, ClassHistoryEntry, ClassLevel, CreateStudentPayload, ImportRowError, ImportSummary,
+    MergeStudentsPayload, MergeStudentsResult, PromotionOutcome, PromotionPayload, PromotionSummary, Student,
+    StudentImportPayload, UpdateStudentPayload,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::session::CurrentUser;
+use crate::tenant::Tenant;
+use crate::{audit, fees, terms, AppState};
+
+/// Every student, with their fee position for the active term
+pub async fn list(State(state): State<AppState>, tenant: Tenant) -> ApiResult<Json<Vec<Student>>> {
+    let term = terms::active(&state.pool, tenant.school_id).await?;
+    let students = fees::load(&state.pool, tenant.school_id, term.map(|term| term.id), None).await?;
+    Ok(Json(students.into_iter().map(fees::StudentFees::into_student).collect()))
+}
+
+/// The student as `list` would show them
+pub async fn fetch_one(pool: &PgPool, school_id: Uuid, id: Uuid) -> ApiResult<Student> {
+    let term = terms::active(pool, school_id).await?;
+    fees::load(pool, school_id, term.map(|term| term.id), Some(&[id]))
+        .await?
+        .pop()
+        .map(fees::StudentFees::into_student)
+        .ok_or_else(|| ApiError::not_found("That student doesn't exist."))
+}
+
+fn check_names(first_name: &str, surname: &str) -> ApiResult<()> {
+    if first_name.trim().is_empty() || surname.trim().is_empty() {
+        return Err(ApiError::bad_request("First name and surname are required."));
+    }
+    Ok(())
+}
+
+/// Creates the student under the client's id. A create replayed with an id
+/// that already exists returns that student instead of adding another.
+pub async fn create(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<CreateStudentPayload>,
+) -> ApiResult<Json<Student>> {
+    check_names(&payload.first_name, &payload.surname)?;
+    let mut tx = state.pool.begin().await?;
+    let inserted = sqlx::query(
+        "INSERT INTO students (id, school_id, first_name, surname, other_names, gender, class_level)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         ON CONFLICT (id) DO NOTHING",
+    )
+    .bind(payload.id)
+    .bind(tenant.school_id)
+    .bind(payload.first_name.trim())
+    .bind(payload.surname.trim())
+    .bind(payload.other_names.as_deref().map(str::trim).filter(|names| !names.is_empty()))
+    .bind(&payload.gender)
+    .bind(&payload.class_level)
+    .execute(&mut *tx)
+    .await?;
+    if inserted.rows_affected() == 1 {
+        audit::record(
+            &mut *tx,
+            tenant.school_id,
+            Some(user.id),
+            ActivityType::StudentAdded,
+            format!("{} {} added to {}", payload.first_name.trim(), payload.surname.trim(), payload.class_level),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+    fetch_one(&state.pool, tenant.school_id, payload.id).await.map(Json)
+}
+
+pub async fn update(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Json(payload): Json<UpdateStudentPayload>,
+) -> ApiResult<Json<Student>> {
+    check_names(&payload.first_name, &payload.surname)?;
+    let updated = sqlx::query(
+        "UPDATE students SET first_name = $3, surname = $4, other_names = $5, gender = $6, class_level = $7
+         WHERE id = $1 AND school_id = $2",
+    )
+    .bind(payload.id)
+    .bind(tenant.school_id)
+    .bind(payload.first_name.trim())
+    .bind(payload.surname.trim())
+    .bind(payload.other_names.as_deref().map(str::trim).filter(|names| !names.is_empty()))
+    .bind(&payload.gender)
+    .bind(&payload.class_level)
+    .execute(&state.pool)
+    .await?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::not_found("That student doesn't exist."));
+    }
+    fetch_one(&state.pool, tenant.school_id, payload.id).await.map(Json)
+}
+
+/// Creates a student for every row that passes `StudentImportRow::validate`
+/// and reports the rest back. The valid rows go in together or not at all.
+pub async fn import(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<StudentImportPayload>,
+) -> ApiResult<Json<ImportSummary>> {
+    let mut rejected = Vec::new();
+    let mut students = Vec::new();
+    for row in &payload.rows {
+        match row.validate() {
+            Ok(student) => students.push(student),
+            Err(errors) => rejected.push(ImportRowError { row_number: row.row_number, message: errors.join(" ") }),
+        }
+    }
+
+    let mut tx = state.pool.begin().await?;
+    for student in &students {
+        sqlx::query(
+            "INSERT INTO students (id, school_id, first_name, surname, other_names, gender, class_level)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(student.id)
+        .bind(tenant.school_id)
+        .bind(&student.first_name)
+        .bind(&student.surname)
+        .bind(&student.other_names)
+        .bind(&student.gender)
+        .bind(&student.class_level)
+        .execute(&mut *tx)
+        .await?;
+    }
+    if !students.is_empty() {
+        audit::record(
+            &mut *tx,
+            tenant.school_id,
+            Some(user.id),
+            ActivityType::StudentAdded,
+            match students.len() {
+                1 => "1 student imported".to_string(),
+                count => format!("{} students imported", count),
+            },
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(Json(ImportSummary { created: students.len(), rejected }))
+}
+
+/// Moves the duplicate's payments, enrolments, exemptions and installment
+/// plans onto the surviving student and keeps the duplicate as an inactive
+/// record pointing at the survivor
+pub async fn merge(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<MergeStudentsPayload>,
+) -> ApiResult<Json<MergeStudentsResult>> {
+    if !user.role.can_merge_students() {
+        return Err(ApiError::forbidden("Only an admin, headteacher or data entry clerk can merge students."));
+    }
+    if payload.surviving_id == payload.duplicate_id {
+        return Err(ApiError::bad_request("Choose two different students to merge."));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let names: Vec<(Uuid, String)> = sqlx::query_as(
+        "SELECT id, first_name || ' ' || surname FROM students
+         WHERE school_id = $1 AND id = ANY($2) AND is_active AND merged_into IS NULL
+         FOR UPDATE",
+    )
+    .bind(tenant.school_id)
+    .bind([payload.surviving_id, payload.duplicate_id])
+    .fetch_all(&mut *tx)
+    .await?;
+    let name_of = |id: Uuid| names.iter().find(|(found, _)| *found == id).map(|(_, name)| name.clone());
+    let (Some(surviving_name), Some(duplicate_name)) = (name_of(payload.surviving_id), name_of(payload.duplicate_id)) else {
+        return Err(ApiError::not_found("One of those students doesn't exist or has already been merged."));
+    };
+
+    let payments_moved = sqlx::query("UPDATE payments SET student_id = $2 WHERE student_id = $1")
+        .bind(payload.duplicate_id)
+        .bind(payload.surviving_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as usize;
+    // Terms the survivor wasn't enrolled in follow the payments across
+    sqlx::query(
+        "UPDATE enrolments SET student_id = $2
+         WHERE student_id = $1
+           AND term_id NOT IN (SELECT term_id FROM enrolments WHERE student_id = $2)",
+    )
+    .bind(payload.duplicate_id)
+    .bind(payload.surviving_id)
+    .execute(&mut *tx)
+    .await?;
+    // As do exemptions and installment plans, except for terms where the
+    // survivor already has one
+    let exemptions_moved = sqlx::query(
+        "UPDATE exemptions SET student_id = $2
+         WHERE student_id = $1
+           AND term_id NOT IN (SELECT term_id FROM exemptions WHERE student_id = $2)",
+    )
+    .bind(payload.duplicate_id)
+    .bind(payload.surviving_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected() as usize;
+    sqlx::query(
+        "UPDATE installment_plans SET student_id = $2
+         WHERE student_id = $1
+           AND term_id NOT IN (SELECT term_id FROM installment_plans WHERE student_id = $2)",
+    )
+    .bind(payload.duplicate_id)
+    .bind(payload.surviving_id)
+    .execute(&mut *tx)
+    .await?;
+    // The survivor keeps the duplicate's guardians
+    sqlx::query(
+        "INSERT INTO guardian_students (guardian_id, student_id)
+         SELECT guardian_id, $2 FROM guardian_students WHERE student_id = $1
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(payload.duplicate_id)
+    .bind(payload.surviving_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM guardian_students WHERE student_id = $1")
+        .bind(payload.duplicate_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE students SET is_active = FALSE, merged_into = $2 WHERE id = $1")
+        .bind(payload.duplicate_id)
+        .bind(payload.surviving_id)
+        .execute(&mut *tx)
+        .await?;
+    audit::record(
+        &mut *tx,
+        tenant.school_id,
+        Some(user.id),
+        ActivityType::StudentsMerged,
+        format!("{} merged into {}", duplicate_name, surviving_name),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(MergeStudentsResult {
+        surviving: fetch_one(&state.pool, tenant.school_id, payload.surviving_id).await?,
+        duplicate: fetch_one(&state.pool, tenant.school_id, payload.duplicate_id).await?,
+        payments_moved,
+        exemptions_moved,
+    }))
+}
+
+/// Records the class a student was in for `academic_year`, replacing what
+/// was there
+async fn write_history(
+    tx: &mut sqlx::PgConnection,
+    school_id: Uuid,
+    student_id: Uuid,
+    academic_year: AcademicYear,
+    class_level: &ClassLevel,
+    outcome: Option<&PromotionOutcome>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO class_history (student_id, school_id, academic_year, class_level, outcome)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (student_id, academic_year) DO UPDATE SET class_level = $4, outcome = $5",
+    )
+    .bind(student_id)
+    .bind(school_id)
+    .bind(academic_year)
+    .bind(class_level)
+    .bind(outcome)
+    .execute(tx)
+    .await?;
+    Ok(())
+}
+
+/// Closes `academic_year` in each student's class history with the decided
+/// outcome and opens the next year at their new class. Graduates and leavers
+/// are deactivated. Every decision applies or none do, and a year closes once.
+pub async fn promote(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<PromotionPayload>,
+) -> ApiResult<Json<PromotionSummary>> {
+    if !user.role.can_promote_students() {
+        return Err(ApiError::forbidden("Only an admin or headteacher can close the academic year."));
+    }
+    if payload.next_academic_year != payload.academic_year.next() {
+        return Err(ApiError::bad_request(format!(
+            "{} must be followed by {}.",
+            payload.academic_year,
+            payload.academic_year.next()
+        )));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let mut summary = PromotionSummary { promoted: 0, repeated: 0, graduated: 0, left: 0 };
+    for decision in &payload.decisions {
+        let current: Option<(String, ClassLevel)> = sqlx::query_as(
+            "SELECT first_name || ' ' || surname, class_level FROM students
+             WHERE id = $1 AND school_id = $2 AND is_active AND merged_into IS NULL
+             FOR UPDATE",
+        )
+        .bind(decision.student_id)
+        .bind(tenant.school_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((name, class_level)) = current else {
+            return Err(ApiError::not_found("A student in the promotion no longer exists or is inactive."));
+        };
+        if class_level != decision.current_class_level {
+            return Err(ApiError::bad_request(format!(
+                "{} has moved to {} since the promotion was prepared. Reopen the wizard.",
+                name, class_level
+            )));
+        }
+        // Closing again would overwrite the year's class with the one the
+        // student was promoted to
+        let closed: Option<PromotionOutcome> = sqlx::query_scalar(
+            "SELECT outcome FROM class_history WHERE student_id = $1 AND academic_year = $2 AND outcome IS NOT NULL",
+        )
+        .bind(decision.student_id)
+        .bind(payload.academic_year)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if closed.is_some() {
+            return Err(ApiError::bad_request(format!(
+                "{} has already been closed for {}.",
+                payload.academic_year, name
+            )));
+        }
+
+        write_history(&mut tx, tenant.school_id, decision.student_id, payload.academic_year, &class_level, Some(&decision.outcome))
+            .await?;
+        match decision.outcome {
+            PromotionOutcome::Promoted => {
+                let Some(next_class_level) = decision.next_class_level.clone() else {
+                    return Err(ApiError::bad_request(format!("Choose the class {} moves up to.", name)));
+                };
+                sqlx::query("UPDATE students SET class_level = $2 WHERE id = $1")
+                    .bind(decision.student_id)
+                    .bind(&next_class_level)
+                    .execute(&mut *tx)
+                    .await?;
+                write_history(&mut tx, tenant.school_id, decision.student_id, payload.next_academic_year, &next_class_level, None)
+                    .await?;
+                summary.promoted += 1;
+            }
+            PromotionOutcome::Repeated => {
+                write_history(&mut tx, tenant.school_id, decision.student_id, payload.next_academic_year, &class_level, None)
+                    .await?;
+                summary.repeated += 1;
+            }
+            PromotionOutcome::Graduated | PromotionOutcome::Left => {
+                sqlx::query("UPDATE students SET is_active = FALSE WHERE id = $1")
+                    .bind(decision.student_id)
+                    .execute(&mut *tx)
+                    .await?;
+                if decision.outcome == PromotionOutcome::Graduated {
+                    summary.graduated += 1;
+                } else {
+                    summary.left += 1;
+                }
+            }
+        }
+    }
+    audit::record(
+        &mut *tx,
+        tenant.school_id,
+        Some(user.id),
+        ActivityType::StudentsPromoted,
+        format!(
+            "{} closed: {} promoted, {} repeating, {} graduated, {} left",
+            payload.academic_year, summary.promoted, summary.repeated, summary.graduated, summary.left
+        ),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(summary))
+}
+
+/// The student's class in each academic year on record, oldest first
+pub async fn class_history(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Vec<ClassHistoryEntry>>> {
+    Ok(Json(
+        sqlx::query_as(
+            "SELECT student_id, academic_year, class_level, outcome FROM class_history
+             WHERE student_id = $1 AND school_id = $2
+             ORDER BY academic_year",
+        )
+        .bind(id)
+        .bind(tenant.school_id)
+        .fetch_all(&state.pool)
+        .await?,
+    ))
+}
@@ -0,0 +1,483 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use kyefa_models::{
+    ActivityType, ChangePasswordPayload, CreateUserPayload, PasswordPolicy, ResetPasswordPayload, School,
+    UpdateUserPayload, UserResponse, UserRole,
+};
+use std::collections::HashMap;
+
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+use crate::session::{self, CurrentUser};
+use crate::tenant::Tenant;
+use crate::{audit, passwords, settings, AppState};
+use super::auth;
+
+const USER_COLUMNS: &str = "id, username, role, is_active, first_name, surname, other_names, must_change_password";
+
+#[derive(sqlx::FromRow)]
+struct UserRow {
+    id: Uuid,
+    username: String,
+    role: UserRole,
+    is_active: bool,
+    first_name: String,
+    surname: String,
+    other_names: Option<String>,
+    must_change_password: bool,
+}
+
+impl UserRow {
+    fn into_response(self, schools: Vec<School>) -> UserResponse {
+        UserResponse {
+            id: self.id.to_string(),
+            username: self.username,
+            role: self.role,
+            is_active: self.is_active,
+            first_name: self.first_name,
+            surname: self.surname,
+            other_names: self.other_names,
+            must_change_password: self.must_change_password,
+            locked_until: None,
+            schools,
+        }
+    }
+}
+
+/// The schools each of `user_ids` belongs to, inactive ones included so an
+/// edit doesn't drop them
+async fn schools_of(executor: impl PgExecutor<'_>, user_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<School>>, sqlx::Error> {
+    let rows: Vec<(Uuid, Uuid, String, bool)> = sqlx::query_as(
+        "SELECT us.user_id, s.id, s.name, s.is_active
+         FROM user_schools us JOIN schools s ON s.id = us.school_id
+         WHERE us.user_id = ANY($1)
+         ORDER BY s.name",
+    )
+    .bind(user_ids)
+    .fetch_all(executor)
+    .await?;
+    let mut schools: HashMap<Uuid, Vec<School>> = HashMap::new();
+    for (user_id, id, name, is_active) in rows {
+        schools.entry(user_id).or_default().push(School { id, name, is_active });
+    }
+    Ok(schools)
+}
+
+async fn respond(pool: &PgPool, row: UserRow) -> ApiResult<Json<UserResponse>> {
+    let schools = schools_of(pool, &[row.id]).await?.remove(&row.id).unwrap_or_default();
+    Ok(Json(row.into_response(schools)))
+}
+
+/// Accounts an admin at the school can manage: those working there, and
+/// those left with no school at all so they can be given one
+const MANAGED_BY_SCHOOL: &str = "(EXISTS (SELECT 1 FROM user_schools us WHERE us.user_id = users.id AND us.school_id = $1)
+                                 OR NOT EXISTS (SELECT 1 FROM user_schools us WHERE us.user_id = users.id))";
+
+async fn require_managed(conn: &mut PgConnection, school_id: Uuid, id: Uuid) -> ApiResult<()> {
+    let is_managed: bool =
+        sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM users WHERE id = $2 AND {})", MANAGED_BY_SCHOOL))
+            .bind(school_id)
+            .bind(id)
+            .fetch_one(conn)
+            .await?;
+    match is_managed {
+        true => Ok(()),
+        false => Err(ApiError::not_found("That user doesn't exist.")),
+    }
+}
+
+/// Gives `user_id` the schools in `requested` that `admin_id` works in, and
+/// takes away the admin's other schools. Schools the admin isn't part of are
+/// neither granted nor taken away. The account has to end up with a school.
+async fn set_schools(conn: &mut PgConnection, admin_id: Uuid, user_id: Uuid, requested: &[Uuid]) -> ApiResult<()> {
+    let admin_schools: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT us.school_id FROM user_schools us JOIN schools s ON s.id = us.school_id
+         WHERE us.user_id = $1 AND s.is_active",
+    )
+    .bind(admin_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    let current: Vec<Uuid> = sqlx::query_scalar("SELECT school_id FROM user_schools WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_all(&mut *conn)
+        .await?;
+    if requested.iter().any(|id| !admin_schools.contains(id) && !current.contains(id)) {
+        return Err(ApiError::bad_request("You can only give access to schools you work in."));
+    }
+
+    sqlx::query("DELETE FROM user_schools WHERE user_id = $1 AND school_id = ANY($2) AND NOT school_id = ANY($3)")
+        .bind(user_id)
+        .bind(&admin_schools)
+        .bind(requested)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        "INSERT INTO user_schools (user_id, school_id)
+         SELECT $1, school_id FROM UNNEST($2::uuid[]) AS school_id WHERE school_id = ANY($3)
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(requested)
+    .bind(&admin_schools)
+    .execute(&mut *conn)
+    .await?;
+
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_schools WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    if remaining == 0 {
+        return Err(ApiError::bad_request("Choose at least one school for the account."));
+    }
+    Ok(())
+}
+
+fn require_admin(user: &CurrentUser) -> ApiResult<()> {
+    match user.is_admin() {
+        true => Ok(()),
+        false => Err(ApiError::forbidden("Only an admin can manage user accounts.")),
+    }
+}
+
+fn check(username: &str, first_name: &str, surname: &str) -> ApiResult<()> {
+    if username.trim().is_empty() || username.trim().contains(char::is_whitespace) {
+        return Err(ApiError::bad_request("Usernames can't be empty or contain spaces."));
+    }
+    if first_name.trim().is_empty() || surname.trim().is_empty() {
+        return Err(ApiError::bad_request("First name and surname are required."));
+    }
+    Ok(())
+}
+
+/// A temporary password must still meet the school's policy, so the user
+/// isn't handed one they couldn't have chosen
+async fn check_password(pool: &PgPool, school_id: Uuid, password: &str) -> ApiResult<String> {
+    let policy = settings::load(pool, school_id).await?.password_policy;
+    policy.check(password).map_err(ApiError::bad_request)?;
+    hash_password(password)
+}
+
+fn hash_password(password: &str) -> ApiResult<String> {
+    passwords::hash(password).map_err(|e| {
+        tracing::error!("Couldn't hash a password: {}", e);
+        ApiError::internal("The server couldn't set the password. Try again shortly.")
+    })
+}
+
+fn username_taken(e: sqlx::Error) -> ApiError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            ApiError::bad_request("That username is already taken. Choose another.")
+        }
+        _ => e.into(),
+    }
+}
+
+/// Every account the school's admins manage, deactivated ones included
+pub async fn list(State(state): State<AppState>, user: CurrentUser, tenant: Tenant) -> ApiResult<Json<Vec<UserResponse>>> {
+    require_admin(&user)?;
+    let rows: Vec<UserRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM users WHERE {} ORDER BY is_active DESC, surname, first_name",
+        USER_COLUMNS, MANAGED_BY_SCHOOL
+    ))
+    .bind(tenant.school_id)
+    .fetch_all(&state.pool)
+    .await?;
+    let ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+    let mut schools = schools_of(&state.pool, &ids).await?;
+    Ok(Json(
+        rows.into_iter()
+            .map(|row| {
+                let user_schools = schools.remove(&row.id).unwrap_or_default();
+                row.into_response(user_schools)
+            })
+            .collect(),
+    ))
+}
+
+/// Creates an account with a temporary password the user replaces when they
+/// first log in
+pub async fn create(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<CreateUserPayload>,
+) -> ApiResult<Json<UserResponse>> {
+    require_admin(&user)?;
+    check(&payload.username, &payload.first_name, &payload.surname)?;
+    let password_hash = check_password(&state.pool, tenant.school_id, &payload.password).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let row: UserRow = sqlx::query_as(&format!(
+        "INSERT INTO users (id, username, password_hash, role, first_name, surname, other_names, must_change_password)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, TRUE)
+         RETURNING {}",
+        USER_COLUMNS
+    ))
+    .bind(Uuid::new_v4())
+    .bind(payload.username.trim())
+    .bind(password_hash)
+    .bind(&payload.role)
+    .bind(payload.first_name.trim())
+    .bind(payload.surname.trim())
+    .bind(payload.other_names.as_deref().map(str::trim).filter(|names| !names.is_empty()))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(username_taken)?;
+    set_schools(&mut tx, user.id, row.id, &payload.school_ids).await?;
+    audit::record(
+        &mut *tx,
+        tenant.school_id,
+        Some(user.id),
+        ActivityType::UserCreated,
+        format!("{} {} given a {} account", row.first_name, row.surname, row.role),
+    )
+    .await?;
+    tx.commit().await?;
+
+    respond(&state.pool, row).await
+}
+
+/// Edits an account, including deactivating it, which also logs the user out.
+/// Every school keeps at least one active admin, so nobody can lock everyone
+/// out of its user management.
+pub async fn update(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateUserPayload>,
+) -> ApiResult<Json<UserResponse>> {
+    require_admin(&user)?;
+    check(&payload.username, &payload.first_name, &payload.surname)?;
+    if id == user.id && !payload.is_active {
+        return Err(ApiError::bad_request("You can't deactivate your own account."));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    // Holding the admins' rows makes two admins demoting each other at once
+    // wait for one another rather than both succeeding
+    sqlx::query("SELECT id FROM users WHERE role = 'admin' AND is_active FOR UPDATE")
+        .execute(&mut *tx)
+        .await?;
+    require_managed(&mut tx, tenant.school_id, id).await?;
+    // Schools the user was an active admin of, which can't be left without one
+    let admin_of: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT us.school_id FROM user_schools us JOIN users u ON u.id = us.user_id
+         WHERE us.user_id = $1 AND u.role = 'admin' AND u.is_active",
+    )
+    .bind(id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let row: Option<UserRow> = sqlx::query_as(&format!(
+        "UPDATE users SET username = $2, role = $3, first_name = $4, surname = $5, other_names = $6, is_active = $7
+         WHERE id = $1
+         RETURNING {}",
+        USER_COLUMNS
+    ))
+    .bind(id)
+    .bind(payload.username.trim())
+    .bind(&payload.role)
+    .bind(payload.first_name.trim())
+    .bind(payload.surname.trim())
+    .bind(payload.other_names.as_deref().map(str::trim).filter(|names| !names.is_empty()))
+    .bind(payload.is_active)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(username_taken)?;
+    let Some(row) = row else {
+        return Err(ApiError::not_found("That user doesn't exist."));
+    };
+
+    set_schools(&mut tx, user.id, id, &payload.school_ids).await?;
+
+    let without_admin: Option<String> = sqlx::query_scalar(
+        "SELECT s.name FROM schools s
+         WHERE s.is_active AND s.id = ANY($1)
+           AND NOT EXISTS (SELECT 1 FROM user_schools us JOIN users u ON u.id = us.user_id
+                           WHERE us.school_id = s.id AND u.role = 'admin' AND u.is_active)
+         ORDER BY s.name LIMIT 1",
+    )
+    .bind(&admin_of)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if let Some(school) = without_admin {
+        return Err(ApiError::bad_request(format!(
+            "That would leave {} with no active admin. Make someone else an admin there first.",
+            school
+        )));
+    }
+    if !row.is_active {
+        session::end_all(&mut *tx, id).await?;
+    }
+    tx.commit().await?;
+
+    respond(&state.pool, row).await
+}
+
+/// Gives the user a temporary password they have to replace at their next
+/// login. It also lifts a lockout and logs them out everywhere.
+pub async fn reset_password(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ResetPasswordPayload>,
+) -> ApiResult<Json<UserResponse>> {
+    require_admin(&user)?;
+    let password_hash = check_password(&state.pool, tenant.school_id, &payload.temporary_password).await?;
+
+    let mut tx = state.pool.begin().await?;
+    require_managed(&mut tx, tenant.school_id, id).await?;
+    let row: Option<UserRow> = sqlx::query_as(&format!(
+        "UPDATE users SET password_hash = $2, must_change_password = TRUE, failed_login_count = 0, locked_until = NULL
+         WHERE id = $1
+         RETURNING {}",
+        USER_COLUMNS
+    ))
+    .bind(id)
+    .bind(password_hash)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(row) = row else {
+        return Err(ApiError::not_found("That user doesn't exist."));
+    };
+    session::end_all(&mut *tx, id).await?;
+    tx.commit().await?;
+
+    respond(&state.pool, row).await
+}
+
+#[derive(sqlx::FromRow)]
+struct PasswordRow {
+    username: String,
+    password_hash: String,
+    first_name: String,
+    surname: String,
+    locked_minutes: i32,
+}
+
+/// Replaces the user's own password once they've given the current one. The
+/// new one must meet the school's policy and can't be any of their last
+/// `history_count` passwords, the current one included. Both outcomes go in
+/// the audit log, and a change ends all the user's sessions. A wrong current
+/// password counts towards the lockout as it would at the login screen, and
+/// is answered with 403 so it can't be mistaken for an expired session.
+pub async fn change_password(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    tenant: Tenant,
+    Json(payload): Json<ChangePasswordPayload>,
+) -> ApiResult<StatusCode> {
+    let id = user.id;
+    let policy = settings::load(&state.pool, tenant.school_id).await?.password_policy;
+
+    let mut tx = state.pool.begin().await?;
+    let account: Option<PasswordRow> =
+        sqlx::query_as(
+            "SELECT username, password_hash, first_name, surname,
+                    COALESCE(CEIL(EXTRACT(EPOCH FROM locked_until - NOW()) / 60), 0)::INT AS locked_minutes
+             FROM users WHERE id = $1 AND is_active FOR UPDATE",
+        )
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    let Some(account) = account else {
+        return Err(ApiError::not_found("That user doesn't exist."));
+    };
+    if account.locked_minutes > 0 {
+        return Err(ApiError::new(
+            StatusCode::LOCKED,
+            format!(
+                "Too many wrong passwords. Try again in {} minute{}.",
+                account.locked_minutes,
+                if account.locked_minutes == 1 { "" } else { "s" }
+            ),
+        ));
+    }
+    if !passwords::verify(&payload.current_password, &account.password_hash) {
+        drop(tx);
+        auth::count_failure(&state.pool, id, &account.username).await?;
+        audit::record(
+            &state.pool,
+            tenant.school_id,
+            Some(id),
+            ActivityType::PasswordChangeFailed,
+            format!("{} {} gave the wrong current password when changing it", account.first_name, account.surname),
+        )
+        .await?;
+        return Err(ApiError::forbidden("The current password is incorrect."));
+    }
+    policy.check(&payload.new_password).map_err(ApiError::bad_request)?;
+
+    if policy.history_count > 0 {
+        let previous: Vec<String> = sqlx::query_scalar(
+            "SELECT password_hash FROM password_history WHERE user_id = $1 ORDER BY replaced_at DESC LIMIT $2",
+        )
+        .bind(id)
+        .bind(policy.history_count as i64 - 1)
+        .fetch_all(&mut *tx)
+        .await?;
+        let recent = std::iter::once(account.password_hash.as_str()).chain(previous.iter().map(String::as_str));
+        if passwords::matches_any(&payload.new_password, recent) {
+            return Err(ApiError::bad_request(match policy.history_count {
+                1 => "Choose a password different from your current one.".to_string(),
+                count => format!("Choose a password you haven't used in your last {} passwords.", count),
+            }));
+        }
+    }
+
+    let new_hash = hash_password(&payload.new_password)?;
+    sqlx::query("INSERT INTO password_history (id, user_id, password_hash) VALUES ($1, $2, $3)")
+        .bind(Uuid::new_v4())
+        .bind(id)
+        .bind(&account.password_hash)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "DELETE FROM password_history WHERE user_id = $1 AND id NOT IN (
+             SELECT id FROM password_history WHERE user_id = $1 ORDER BY replaced_at DESC LIMIT $2)",
+    )
+    .bind(id)
+    .bind(PasswordPolicy::MAX_HISTORY_COUNT as i64)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE users SET password_hash = $2, must_change_password = FALSE WHERE id = $1")
+        .bind(id)
+        .bind(new_hash)
+        .execute(&mut *tx)
+        .await?;
+    audit::record(
+        &mut *tx,
+        tenant.school_id,
+        Some(id),
+        ActivityType::PasswordChanged,
+        format!("{} {} changed their password", account.first_name, account.surname),
+    )
+    .await?;
+    session::end_all(&mut *tx, id).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usernames_are_one_word() {
+        assert!(check("ama.mensah", "Ama", "Mensah").is_ok());
+        assert!(check("  ", "Ama", "Mensah").is_err());
+        assert!(check("ama mensah", "Ama", "Mensah").is_err());
+    }
+
+    #[test]
+    fn names_are_required() {
+        assert!(check("ama", " ", "Mensah").is_err());
+        assert!(check("ama", "Ama", "").is_err());
+    }
+}
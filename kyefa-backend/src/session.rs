@@ -0,0 +1,145 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use kyefa_models::UserRole;
+use sha2::{Digest, Sha256};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::AppState;
+
+/// How long a login lasts. The desktop logs idle users out long before this.
+const SESSION_HOURS: i32 = 12;
+
+/// Paths a user can still use while they have a temporary password to replace
+const ALLOWED_BEFORE_PASSWORD_CHANGE: &[&str] = &["/account/change-password", "/account/unlock", "/logout"];
+
+/// The user a request is made by, from the session token in its
+/// `Authorization: Bearer` header. Handlers take the acting user from here,
+/// never from the request body.
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    pub id: Uuid,
+    pub role: UserRole,
+    /// Identifies the session the request came with, so logging out ends only it
+    token_hash: Vec<u8>,
+}
+
+impl CurrentUser {
+    pub fn is_admin(&self) -> bool {
+        self.role == UserRole::Admin
+    }
+}
+
+impl FromRequestParts<AppState> for CurrentUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        // `Tenant` looks the user up too, so a handler taking both reads the session once
+        if let Some(user) = parts.extensions.get::<CurrentUser>() {
+            return Ok(user.clone());
+        }
+        let ended = || ApiError::unauthorized("Your session has ended. Log in again.");
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(ended)?;
+        let token_hash = token_hash(token);
+
+        let user: Option<(Uuid, UserRole, bool)> = sqlx::query_as(
+            "SELECT u.id, u.role, u.must_change_password
+             FROM sessions s JOIN users u ON u.id = s.user_id
+             WHERE s.token_hash = $1 AND s.expires_at > NOW() AND u.is_active",
+        )
+        .bind(&token_hash)
+        .fetch_optional(&state.pool)
+        .await?;
+        let Some((id, role, must_change_password)) = user else {
+            return Err(ended());
+        };
+        if must_change_password && !ALLOWED_BEFORE_PASSWORD_CHANGE.contains(&parts.uri.path()) {
+            return Err(ApiError::forbidden("Choose a new password before continuing."));
+        }
+
+        let user = CurrentUser { id, role, token_hash };
+        parts.extensions.insert(user.clone());
+        Ok(user)
+    }
+}
+
+/// What the sessions table keeps of a token
+fn token_hash(token: &str) -> Vec<u8> {
+    Sha256::digest(token.as_bytes()).to_vec()
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Starts a session for `user_id`, returning the token the desktop sends back
+pub async fn start(executor: impl PgExecutor<'_>, user_id: Uuid) -> Result<String, sqlx::Error> {
+    let token = new_token();
+    sqlx::query(
+        "INSERT INTO sessions (token_hash, user_id, expires_at)
+         VALUES ($1, $2, NOW() + make_interval(hours => $3))",
+    )
+    .bind(token_hash(&token))
+    .bind(user_id)
+    .bind(SESSION_HOURS)
+    .execute(executor)
+    .await?;
+    Ok(token)
+}
+
+/// Ends the session `user` made the request with
+pub async fn end(executor: impl PgExecutor<'_>, user: &CurrentUser) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM sessions WHERE token_hash = $1")
+        .bind(&user.token_hash)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Ends every session `user_id` has, e.g. after their password changes
+pub async fn end_all(executor: impl PgExecutor<'_>, user_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM sessions WHERE user_id = $1")
+        .bind(user_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Drops sessions that have run out
+pub async fn prune(executor: impl PgExecutor<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM sessions WHERE expires_at <= NOW()")
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_long_and_unique() {
+        let first = new_token();
+        assert_eq!(first.len(), 64);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, new_token());
+    }
+
+    #[test]
+    fn only_the_hash_of_a_token_is_kept() {
+        let token = new_token();
+        assert_eq!(token_hash(&token), token_hash(&token));
+        assert_ne!(token_hash(&token), token.as_bytes());
+        assert_ne!(token_hash(&token), token_hash(&new_token()));
+    }
+}
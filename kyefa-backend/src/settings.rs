@@ -0,0 +1,54 @@
+use kyefa_models::{LockoutPolicy, PasswordPolicy, SchoolSettings};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+pub const SETTINGS_COLUMNS: &str = "currency_symbol, date_format, default_payment_method, admin_share_percentage,
+     staff_share_percentage, teacher_share_percentage, password_min_length, password_history_count,
+     max_failed_attempts, lockout_minutes, sibling_discount_percentage";
+
+#[derive(sqlx::FromRow)]
+pub struct SettingsRow {
+    currency_symbol: String,
+    date_format: String,
+    default_payment_method: String,
+    admin_share_percentage: f64,
+    staff_share_percentage: f64,
+    teacher_share_percentage: f64,
+    password_min_length: i32,
+    password_history_count: i32,
+    max_failed_attempts: i32,
+    lockout_minutes: i32,
+    sibling_discount_percentage: f64,
+}
+
+impl From<SettingsRow> for SchoolSettings {
+    fn from(row: SettingsRow) -> Self {
+        SchoolSettings {
+            currency_symbol: row.currency_symbol,
+            date_format: row.date_format,
+            default_payment_method: row.default_payment_method,
+            admin_share_percentage: row.admin_share_percentage,
+            staff_share_percentage: row.staff_share_percentage,
+            teacher_share_percentage: row.teacher_share_percentage,
+            password_policy: PasswordPolicy {
+                min_length: row.password_min_length as usize,
+                history_count: row.password_history_count as usize,
+            },
+            lockout_policy: LockoutPolicy {
+                max_failed_attempts: row.max_failed_attempts as u32,
+                lockout_minutes: row.lockout_minutes as u32,
+            },
+            sibling_discount_percentage: row.sibling_discount_percentage,
+        }
+    }
+}
+
+/// The school's settings, or the defaults until an admin has saved some
+pub async fn load(executor: impl PgExecutor<'_>, school_id: Uuid) -> Result<SchoolSettings, sqlx::Error> {
+    let row: Option<SettingsRow> =
+        sqlx::query_as(&format!("SELECT {} FROM school_settings WHERE school_id = $1", SETTINGS_COLUMNS))
+            .bind(school_id)
+            .fetch_optional(executor)
+            .await?;
+    Ok(row.map(SchoolSettings::from).unwrap_or_default())
+}
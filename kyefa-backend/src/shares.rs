@@ -0,0 +1,297 @@
+use kyefa_models::SchoolSettings;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::settings;
+
+/// How revenue is divided: the shares from the school's settings, with the
+/// base share and caps of its newest `ConstraintConfig` if it has one
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitRules {
+    /// The `ConstraintConfig` the base share and caps came from, if any
+    pub constraint_id: Option<Uuid>,
+    pub name: String,
+    /// Part of the teachers' pool shared equally before periods are counted
+    pub base_percentage: f64,
+    pub admin_percentage: f64,
+    pub support_staff_percentage: f64,
+    pub max_periods_paid: Option<u32>,
+    /// Largest fraction of the period pool one teacher can receive
+    pub max_ratio: Option<f64>,
+}
+
+impl SplitRules {
+    pub fn from_settings(settings: &SchoolSettings) -> Self {
+        Self {
+            constraint_id: None,
+            name: "School share settings".to_string(),
+            base_percentage: 0.0,
+            admin_percentage: settings.admin_share_percentage,
+            support_staff_percentage: settings.staff_share_percentage,
+            max_periods_paid: None,
+            max_ratio: None,
+        }
+    }
+
+    pub fn teacher_percentage(&self) -> f64 {
+        (100.0 - self.admin_percentage - self.support_staff_percentage).max(0.0)
+    }
+}
+
+/// One teacher's part of the teachers' pool
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeacherSplit {
+    pub teacher_id: Uuid,
+    pub periods: usize,
+    /// `periods` after `max_periods_paid`
+    pub periods_paid: usize,
+    pub base_share: f64,
+    pub period_share: f64,
+    /// Whether the period cap or the ratio limit reduced `period_share`
+    pub capped: bool,
+}
+
+impl TeacherSplit {
+    pub fn total(&self) -> f64 {
+        self.base_share + self.period_share
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevenueSplit {
+    pub admin_share: f64,
+    pub support_staff_share: f64,
+    pub teacher_share: f64,
+    pub teachers: Vec<TeacherSplit>,
+}
+
+impl RevenueSplit {
+    /// The teacher's total as a percentage of the teachers' pool
+    pub fn percentage_of_pool(&self, teacher: &TeacherSplit) -> f64 {
+        if self.teacher_share > 0.0 {
+            teacher.total() / self.teacher_share * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Splits `revenue` under `rules` between admin, support staff and the
+/// teachers in `periods`, each paired with the periods they taught. The
+/// teachers' base share is divided equally and the rest by periods paid.
+/// Whatever the ratio limit holds back stays undistributed.
+pub fn split(revenue: f64, rules: &SplitRules, periods: &[(Uuid, usize)]) -> RevenueSplit {
+    let teacher_share = revenue * rules.teacher_percentage() / 100.0;
+    let base_pool = teacher_share * rules.base_percentage / 100.0;
+    let period_pool = teacher_share - base_pool;
+    let base_each = if periods.is_empty() { 0.0 } else { base_pool / periods.len() as f64 };
+
+    let paid: Vec<usize> = periods
+        .iter()
+        .map(|&(_, taught)| rules.max_periods_paid.map_or(taught, |cap| taught.min(cap as usize)))
+        .collect();
+    let total_paid: usize = paid.iter().sum();
+    let rate = if total_paid > 0 { period_pool / total_paid as f64 } else { 0.0 };
+    let ratio_limit = rules.max_ratio.map(|ratio| period_pool * ratio);
+
+    let teachers = periods
+        .iter()
+        .zip(paid)
+        .map(|(&(teacher_id, taught), periods_paid)| {
+            let uncapped = rate * periods_paid as f64;
+            let period_share = ratio_limit.map_or(uncapped, |limit| uncapped.min(limit));
+            TeacherSplit {
+                teacher_id,
+                periods: taught,
+                periods_paid,
+                base_share: base_each,
+                period_share,
+                capped: periods_paid < taught || period_share < uncapped,
+            }
+        })
+        .collect();
+
+    RevenueSplit {
+        admin_share: revenue * rules.admin_percentage / 100.0,
+        support_staff_share: revenue * rules.support_staff_percentage / 100.0,
+        teacher_share,
+        teachers,
+    }
+}
+
+/// What each of a teacher's `periods` is paid, oldest first: the period
+/// share spread evenly over the periods paid. Periods beyond the cap get
+/// nothing and are left out.
+pub fn period_payments(teacher: &TeacherSplit, periods: &[Uuid]) -> Vec<(Uuid, f64)> {
+    let paid = teacher.periods_paid.min(periods.len());
+    if paid == 0 {
+        return Vec::new();
+    }
+    let each = teacher.period_share / paid as f64;
+    periods.iter().take(paid).map(|&id| (id, each)).collect()
+}
+
+#[derive(sqlx::FromRow)]
+struct ConstraintRow {
+    id: Uuid,
+    name: String,
+    base_percentage: f64,
+    max_periods_paid: Option<i32>,
+    max_ratio: Option<f64>,
+}
+
+/// The rules in force for the school. The shares always come from its
+/// settings, so what an admin saves there is what gets paid out.
+pub async fn rules(pool: &PgPool, school_id: Uuid) -> Result<SplitRules, sqlx::Error> {
+    let shares = SplitRules::from_settings(&settings::load(pool, school_id).await?);
+    let newest: Option<ConstraintRow> = sqlx::query_as(
+        "SELECT id, name, base_percentage, max_periods_paid, max_ratio
+         FROM constraint_configs WHERE school_id = $1
+         ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(school_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match newest {
+        Some(row) => SplitRules {
+            constraint_id: Some(row.id),
+            name: row.name,
+            base_percentage: row.base_percentage,
+            max_periods_paid: row.max_periods_paid.map(|cap| cap as u32),
+            max_ratio: row.max_ratio,
+            ..shares
+        },
+        None => shares,
+    })
+}
+
+/// Active participating teachers with the periods the committee verified in
+/// `term_id` between the optional dates, in name order. Only verified
+/// periods are ever paid for, so every money figure is worked out from these.
+pub async fn teacher_periods(
+    executor: impl PgExecutor<'_>,
+    school_id: Uuid,
+    term_id: Uuid,
+    date_from: Option<chrono::NaiveDate>,
+    date_to: Option<chrono::NaiveDate>,
+) -> Result<Vec<(Uuid, String, usize)>, sqlx::Error> {
+    let rows: Vec<(Uuid, String, i64)> = sqlx::query_as(
+        "SELECT t.id, t.first_name || ' ' || t.surname,
+                (SELECT COUNT(*) FROM conducted_periods cp
+                 WHERE cp.teacher_id = t.id AND cp.term_id = $2 AND cp.was_conducted
+                   AND cp.verification = 'verified'
+                   AND ($3::date IS NULL OR cp.date::date >= $3)
+                   AND ($4::date IS NULL OR cp.date::date <= $4))
+         FROM teachers t
+         WHERE t.school_id = $1 AND t.is_active AND t.status = 'participating'
+         ORDER BY t.surname, t.first_name",
+    )
+    .bind(school_id)
+    .bind(term_id)
+    .bind(date_from)
+    .bind(date_to)
+    .fetch_all(executor)
+    .await?;
+    Ok(rows.into_iter().map(|(id, name, count)| (id, name, count as usize)).collect())
+}
+
+/// Each teacher's verified periods over the whole of `term_id`, as `split`
+/// takes them
+pub async fn verified_periods(
+    executor: impl PgExecutor<'_>,
+    school_id: Uuid,
+    term_id: Uuid,
+) -> Result<Vec<(Uuid, usize)>, sqlx::Error> {
+    Ok(teacher_periods(executor, school_id, term_id, None, None)
+        .await?
+        .into_iter()
+        .map(|(id, _, count)| (id, count))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(base: f64, max_periods_paid: Option<u32>, max_ratio: Option<f64>) -> SplitRules {
+        SplitRules {
+            constraint_id: None,
+            name: "Test".to_string(),
+            base_percentage: base,
+            admin_percentage: 30.0,
+            support_staff_percentage: 20.0,
+            max_periods_paid,
+            max_ratio,
+        }
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn splits_between_admin_staff_and_teachers() {
+        let split = split(1000.0, &rules(0.0, None, None), &[]);
+        assert!(close(split.admin_share, 300.0));
+        assert!(close(split.support_staff_share, 200.0));
+        assert!(close(split.teacher_share, 500.0));
+    }
+
+    #[test]
+    fn base_share_is_equal_and_the_rest_follows_periods() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let split = split(1000.0, &rules(20.0, None, None), &[(a, 30), (b, 10)]);
+        // Pool of 500: 100 shared equally, 400 at 10 per period
+        assert!(close(split.teachers[0].base_share, 50.0));
+        assert!(close(split.teachers[0].period_share, 300.0));
+        assert!(close(split.teachers[1].period_share, 100.0));
+        assert!(!split.teachers[0].capped);
+        assert!(close(split.percentage_of_pool(&split.teachers[0]), 70.0));
+    }
+
+    #[test]
+    fn period_cap_limits_periods_paid() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let split = split(1000.0, &rules(0.0, Some(20), None), &[(a, 30), (b, 20)]);
+        assert_eq!(split.teachers[0].periods_paid, 20);
+        assert!(split.teachers[0].capped);
+        assert!(!split.teachers[1].capped);
+        assert!(close(split.teachers[0].period_share, 250.0));
+    }
+
+    #[test]
+    fn ratio_limit_holds_back_the_excess() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let split = split(1000.0, &rules(0.0, None, Some(0.6)), &[(a, 90), (b, 10)]);
+        assert!(close(split.teachers[0].period_share, 300.0));
+        assert!(split.teachers[0].capped);
+        assert!(close(split.teachers[1].period_share, 50.0));
+    }
+
+    #[test]
+    fn nothing_to_share_without_periods() {
+        let a = Uuid::new_v4();
+        let split = split(1000.0, &rules(0.0, None, None), &[(a, 0)]);
+        assert_eq!(split.teachers[0].period_share, 0.0);
+    }
+
+    #[test]
+    fn period_payments_spread_the_share_over_the_periods_paid() {
+        let (a, periods) = (Uuid::new_v4(), [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()]);
+        let split = split(1000.0, &rules(0.0, Some(2), None), &[(a, 3)]);
+        let payments = period_payments(&split.teachers[0], &periods);
+        // Pool of 500 over the 2 periods the cap allows, the oldest first
+        assert_eq!(payments.len(), 2);
+        assert_eq!(payments[0].0, periods[0]);
+        assert!(close(payments[0].1, 250.0));
+        assert!(close(payments.iter().map(|(_, amount)| amount).sum(), split.teachers[0].period_share));
+    }
+
+    #[test]
+    fn no_period_payments_without_periods() {
+        let a = Uuid::new_v4();
+        let split = split(1000.0, &rules(50.0, None, None), &[(a, 0)]);
+        assert!(period_payments(&split.teachers[0], &[]).is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use uuid::Uuid;
+
+use kyefa_models::SCHOOL_ID_HEADER;
+
+use crate::error::ApiError;
+use crate::session::CurrentUser;
+use crate::AppState;
+
+/// The school a request is scoped to, named by `SCHOOL_ID_HEADER`. Every
+/// query a handler runs filters on `school_id`. The header is only trusted
+/// for a school the session's user belongs to, so a handler taking a
+/// `Tenant` never serves another school's records.
+#[derive(Debug, Clone, Copy)]
+pub struct Tenant {
+    pub school_id: Uuid,
+}
+
+impl FromRequestParts<AppState> for Tenant {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = CurrentUser::from_request_parts(parts, state).await?;
+        let school_id = parts
+            .headers
+            .get(SCHOOL_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| Uuid::parse_str(value).ok())
+            .ok_or_else(|| ApiError::bad_request("Choose a school before continuing."))?;
+
+        let is_member: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM user_schools us JOIN schools s ON s.id = us.school_id
+                            WHERE us.user_id = $1 AND us.school_id = $2 AND s.is_active)",
+        )
+        .bind(user.id)
+        .bind(school_id)
+        .fetch_one(&state.pool)
+        .await?;
+        match is_member {
+            true => Ok(Tenant { school_id }),
+            false => Err(ApiError::forbidden("That school isn't available to you.")),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use chrono::NaiveDate;
+use kyefa_models::TermRow;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const TERM_COLUMNS: &str = "id, school_id, name, academic_year, start_date, end_date, is_active";
+
+/// The school's active term, if one is set
+pub async fn active(pool: &PgPool, school_id: Uuid) -> Result<Option<TermRow>, sqlx::Error> {
+    sqlx::query_as(&format!("SELECT {} FROM terms WHERE school_id = $1 AND is_active", TERM_COLUMNS))
+        .bind(school_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The term containing `date`, falling back to the active term when there is
+/// no date or no term covers it
+pub async fn containing(pool: &PgPool, school_id: Uuid, date: Option<NaiveDate>) -> Result<Option<TermRow>, sqlx::Error> {
+    if let Some(date) = date {
+        let term: Option<TermRow> = sqlx::query_as(&format!(
+            "SELECT {} FROM terms WHERE school_id = $1 AND start_date::date <= $2 AND $2 <= end_date::date
+             ORDER BY start_date DESC LIMIT 1",
+            TERM_COLUMNS
+        ))
+        .bind(school_id)
+        .bind(date)
+        .fetch_optional(pool)
+        .await?;
+        if term.is_some() {
+            return Ok(term);
+        }
+    }
+    active(pool, school_id).await
+}
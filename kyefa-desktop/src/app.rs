@@ -1,26 +1,50 @@
-use iced::{Element, Task, Theme, Settings, Subscription};
+use iced::{Element, Task, Theme, Settings, Subscription, Event};
+use iced::{event, keyboard, mouse, window};
 use uuid::Uuid;
 use std::str::FromStr;
-use std::time::Duration;
-use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
 
-use chrono::{DateTime, Utc, NaiveDate};
+use chrono::{DateTime, Utc, NaiveDate, Local};
 use kyefa_models::{
     User, UserAccount, UserProfile, UserRole, 
     PersonName, UserResponse, Gender,
     ClassLevel, Student, TeachingPeriod, Payment, 
     PaymentStatus, ReportType, RecentActivity, ActivityType, 
     PaymentSummary, TeacherEarnings, CreateStudentPayload,
-    UpdateStudentPayload,
+    UpdateStudentPayload, Teacher, ReportFilter, ReportOverview,
+    ProjectedIncomeReport, StudentPaymentHistory, Term, PaymentFilter, RecordPaymentPayload,
+    HomeSummary, WeeklyCollection, TermComparison, StudentImportPayload, ImportSummary, ImportRowError,
+    MergeStudentsPayload, MergeStudentsResult, AcademicYear, PromotionOutcome,
+    PromotionDecision, PromotionPayload, PromotionSummary, Enrolment, EnrolStudentsPayload,
+    WithdrawEnrolmentPayload, Exemption, ExemptionReason, CreateExemptionPayload, ExemptionReportRow,
+    Installment, InstallmentPlan, SaveInstallmentPlanPayload, OverdueAgingRow,
+    Guardian, GuardianRelationship, SaveGuardianPayload, SchoolSettings, UpdateSchoolSettingsPayload,
+    CreateUserPayload, UpdateUserPayload, ResetPasswordPayload, PasswordPolicy, ChangePasswordPayload,
+    LockoutPolicy, LoginAttempt, LoginHistoryQuery,
+    TeacherPortal, TeacherPeriod, PeriodVerification, DisputePeriodPayload, School,
+    PeriodForReview, PeriodReviewQuery, ReviewPeriodPayload, Disbursement, RunPayoutPayload,
+    DEFAULT_CURRENCY_SYMBOL, format_money,
 };
 use crate::routes;
-use crate::ui::{login_view, dashboard_view};
+use crate::export::{self, ExportFormat, ReportDocument};
+use crate::import::{self, PeriodImportContext};
+use crate::duplicates::{self, DuplicateCandidate};
+use crate::offline::{self, Saved, LocalStore, SyncReport, SyncState};
+use crate::backup::{self, BackupEntry};
+use crate::settings;
+use crate::ui::{login_view, dashboard_view, palette};
+use crate::ui::date_input::{DateRangeInput, DateRangeMessage, DateInput, chrono_format, current_term, format_date, DEFAULT_DATE_FORMAT};
+use crate::ui::import_preview::ImportPreviewMessage;
 use crate::error::{AppError, LoginError, PasswordChangeError, DashboardError};
 
 pub fn run() -> iced::Result {
     iced::application(KyefaApp::title, KyefaApp::update, KyefaApp::view)
         .antialiasing(true)
-        .theme(|_| Theme::Light)
+        .theme(KyefaApp::theme)
+        .subscription(KyefaApp::subscription)
         .settings(Settings { ..Settings::default() })
         .run_with(move || KyefaApp::new())
 }
@@ -28,6 +52,8 @@ pub fn run() -> iced::Result {
 pub struct KyefaApp {
     pub state: AppState,
     pub settings: AppSettings,
+    /// Last detected OS preference, used while the theme setting is `System`
+    pub system_theme: Theme,
     pub global_error: Option<AppError>,
 }
 
@@ -49,26 +75,105 @@ pub enum Message {
 
     AppLoading(bool),
     AppErrorOccurred(AppError),
+
+    /// Checks whether an automatic backup is due.
+    BackupTick,
+    /// A key press, click or scroll, which keeps the session from going idle
+    UserActivity,
+    /// Checks whether the session has been idle long enough to lock or log out.
+    IdleTick,
+    CheckSystemTheme,
+    SystemThemeDetected(Theme),
+    /// The answer to a task started while the school was active
+    ForSchool(Uuid, Box<Message>),
 }
 
 impl KyefaApp {
     fn new() -> (Self, Task<Message>) {
+        let (settings, global_error) = match settings::load() {
+            Ok(settings) => (settings, None),
+            Err(e) => (AppSettings::default(), Some(e)),
+        };
         (Self {
             state: AppState::Login(LoginState::new()),
-            settings: AppSettings::default(),
-            global_error: None,
+            settings,
+            system_theme: palette::system_theme(),
+            global_error,
         },
         Task::none())
     }
 
+    /// Writes the settings file, reporting a failure without undoing the change in memory.
+    fn save_settings(&mut self) {
+        if let Err(e) = settings::save(&self.settings) {
+            self.global_error = Some(e);
+        }
+    }
+
+    /// Scopes every request from here on to `school_id` and remembers it for
+    /// the next login on this computer.
+    fn select_school(&mut self, school_id: Uuid) {
+        self.settings.active_school = Some(school_id);
+        routes::set_active_school(Some(school_id));
+        self.save_settings();
+    }
+
+    /// Ends the session and shows `login_state`. Requests still running for
+    /// the old session come back without touching the login screen.
+    fn log_out(&mut self, login_state: LoginState) -> Task<Message> {
+        self.state = AppState::Login(login_state);
+        Task::future(routes::logout()).discard()
+    }
+
+    /// Shows `dashboard_state` and fetches what it needs.
+    fn open_dashboard(&mut self, mut dashboard_state: DashboardState) -> Task<Message> {
+        let load = dashboard_state.load();
+        self.state = AppState::Dashboard(dashboard_state);
+        Task::batch(vec![
+            load,
+            Task::perform(async {
+                Message::Dashboard(DashboardMessage::StudentManager(StudentManagerMessage::FetchStudents))
+            }, |m| m),
+            Task::perform(async { Message::BackupTick }, |m| m),
+        ])
+    }
+
     fn title(&self) -> String {
         String::from("Kyefa")
     }
 
+    fn theme(&self) -> Theme {
+        match self.settings.theme {
+            AppTheme::Light => Theme::Light,
+            AppTheme::Dark => Theme::Dark,
+            AppTheme::System => self.system_theme.clone(),
+        }
+    }
+
+    /// Tags what each task started here answers with the school active once
+    /// `message` is handled, which its requests were sent for. Answers for a
+    /// school the user has switched away from, or logged out of, are dropped
+    /// rather than shown on the new school's dashboard.
     fn update(&mut self, message: Message) -> Task<Message> {
+        let task = match message {
+            Message::ForSchool(school_id, message) if routes::active_school() == Some(school_id) => self.handle(*message),
+            Message::ForSchool(..) => return Task::none(),
+            message => self.handle(message),
+        };
+        match routes::active_school() {
+            Some(school_id) => task.map(move |message| match message {
+                Message::ForSchool(..) => message,
+                message => Message::ForSchool(school_id, Box::new(message)),
+            }),
+            None => task,
+        }
+    }
+
+    fn handle(&mut self, message: Message) -> Task<Message> {
         self.global_error = None;
 
         match message {
+            Message::ForSchool(..) => self.update(message),
             Message::AppLoading(is_loading) => {
                 match &mut self.state {
                     AppState::Login(state) => state.is_authenticating = is_loading,
@@ -92,33 +197,22 @@ impl KyefaApp {
                             Task::none()
                         }
                         LoginMessage::AttemptLogin => {
-                            // login_state.is_authenticating = true;
-                            // login_state.error = None;
-                            // let username = login_state.username_input.clone();
-                            // let password = login_state.password_input.clone();
-                            // Task::batch(vec![
-                            //     Task::perform(
-                            //         async move { routes::login(&username, &password).await },
-                            //         |result| match result {
-                            //             Ok(user) => Message::LoginSuccess(user),
-                            //             Err(error) => Message::LoginFailed(error),
-                            //         },
-                            //     ),
-                            //     Task::perform(async { Message::AppLoading(true) }, |m| m),
-                            // ])
-
-                            let temp_user = UserResponse {
-                                id: Uuid::new_v4().to_string().to_string(),
-                                username: "admin".to_string(),
-                                role: UserRole::Admin,
-                                is_active: true,
-                                first_name: "John".to_string(),
-                                surname: "Doe".to_string(),
-                                other_names: Some("Admin".to_string()),
-                            };
-                            self.state = AppState::Dashboard(DashboardState::new(temp_user));
-                            Task::none()
-
+                            if login_state.is_authenticating {
+                                return Task::none();
+                            }
+                            login_state.notice = None;
+                            login_state.is_authenticating = true;
+                            login_state.error = None;
+                            let username = login_state.username_input.clone();
+                            let password = std::mem::take(&mut login_state.password_input);
+                            let school = self.settings.active_school;
+                            Task::perform(
+                                async move { routes::login(&username, &password, school).await },
+                                |result| match result {
+                                    Ok(user) => Message::LoginSuccess(user),
+                                    Err(error) => Message::LoginFailed(error),
+                                },
+                            )
                         }
                     }
                 } else {
@@ -126,32 +220,238 @@ impl KyefaApp {
                 }
             }
             Message::LoginSuccess(user) => {
-                self.state = AppState::Dashboard(DashboardState::new(user));
+                // The school used last on this computer, if the user still has it
+                let schools: Vec<Uuid> = user.schools.iter()
+                    .filter(|school| school.is_active)
+                    .map(|school| school.id)
+                    .collect();
+                let school_id = schools.iter()
+                    .find(|id| Some(**id) == self.settings.active_school)
+                    .or(schools.first());
+                let Some(&school_id) = school_id else {
+                    return self.update(Message::LoginFailed(LoginError::NoSchool));
+                };
+                self.select_school(school_id);
+                let dashboard_state = DashboardState::new(user, &self.settings);
                 Task::batch(vec![
                     Task::perform(async { Message::AppLoading(false) }, |m| m),
-                    Task::perform(async {
-                        Message::Dashboard(DashboardMessage::StudentManager(StudentManagerMessage::FetchStudents))
-                    }, |m| m),
+                    self.open_dashboard(dashboard_state),
                 ])
             }
             Message::LoginFailed(error) => {
+                // A user with no school got a session they can't use
+                routes::end_session();
                 if let AppState::Login(login_state) = &mut self.state {
                     login_state.error = Some(error);
                     login_state.is_authenticating = false;
                 }
                 Task::perform(async { Message::AppLoading(false) }, |m| m)
             }
-            Message::Dashboard(dashboard_message) => {
+            Message::UserActivity => {
+                // Input on the lock screen doesn't count, so the logout still happens
                 if let AppState::Dashboard(dashboard_state) = &mut self.state {
-                    dashboard_state.update(dashboard_message)
+                    if dashboard_state.lock.is_none() {
+                        dashboard_state.last_activity = Instant::now();
+                    }
+                }
+                Task::none()
+            }
+            Message::IdleTick => {
+                let AppState::Dashboard(dashboard_state) = &mut self.state else {
+                    return Task::none();
+                };
+                let idle = dashboard_state.last_activity.elapsed();
+                let after = |minutes: u32| (minutes > 0).then(|| Duration::from_secs(u64::from(minutes) * 60));
+                if after(self.settings.logout_after_minutes).is_some_and(|limit| idle >= limit) {
+                    let mut login_state = LoginState::new();
+                    login_state.notice = Some(format!(
+                        "You were logged out after {} minutes without activity.",
+                        self.settings.logout_after_minutes,
+                    ));
+                    return self.log_out(login_state);
+                } else if after(self.settings.lock_after_minutes).is_some_and(|limit| idle >= limit) {
+                    return dashboard_state.update(DashboardMessage::LockScreen);
+                }
+                Task::none()
+            }
+            Message::CheckSystemTheme => {
+                Task::perform(async { palette::system_theme() }, Message::SystemThemeDetected)
+            }
+            Message::SystemThemeDetected(theme) => {
+                self.system_theme = theme;
+                Task::none()
+            }
+            Message::BackupTick => {
+                let is_due = self.settings.auto_backup_enabled
+                    && backup::is_due(&self.settings.school_backup_folder(), &self.settings.backup_frequency, Utc::now());
+                match &mut self.state {
+                    // Only an admin may export the school's data
+                    AppState::Dashboard(dashboard_state)
+                        if is_due && matches!(dashboard_state.active_user.role, UserRole::Admin) =>
+                    {
+                        dashboard_state.update(DashboardMessage::BackUpNow)
+                    }
+                    _ => Task::none(),
+                }
+            }
+            Message::Dashboard(DashboardMessage::BackupFolderChosen(Some(folder))) => {
+                self.settings.backup_folder = folder;
+                self.save_settings();
+                if let AppState::Dashboard(dashboard_state) = &mut self.state {
+                    let folder = self.settings.school_backup_folder();
+                    dashboard_state.update(DashboardMessage::BackupFolderChosen(Some(folder)))
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Dashboard(DashboardMessage::NavigateToSettings) => {
+                if let AppState::Dashboard(dashboard_state) = &mut self.state {
+                    let can_edit_school = matches!(dashboard_state.active_user.role, UserRole::Admin);
+                    dashboard_state.settings_form = Some(SettingsForm::new(&self.settings, can_edit_school));
+                    dashboard_state.update(DashboardMessage::NavigateToSettings)
                 } else {
                     Task::none()
                 }
             }
-            Message::Logout => {
-                self.state = AppState::Login(LoginState::new());
+            Message::Dashboard(DashboardMessage::Settings(SettingsMessage::Save)) => {
+                let AppState::Dashboard(dashboard_state) = &mut self.state else {
+                    return Task::none();
+                };
+                let Some(form) = dashboard_state.settings_form.as_mut().filter(|form| !form.is_saving) else {
+                    return Task::none();
+                };
+                form.error = None;
+                form.message = None;
+
+                let local = match form.apply_local(&self.settings) {
+                    Ok(local) => local,
+                    Err(e) => {
+                        form.error = Some(e);
+                        return Task::none();
+                    }
+                };
+                let school = if form.can_edit_school {
+                    match form.school() {
+                        Ok(school) => Some(school).filter(|school| *school != self.settings.school()),
+                        Err(e) => {
+                            form.error = Some(e);
+                            return Task::none();
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                self.settings = local;
+                if let Err(e) = settings::save(&self.settings) {
+                    form.error = Some(e.to_string());
+                    return Task::none();
+                }
+                let Some(school) = school else {
+                    form.message = Some("Settings saved.".to_string());
+                    dashboard_state.apply_settings(&self.settings);
+                    return Task::none();
+                };
+                form.is_saving = true;
+                let key = form.request_key;
+                dashboard_state.apply_settings(&self.settings);
+                let payload = UpdateSchoolSettingsPayload { settings: school };
+                Task::perform(routes::save_school_settings(payload, key), |result| {
+                    Message::Dashboard(DashboardMessage::Settings(SettingsMessage::SchoolSettingsSaved(result)))
+                })
+            }
+            Message::Dashboard(DashboardMessage::Settings(SettingsMessage::SchoolSettingsSaved(result))) => {
+                let AppState::Dashboard(dashboard_state) = &mut self.state else {
+                    return Task::none();
+                };
+                let saved = result.map(|school| {
+                    self.settings.apply_school(school);
+                    settings::save(&self.settings)
+                });
+                dashboard_state.apply_settings(&self.settings);
+                if let Some(form) = dashboard_state.settings_form.as_mut() {
+                    form.is_saving = false;
+                    match saved {
+                        Ok(Ok(())) => {
+                            form.request_key = Uuid::new_v4();
+                            form.message = Some("Settings saved for the whole school.".to_string());
+                        }
+                        Ok(Err(e)) => form.error = Some(e.to_string()),
+                        Err(e) => form.error = Some(format!(
+                            "Saved on this computer, but the school-wide settings were not saved: {}", e,
+                        )),
+                    }
+                }
+                Task::none()
+            }
+            Message::Dashboard(DashboardMessage::Settings(SettingsMessage::Discard)) => {
+                if let AppState::Dashboard(dashboard_state) = &mut self.state {
+                    let can_edit_school = matches!(dashboard_state.active_user.role, UserRole::Admin);
+                    dashboard_state.settings_form = Some(SettingsForm::new(&self.settings, can_edit_school));
+                }
+                Task::none()
+            }
+            Message::Dashboard(DashboardMessage::SchoolSettingsLoaded(result)) => {
+                // Unreachable backend: keep the copy from the settings file
+                if let (Ok(school), AppState::Dashboard(dashboard_state)) = (result, &mut self.state) {
+                    if school != self.settings.school() {
+                        self.settings.apply_school(school);
+                        dashboard_state.apply_settings(&self.settings);
+                        if let Err(e) = settings::save(&self.settings) {
+                            self.global_error = Some(e);
+                        }
+                    }
+                }
                 Task::none()
             }
+            Message::Dashboard(DashboardMessage::SwitchSchool(school)) => {
+                // Everything on screen belongs to the old school, so the dashboard starts afresh
+                let AppState::Dashboard(dashboard_state) = &self.state else {
+                    return Task::none();
+                };
+                if dashboard_state.active_school.as_ref().is_some_and(|active| active.id == school.id) {
+                    return Task::none();
+                }
+                let active_user = dashboard_state.active_user.clone();
+                let schools = dashboard_state.schools.clone();
+                let must_change_password = dashboard_state.must_change_password;
+                self.select_school(school.id);
+                let dashboard_state = DashboardState::for_school(active_user, schools, must_change_password, &self.settings);
+                self.open_dashboard(dashboard_state)
+            }
+            Message::Dashboard(DashboardMessage::PasswordChanged(Ok(()))) => {
+                // The backend ended every session for this user, this one included
+                let mut login_state = LoginState::new();
+                login_state.notice = Some("Password changed. Log in with your new password.".to_string());
+                self.log_out(login_state)
+            }
+            Message::Dashboard(dashboard_message) => {
+                if let AppState::Dashboard(dashboard_state) = &mut self.state {
+                    dashboard_state.update(dashboard_message)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Logout => self.log_out(LoginState::new()),
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        // Follow the OS switching between light and dark, e.g. on a schedule at dusk
+        let system_theme = if self.settings.theme == AppTheme::System {
+            iced::time::every(SYSTEM_THEME_INTERVAL).map(|_| Message::CheckSystemTheme)
+        } else {
+            Subscription::none()
+        };
+        match &self.state {
+            AppState::Dashboard(_) => Subscription::batch(vec![
+                iced::time::every(SYNC_INTERVAL).map(|_| Message::Dashboard(DashboardMessage::SyncTick)),
+                iced::time::every(BACKUP_CHECK_INTERVAL).map(|_| Message::BackupTick),
+                iced::time::every(IDLE_CHECK_INTERVAL).map(|_| Message::IdleTick),
+                iced::event::listen_with(activity_event),
+                system_theme,
+            ]),
+            AppState::Login(_) => system_theme,
         }
     }
 
@@ -176,6 +476,8 @@ pub struct LoginState {
     pub username_input: String,
     pub password_input: String,
     pub error: Option<LoginError>,
+    /// Why the user is back at the login screen, e.g. after changing their password
+    pub notice: Option<String>,
     pub is_authenticating: bool,
 }
 
@@ -185,6 +487,7 @@ impl LoginState {
             username_input: String::new(),
             password_input: String::new(),
             error: None,
+            notice: None,
             is_authenticating: false,
         }
     }
@@ -193,65 +496,474 @@ impl LoginState {
 #[derive(Debug)]
 pub struct DashboardState {
     pub current_view: DashboardView,
+    pub home: HomeState,
     pub student_manager: StudentManagerState,
     pub teaching_period_manager: TeachingPeriodManagerState,
     pub payment_tracking: PaymentTrackingState,
     pub user_access_manager: UserAccessManagerState,
     pub reports_analytics: ReportsAnalyticsState,
+    pub teacher_portal: TeacherPortalState,
+    pub period_review: PeriodReviewState,
     pub active_user: UserProfile,
+    /// Active schools the user belongs to
+    pub schools: Vec<School>,
+    /// School every request is scoped to; `None` only when the user has no school
+    pub active_school: Option<School>,
+    /// Set after an admin reset; the dashboard stays locked until they pick their own password
+    pub must_change_password: bool,
+    pub change_password: Option<ChangePasswordForm>,
+    pub password_policy: PasswordPolicy,
+    /// The school's date pattern, already checked; every date shown uses it
+    pub date_format: String,
+    /// The school's currency symbol; every amount shown uses it
+    pub currency_symbol: String,
+    pub last_activity: Instant,
+    /// Set while the screen is locked. Everything else is left as it was, so
+    /// half-filled forms are still there after unlocking.
+    pub lock: Option<LockScreen>,
+    pub terms: Vec<Term>,
+    pub sync: SyncStatus,
+    pub backups: BackupStatus,
+    pub settings_form: Option<SettingsForm>,
     pub error: Option<DashboardError>,
     pub is_loading: bool,     
     pub global_app_error: Option<AppError>,
 }
 
-impl DashboardState {
-    fn new(user_account: UserResponse) -> Self {
+/// How often queued offline writes are retried
+const SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the app checks whether an automatic backup is due
+const BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How often idle time is compared against the lock and logout settings
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Input that counts as the user still being there. Cursor movement is left
+/// out: it would flood the update loop, and a nudged mouse shouldn't keep an
+/// unattended session open.
+fn activity_event(event: Event, _status: event::Status, _window: window::Id) -> Option<Message> {
+    match event {
+        Event::Keyboard(keyboard::Event::KeyPressed { .. })
+        | Event::Mouse(mouse::Event::ButtonPressed(_))
+        | Event::Mouse(mouse::Event::WheelScrolled { .. }) => Some(Message::UserActivity),
+        _ => None,
+    }
+}
+
+/// How often the OS light/dark preference is re-read while the theme is `System`
+const SYSTEM_THEME_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The password prompt covering a locked dashboard
+#[derive(Debug, Clone, Default)]
+pub struct LockScreen {
+    pub password: String,
+    pub is_verifying: bool,
+    pub error: Option<String>,
+}
+
+/// Archives in the backup folder and the restore flow
+#[derive(Debug, Clone)]
+pub struct BackupStatus {
+    pub folder: PathBuf,
+    pub retention: usize,
+    pub encrypt: bool,
+    pub passphrase: Option<String>,
+    pub passphrase_input: String,
+    pub entries: Vec<BackupEntry>,
+    pub show_panel: bool,
+    pub is_backing_up: bool,
+    /// Archive waiting for confirmation before it replaces the server's data
+    pub confirm_restore: Option<BackupEntry>,
+    /// Passphrase typed for the archive being restored, which may predate the current one
+    pub restore_passphrase: String,
+    pub restore_key: Uuid,
+    pub is_restoring: bool,
+    pub message: Option<String>,
+    pub error: Option<String>,
+}
+
+impl BackupStatus {
+    fn new(settings: &AppSettings) -> Self {
+        let (passphrase, error) = match settings::load_backup_passphrase() {
+            Ok(passphrase) => (passphrase, None),
+            Err(e) => (None, Some(e.to_string())),
+        };
         Self {
+            folder: settings.school_backup_folder(),
+            retention: settings.backup_retention,
+            encrypt: settings.encrypt_backups,
+            passphrase,
+            passphrase_input: String::new(),
+            entries: Vec::new(),
+            show_panel: false,
+            is_backing_up: false,
+            confirm_restore: None,
+            restore_passphrase: String::new(),
+            restore_key: Uuid::new_v4(),
+            is_restoring: false,
+            message: None,
+            error,
+        }
+    }
+
+    fn list(&self) -> Task<Message> {
+        let folder = self.folder.clone();
+        Task::perform(async move { backup::list_backups(&folder) }, |result| {
+            Message::Dashboard(DashboardMessage::BackupsListed(result))
+        })
+    }
+}
+
+/// What the offline queue holds, for the sidebar indicator and the sync-status panel
+#[derive(Debug, Clone, Default)]
+pub struct SyncStatus {
+    pub store: LocalStore,
+    pub is_syncing: bool,
+    /// The last sync could not reach the backend
+    pub is_offline: bool,
+    pub show_panel: bool,
+    pub message: Option<String>,
+}
+
+impl SyncStatus {
+    /// Writes waiting for the active school and user; the rest wait until they're back
+    pub fn pending_count(&self) -> usize {
+        self.store.queue.iter()
+            .filter(|pending| pending.state == SyncState::Pending && pending.is_for_session())
+            .count()
+    }
+
+    pub fn conflict_count(&self) -> usize {
+        self.store.queue.iter().filter(|pending| matches!(pending.state, SyncState::Conflict(_))).count()
+    }
+}
+
+impl DashboardState {
+    fn new(user_account: UserResponse, settings: &AppSettings) -> Self {
+        let must_change_password = user_account.must_change_password;
+        let schools = user_account.schools.iter().filter(|school| school.is_active).cloned().collect();
+        Self::for_school(user_account.into(), schools, must_change_password, settings)
+    }
+
+    /// A fresh dashboard for the school `settings` has active
+    fn for_school(active_user: UserProfile, schools: Vec<School>, must_change_password: bool, settings: &AppSettings) -> Self {
+        let active_school = schools.iter().find(|school| Some(school.id) == settings.active_school).cloned();
+        let mut state = Self {
             current_view: DashboardView::Home,
+            home: HomeState::new(),
             student_manager: StudentManagerState::new(),
             teaching_period_manager: TeachingPeriodManagerState::default(),
             payment_tracking: PaymentTrackingState::default(),
-            user_access_manager: UserAccessManagerState::default(),
+            user_access_manager: UserAccessManagerState {
+                schools: schools.clone(),
+                active_school_id: settings.active_school,
+                ..UserAccessManagerState::default()
+            },
             reports_analytics: ReportsAnalyticsState::default(),
-            active_user: user_account.into(),
+            teacher_portal: TeacherPortalState::default(),
+            period_review: PeriodReviewState {
+                can_run_payouts: active_user.role.can_run_payouts(),
+                ..PeriodReviewState::default()
+            },
+            active_user,
+            schools,
+            active_school,
+            must_change_password,
+            change_password: must_change_password.then(ChangePasswordForm::default),
+            password_policy: settings.school().password_policy,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            currency_symbol: DEFAULT_CURRENCY_SYMBOL.to_string(),
+            last_activity: Instant::now(),
+            lock: None,
+            terms: Vec::new(),
+            sync: SyncStatus::default(),
+            backups: BackupStatus::new(settings),
+            settings_form: None,
             error: None,
             is_loading: false,
             global_app_error: None,
+        };
+        state.apply_settings(settings);
+        state
+    }
+
+    /// Brings the dashboard in line with changed settings.
+    pub fn apply_settings(&mut self, settings: &AppSettings) {
+        let school = settings.school();
+        self.apply_date_format(&school.date_format);
+        self.apply_currency_symbol(&school.currency_symbol);
+        self.backups.retention = settings.backup_retention;
+        self.backups.encrypt = settings.encrypt_backups;
+        self.password_policy = school.password_policy.clone();
+        self.user_access_manager.password_policy = school.password_policy;
+        if !self.payment_tracking.show_payment_dialog {
+            self.payment_tracking.payment_method = school.default_payment_method;
+        }
+    }
+
+    /// Pushes the configured date format down to every date input, falling
+    /// back to the default when the school's pattern isn't valid.
+    pub fn apply_date_format(&mut self, date_format: &str) {
+        let date_format = if chrono_format(date_format).is_ok() { date_format } else { DEFAULT_DATE_FORMAT };
+        self.date_format = date_format.to_string();
+        self.reports_analytics.date_range.set_format(date_format);
+        self.payment_tracking.date_range.set_format(date_format);
+        self.teaching_period_manager.new_period_date.set_format(date_format);
+        self.student_manager.set_date_format(date_format);
+        self.teacher_portal.date_format = date_format.to_string();
+        self.period_review.date_format = date_format.to_string();
+    }
+
+    /// Pushes the school's currency symbol down to every screen showing amounts.
+    pub fn apply_currency_symbol(&mut self, currency_symbol: &str) {
+        self.currency_symbol = currency_symbol.to_string();
+        self.student_manager.currency_symbol = currency_symbol.to_string();
+        self.payment_tracking.currency_symbol = currency_symbol.to_string();
+        self.reports_analytics.currency_symbol = currency_symbol.to_string();
+        self.teaching_period_manager.currency_symbol = currency_symbol.to_string();
+        self.teacher_portal.currency_symbol = currency_symbol.to_string();
+        self.period_review.currency_symbol = currency_symbol.to_string();
+    }
+
+    /// Fetches what the dashboard needs as soon as it opens.
+    fn load(&mut self) -> Task<Message> {
+        Task::batch(vec![
+            Task::perform(routes::fetch_all_terms(), |result| {
+                Message::Dashboard(DashboardMessage::TermsLoaded(result))
+            }),
+            Task::perform(routes::fetch_school_settings(), |result| {
+                Message::Dashboard(DashboardMessage::SchoolSettingsLoaded(result))
+            }),
+            self.home.update(HomeMessage::LoadHome)
+                .map(|msg| Message::Dashboard(DashboardMessage::Home(msg))),
+            self.start_sync(),
+        ])
+    }
+
+    /// Replays the offline queue unless a sync is already running.
+    fn start_sync(&mut self) -> Task<Message> {
+        if self.sync.is_syncing {
+            return Task::none();
         }
+        self.sync.is_syncing = true;
+        Task::perform(offline::sync(), |result| Message::Dashboard(DashboardMessage::SyncFinished(result)))
+    }
+
+    /// Re-reads the queue after a write may have been added to it.
+    fn refresh_queue(&self) -> Task<Message> {
+        Task::perform(async { offline::snapshot() }, |result| Message::Dashboard(DashboardMessage::QueueUpdated(result)))
     }
 
     pub fn update(&mut self, message: DashboardMessage) -> Task<Message> {
         match message {
             DashboardMessage::NavigateToStudentManager => {
                 self.current_view = DashboardView::StudentManager;
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
                 Task::none()
             },
             DashboardMessage::NavigateToTeachingPeriodManager => {
                 self.current_view = DashboardView::TeachingPeriodManager;
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
                 Task::none()
             },
             DashboardMessage::NavigateToPaymentTracking => {
                 self.current_view = DashboardView::PaymentTrackingManager;
-                Task::none()
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
+                self.payment_tracking.update(PaymentTrackingMessage::LoadPayments)
+                    .map(|msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg)))
             },
             DashboardMessage::NavigateToReportsAnalytics => {
                 self.current_view = DashboardView::ReportsAnalytics;
-                Task::none()
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
+                self.reports_analytics.update(ReportsAnalyticsMessage::LoadReports)
+                    .map(|msg| Message::Dashboard(DashboardMessage::ReportsAnalytics(msg)))
             },
             DashboardMessage::NavigateToUserAccessManager => {
                 self.current_view = DashboardView::UserAccessManager;
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
+                self.user_access_manager.update(UserAccessMessage::LoadUsers)
+                    .map(|msg| Message::Dashboard(DashboardMessage::UserAccess(msg)))
+            },
+            DashboardMessage::NavigateToTeacherPortal => {
+                self.current_view = DashboardView::TeacherPortal;
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
+                self.update(DashboardMessage::TeacherPortal(TeacherPortalMessage::LoadPortal))
+            },
+            DashboardMessage::NavigateToPeriodReview => {
+                self.current_view = DashboardView::PeriodReview;
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
+                self.period_review.set_terms(&self.terms);
+                self.period_review.update(PeriodReviewMessage::LoadPeriods)
+                    .map(|msg| Message::Dashboard(DashboardMessage::PeriodReview(msg)))
+            },
+            DashboardMessage::PeriodReview(review_msg) => {
+                self.period_review.update(review_msg)
+                    .map(|msg| Message::Dashboard(DashboardMessage::PeriodReview(msg)))
+            },
+            DashboardMessage::NavigateToSettings => {
+                self.current_view = DashboardView::Settings;
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
+                Task::none()
+            },
+            DashboardMessage::Settings(settings_msg) => {
+                let Some(form) = self.settings_form.as_mut() else {
+                    return Task::none();
+                };
+                form.message = None;
+                match settings_msg {
+                    SettingsMessage::CurrencySymbolChanged(value) => form.currency_symbol = value,
+                    SettingsMessage::DateFormatSelected(value) => form.date_format = value,
+                    SettingsMessage::PaymentMethodSelected(value) => form.default_payment_method = value,
+                    SettingsMessage::AdminShareChanged(value) => form.admin_share = value,
+                    SettingsMessage::StaffShareChanged(value) => form.staff_share = value,
+                    SettingsMessage::TeacherShareChanged(value) => form.teacher_share = value,
+                    SettingsMessage::PasswordMinLengthChanged(value) => form.password_min_length = value,
+                    SettingsMessage::PasswordHistoryChanged(value) => form.password_history = value,
+                    SettingsMessage::MaxFailedLoginsChanged(value) => form.max_failed_logins = value,
+                    SettingsMessage::LockoutMinutesChanged(value) => form.lockout_minutes = value,
+                    SettingsMessage::SiblingDiscountChanged(value) => form.sibling_discount = value,
+                    SettingsMessage::BackupFrequencySelected(value) => form.backup_frequency = value,
+                    SettingsMessage::AutoBackupToggled(value) => form.auto_backup_enabled = value,
+                    SettingsMessage::BackupRetentionChanged(value) => form.backup_retention = value,
+                    SettingsMessage::EncryptBackupsToggled(value) => form.encrypt_backups = value,
+                    SettingsMessage::LockAfterChanged(value) => form.lock_after = value,
+                    SettingsMessage::LogoutAfterChanged(value) => form.logout_after = value,
+                    SettingsMessage::ThemeSelected(theme) => form.theme = theme,
+                    // Handled by KyefaApp, which owns the saved settings
+                    SettingsMessage::Save | SettingsMessage::SchoolSettingsSaved(_) | SettingsMessage::Discard => {}
+                }
+                Task::none()
+            },
+            DashboardMessage::SchoolSettingsLoaded(_) | DashboardMessage::SwitchSchool(_) => {
+                // Handled by KyefaApp, which owns the saved settings
                 Task::none()
             },
             DashboardMessage::NavigateToHome => {
                 self.current_view = DashboardView::Home;
+                self.sync.show_panel = false;
+                self.backups.show_panel = false;
+                self.home.update(HomeMessage::LoadHome)
+                    .map(|msg| Message::Dashboard(DashboardMessage::Home(msg)))
+            },
+            DashboardMessage::TeacherPortal(TeacherPortalMessage::LoadPortal) => {
+                self.teacher_portal.is_loading = self.teacher_portal.portal.is_none();
+                self.teacher_portal.error_message = None;
+                Task::perform(routes::fetch_teacher_portal(), |result| {
+                    Message::Dashboard(DashboardMessage::TeacherPortal(TeacherPortalMessage::PortalLoaded(result)))
+                })
+            },
+            DashboardMessage::TeacherPortal(TeacherPortalMessage::SubmitDispute) => {
+                self.teacher_portal.submit_dispute()
+                    .map(|msg| Message::Dashboard(DashboardMessage::TeacherPortal(msg)))
+            },
+            DashboardMessage::TeacherPortal(portal_msg) => {
+                self.teacher_portal.update(portal_msg)
+                    .map(|msg| Message::Dashboard(DashboardMessage::TeacherPortal(msg)))
+            },
+            DashboardMessage::Home(home_msg) => {
+                self.home.update(home_msg)
+                    .map(|msg| Message::Dashboard(DashboardMessage::Home(msg)))
+            },
+            DashboardMessage::LockScreen => {
+                if self.lock.is_none() {
+                    self.lock = Some(LockScreen::default());
+                }
+                Task::none()
+            },
+            DashboardMessage::UpdateUnlockPassword(value) => {
+                if let Some(lock) = self.lock.as_mut() {
+                    lock.password = value;
+                }
+                Task::none()
+            },
+            DashboardMessage::Unlock => {
+                let Some(lock) = self.lock.as_mut().filter(|lock| !lock.is_verifying && !lock.password.is_empty()) else {
+                    return Task::none();
+                };
+                // Checked by the backend against the session's user, so it counts towards the lockout
+                lock.is_verifying = true;
+                lock.error = None;
+                let password = std::mem::take(&mut lock.password);
+                Task::perform(async move { routes::unlock(&password).await }, |result| {
+                    Message::Dashboard(DashboardMessage::Unlocked(result))
+                })
+            },
+            DashboardMessage::Unlocked(result) => {
+                let Some(lock) = self.lock.as_mut() else {
+                    return Task::none();
+                };
+                lock.is_verifying = false;
+                match result {
+                    Ok(()) => {
+                        self.lock = None;
+                        self.last_activity = Instant::now();
+                    }
+                    Err(LoginError::InvalidCredentials(message)) => lock.error = Some(message),
+                    Err(e) => lock.error = Some(e.to_string()),
+                }
                 Task::none()
             },
             DashboardMessage::ChangePassword => {
-                // TODO: Implement ChangePassword logic
+                self.change_password = Some(ChangePasswordForm::default());
+                Task::none()
+            },
+            DashboardMessage::UpdateCurrentPassword(value) => {
+                if let Some(form) = self.change_password.as_mut() {
+                    form.current_password = value;
+                }
+                Task::none()
+            },
+            DashboardMessage::UpdateNewPassword(value) => {
+                if let Some(form) = self.change_password.as_mut() {
+                    form.new_password = value;
+                }
+                Task::none()
+            },
+            DashboardMessage::UpdateConfirmPassword(value) => {
+                if let Some(form) = self.change_password.as_mut() {
+                    form.confirm_password = value;
+                }
+                Task::none()
+            },
+            DashboardMessage::SubmitPasswordChange => {
+                let Some(form) = self.change_password.as_mut().filter(|form| !form.is_saving) else {
+                    return Task::none();
+                };
+                let payload = match form.payload(&self.password_policy) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        form.error = Some(e);
+                        return Task::none();
+                    }
+                };
+                form.is_saving = true;
+                form.error = None;
+                Task::perform(routes::change_password(payload, form.request_key), |result| {
+                    Message::Dashboard(DashboardMessage::PasswordChanged(result))
+                })
+            },
+            DashboardMessage::CancelPasswordChange => {
+                // A reset password has to be replaced before the dashboard can be used
+                if !self.must_change_password {
+                    self.change_password = None;
+                }
                 Task::none()
             },
-            DashboardMessage::PasswordChanged(_) => {
-                // TODO: Implement PasswordChanged logic
+            DashboardMessage::PasswordChanged(result) => {
+                // Success logs the user out and is handled by KyefaApp
+                if let (Err(e), Some(form)) = (result, self.change_password.as_mut()) {
+                    form.is_saving = false;
+                    form.error = Some(e);
+                }
                 Task::none()
             },
             DashboardMessage::Loading(is_loading) => {
@@ -262,6 +974,30 @@ impl DashboardState {
                 self.global_app_error = Some(error);
                 Task::perform(async { Message::AppLoading(false) }, |m| m)
             },
+            DashboardMessage::StudentManager(StudentManagerMessage::ShowPromotionWizard) => {
+                let academic_year = current_term(Local::now().date_naive(), &self.terms)
+                    .map(|term| term.academic_year);
+                self.student_manager.open_promotion_wizard(academic_year);
+                Task::none()
+            },
+            DashboardMessage::StudentManager(StudentManagerMessage::ShowEnrolment) => {
+                self.student_manager.open_enrolment(self.terms.clone())
+                    .map(|msg| Message::Dashboard(DashboardMessage::StudentManager(msg)))
+            },
+            DashboardMessage::StudentManager(StudentManagerMessage::SubmitPromotion) => {
+                self.student_manager.submit_promotion()
+                    .map(|msg| Message::Dashboard(DashboardMessage::StudentManager(msg)))
+            },
+            DashboardMessage::StudentManager(StudentManagerMessage::ConfirmMerge) => {
+                self.student_manager.merge()
+                    .map(|msg| Message::Dashboard(DashboardMessage::StudentManager(msg)))
+            },
+            DashboardMessage::StudentManager(StudentManagerMessage::StudentCreated(saved)) => {
+                let queued = matches!(saved, Saved::Queued(_));
+                let task = self.student_manager.update(StudentManagerMessage::StudentCreated(saved))
+                    .map(|msg| Message::Dashboard(DashboardMessage::StudentManager(msg)));
+                if queued { Task::batch(vec![task, self.refresh_queue()]) } else { task }
+            },
             DashboardMessage::StudentManager(student_manager_msg) => {
                 self.student_manager.update(student_manager_msg).map(|msg| Message::Dashboard(DashboardMessage::StudentManager(msg)))
             },
@@ -270,102 +1006,1483 @@ impl DashboardState {
                 self.student_manager.students = students;
                 Task::perform(async { Message::AppLoading(false) }, |m| m)
             },
-            // Placeholder for other message types
-            _ => Task::none(),
-        }
-    }
-
-    fn view(&self) -> Element<'_, Message> {
-        use iced::widget::text;
-        text("Welcome to the Dashboard!").into()
-    }
-}
-
-#[derive(Debug, Default)]
-pub enum DashboardView {
-    #[default]
-    Home,
-    StudentManager,
-    TeachingPeriodManager,
-    PaymentTrackingManager,
-    UserAccessManager,
-    ReportsAnalytics,
-}
-
-#[derive(Debug, Clone)]
-pub enum DashboardMessage {
-    NavigateToHome,
-    NavigateToStudentManager,
-    NavigateToTeachingPeriodManager,
-    NavigateToPaymentTracking,
-    NavigateToReportsAnalytics,
-    NavigateToUserAccessManager,
-
-    ChangePassword,
-    PasswordChanged(Result<(), PasswordChangeError>), 
-
-    Loading(bool),              
-    ErrorOccurred(AppError),
-
-    StudentManager(StudentManagerMessage),
-    StudentsFetched(Vec<Student>),
-    TeachingPeriod(TeachingPeriodMessage),
-    PaymentTracking(PaymentTrackingMessage),
-    UserAccess(UserAccessMessage),
-    ReportsAnalytics(ReportsAnalyticsMessage),
-}
-
-#[derive(Debug, Clone)]
-pub enum StudentManagerMessage {
-    FirstNameInputChanged(String),
-    SurnameInputChanged(String),
-    OtherNamesInputChanged(String),
-    GenderSelected(Gender),
-    ClassLevelSelected(ClassLevel),
-    SubmitNewStudent,      
-    StudentCreated(Student),
-    StudentCreationFailed(String),
-    FetchStudents,         
-    FetchedStudents(Vec<Student>),
-    StudentFetchFailed(AppError),
-    ClearForm,
-    ClearSuccessMessage,
-
-    EditStudent(Uuid),
-    EditFirstNameInputChanged(String),
-    EditSurnameInputChanged(String),
-    EditOtherNamesInputChanged(String),
-    EditGenderSelected(Gender),
-    EditClassLevelSelected(ClassLevel),
-    UpdateStudent(Uuid),
-    DeleteStudent(Uuid),
-    StudentUpdated(Result<Student, String>),
-    StudentDeleted(Result<(), String>),
-    CancelEdit,
-
-    GenerateExcelTemplate,
-    TemplateGenerationResult(Result<(), AppError>),
-    ImportStudentsFromExcel,
-    ImportResult(Result<(), AppError>),
-}
-  
-#[derive(Debug, Clone)]
-pub struct StudentManagerState {
-    pub first_name_input: String,
-    pub surname_input: String,
-    pub other_names_input: String,
-    pub selected_gender: Option<Gender>,
-    pub selected_class_level: Option<ClassLevel>,
-    pub students: Vec<Student>,
-    pub show_success_message: bool,
+            DashboardMessage::TermsLoaded(result) => {
+                match result {
+                    Ok(terms) => {
+                        self.terms = terms;
+                        Task::none()
+                    },
+                    Err(e) => Task::perform(async { e }, |e| Message::Dashboard(DashboardMessage::ErrorOccurred(e))),
+                }
+            },
+            DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::DateRange(range_msg)) => {
+                let is_preset = matches!(range_msg, DateRangeMessage::PresetSelected(_));
+                self.reports_analytics.date_range.update(range_msg, &self.terms);
+                self.reports_analytics.error_message = None;
+                if is_preset {
+                    self.reports_analytics.update(ReportsAnalyticsMessage::ApplyReportFilters)
+                        .map(|msg| Message::Dashboard(DashboardMessage::ReportsAnalytics(msg)))
+                } else {
+                    Task::none()
+                }
+            },
+            DashboardMessage::PaymentTracking(PaymentTrackingMessage::DateRange(range_msg)) => {
+                let is_preset = matches!(range_msg, DateRangeMessage::PresetSelected(_));
+                self.payment_tracking.date_range.update(range_msg, &self.terms);
+                if is_preset {
+                    self.payment_tracking.update(PaymentTrackingMessage::LoadPayments)
+                        .map(|msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg)))
+                } else {
+                    Task::none()
+                }
+            },
+            DashboardMessage::PaymentTracking(PaymentTrackingMessage::ShowExemptionDialog(student_id)) => {
+                self.payment_tracking.open_exemption(student_id, &self.active_user.role, &self.terms);
+                Task::none()
+            },
+            DashboardMessage::PaymentTracking(PaymentTrackingMessage::SubmitExemption) => {
+                self.payment_tracking.submit_exemption()
+                    .map(|msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg)))
+            },
+            DashboardMessage::PaymentTracking(PaymentTrackingMessage::ShowInstallmentPlan(student_id)) => {
+                self.payment_tracking.open_installment_plan(student_id, &self.terms);
+                Task::none()
+            },
+            DashboardMessage::PaymentTracking(PaymentTrackingMessage::SaveInstallmentPlan) => {
+                self.payment_tracking.save_installment_plan()
+                    .map(|msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg)))
+            },
+            DashboardMessage::PaymentTracking(PaymentTrackingMessage::SubmitPayment) => {
+                let term_id = current_term(Local::now().date_naive(), &self.terms).map(|term| term.id);
+                self.payment_tracking.submit_payment(term_id)
+                    .map(|msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg)))
+            },
+            DashboardMessage::PaymentTracking(PaymentTrackingMessage::PaymentRecorded(result)) => {
+                let queued = matches!(result, Ok(Saved::Queued(_)));
+                let task = self.payment_tracking.update(PaymentTrackingMessage::PaymentRecorded(result))
+                    .map(|msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg)));
+                if queued { Task::batch(vec![task, self.refresh_queue()]) } else { task }
+            },
+            DashboardMessage::UserAccess(
+                UserAccessMessage::SubmitNewUser
+                | UserAccessMessage::SubmitEditUser
+                | UserAccessMessage::ConfirmDeactivateUser
+                | UserAccessMessage::ReactivateUser(_)
+                | UserAccessMessage::ConfirmResetPassword,
+            ) if !matches!(self.active_user.role, UserRole::Admin) => {
+                self.user_access_manager.error_message = Some("Only an admin can manage users.".to_string());
+                Task::none()
+            },
+            DashboardMessage::UserAccess(UserAccessMessage::SubmitNewUser) => {
+                self.user_access_manager.submit_new_user()
+                    .map(|msg| Message::Dashboard(DashboardMessage::UserAccess(msg)))
+            },
+            DashboardMessage::UserAccess(UserAccessMessage::SubmitEditUser) => {
+                self.user_access_manager.submit_edit_user()
+                    .map(|msg| Message::Dashboard(DashboardMessage::UserAccess(msg)))
+            },
+            DashboardMessage::UserAccess(UserAccessMessage::ConfirmDeactivateUser) => {
+                let Some(id) = self.user_access_manager.selected_user_id.clone() else {
+                    return Task::none();
+                };
+                self.user_access_manager.set_active(&id, false, self.active_user.id)
+                    .map(|msg| Message::Dashboard(DashboardMessage::UserAccess(msg)))
+            },
+            DashboardMessage::UserAccess(UserAccessMessage::ReactivateUser(id)) => {
+                self.user_access_manager.set_active(&id, true, self.active_user.id)
+                    .map(|msg| Message::Dashboard(DashboardMessage::UserAccess(msg)))
+            },
+            DashboardMessage::UserAccess(UserAccessMessage::ConfirmResetPassword) => {
+                self.user_access_manager.reset_password()
+                    .map(|msg| Message::Dashboard(DashboardMessage::UserAccess(msg)))
+            },
+            DashboardMessage::UserAccess(user_access_msg) => {
+                self.user_access_manager.update(user_access_msg)
+                    .map(|msg| Message::Dashboard(DashboardMessage::UserAccess(msg)))
+            },
+            DashboardMessage::PaymentTracking(payment_msg) => {
+                self.payment_tracking.update(payment_msg)
+                    .map(|msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg)))
+            },
+            DashboardMessage::TeachingPeriod(TeachingPeriodMessage::ImportResult(result)) => {
+                let queued = matches!(result, Ok(Saved::Queued(_)));
+                let task = self.teaching_period_manager.update(TeachingPeriodMessage::ImportResult(result))
+                    .map(|msg| Message::Dashboard(DashboardMessage::TeachingPeriod(msg)));
+                if queued { Task::batch(vec![task, self.refresh_queue()]) } else { task }
+            },
+            DashboardMessage::TeachingPeriod(period_msg) => {
+                self.teaching_period_manager.update(period_msg)
+                    .map(|msg| Message::Dashboard(DashboardMessage::TeachingPeriod(msg)))
+            },
+            DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::ExportReport) => {
+                let generated_by = format!("{} {} ({})",
+                    self.active_user.name.first_name,
+                    self.active_user.name.surname,
+                    self.active_user.username);
+                self.reports_analytics.export(&generated_by)
+                    .map(|msg| Message::Dashboard(DashboardMessage::ReportsAnalytics(msg)))
+            },
+            DashboardMessage::ReportsAnalytics(reports_msg) => {
+                self.reports_analytics.update(reports_msg)
+                    .map(|msg| Message::Dashboard(DashboardMessage::ReportsAnalytics(msg)))
+            },
+            DashboardMessage::SyncTick => {
+                if self.sync.pending_count() > 0 {
+                    self.start_sync()
+                } else {
+                    Task::none()
+                }
+            },
+            DashboardMessage::SyncNow => {
+                self.sync.message = None;
+                self.start_sync()
+            },
+            DashboardMessage::SyncFinished(result) => {
+                self.sync.is_syncing = false;
+                match result {
+                    Ok(report) => {
+                        self.sync.store = report.store;
+                        self.sync.is_offline = report.offline;
+                        self.sync.message = match (report.applied, report.conflicts) {
+                            (0, 0) => None,
+                            (applied, 0) => Some(format!("Sent {} saved changes to the server.", applied)),
+                            (applied, conflicts) => Some(format!(
+                                "Sent {} saved changes; the server turned down {}. Review them below.",
+                                applied, conflicts,
+                            )),
+                        };
+                        if report.applied > 0 {
+                            // Swap provisional records for the server's copies
+                            self.student_manager.update(StudentManagerMessage::FetchStudents)
+                                .map(|msg| Message::Dashboard(DashboardMessage::StudentManager(msg)))
+                        } else {
+                            Task::none()
+                        }
+                    }
+                    Err(e) => {
+                        self.sync.message = Some(format!("Sync failed: {}", e));
+                        Task::none()
+                    }
+                }
+            },
+            DashboardMessage::QueueUpdated(result) => {
+                match result {
+                    Ok(store) => self.sync.store = store,
+                    Err(e) => self.sync.message = Some(format!("Could not read saved changes: {}", e)),
+                }
+                Task::none()
+            },
+            DashboardMessage::ShowSyncStatus => {
+                self.sync.show_panel = true;
+                self.backups.show_panel = false;
+                self.refresh_queue()
+            },
+            DashboardMessage::CloseSyncStatus => {
+                self.sync.show_panel = false;
+                Task::none()
+            },
+            DashboardMessage::RetryWrite(id) => {
+                match offline::retry(id) {
+                    Ok(store) => {
+                        self.sync.store = store;
+                        self.start_sync()
+                    }
+                    Err(e) => {
+                        self.sync.message = Some(format!("Could not retry: {}", e));
+                        Task::none()
+                    }
+                }
+            },
+            DashboardMessage::DiscardWrite(id) => {
+                match offline::discard(id) {
+                    Ok(store) => {
+                        self.sync.store = store;
+                        self.student_manager.update(StudentManagerMessage::FetchStudents)
+                            .map(|msg| Message::Dashboard(DashboardMessage::StudentManager(msg)))
+                    }
+                    Err(e) => {
+                        self.sync.message = Some(format!("Could not discard: {}", e));
+                        Task::none()
+                    }
+                }
+            },
+            DashboardMessage::ShowBackups => {
+                self.backups.show_panel = true;
+                self.sync.show_panel = false;
+                self.backups.list()
+            },
+            DashboardMessage::CloseBackups => {
+                self.backups.show_panel = false;
+                self.backups.confirm_restore = None;
+                Task::none()
+            },
+            DashboardMessage::BackUpNow => {
+                if self.backups.is_backing_up {
+                    return Task::none();
+                }
+                if !matches!(self.active_user.role, UserRole::Admin) {
+                    self.backups.error = Some("Only an admin can back up the school's data.".to_string());
+                    return Task::none();
+                }
+                if self.backups.encrypt && self.backups.passphrase.is_none() {
+                    self.backups.error = Some("Set a backup passphrase before backing up.".to_string());
+                    return Task::none();
+                }
+                self.backups.is_backing_up = true;
+                self.backups.error = None;
+                let passphrase = self.backups.passphrase.clone().filter(|_| self.backups.encrypt);
+                Task::perform(
+                    backup::create_backup(self.backups.folder.clone(), self.backups.retention, passphrase),
+                    |result| Message::Dashboard(DashboardMessage::BackupFinished(result)),
+                )
+            },
+            DashboardMessage::BackupFinished(result) => {
+                self.backups.is_backing_up = false;
+                match result {
+                    Ok(entry) => {
+                        self.backups.message = Some(format!("Backed up to {}.", entry.file_name));
+                        self.backups.list()
+                    }
+                    Err(e) => {
+                        self.backups.error = Some(format!("Backup failed: {}", e));
+                        Task::none()
+                    }
+                }
+            },
+            DashboardMessage::BackupsListed(result) => {
+                match result {
+                    Ok(entries) => self.backups.entries = entries,
+                    Err(e) => self.backups.error = Some(e.to_string()),
+                }
+                Task::none()
+            },
+            DashboardMessage::ChooseBackupFolder => {
+                Task::perform(
+                    async { rfd::FileDialog::new().pick_folder() },
+                    |folder| Message::Dashboard(DashboardMessage::BackupFolderChosen(folder)),
+                )
+            },
+            DashboardMessage::BackupFolderChosen(folder) => {
+                let Some(folder) = folder else {
+                    return Task::none();
+                };
+                self.backups.folder = folder;
+                self.backups.entries.clear();
+                self.backups.confirm_restore = None;
+                self.backups.message = None;
+                self.backups.error = None;
+                self.backups.list()
+            },
+            DashboardMessage::BackupPassphraseChanged(value) => {
+                self.backups.passphrase_input = value;
+                self.backups.error = None;
+                Task::none()
+            },
+            DashboardMessage::SaveBackupPassphrase => {
+                if self.backups.passphrase_input.chars().count() < backup::MIN_PASSPHRASE_LEN {
+                    self.backups.error = Some(format!(
+                        "The passphrase must be at least {} characters.",
+                        backup::MIN_PASSPHRASE_LEN,
+                    ));
+                } else {
+                    let passphrase = std::mem::take(&mut self.backups.passphrase_input);
+                    match settings::save_backup_passphrase(&passphrase) {
+                        Ok(()) => {
+                            self.backups.passphrase = Some(passphrase);
+                            self.backups.message = Some("Backup passphrase saved. Keep a copy somewhere safe; backups can't be restored without it.".to_string());
+                        }
+                        Err(e) => self.backups.error = Some(e.to_string()),
+                    }
+                }
+                Task::none()
+            },
+            DashboardMessage::SelectRestore(entry) => {
+                // Check the archive up front so a damaged one is never offered for
+                // restore. Encrypted ones are only fully checked once the passphrase is in.
+                let checked = if entry.encrypted {
+                    backup::check_file(&self.backups.folder, &entry).map(|_| ())
+                } else {
+                    backup::verify(&self.backups.folder, &entry, None).map(|_| ())
+                };
+                match checked {
+                    Ok(()) => {
+                        self.backups.error = None;
+                        self.backups.restore_passphrase.clear();
+                        self.backups.restore_key = Uuid::new_v4();
+                        self.backups.confirm_restore = Some(entry);
+                    }
+                    Err(e) => self.backups.error = Some(e.to_string()),
+                }
+                Task::none()
+            },
+            DashboardMessage::RestorePassphraseChanged(value) => {
+                self.backups.restore_passphrase = value;
+                self.backups.error = None;
+                Task::none()
+            },
+            DashboardMessage::CancelRestore => {
+                self.backups.confirm_restore = None;
+                self.backups.restore_passphrase.clear();
+                Task::none()
+            },
+            DashboardMessage::ConfirmRestore => {
+                if !matches!(self.active_user.role, UserRole::Admin) {
+                    self.backups.error = Some("Only an admin can restore a backup.".to_string());
+                    return Task::none();
+                }
+                let Some(entry) = self.backups.confirm_restore.clone().filter(|_| !self.backups.is_restoring) else {
+                    return Task::none();
+                };
+                let passphrase = if entry.encrypted {
+                    if self.backups.restore_passphrase.is_empty() {
+                        self.backups.error = Some("Enter the passphrase this backup was made with.".to_string());
+                        return Task::none();
+                    }
+                    Some(self.backups.restore_passphrase.clone())
+                } else {
+                    None
+                };
+                self.backups.is_restoring = true;
+                self.backups.error = None;
+                Task::perform(
+                    backup::restore(self.backups.folder.clone(), entry, passphrase, self.backups.restore_key),
+                    |result| Message::Dashboard(DashboardMessage::RestoreFinished(result)),
+                )
+            },
+            DashboardMessage::RestoreFinished(result) => {
+                self.backups.is_restoring = false;
+                match result {
+                    Ok(()) => {
+                        self.backups.restore_passphrase.clear();
+                        let restored = self.backups.confirm_restore.take();
+                        self.backups.message = restored.map(|entry| format!("Restored data from {}.", entry.file_name));
+                        Task::batch(vec![
+                            self.load(),
+                            self.student_manager.update(StudentManagerMessage::FetchStudents)
+                                .map(|msg| Message::Dashboard(DashboardMessage::StudentManager(msg))),
+                        ])
+                    }
+                    // Confirming again resends under the same key, which reports how the first one ended
+                    Err(AppError::Unreachable(e)) => {
+                        self.backups.error = Some(e);
+                        Task::none()
+                    }
+                    Err(e) => {
+                        self.backups.error = Some(format!("Restore failed: {}", e));
+                        Task::none()
+                    }
+                }
+            },
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        use iced::widget::text;
+        text("Welcome to the Dashboard!").into()
+    }
+}
+
+#[derive(Debug, Default)]
+pub enum DashboardView {
+    #[default]
+    Home,
+    StudentManager,
+    TeachingPeriodManager,
+    PaymentTrackingManager,
+    UserAccessManager,
+    ReportsAnalytics,
+    TeacherPortal,
+    PeriodReview,
+    Settings,
+}
+
+#[derive(Debug, Clone)]
+pub enum DashboardMessage {
+    NavigateToHome,
+    NavigateToStudentManager,
+    NavigateToTeachingPeriodManager,
+    NavigateToPaymentTracking,
+    NavigateToReportsAnalytics,
+    NavigateToUserAccessManager,
+    NavigateToTeacherPortal,
+    NavigateToPeriodReview,
+    NavigateToSettings,
+
+    LockScreen,
+    UpdateUnlockPassword(String),
+    Unlock,
+    Unlocked(Result<(), LoginError>),
+
+    ChangePassword,
+    UpdateCurrentPassword(String),
+    UpdateNewPassword(String),
+    UpdateConfirmPassword(String),
+    SubmitPasswordChange,
+    CancelPasswordChange,
+    PasswordChanged(Result<(), PasswordChangeError>), 
+
+    Loading(bool),              
+    ErrorOccurred(AppError),
+
+    Home(HomeMessage),
+    TeacherPortal(TeacherPortalMessage),
+    PeriodReview(PeriodReviewMessage),
+    StudentManager(StudentManagerMessage),
+    StudentsFetched(Vec<Student>),
+    TermsLoaded(Result<Vec<Term>, AppError>),
+    TeachingPeriod(TeachingPeriodMessage),
+    PaymentTracking(PaymentTrackingMessage),
+    UserAccess(UserAccessMessage),
+    ReportsAnalytics(ReportsAnalyticsMessage),
+    Settings(SettingsMessage),
+    SchoolSettingsLoaded(Result<SchoolSettings, AppError>),
+    SwitchSchool(School),
+
+    SyncTick,
+    SyncNow,
+    SyncFinished(Result<SyncReport, AppError>),
+    QueueUpdated(Result<LocalStore, AppError>),
+    ShowSyncStatus,
+    CloseSyncStatus,
+    RetryWrite(Uuid),
+    DiscardWrite(Uuid),
+
+    ShowBackups,
+    CloseBackups,
+    BackUpNow,
+    BackupFinished(Result<BackupEntry, AppError>),
+    BackupsListed(Result<Vec<BackupEntry>, AppError>),
+    ChooseBackupFolder,
+    BackupFolderChosen(Option<PathBuf>),
+    BackupPassphraseChanged(String),
+    SaveBackupPassphrase,
+    SelectRestore(BackupEntry),
+    RestorePassphraseChanged(String),
+    CancelRestore,
+    ConfirmRestore,
+    RestoreFinished(Result<(), AppError>),
+}
+
+#[derive(Debug, Clone)]
+pub enum SettingsMessage {
+    CurrencySymbolChanged(String),
+    DateFormatSelected(String),
+    PaymentMethodSelected(String),
+    AdminShareChanged(String),
+    StaffShareChanged(String),
+    TeacherShareChanged(String),
+    PasswordMinLengthChanged(String),
+    PasswordHistoryChanged(String),
+    MaxFailedLoginsChanged(String),
+    LockoutMinutesChanged(String),
+    SiblingDiscountChanged(String),
+    BackupFrequencySelected(String),
+    AutoBackupToggled(bool),
+    BackupRetentionChanged(String),
+    EncryptBackupsToggled(bool),
+    LockAfterChanged(String),
+    LogoutAfterChanged(String),
+    ThemeSelected(AppTheme),
+    Save,
+    SchoolSettingsSaved(Result<SchoolSettings, AppError>),
+    Discard,
+}
+
+/// The change-password dialog for the logged-in user
+#[derive(Debug, Clone)]
+pub struct ChangePasswordForm {
+    pub current_password: String,
+    pub new_password: String,
+    pub confirm_password: String,
+    /// Idempotency key for the change, kept until it succeeds
+    pub request_key: Uuid,
+    pub is_saving: bool,
+    pub error: Option<PasswordChangeError>,
+}
+
+impl Default for ChangePasswordForm {
+    fn default() -> Self {
+        Self {
+            current_password: String::new(),
+            new_password: String::new(),
+            confirm_password: String::new(),
+            request_key: Uuid::new_v4(),
+            is_saving: false,
+            error: None,
+        }
+    }
+}
+
+impl ChangePasswordForm {
+    /// Checks what can be checked here; reuse of an old password is left to the backend.
+    fn payload(&self, policy: &PasswordPolicy) -> Result<ChangePasswordPayload, PasswordChangeError> {
+        if self.current_password.is_empty() {
+            return Err(PasswordChangeError::CurrentPasswordIncorrect);
+        }
+        policy.check(&self.new_password).map_err(PasswordChangeError::NewPasswordInvalid)?;
+        if self.new_password != self.confirm_password {
+            return Err(PasswordChangeError::NewPasswordInvalid("The new passwords don't match.".to_string()));
+        }
+        if self.new_password == self.current_password {
+            return Err(PasswordChangeError::NewPasswordInvalid(
+                "The new password must be different from the current one.".to_string(),
+            ));
+        }
+        Ok(ChangePasswordPayload {
+            current_password: self.current_password.clone(),
+            new_password: self.new_password.clone(),
+        })
+    }
+}
+
+/// Settings being edited. School-wide fields are read-only for non-admins.
+#[derive(Debug, Clone)]
+pub struct SettingsForm {
+    pub currency_symbol: String,
+    pub date_format: String,
+    pub default_payment_method: String,
+    pub admin_share: String,
+    pub staff_share: String,
+    pub teacher_share: String,
+    pub password_min_length: String,
+    pub password_history: String,
+    pub max_failed_logins: String,
+    pub lockout_minutes: String,
+    pub sibling_discount: String,
+    pub backup_frequency: String,
+    pub auto_backup_enabled: bool,
+    pub backup_retention: String,
+    pub encrypt_backups: bool,
+    pub theme: AppTheme,
+    pub lock_after: String,
+    pub logout_after: String,
+    pub can_edit_school: bool,
+    /// Idempotency key for saving the school-wide settings, renewed after each save
+    pub request_key: Uuid,
+    pub is_saving: bool,
+    pub error: Option<String>,
+    pub message: Option<String>,
+}
+
+fn parse_percentage(label: &str, value: &str) -> Result<f64, String> {
+    value.trim().parse::<f64>().map_err(|_| format!("{} must be a percentage.", label))
+}
+
+impl SettingsForm {
+    pub fn new(settings: &AppSettings, can_edit_school: bool) -> Self {
+        let school = settings.school();
+        Self {
+            currency_symbol: school.currency_symbol,
+            date_format: school.date_format,
+            default_payment_method: school.default_payment_method,
+            admin_share: school.admin_share_percentage.to_string(),
+            staff_share: school.staff_share_percentage.to_string(),
+            teacher_share: school.teacher_share_percentage.to_string(),
+            password_min_length: school.password_policy.min_length.to_string(),
+            password_history: school.password_policy.history_count.to_string(),
+            max_failed_logins: school.lockout_policy.max_failed_attempts.to_string(),
+            lockout_minutes: school.lockout_policy.lockout_minutes.to_string(),
+            sibling_discount: school.sibling_discount_percentage.to_string(),
+            backup_frequency: settings.backup_frequency.clone(),
+            auto_backup_enabled: settings.auto_backup_enabled,
+            backup_retention: settings.backup_retention.to_string(),
+            encrypt_backups: settings.encrypt_backups,
+            theme: settings.theme.clone(),
+            lock_after: settings.lock_after_minutes.to_string(),
+            logout_after: settings.logout_after_minutes.to_string(),
+            can_edit_school,
+            request_key: Uuid::new_v4(),
+            is_saving: false,
+            error: None,
+            message: None,
+        }
+    }
+
+    /// The school-wide values, range-checked by `SchoolSettings::validate`
+    /// as the backend will check them
+    pub fn school(&self) -> Result<SchoolSettings, String> {
+        let whole = |value: &str, message: &str| value.trim().parse::<u32>().map_err(|_| message.to_string());
+        let school = SchoolSettings {
+            currency_symbol: self.currency_symbol.trim().to_string(),
+            date_format: self.date_format.clone(),
+            default_payment_method: self.default_payment_method.clone(),
+            admin_share_percentage: parse_percentage("Admin share", &self.admin_share)?,
+            staff_share_percentage: parse_percentage("Staff share", &self.staff_share)?,
+            teacher_share_percentage: parse_percentage("Teacher share", &self.teacher_share)?,
+            password_policy: PasswordPolicy {
+                min_length: whole(&self.password_min_length, "Minimum password length must be a whole number.")? as usize,
+                history_count: whole(&self.password_history, "Blocked previous passwords must be a whole number.")? as usize,
+            },
+            lockout_policy: LockoutPolicy {
+                max_failed_attempts: whole(&self.max_failed_logins, "Failed logins before a lockout must be a whole number.")?,
+                lockout_minutes: whole(&self.lockout_minutes, "Lockout minutes must be a whole number.")?,
+            },
+            sibling_discount_percentage: parse_percentage("Sibling discount", &self.sibling_discount)?,
+        };
+        school.validate()?;
+        Ok(school)
+    }
+
+    /// `current` with this computer's settings from the form applied;
+    /// school-wide values are left for the backend to confirm.
+    pub fn apply_local(&self, current: &AppSettings) -> Result<AppSettings, String> {
+        let backup_retention = match self.backup_retention.trim().parse::<usize>() {
+            Ok(count) if count >= 1 => count,
+            _ => return Err("Keep at least one backup.".to_string()),
+        };
+        let lock_after_minutes = self.lock_after.trim().parse::<u32>()
+            .map_err(|_| "Lock after must be a number of minutes, or 0 to never lock.".to_string())?;
+        let logout_after_minutes = self.logout_after.trim().parse::<u32>()
+            .map_err(|_| "Log out after must be a number of minutes, or 0 to never log out.".to_string())?;
+        if lock_after_minutes > 0 && logout_after_minutes > 0 && logout_after_minutes <= lock_after_minutes {
+            return Err("Logging out must take longer than locking the screen.".to_string());
+        }
+        Ok(AppSettings {
+            backup_frequency: self.backup_frequency.clone(),
+            auto_backup_enabled: self.auto_backup_enabled,
+            backup_retention,
+            encrypt_backups: self.encrypt_backups,
+            theme: self.theme.clone(),
+            lock_after_minutes,
+            logout_after_minutes,
+            ..current.clone()
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum HomeMessage {
+    LoadHome,
+    SummaryLoaded(Result<HomeSummary, AppError>),
+    ActivityLoaded(Result<Vec<RecentActivity>, AppError>),
+}
+
+/// Number of audit log entries shown in the home activity feed
+const RECENT_ACTIVITY_LIMIT: u32 = 15;
+
+#[derive(Debug)]
+pub struct HomeState {
+    pub summary: Option<HomeSummary>,
+    pub recent_activities: Vec<RecentActivity>,
+    pub is_loading: bool,
+    pub error_message: Option<String>,
+}
+
+impl HomeState {
+    pub fn new() -> Self {
+        Self {
+            summary: None,
+            recent_activities: Vec::new(),
+            is_loading: false,
+            error_message: None,
+        }
+    }
+
+    pub fn update(&mut self, message: HomeMessage) -> Task<HomeMessage> {
+        match message {
+            HomeMessage::LoadHome => {
+                self.is_loading = self.summary.is_none();
+                self.error_message = None;
+                Task::batch(vec![
+                    Task::perform(routes::fetch_home_summary(), HomeMessage::SummaryLoaded),
+                    Task::perform(
+                        routes::fetch_recent_activity(RECENT_ACTIVITY_LIMIT),
+                        HomeMessage::ActivityLoaded,
+                    ),
+                ])
+            },
+            HomeMessage::SummaryLoaded(result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(summary) => self.summary = Some(summary),
+                    Err(e) => self.error_message = Some(format!("Failed to load dashboard: {}", e)),
+                }
+                Task::none()
+            },
+            HomeMessage::ActivityLoaded(result) => {
+                match result {
+                    Ok(activities) => self.recent_activities = activities,
+                    Err(e) => self.error_message = Some(format!("Failed to load recent activity: {}", e)),
+                }
+                Task::none()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TeacherPortalMessage {
+    LoadPortal,
+    PortalLoaded(Result<TeacherPortal, AppError>),
+    ShowDispute(Uuid),
+    UpdateDisputeReason(String),
+    SubmitDispute,
+    CancelDispute,
+    PeriodDisputed(Result<TeacherPeriod, AppError>),
+    ExportPayslip(Uuid),
+    PayslipExported(Result<PathBuf, AppError>),
+}
+
+/// A teacher's own periods, projected share and payslips
+#[derive(Debug, Default)]
+pub struct TeacherPortalState {
+    pub portal: Option<TeacherPortal>,
+    /// Rejected period the teacher is writing a dispute for
+    pub disputing: Option<Uuid>,
+    pub dispute_reason: String,
+    /// Idempotency key for the dispute being written, kept until it is sent
+    pub dispute_key: Uuid,
+    pub date_format: String,
+    pub currency_symbol: String,
+    pub is_loading: bool,
+    pub is_saving: bool,
+    pub error_message: Option<String>,
+    pub message: Option<String>,
+}
+
+impl TeacherPortalState {
+    pub fn disputed_period(&self) -> Option<&TeacherPeriod> {
+        let id = self.disputing?;
+        self.portal.as_ref()?.periods.iter().find(|period| period.id == id)
+    }
+
+    pub fn submit_dispute(&mut self) -> Task<TeacherPortalMessage> {
+        let Some(period) = self.disputed_period() else {
+            return Task::none();
+        };
+        if period.verification != PeriodVerification::Rejected {
+            self.error_message = Some("Only a rejected period can be disputed.".to_string());
+            return Task::none();
+        }
+        let reason = self.dispute_reason.trim().to_string();
+        if reason.is_empty() {
+            self.error_message = Some("Say why the period should be accepted.".to_string());
+            return Task::none();
+        }
+        let id = period.id;
+        self.is_saving = true;
+        self.error_message = None;
+        Task::perform(
+            routes::dispute_period(id, DisputePeriodPayload { reason }, self.dispute_key),
+            TeacherPortalMessage::PeriodDisputed,
+        )
+    }
+
+    pub fn update(&mut self, message: TeacherPortalMessage) -> Task<TeacherPortalMessage> {
+        match message {
+            TeacherPortalMessage::LoadPortal => {
+                // Handled by DashboardState, which knows the logged-in user
+                Task::none()
+            },
+            TeacherPortalMessage::PortalLoaded(result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(portal) => self.portal = Some(portal),
+                    Err(e) => self.error_message = Some(format!("Failed to load your periods: {}", e)),
+                }
+                Task::none()
+            },
+            TeacherPortalMessage::ShowDispute(id) => {
+                self.disputing = Some(id);
+                self.dispute_key = Uuid::new_v4();
+                self.dispute_reason.clear();
+                self.error_message = None;
+                self.message = None;
+                Task::none()
+            },
+            TeacherPortalMessage::UpdateDisputeReason(reason) => {
+                self.dispute_reason = reason;
+                Task::none()
+            },
+            TeacherPortalMessage::SubmitDispute => {
+                // Handled by DashboardState, which knows the logged-in user
+                Task::none()
+            },
+            TeacherPortalMessage::CancelDispute => {
+                self.disputing = None;
+                self.dispute_reason.clear();
+                self.error_message = None;
+                Task::none()
+            },
+            TeacherPortalMessage::PeriodDisputed(result) => {
+                self.is_saving = false;
+                match result {
+                    Ok(period) => {
+                        if let Some(existing) = self.portal.as_mut()
+                            .and_then(|portal| portal.periods.iter_mut().find(|p| p.id == period.id))
+                        {
+                            *existing = period;
+                        }
+                        self.disputing = None;
+                        self.dispute_reason.clear();
+                        self.message = Some("Dispute sent. The committee will look at the period again.".to_string());
+                    },
+                    Err(e) => self.error_message = Some(format!("Dispute failed: {}", e)),
+                }
+                Task::none()
+            },
+            TeacherPortalMessage::ExportPayslip(payout_id) => {
+                let Some(portal) = &self.portal else {
+                    return Task::none();
+                };
+                let Some(payslip) = portal.payslips.iter().find(|p| p.payout_id == payout_id) else {
+                    return Task::none();
+                };
+                self.message = None;
+                let document = ReportDocument::payslip(&portal.teacher_name, payslip, &self.date_format, &self.currency_symbol);
+                Task::perform(
+                    async move { export::pick_path_and_export(document, ExportFormat::Pdf) },
+                    TeacherPortalMessage::PayslipExported,
+                )
+            },
+            TeacherPortalMessage::PayslipExported(result) => {
+                match result {
+                    Ok(path) => self.message = Some(format!("Payslip saved to {}", path.display())),
+                    Err(e) => self.error_message = Some(format!("Export failed: {}", e)),
+                }
+                Task::none()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PeriodReviewMessage {
+    LoadPeriods,
+    PeriodsLoaded(Result<Vec<PeriodForReview>, AppError>),
+    TermSelected(Term),
+    VerificationSelected(PeriodVerification),
+    Verify(Uuid),
+    ShowRejection(Uuid),
+    UpdateRejectionReason(String),
+    SubmitRejection,
+    CancelRejection,
+    PeriodReviewed(Result<TeacherPeriod, AppError>),
+    ConfirmPayout,
+    CancelPayout,
+    RunPayout,
+    PayoutRun(Result<Disbursement, AppError>),
+}
+
+/// The committee's screen for verifying and rejecting logged periods,
+/// settling teachers' disputes and paying a reviewed term out
+#[derive(Debug)]
+pub struct PeriodReviewState {
+    pub terms: Vec<Term>,
+    pub term: Option<Term>,
+    /// Only periods in this state are listed
+    pub verification: PeriodVerification,
+    pub periods: Vec<PeriodForReview>,
+    /// Period the reviewer is writing a rejection for
+    pub rejecting: Option<Uuid>,
+    pub rejection_reason: String,
+    /// Idempotency key for the review being sent, new for each decision
+    pub review_key: Uuid,
+    /// Set while the reviewer is asked to confirm the payout
+    pub confirming_payout: bool,
+    pub payout_key: Uuid,
+    pub can_run_payouts: bool,
+    pub date_format: String,
+    pub currency_symbol: String,
+    pub is_loading: bool,
+    pub is_saving: bool,
+    pub error_message: Option<String>,
+    pub message: Option<String>,
+}
+
+impl Default for PeriodReviewState {
+    fn default() -> Self {
+        Self {
+            terms: Vec::new(),
+            term: None,
+            verification: PeriodVerification::Pending,
+            periods: Vec::new(),
+            rejecting: None,
+            rejection_reason: String::new(),
+            review_key: Uuid::new_v4(),
+            confirming_payout: false,
+            payout_key: Uuid::new_v4(),
+            can_run_payouts: false,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            currency_symbol: DEFAULT_CURRENCY_SYMBOL.to_string(),
+            is_loading: false,
+            is_saving: false,
+            error_message: None,
+            message: None,
+        }
+    }
+}
+
+impl PeriodReviewState {
+    /// Keeps the chosen term if it still exists, otherwise starts at the current one
+    pub fn set_terms(&mut self, terms: &[Term]) {
+        self.terms = terms.to_vec();
+        let chosen = self.term.as_ref().and_then(|term| terms.iter().find(|t| t.id == term.id));
+        self.term = chosen.or_else(|| current_term(Local::now().date_naive(), terms)).cloned();
+    }
+
+    pub fn rejected_period(&self) -> Option<&PeriodForReview> {
+        let id = self.rejecting?;
+        self.periods.iter().find(|row| row.period.id == id)
+    }
+
+    fn review(&mut self, id: Uuid, payload: ReviewPeriodPayload) -> Task<PeriodReviewMessage> {
+        self.is_saving = true;
+        self.error_message = None;
+        self.message = None;
+        Task::perform(routes::review_period(id, payload, self.review_key), PeriodReviewMessage::PeriodReviewed)
+    }
+
+    pub fn update(&mut self, message: PeriodReviewMessage) -> Task<PeriodReviewMessage> {
+        match message {
+            PeriodReviewMessage::LoadPeriods => {
+                self.is_loading = true;
+                self.error_message = None;
+                let query = PeriodReviewQuery {
+                    term_id: self.term.as_ref().map(|term| term.id),
+                    verification: Some(self.verification.clone()),
+                };
+                Task::perform(routes::fetch_periods_for_review(query), PeriodReviewMessage::PeriodsLoaded)
+            },
+            PeriodReviewMessage::PeriodsLoaded(result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(periods) => self.periods = periods,
+                    Err(e) => self.error_message = Some(format!("Failed to load periods: {}", e)),
+                }
+                Task::none()
+            },
+            PeriodReviewMessage::TermSelected(term) => {
+                self.term = Some(term);
+                self.confirming_payout = false;
+                self.message = None;
+                self.update(PeriodReviewMessage::LoadPeriods)
+            },
+            PeriodReviewMessage::VerificationSelected(verification) => {
+                self.verification = verification;
+                self.message = None;
+                self.update(PeriodReviewMessage::LoadPeriods)
+            },
+            PeriodReviewMessage::Verify(id) => {
+                if self.is_saving {
+                    return Task::none();
+                }
+                self.review_key = Uuid::new_v4();
+                self.review(id, ReviewPeriodPayload { verified: true, rejection_reason: None })
+            },
+            PeriodReviewMessage::ShowRejection(id) => {
+                self.rejecting = Some(id);
+                self.review_key = Uuid::new_v4();
+                self.rejection_reason.clear();
+                self.error_message = None;
+                self.message = None;
+                Task::none()
+            },
+            PeriodReviewMessage::UpdateRejectionReason(reason) => {
+                self.rejection_reason = reason;
+                Task::none()
+            },
+            PeriodReviewMessage::SubmitRejection => {
+                let Some(id) = self.rejecting else {
+                    return Task::none();
+                };
+                let reason = self.rejection_reason.trim().to_string();
+                if reason.is_empty() {
+                    self.error_message = Some("Give a reason for the rejection, so the teacher knows what to fix.".to_string());
+                    return Task::none();
+                }
+                self.review(id, ReviewPeriodPayload { verified: false, rejection_reason: Some(reason) })
+            },
+            PeriodReviewMessage::CancelRejection => {
+                self.rejecting = None;
+                self.rejection_reason.clear();
+                self.error_message = None;
+                Task::none()
+            },
+            PeriodReviewMessage::PeriodReviewed(result) => {
+                self.is_saving = false;
+                match result {
+                    Ok(period) => {
+                        let teacher = self.periods.iter()
+                            .find(|row| row.period.id == period.id)
+                            .map(|row| row.teacher_name.clone())
+                            .unwrap_or_default();
+                        self.message = Some(format!(
+                            "{} {} period of {} marked {}.",
+                            teacher,
+                            period.subject,
+                            format_date(period.date, &self.date_format),
+                            period.verification.to_string().to_lowercase(),
+                        ));
+                        // It no longer matches the filter, so it leaves the list
+                        if period.verification != self.verification {
+                            self.periods.retain(|row| row.period.id != period.id);
+                        } else if let Some(row) = self.periods.iter_mut().find(|row| row.period.id == period.id) {
+                            row.period = period;
+                        }
+                        self.rejecting = None;
+                        self.rejection_reason.clear();
+                    },
+                    Err(e) => self.error_message = Some(format!("Review failed: {}", e)),
+                }
+                Task::none()
+            },
+            PeriodReviewMessage::ConfirmPayout => {
+                if self.can_run_payouts && self.term.is_some() {
+                    self.confirming_payout = true;
+                    self.payout_key = Uuid::new_v4();
+                    self.error_message = None;
+                    self.message = None;
+                }
+                Task::none()
+            },
+            PeriodReviewMessage::CancelPayout => {
+                self.confirming_payout = false;
+                Task::none()
+            },
+            PeriodReviewMessage::RunPayout => {
+                let Some(term) = &self.term else {
+                    return Task::none();
+                };
+                if !self.can_run_payouts || self.is_saving {
+                    return Task::none();
+                }
+                self.is_saving = true;
+                self.error_message = None;
+                Task::perform(
+                    routes::run_payout(RunPayoutPayload { term_id: term.id }, self.payout_key),
+                    PeriodReviewMessage::PayoutRun,
+                )
+            },
+            PeriodReviewMessage::PayoutRun(result) => {
+                self.is_saving = false;
+                self.confirming_payout = false;
+                match result {
+                    Ok(disbursement) => {
+                        let term = self.terms.iter()
+                            .find(|term| term.id == disbursement.term_id)
+                            .map(|term| term.name.clone())
+                            .unwrap_or_default();
+                        self.message = Some(format!(
+                            "{} paid out: {} to teachers from {} collected. Their payslips are ready in their portal.",
+                            term,
+                            format_money(disbursement.teacher_base_share + disbursement.teacher_period_share, &self.currency_symbol),
+                            format_money(disbursement.total_revenue, &self.currency_symbol),
+                        ));
+                    },
+                    Err(e) => self.error_message = Some(format!("Payout failed: {}", e)),
+                }
+                Task::none()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StudentManagerMessage {
+    FirstNameInputChanged(String),
+    SurnameInputChanged(String),
+    OtherNamesInputChanged(String),
+    GenderSelected(Gender),
+    ClassLevelSelected(ClassLevel),
+    SubmitNewStudent,      
+    StudentCreated(Saved<Student>),
+    StudentCreationFailed(String),
+    FetchStudents,         
+    FetchedStudents(Vec<Student>),
+    StudentFetchFailed(AppError),
+    ClearForm,
+    ClearSuccessMessage,
+
+    EditStudent(Uuid),
+    EditFirstNameInputChanged(String),
+    EditSurnameInputChanged(String),
+    EditOtherNamesInputChanged(String),
+    EditGenderSelected(Gender),
+    EditClassLevelSelected(ClassLevel),
+    UpdateStudent(Uuid),
+    DeleteStudent(Uuid),
+    StudentUpdated(Result<Student, String>),
+    StudentDeleted(Result<(), String>),
+    CancelEdit,
+
+    GenerateExcelTemplate,
+    TemplateGenerationResult(Result<(), AppError>),
+    ImportStudentsFromExcel,
+    ImportPreviewLoaded(Result<ImportPreview, AppError>),
+    ImportPreview(ImportPreviewMessage),
+    ConfirmImport,
+    CancelImport,
+    ImportResult(Result<ImportSummary, AppError>),
+
+    FindDuplicates,
+    CloseDuplicates,
+    DismissDuplicate(Uuid, Uuid),
+    /// (surviving, duplicate)
+    SelectMerge(Uuid, Uuid),
+    CancelMerge,
+    ConfirmMerge,
+    MergeCompleted(Result<MergeStudentsResult, AppError>),
+
+    ShowPromotionWizard,
+    PromotionOutcomeChanged(Uuid, PromotionOutcome),
+    ReviewPromotion,
+    EditPromotion,
+    SubmitPromotion,
+    CancelPromotion,
+    PromotionCompleted(Result<PromotionSummary, AppError>),
+
+    ShowEnrolment,
+    EnrolmentTermSelected(Term),
+    EnrolmentsLoaded(Result<Vec<Enrolment>, AppError>),
+    ToggleEnrolmentSelection(Uuid),
+    EnrolmentJoinDateChanged(String),
+    EnrolSelected,
+    EnrolmentsSaved(Result<Vec<Enrolment>, AppError>),
+    StartWithdrawal(Uuid),
+    EnrolmentLeaveDateChanged(String),
+    ConfirmWithdrawal,
+    CancelWithdrawal,
+    EnrolmentUpdated(Result<Enrolment, AppError>),
+    CloseEnrolment,
+
+    ShowFamilies,
+    CloseFamilies,
+    GuardiansLoaded(Result<Vec<Guardian>, AppError>),
+    FamilySearchChanged(String),
+    NewGuardian,
+    EditGuardian(Uuid),
+    GuardianFirstNameChanged(String),
+    GuardianSurnameChanged(String),
+    GuardianRelationshipSelected(GuardianRelationship),
+    GuardianPhoneChanged(String),
+    GuardianAlternatePhoneChanged(String),
+    GuardianPrimaryToggled(bool),
+    GuardianStudentSearchChanged(String),
+    ToggleGuardianChild(Uuid),
+    SaveGuardian,
+    GuardianSaved(Result<Guardian, AppError>),
+    CancelGuardianForm,
+}
+  
+#[derive(Debug, Clone)]
+pub struct StudentManagerState {
+    pub first_name_input: String,
+    pub surname_input: String,
+    pub other_names_input: String,
+    pub selected_gender: Option<Gender>,
+    pub selected_class_level: Option<ClassLevel>,
+    pub students: Vec<Student>,
+    pub show_success_message: bool,
     pub form_error_message: Option<String>,
 
-    pub editing_student_id: Option<Uuid>,
-    pub edit_first_name_input: String,
-    pub edit_surname_input: String,
-    pub edit_other_names_input: String,
-    pub edit_selected_gender: Option<Gender>,
-    pub edit_selected_class_level: Option<ClassLevel>,
+    pub editing_student_id: Option<Uuid>,
+    pub edit_key: Uuid,
+    pub edit_first_name_input: String,
+    pub edit_surname_input: String,
+    pub edit_other_names_input: String,
+    pub edit_selected_gender: Option<Gender>,
+    pub edit_selected_class_level: Option<ClassLevel>,
+
+    pub import_preview: Option<ImportPreview>,
+    pub import_key: Uuid,
+    pub is_importing: bool,
+    pub import_message: Option<String>,
+
+    pub show_duplicates: bool,
+    pub duplicate_candidates: Vec<DuplicateCandidate>,
+    pub dismissed_duplicates: HashSet<(Uuid, Uuid)>,
+    /// (surviving, duplicate) awaiting confirmation; kept after a failed merge
+    /// so it can be confirmed again
+    pub pending_merge: Option<(Uuid, Uuid)>,
+    /// Idempotency key for the pending merge
+    pub merge_key: Uuid,
+    pub is_merging: bool,
+
+    pub promotion: Option<PromotionWizard>,
+
+    pub enrolment: Option<EnrolmentPanel>,
+    pub date_format: String,
+    pub currency_symbol: String,
+
+    pub families: Option<FamilyPanel>,
+}
+
+/// Guardians and the families they form, with a guardian being added or edited
+#[derive(Debug, Clone, Default)]
+pub struct FamilyPanel {
+    pub guardians: Vec<Guardian>,
+    pub search_query: String,
+    pub form: Option<GuardianForm>,
+    pub is_loading: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GuardianForm {
+    /// `None` when adding a new guardian
+    pub editing_id: Option<Uuid>,
+    pub first_name: String,
+    pub surname: String,
+    pub relationship: Option<GuardianRelationship>,
+    pub phone_number: String,
+    pub alternate_phone: String,
+    pub is_primary_contact: bool,
+    pub student_ids: HashSet<Uuid>,
+    pub student_search: String,
+    pub request_key: Uuid,
+    pub is_saving: bool,
+}
+
+/// Strips spacing from a phone number and checks it has 10 to 15 digits,
+/// optionally after a leading '+'.
+fn normalise_phone(value: &str) -> Result<String, String> {
+    let compact: String = value.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+    let digits = compact.strip_prefix('+').unwrap_or(&compact);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) || !(10..=15).contains(&digits.len()) {
+        return Err(format!("'{}' is not a valid phone number.", value.trim()));
+    }
+    Ok(compact)
+}
+
+impl GuardianForm {
+    pub fn from_guardian(guardian: &Guardian) -> Self {
+        Self {
+            editing_id: Some(guardian.id),
+            first_name: guardian.name.first_name.clone(),
+            surname: guardian.name.surname.clone(),
+            relationship: Some(guardian.relationship.clone()),
+            phone_number: guardian.phone_number.clone(),
+            alternate_phone: guardian.alternate_phone.clone().unwrap_or_default(),
+            is_primary_contact: guardian.is_primary_contact,
+            student_ids: guardian.student_ids.iter().copied().collect(),
+            student_search: String::new(),
+            request_key: Uuid::new_v4(),
+            is_saving: false,
+        }
+    }
+
+    pub fn payload(&self) -> Result<SaveGuardianPayload, String> {
+        if self.first_name.trim().is_empty() {
+            return Err("First name cannot be empty.".to_string());
+        }
+        if self.surname.trim().is_empty() {
+            return Err("Surname cannot be empty.".to_string());
+        }
+        let relationship = self.relationship.clone()
+            .ok_or_else(|| "Please select how the guardian is related.".to_string())?;
+        let phone_number = normalise_phone(&self.phone_number)?;
+        let alternate_phone = match self.alternate_phone.trim() {
+            "" => None,
+            phone => Some(normalise_phone(phone)?),
+        };
+        if self.student_ids.is_empty() {
+            return Err("Link the guardian to at least one student.".to_string());
+        }
+
+        Ok(SaveGuardianPayload {
+            first_name: self.first_name.trim().to_string(),
+            surname: self.surname.trim().to_string(),
+            other_names: None,
+            relationship,
+            phone_number,
+            alternate_phone,
+            is_primary_contact: self.is_primary_contact,
+            student_ids: self.student_ids.iter().copied().collect(),
+        })
+    }
+}
+
+/// End-of-year promotion being reviewed before it is applied
+#[derive(Debug, Clone)]
+pub struct PromotionWizard {
+    pub academic_year: AcademicYear,
+    pub next_academic_year: AcademicYear,
+    pub rows: Vec<PromotionRow>,
+    /// On the confirmation step rather than editing outcomes
+    pub reviewing: bool,
+    /// Idempotency key for applying the promotion, kept until it succeeds
+    pub request_key: Uuid,
+    pub is_submitting: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PromotionRow {
+    pub student: Student,
+    pub outcome: PromotionOutcome,
+}
+
+impl PromotionRow {
+    pub fn next_class_level(&self) -> Option<ClassLevel> {
+        match self.outcome {
+            PromotionOutcome::Promoted => self.student.class_level.next(),
+            PromotionOutcome::Repeated => Some(self.student.class_level.clone()),
+            PromotionOutcome::Graduated | PromotionOutcome::Left => None,
+        }
+    }
+
+    /// Outcomes that make sense for this student's class
+    pub fn options(&self) -> Vec<PromotionOutcome> {
+        match self.student.class_level.next() {
+            Some(_) => vec![PromotionOutcome::Promoted, PromotionOutcome::Repeated, PromotionOutcome::Left],
+            None => vec![PromotionOutcome::Graduated, PromotionOutcome::Repeated, PromotionOutcome::Left],
+        }
+    }
+}
+
+/// Enrolment for one term: who is in it, and students picked to join or leave
+#[derive(Debug, Clone)]
+pub struct EnrolmentPanel {
+    pub terms: Vec<Term>,
+    pub selected_term: Option<Term>,
+    pub enrolments: Vec<Enrolment>,
+    pub selected_students: HashSet<Uuid>,
+    pub join_date: DateInput,
+    pub enrol_key: Uuid,
+    /// Enrolment being withdrawn, awaiting a leave date
+    pub withdrawing: Option<Uuid>,
+    pub leave_date: DateInput,
+    pub withdraw_key: Uuid,
+    pub is_loading: bool,
+    pub is_saving: bool,
+    pub error: Option<String>,
+}
+
+impl EnrolmentPanel {
+    /// Opens on the current term, with joining defaulting to its first day.
+    pub fn new(terms: Vec<Term>, date_format: &str) -> Self {
+        let selected_term = current_term(Local::now().date_naive(), &terms).cloned();
+        let mut panel = Self {
+            terms,
+            selected_term: None,
+            enrolments: Vec::new(),
+            selected_students: HashSet::new(),
+            join_date: DateInput { value: String::new(), format: date_format.to_string() },
+            enrol_key: Uuid::new_v4(),
+            withdrawing: None,
+            leave_date: DateInput { value: String::new(), format: date_format.to_string() },
+            withdraw_key: Uuid::new_v4(),
+            is_loading: false,
+            is_saving: false,
+            error: None,
+        };
+        if let Some(term) = selected_term {
+            panel.select_term(term);
+        }
+        panel
+    }
+
+    fn select_term(&mut self, term: Term) {
+        self.join_date.value = format_date(term.start_date.date(), &self.join_date.format);
+        self.selected_term = Some(term);
+        self.enrolments.clear();
+        self.selected_students.clear();
+        self.withdrawing = None;
+        self.error = None;
+    }
+
+    fn load(&mut self) -> Task<StudentManagerMessage> {
+        let Some(term) = &self.selected_term else {
+            return Task::none();
+        };
+        self.is_loading = true;
+        Task::perform(routes::fetch_enrolments(term.id), StudentManagerMessage::EnrolmentsLoaded)
+    }
+
+    /// The student's latest enrolment in the selected term, if any
+    pub fn enrolment_for(&self, student_id: Uuid) -> Option<&Enrolment> {
+        self.enrolments
+            .iter()
+            .filter(|e| e.student_id == student_id)
+            .max_by_key(|e| e.joined_on)
+    }
+
+    /// Whether the student is enrolled and hasn't left
+    pub fn is_enrolled(&self, student_id: Uuid) -> bool {
+        self.enrolment_for(student_id).is_some_and(|e| e.left_on.is_none())
+    }
+
+    /// Fees expected for the term: only enrolled students are charged.
+    pub fn expected_fees(&self) -> f64 {
+        self.enrolments.iter().map(|e| e.fee_amount).sum()
+    }
+
+    /// `date` parsed and checked to fall within the selected term
+    fn date_in_term(&self, input: &DateInput, what: &str) -> Result<NaiveDate, String> {
+        let term = self.selected_term.as_ref().ok_or_else(|| "Select a term first.".to_string())?;
+        let date = input.date()?.ok_or_else(|| format!("Enter the {} date.", what))?;
+        if date < term.start_date.date() || date > term.end_date.date() {
+            return Err(format!(
+                "The {} date must fall within {} ({} to {}).",
+                what,
+                term.name,
+                format_date(term.start_date.date(), &input.format),
+                format_date(term.end_date.date(), &input.format),
+            ));
+        }
+        Ok(date)
+    }
+}
+
+impl PromotionWizard {
+    /// Proposes the next class for every active student, or graduation from a final-year class.
+    pub fn new(students: &[Student], academic_year: AcademicYear) -> Result<Self, String> {
+        let next_academic_year = academic_year.next();
+
+        let mut rows: Vec<PromotionRow> = students
+            .iter()
+            .filter(|student| student.is_active && student.merged_into.is_none())
+            .map(|student| PromotionRow {
+                outcome: match student.class_level.next() {
+                    Some(_) => PromotionOutcome::Promoted,
+                    None => PromotionOutcome::Graduated,
+                },
+                student: student.clone(),
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            let class_index = |c: &ClassLevel| ClassLevel::ALL.iter().position(|level| level == c);
+            class_index(&a.student.class_level)
+                .cmp(&class_index(&b.student.class_level))
+                .then_with(|| a.student.name.surname.cmp(&b.student.name.surname))
+        });
+
+        if rows.is_empty() {
+            return Err("There are no active students to promote.".to_string());
+        }
+
+        Ok(Self {
+            academic_year,
+            next_academic_year,
+            rows,
+            reviewing: false,
+            request_key: Uuid::new_v4(),
+            is_submitting: false,
+        })
+    }
+
+    pub fn count(&self, outcome: &PromotionOutcome) -> usize {
+        self.rows.iter().filter(|row| &row.outcome == outcome).count()
+    }
+
+    pub fn payload(&self) -> PromotionPayload {
+        PromotionPayload {
+            academic_year: self.academic_year,
+            next_academic_year: self.next_academic_year,
+            decisions: self.rows
+                .iter()
+                .map(|row| PromotionDecision {
+                    student_id: row.student.id,
+                    current_class_level: row.student.class_level.clone(),
+                    outcome: row.outcome.clone(),
+                    next_class_level: match row.outcome {
+                        PromotionOutcome::Promoted => row.next_class_level(),
+                        _ => None,
+                    },
+                })
+                .collect(),
+        }
+    }
 }
 
 impl StudentManagerState {
@@ -380,14 +2497,105 @@ impl StudentManagerState {
             show_success_message: false,
             form_error_message: None,
             editing_student_id: None,
+            edit_key: Uuid::new_v4(),
             edit_first_name_input: String::new(),
             edit_surname_input: String::new(),
             edit_other_names_input: String::new(),
             edit_selected_gender: None,
             edit_selected_class_level: None,
+            import_preview: None,
+            import_key: Uuid::new_v4(),
+            is_importing: false,
+            import_message: None,
+            show_duplicates: false,
+            duplicate_candidates: Vec::new(),
+            dismissed_duplicates: HashSet::new(),
+            pending_merge: None,
+            merge_key: Uuid::new_v4(),
+            is_merging: false,
+            promotion: None,
+            enrolment: None,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            currency_symbol: DEFAULT_CURRENCY_SYMBOL.to_string(),
+            families: None,
+        }
+    }
+
+    /// Guardian form of the open family panel
+    fn guardian_form(&mut self) -> Option<&mut GuardianForm> {
+        self.families.as_mut().and_then(|panel| panel.form.as_mut())
+    }
+
+    pub fn set_date_format(&mut self, date_format: &str) {
+        self.date_format = date_format.to_string();
+        if let Some(panel) = self.enrolment.as_mut() {
+            panel.join_date.set_format(date_format);
+            panel.leave_date.set_format(date_format);
+        }
+    }
+
+    /// Opens the enrolment panel and loads the current term's enrolments.
+    pub fn open_enrolment(&mut self, terms: Vec<Term>) -> Task<StudentManagerMessage> {
+        self.import_message = None;
+        if terms.is_empty() {
+            self.form_error_message = Some("No terms have been set up yet.".to_string());
+            return Task::none();
+        }
+        let mut panel = EnrolmentPanel::new(terms, &self.date_format);
+        let task = panel.load();
+        self.enrolment = Some(panel);
+        task
+    }
+
+    /// Opens the promotion wizard for the year of the current term.
+    pub fn open_promotion_wizard(&mut self, academic_year: Option<AcademicYear>) {
+        self.import_message = None;
+        let wizard = academic_year
+            .ok_or_else(|| "There is no active term, so the academic year to close is unknown.".to_string())
+            .and_then(|year| PromotionWizard::new(&self.students, year));
+        match wizard {
+            Ok(wizard) => {
+                self.form_error_message = None;
+                self.promotion = Some(wizard);
+            }
+            Err(e) => self.form_error_message = Some(e),
         }
     }
 
+    /// Applies the reviewed promotion; the backend records who did it in the audit log.
+    pub fn submit_promotion(&mut self) -> Task<StudentManagerMessage> {
+        let Some(wizard) = self.promotion.as_mut().filter(|w| w.reviewing && !w.is_submitting) else {
+            return Task::none();
+        };
+        wizard.is_submitting = true;
+        Task::perform(
+            routes::promote_students(wizard.payload(), wizard.request_key),
+            StudentManagerMessage::PromotionCompleted,
+        )
+    }
+
+    /// Re-runs the duplicate finder, leaving out pairs the user said were different people.
+    fn refresh_duplicates(&mut self) {
+        let dismissed = &self.dismissed_duplicates;
+        self.duplicate_candidates = duplicates::find_duplicates(&self.students)
+            .into_iter()
+            .filter(|c| !dismissed.contains(&(c.first.id, c.second.id)) && !dismissed.contains(&(c.second.id, c.first.id)))
+            .collect();
+    }
+
+    /// Sends the confirmed merge; the backend records who made it in the audit log.
+    pub fn merge(&mut self) -> Task<StudentManagerMessage> {
+        let Some((surviving_id, duplicate_id)) = self.pending_merge else {
+            return Task::none();
+        };
+        self.is_merging = true;
+        self.form_error_message = None;
+        Task::perform(
+            routes::merge_students(MergeStudentsPayload { surviving_id, duplicate_id }, self.merge_key),
+            StudentManagerMessage::MergeCompleted,
+        )
+    }
+
     pub fn validate_form(&self) -> Result<(), String> {
         if self.first_name_input.trim().is_empty() {
             return Err("First name cannot be empty.".to_string());
@@ -473,6 +2681,7 @@ impl StudentManagerState {
                         self.form_error_message = None;
                         self.show_success_message = false;
                         let payload = CreateStudentPayload {
+                            id: Uuid::new_v4(),
                             first_name: self.first_name_input.clone(),
                             surname: self.surname_input.clone(),
                             other_names: if self.other_names_input.is_empty() {
@@ -486,8 +2695,8 @@ impl StudentManagerState {
 
                         Task::perform(
                             async move { 
-                                match routes::create_student(payload).await {
-                                    Ok(student) => StudentManagerMessage::StudentCreated(student),
+                                match offline::create_student(payload).await {
+                                    Ok(saved) => StudentManagerMessage::StudentCreated(saved),
                                     Err(err) => StudentManagerMessage::StudentCreationFailed(err.to_string()),
 
                                 }
@@ -501,11 +2710,23 @@ impl StudentManagerState {
                     },
                 }
             },
-            StudentManagerMessage::StudentCreated(student) => {
+            StudentManagerMessage::StudentCreated(saved) => {
+                let student = match saved {
+                    Saved::Synced(student) => {
+                        self.show_success_message = true;
+                        student
+                    }
+                    Saved::Queued(student) => {
+                        self.import_message = Some(format!(
+                            "The server can't be reached, so {} {} was saved on this computer and will be sent once it is back.",
+                            student.name.first_name, student.name.surname,
+                        ));
+                        student
+                    }
+                };
                 self.students.push(student); 
                 self.students.sort_by(|a, b| a.name.surname.cmp(&b.name.surname)); 
                 self.clear_inputs();
-                self.show_success_message = true;
                 
                 Task::perform(
                     async {
@@ -522,9 +2743,16 @@ impl StudentManagerState {
             }
 
             StudentManagerMessage::FetchStudents => {
-                Task::none()
+                Task::perform(offline::fetch_students(), |r| {
+                    match r {
+                        Ok(students) => StudentManagerMessage::FetchedStudents(students),
+                        Err(e) => StudentManagerMessage::StudentFetchFailed(e),
+                    }
+                })
             },
-            StudentManagerMessage::FetchedStudents(_students) => {
+            StudentManagerMessage::FetchedStudents(mut students) => {
+                students.sort_by(|a, b| a.name.surname.cmp(&b.name.surname));
+                self.students = students;
                 Task::none()
             },
             StudentManagerMessage::StudentFetchFailed(_error) => {
@@ -542,6 +2770,7 @@ impl StudentManagerState {
             StudentManagerMessage::EditStudent(id) => {
                 if let Some(student) = self.students.iter().find(|s| s.id == id) {
                     self.editing_student_id = Some(id);
+                    self.edit_key = Uuid::new_v4();
                     self.edit_first_name_input = student.name.first_name.clone();
                     self.edit_surname_input = student.name.surname.clone();
                     self.edit_other_names_input = student.name.other_names.clone().unwrap_or_default();
@@ -592,8 +2821,9 @@ impl StudentManagerState {
                             class_level: self.edit_selected_class_level.clone().unwrap(),
                         };
 
+                        let key = self.edit_key;
                         Task::perform(
-                            async { routes::update_student(payload).await },
+                            async move { routes::update_student(payload, key).await },
                             |result| match result {
                                 Ok(student) => StudentManagerMessage::StudentUpdated(Ok(student)),
                                 Err(e) => StudentManagerMessage::StudentUpdated(Err(e.to_string())),
@@ -679,17 +2909,64 @@ impl StudentManagerState {
             }
 
             StudentManagerMessage::ImportStudentsFromExcel => {
+                self.import_message = None;
+                let existing = self.students.clone();
                 Task::perform(
-                    routes::pick_and_upload_excel_file(),
-                    |result| StudentManagerMessage::ImportResult(result),
+                    async move { import::pick_and_preview_students(existing) },
+                    StudentManagerMessage::ImportPreviewLoaded,
                 )
             }
+            StudentManagerMessage::ImportPreviewLoaded(result) => {
+                match result {
+                    Ok(preview) => {
+                        self.form_error_message = None;
+                        self.import_preview = Some(preview);
+                        self.import_key = Uuid::new_v4();
+                    }
+                    Err(err) => self.form_error_message = Some(format!("Import failed: {}", err)),
+                }
+                Task::none()
+            }
+            StudentManagerMessage::ImportPreview(preview_msg) => {
+                if let Some(preview) = self.import_preview.as_mut() {
+                    preview.update(preview_msg);
+                    import::validate_student_preview(preview, &self.students);
+                }
+                Task::none()
+            }
+            StudentManagerMessage::ConfirmImport => {
+                let Some(preview) = self.import_preview.as_ref().filter(|p| p.can_commit()) else {
+                    return Task::none();
+                };
+                let payload = StudentImportPayload {
+                    rows: preview.included_rows().map(import::student_import_row).collect(),
+                };
+                self.is_importing = true;
+                Task::perform(routes::import_students(payload, self.import_key), StudentManagerMessage::ImportResult)
+            }
+            StudentManagerMessage::CancelImport => {
+                self.import_preview = None;
+                Task::none()
+            }
             StudentManagerMessage::ImportResult(result) => {
+                self.is_importing = false;
                 match result {
-                    Ok(_) => {
-                        self.show_success_message = true;
-                        // optionally fetch all students again
-                        Task::perform(routes::fetch_all_students(), |r| {
+                    Ok(summary) => {
+                        self.import_key = Uuid::new_v4();
+                        self.import_message = Some(if summary.rejected.is_empty() {
+                            format!("Imported {} students.", summary.created)
+                        } else {
+                            format!("Imported {} students; {} rows were rejected and are shown below.", summary.created, summary.rejected.len())
+                        });
+
+                        if let Some(preview) = self.import_preview.as_mut() {
+                            preview.keep_rejected(&summary.rejected);
+                        }
+                        if self.import_preview.as_ref().is_some_and(|p| p.rows.is_empty()) {
+                            self.import_preview = None;
+                        }
+
+                        Task::perform(offline::fetch_students(), |r| {
                             match r {
                                 Ok(students) => StudentManagerMessage::FetchedStudents(students),
                                 Err(e) => StudentManagerMessage::StudentFetchFailed(e),
@@ -703,6 +2980,403 @@ impl StudentManagerState {
                 }
             }
 
+            StudentManagerMessage::FindDuplicates => {
+                self.show_duplicates = true;
+                self.pending_merge = None;
+                self.refresh_duplicates();
+                Task::none()
+            }
+            StudentManagerMessage::CloseDuplicates => {
+                self.show_duplicates = false;
+                self.pending_merge = None;
+                Task::none()
+            }
+            StudentManagerMessage::DismissDuplicate(first, second) => {
+                self.dismissed_duplicates.insert((first, second));
+                self.refresh_duplicates();
+                Task::none()
+            }
+            StudentManagerMessage::SelectMerge(surviving, duplicate) => {
+                self.pending_merge = Some((surviving, duplicate));
+                self.merge_key = Uuid::new_v4();
+                Task::none()
+            }
+            StudentManagerMessage::CancelMerge => {
+                self.pending_merge = None;
+                Task::none()
+            }
+            StudentManagerMessage::ConfirmMerge => {
+                // Handled by DashboardState, which knows who is performing the merge
+                Task::none()
+            }
+            StudentManagerMessage::MergeCompleted(result) => {
+                self.is_merging = false;
+                match result {
+                    Ok(merge) => {
+                        self.pending_merge = None;
+                        for updated in [merge.surviving.clone(), merge.duplicate.clone()] {
+                            if let Some(student) = self.students.iter_mut().find(|s| s.id == updated.id) {
+                                *student = updated;
+                            }
+                        }
+                        self.import_message = Some(format!(
+                            "Merged {} {} into {} {}: {} payments and {} exemptions moved.",
+                            merge.duplicate.name.first_name,
+                            merge.duplicate.name.surname,
+                            merge.surviving.name.first_name,
+                            merge.surviving.name.surname,
+                            merge.payments_moved,
+                            merge.exemptions_moved,
+                        ));
+                        self.refresh_duplicates();
+                    }
+                    Err(err) => self.form_error_message = Some(format!("Merge failed: {}", err)),
+                }
+                Task::none()
+            }
+
+            StudentManagerMessage::ShowPromotionWizard | StudentManagerMessage::SubmitPromotion => {
+                // Handled by DashboardState, which knows the active user
+                Task::none()
+            }
+            StudentManagerMessage::PromotionOutcomeChanged(student_id, outcome) => {
+                if let Some(row) = self.promotion.as_mut().and_then(|w| w.rows.iter_mut().find(|r| r.student.id == student_id)) {
+                    row.outcome = outcome;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::ReviewPromotion => {
+                if let Some(wizard) = self.promotion.as_mut() {
+                    wizard.reviewing = true;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::EditPromotion => {
+                if let Some(wizard) = self.promotion.as_mut() {
+                    wizard.reviewing = false;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::CancelPromotion => {
+                self.promotion = None;
+                Task::none()
+            }
+            StudentManagerMessage::PromotionCompleted(result) => {
+                match result {
+                    Ok(summary) => {
+                        self.promotion = None;
+                        self.import_message = Some(format!(
+                            "Year closed: {} promoted, {} repeating, {} graduated, {} left.",
+                            summary.promoted, summary.repeated, summary.graduated, summary.left,
+                        ));
+                        Task::perform(offline::fetch_students(), |r| {
+                            match r {
+                                Ok(students) => StudentManagerMessage::FetchedStudents(students),
+                                Err(e) => StudentManagerMessage::StudentFetchFailed(e),
+                            }
+                        })
+                    }
+                    Err(err) => {
+                        if let Some(wizard) = self.promotion.as_mut() {
+                            wizard.is_submitting = false;
+                        }
+                        self.form_error_message = Some(format!("Promotion failed: {}", err));
+                        Task::none()
+                    }
+                }
+            }
+
+            StudentManagerMessage::ShowEnrolment => {
+                // Handled by DashboardState, which holds the terms
+                Task::none()
+            }
+            StudentManagerMessage::EnrolmentTermSelected(term) => {
+                let Some(panel) = self.enrolment.as_mut() else {
+                    return Task::none();
+                };
+                panel.select_term(term);
+                panel.load()
+            }
+            StudentManagerMessage::EnrolmentsLoaded(result) => {
+                if let Some(panel) = self.enrolment.as_mut() {
+                    panel.is_loading = false;
+                    match result {
+                        Ok(enrolments) => panel.enrolments = enrolments,
+                        Err(err) => panel.error = Some(format!("Failed to load enrolments: {}", err)),
+                    }
+                }
+                Task::none()
+            }
+            StudentManagerMessage::ToggleEnrolmentSelection(student_id) => {
+                if let Some(panel) = self.enrolment.as_mut() {
+                    if !panel.selected_students.remove(&student_id) {
+                        panel.selected_students.insert(student_id);
+                    }
+                }
+                Task::none()
+            }
+            StudentManagerMessage::EnrolmentJoinDateChanged(value) => {
+                if let Some(panel) = self.enrolment.as_mut() {
+                    panel.join_date.value = value;
+                    panel.error = None;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::EnrolSelected => {
+                let Some(panel) = self.enrolment.as_mut().filter(|p| !p.is_saving) else {
+                    return Task::none();
+                };
+                if panel.selected_students.is_empty() {
+                    panel.error = Some("Select the students to enrol.".to_string());
+                    return Task::none();
+                }
+                let joined_on = match panel.date_in_term(&panel.join_date, "joining") {
+                    Ok(date) => date,
+                    Err(e) => {
+                        panel.error = Some(e);
+                        return Task::none();
+                    }
+                };
+                let Some(term_id) = panel.selected_term.as_ref().map(|t| t.id) else {
+                    return Task::none();
+                };
+                panel.is_saving = true;
+                panel.error = None;
+                Task::perform(
+                    routes::enrol_students(EnrolStudentsPayload {
+                        term_id,
+                        student_ids: panel.selected_students.iter().copied().collect(),
+                        joined_on,
+                    }, panel.enrol_key),
+                    StudentManagerMessage::EnrolmentsSaved,
+                )
+            }
+            StudentManagerMessage::EnrolmentsSaved(result) => {
+                if let Some(panel) = self.enrolment.as_mut() {
+                    panel.is_saving = false;
+                    match result {
+                        Ok(enrolments) => {
+                            self.import_message = Some(format!("Enrolled {} students.", enrolments.len()));
+                            panel.enrolments.extend(enrolments);
+                            panel.selected_students.clear();
+                            panel.enrol_key = Uuid::new_v4();
+                        }
+                        Err(err) => panel.error = Some(format!("Enrolment failed: {}", err)),
+                    }
+                }
+                Task::none()
+            }
+            StudentManagerMessage::StartWithdrawal(enrolment_id) => {
+                if let Some(panel) = self.enrolment.as_mut() {
+                    let today = Local::now().date_naive();
+                    panel.leave_date.value = format_date(today, &panel.leave_date.format);
+                    panel.withdrawing = Some(enrolment_id);
+                    panel.withdraw_key = Uuid::new_v4();
+                    panel.error = None;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::EnrolmentLeaveDateChanged(value) => {
+                if let Some(panel) = self.enrolment.as_mut() {
+                    panel.leave_date.value = value;
+                    panel.error = None;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::ConfirmWithdrawal => {
+                let Some(panel) = self.enrolment.as_mut().filter(|p| !p.is_saving) else {
+                    return Task::none();
+                };
+                let Some(enrolment) = panel.withdrawing.and_then(|id| panel.enrolments.iter().find(|e| e.id == id)) else {
+                    return Task::none();
+                };
+                let enrolment_id = enrolment.id;
+                let joined_on = enrolment.joined_on;
+                let left_on = match panel.date_in_term(&panel.leave_date, "leaving") {
+                    Ok(date) if date < joined_on => {
+                        panel.error = Some("The leaving date can't be before the student joined.".to_string());
+                        return Task::none();
+                    }
+                    Ok(date) => date,
+                    Err(e) => {
+                        panel.error = Some(e);
+                        return Task::none();
+                    }
+                };
+                panel.is_saving = true;
+                Task::perform(
+                    routes::withdraw_enrolment(WithdrawEnrolmentPayload { enrolment_id, left_on }, panel.withdraw_key),
+                    StudentManagerMessage::EnrolmentUpdated,
+                )
+            }
+            StudentManagerMessage::CancelWithdrawal => {
+                if let Some(panel) = self.enrolment.as_mut() {
+                    panel.withdrawing = None;
+                    panel.error = None;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::EnrolmentUpdated(result) => {
+                if let Some(panel) = self.enrolment.as_mut() {
+                    panel.is_saving = false;
+                    match result {
+                        Ok(updated) => {
+                            if let Some(enrolment) = panel.enrolments.iter_mut().find(|e| e.id == updated.id) {
+                                *enrolment = updated;
+                            }
+                            panel.withdrawing = None;
+                        }
+                        Err(err) => panel.error = Some(format!("Withdrawal failed: {}", err)),
+                    }
+                }
+                Task::none()
+            }
+            StudentManagerMessage::CloseEnrolment => {
+                self.enrolment = None;
+                Task::none()
+            }
+
+            StudentManagerMessage::ShowFamilies => {
+                self.import_message = None;
+                self.families = Some(FamilyPanel { is_loading: true, ..FamilyPanel::default() });
+                Task::perform(routes::fetch_guardians(), StudentManagerMessage::GuardiansLoaded)
+            }
+            StudentManagerMessage::CloseFamilies => {
+                self.families = None;
+                Task::none()
+            }
+            StudentManagerMessage::GuardiansLoaded(result) => {
+                if let Some(panel) = self.families.as_mut() {
+                    panel.is_loading = false;
+                    match result {
+                        Ok(guardians) => panel.guardians = guardians,
+                        Err(err) => panel.error = Some(format!("Failed to load guardians: {}", err)),
+                    }
+                }
+                Task::none()
+            }
+            StudentManagerMessage::FamilySearchChanged(query) => {
+                if let Some(panel) = self.families.as_mut() {
+                    panel.search_query = query;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::NewGuardian => {
+                if let Some(panel) = self.families.as_mut() {
+                    panel.error = None;
+                    panel.form = Some(GuardianForm { request_key: Uuid::new_v4(), ..GuardianForm::default() });
+                }
+                Task::none()
+            }
+            StudentManagerMessage::EditGuardian(id) => {
+                if let Some(panel) = self.families.as_mut() {
+                    if let Some(guardian) = panel.guardians.iter().find(|g| g.id == id) {
+                        panel.error = None;
+                        panel.form = Some(GuardianForm::from_guardian(guardian));
+                    }
+                }
+                Task::none()
+            }
+            StudentManagerMessage::GuardianFirstNameChanged(value) => {
+                if let Some(form) = self.guardian_form() {
+                    form.first_name = value;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::GuardianSurnameChanged(value) => {
+                if let Some(form) = self.guardian_form() {
+                    form.surname = value;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::GuardianRelationshipSelected(relationship) => {
+                if let Some(form) = self.guardian_form() {
+                    form.relationship = Some(relationship);
+                }
+                Task::none()
+            }
+            StudentManagerMessage::GuardianPhoneChanged(value) => {
+                if let Some(form) = self.guardian_form() {
+                    form.phone_number = value;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::GuardianAlternatePhoneChanged(value) => {
+                if let Some(form) = self.guardian_form() {
+                    form.alternate_phone = value;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::GuardianPrimaryToggled(is_primary) => {
+                if let Some(form) = self.guardian_form() {
+                    form.is_primary_contact = is_primary;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::GuardianStudentSearchChanged(query) => {
+                if let Some(form) = self.guardian_form() {
+                    form.student_search = query;
+                }
+                Task::none()
+            }
+            StudentManagerMessage::ToggleGuardianChild(student_id) => {
+                if let Some(form) = self.guardian_form() {
+                    if !form.student_ids.remove(&student_id) {
+                        form.student_ids.insert(student_id);
+                    }
+                }
+                Task::none()
+            }
+            StudentManagerMessage::SaveGuardian => {
+                let Some(panel) = self.families.as_mut() else {
+                    return Task::none();
+                };
+                let Some(form) = panel.form.as_mut().filter(|f| !f.is_saving) else {
+                    return Task::none();
+                };
+                match form.payload() {
+                    Ok(payload) => {
+                        form.is_saving = true;
+                        panel.error = None;
+                        Task::perform(
+                            routes::save_guardian(form.editing_id, payload, form.request_key),
+                            StudentManagerMessage::GuardianSaved,
+                        )
+                    }
+                    Err(e) => {
+                        panel.error = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            StudentManagerMessage::GuardianSaved(result) => {
+                if let Some(panel) = self.families.as_mut() {
+                    match result {
+                        Ok(saved) => {
+                            match panel.guardians.iter_mut().find(|g| g.id == saved.id) {
+                                Some(guardian) => *guardian = saved,
+                                None => panel.guardians.push(saved),
+                            }
+                            panel.form = None;
+                        }
+                        Err(err) => {
+                            if let Some(form) = panel.form.as_mut() {
+                                form.is_saving = false;
+                            }
+                            panel.error = Some(format!("Failed to save guardian: {}", err));
+                        }
+                    }
+                }
+                Task::none()
+            }
+            StudentManagerMessage::CancelGuardianForm => {
+                if let Some(panel) = self.families.as_mut() {
+                    panel.form = None;
+                    panel.error = None;
+                }
+                Task::none()
+            }
+
             StudentManagerMessage::GenerateExcelTemplate => {
                 Task::perform(
                     async {
@@ -727,29 +3401,94 @@ impl StudentManagerState {
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum ImportType {
-    Students,
-    TeachingPeriods,
-}
-
-#[derive(Debug, Clone)]
-pub struct ImportPreview {
-    pub headers: Vec<String>,
-    pub sample_rows: Vec<Vec<String>>,
-    pub total_rows: usize,
-}
-
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportType {
+    Students,
+    TeachingPeriods,
+}
+
+/// A parsed spreadsheet waiting for the user to review it before anything is saved
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    pub import_type: ImportType,
+    pub file_name: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<ImportPreviewRow>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportPreviewRow {
+    /// 1-based row in the sheet, so errors can be found in the file
+    pub row_number: usize,
+    pub values: Vec<String>,
+    pub errors: Vec<String>,
+    pub duplicate_of: Option<String>,
+    pub skip: bool,
+}
+
+impl ImportPreview {
+    pub fn included_rows(&self) -> impl Iterator<Item = &ImportPreviewRow> {
+        self.rows.iter().filter(|row| !row.skip)
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.included_rows().filter(|row| !row.errors.is_empty()).count()
+    }
+
+    /// Committable once every row still included is valid.
+    pub fn can_commit(&self) -> bool {
+        self.included_rows().next().is_some() && self.error_count() == 0
+    }
+
+    /// After a commit, keeps only the rows the server rejected, with its reasons,
+    /// so they can be fixed and sent again.
+    pub fn keep_rejected(&mut self, rejected: &[ImportRowError]) {
+        self.rows.retain_mut(|row| {
+            if row.skip {
+                return false;
+            }
+            row.errors = rejected
+                .iter()
+                .filter(|e| e.row_number == row.row_number)
+                .map(|e| e.message.clone())
+                .collect();
+            !row.errors.is_empty()
+        });
+    }
+
+    /// Applies an edit from the preview table; the caller re-validates afterwards.
+    pub fn update(&mut self, message: ImportPreviewMessage) {
+        match message {
+            ImportPreviewMessage::CellEdited(row, column, value) => {
+                if let Some(cell) = self.rows.get_mut(row).and_then(|r| r.values.get_mut(column)) {
+                    *cell = value;
+                }
+            },
+            ImportPreviewMessage::ToggleSkip(row) => {
+                if let Some(r) = self.rows.get_mut(row) {
+                    r.skip = !r.skip;
+                }
+            },
+            ImportPreviewMessage::SkipInvalid => {
+                for row in self.rows.iter_mut().filter(|r| !r.errors.is_empty()) {
+                    row.skip = true;
+                }
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TeachingPeriodManagerState {
     pub teaching_periods: Vec<TeachingPeriod>,
+    pub currency_symbol: String,
     pub teachers: Vec<User>,
     pub subjects: Vec<String>,
     pub classes: Vec<String>,
     pub new_period_teacher_id: Option<String>,
     pub new_period_subject: String,
     pub new_period_class: String,
-    pub new_period_date: String,
+    pub new_period_date: DateInput,
     pub new_period_start_time: String,
     pub new_period_end_time: String,
     pub new_period_rate: String,
@@ -760,11 +3499,15 @@ pub struct TeachingPeriodManagerState {
     pub show_import_dialog: bool,
     pub import_file_path: Option<String>,
     pub import_preview: Option<ImportPreview>,
+    pub import_context: Option<PeriodImportContext>,
+    pub is_importing: bool,
+    pub import_message: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct PaymentTrackingState {
     pub students: Vec<Student>,
+    pub currency_symbol: String,
     pub payments: Vec<Payment>,
     pub selected_student_id: Option<String>,
     pub payment_amount: String,
@@ -772,33 +3515,255 @@ pub struct PaymentTrackingState {
     pub payment_description: String,
     pub search_query: String,
     pub filter_status: Option<PaymentStatus>,
-    pub filter_date_from: Option<String>,
-    pub filter_date_to: Option<String>,
+    pub date_range: DateRangeInput,
     pub is_loading: bool,
     pub error_message: Option<String>,
     pub show_payment_dialog: bool,
+    pub is_recording_payment: bool,
     pub selected_payment_id: Option<String>,
+    pub exemption_form: Option<ExemptionForm>,
+    pub installment_form: Option<InstallmentPlanForm>,
+    pub success_message: Option<String>,
+}
+
+/// Installment schedule being edited for one student
+#[derive(Debug, Clone)]
+pub struct InstallmentPlanForm {
+    pub student_id: Uuid,
+    pub student_name: String,
+    /// The student's fee in `fee_term`, the term the payment list was loaded for
+    pub fee_amount: f64,
+    pub fee_term: Option<Uuid>,
+    pub terms: Vec<Term>,
+    /// Term the plan is for; starts at the current term
+    pub term: Option<Term>,
+    pub rows: Vec<InstallmentRow>,
+    pub currency_symbol: String,
+    pub request_key: Uuid,
+    pub is_saving: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstallmentRow {
+    pub due_date: DateInput,
+    pub amount: String,
+}
+
+impl InstallmentPlanForm {
+    /// Starts from the student's current plan, or two empty installments.
+    pub fn new(student: &Student, date_format: &str, currency_symbol: &str, terms: &[Term]) -> Self {
+        let row = |due_date: String, amount: String| InstallmentRow {
+            due_date: DateInput { value: due_date, format: date_format.to_string() },
+            amount,
+        };
+        let rows = match &student.installment_plan {
+            Some(plan) => plan.installments
+                .iter()
+                .map(|installment| row(format_date(installment.due_date, date_format), format!("{:.2}", installment.amount)))
+                .collect(),
+            None => vec![row(String::new(), String::new()), row(String::new(), String::new())],
+        };
+        let term = current_term(Local::now().date_naive(), terms).cloned();
+        Self {
+            student_id: student.id,
+            student_name: format!("{} {}", student.name.first_name, student.name.surname),
+            fee_amount: student.fee_amount,
+            fee_term: term.as_ref().map(|term| term.id),
+            terms: terms.to_vec(),
+            term,
+            rows,
+            currency_symbol: currency_symbol.to_string(),
+            request_key: Uuid::new_v4(),
+            is_saving: false,
+            error: None,
+        }
+    }
+
+    /// Whether the plan is for the term `fee_amount` was loaded for, so the
+    /// total can be checked against it here rather than only by the backend
+    pub fn knows_fee(&self) -> bool {
+        self.term.as_ref().is_some_and(|term| Some(term.id) == self.fee_term)
+    }
+
+    /// Sum of the amounts entered so far, ignoring ones that don't parse
+    pub fn total(&self) -> f64 {
+        self.rows.iter().filter_map(|row| row.amount.trim().parse::<f64>().ok()).sum()
+    }
+
+    /// Divides the fee equally, putting any rounding difference on the last installment.
+    pub fn split_evenly(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let count = self.rows.len();
+        let share = (self.fee_amount / count as f64 * 100.0).floor() / 100.0;
+        let last = self.fee_amount - share * (count - 1) as f64;
+        for (index, row) in self.rows.iter_mut().enumerate() {
+            row.amount = format!("{:.2}", if index + 1 == count { last } else { share });
+        }
+    }
+
+    /// The schedule, checked to run in date order and add up to the fee
+    pub fn installments(&self) -> Result<Vec<Installment>, String> {
+        let mut installments = Vec::with_capacity(self.rows.len());
+        for (index, row) in self.rows.iter().enumerate() {
+            let number = index + 1;
+            let due_date = row.due_date.date()?
+                .ok_or_else(|| format!("Enter a due date for installment {}.", number))?;
+            let amount: f64 = row.amount.trim().parse()
+                .map_err(|_| format!("Enter an amount for installment {}.", number))?;
+            if !amount.is_finite() {
+                return Err(format!("Enter installment {} as a number.", number));
+            }
+            if amount <= 0.0 {
+                return Err(format!("Installment {} must be more than zero.", number));
+            }
+            if installments.last().is_some_and(|previous: &Installment| previous.due_date >= due_date) {
+                return Err(format!("Installment {} must fall due after the one before it.", number));
+            }
+            installments.push(Installment { due_date, amount });
+        }
+
+        let total: f64 = installments.iter().map(|installment| installment.amount).sum();
+        if self.knows_fee() && !installments.is_empty() && (total - self.fee_amount).abs() > 0.005 {
+            return Err(format!(
+                "Installments add up to {} but the fee is {}.",
+                format_money(total, &self.currency_symbol),
+                format_money(self.fee_amount, &self.currency_symbol),
+            ));
+        }
+        Ok(installments)
+    }
+}
+
+/// Exemption being entered for one student, approved by the signed-in user
+#[derive(Debug, Clone)]
+pub struct ExemptionForm {
+    pub student_id: Uuid,
+    pub student_name: String,
+    /// The student's fee in `fee_term`, the term the payment list was loaded for
+    pub fee_amount: f64,
+    pub fee_term: Option<Uuid>,
+    pub terms: Vec<Term>,
+    /// Term the exemption is recorded against; starts at the current term
+    pub term: Option<Term>,
+    pub reason: Option<ExemptionReason>,
+    pub is_full: bool,
+    pub amount: String,
+    pub notes: String,
+    pub currency_symbol: String,
+    pub request_key: Uuid,
+    pub is_saving: bool,
+    pub error: Option<String>,
+}
+
+impl ExemptionForm {
+    pub fn new(student: &Student, currency_symbol: &str, terms: &[Term]) -> Self {
+        let term = current_term(Local::now().date_naive(), terms).cloned();
+        Self {
+            student_id: student.id,
+            student_name: format!("{} {}", student.name.first_name, student.name.surname),
+            fee_amount: student.fee_amount,
+            fee_term: term.as_ref().map(|term| term.id),
+            terms: terms.to_vec(),
+            term,
+            reason: None,
+            is_full: true,
+            amount: String::new(),
+            notes: String::new(),
+            currency_symbol: currency_symbol.to_string(),
+            request_key: Uuid::new_v4(),
+            is_saving: false,
+            error: None,
+        }
+    }
+
+    pub fn payload(&self) -> Result<CreateExemptionPayload, String> {
+        let term_id = self.term.as_ref().map(|term| term.id)
+            .ok_or_else(|| "Select the term the exemption is for.".to_string())?;
+        let reason = self.reason.clone().ok_or_else(|| "Select a reason for the exemption.".to_string())?;
+        let amount = if self.is_full {
+            None
+        } else {
+            let amount: f64 = self.amount.trim().parse()
+                .map_err(|_| "Enter the amount exempted.".to_string())?;
+            if !amount.is_finite() {
+                return Err("Enter the amount exempted as a number.".to_string());
+            }
+            if amount <= 0.0 {
+                return Err("The exempted amount must be more than zero.".to_string());
+            }
+            // The fee is only known for the term the list was loaded for; the
+            // backend checks other terms
+            if self.fee_term == Some(term_id) && self.fee_amount > 0.0 && amount >= self.fee_amount {
+                return Err(format!(
+                    "A partial exemption must be less than the fee of {}; use a full exemption instead.",
+                    format_money(self.fee_amount, &self.currency_symbol),
+                ));
+            }
+            Some(amount)
+        };
+        let notes = self.notes.trim();
+        if notes.is_empty() {
+            return Err("Add supporting notes, e.g. the scholarship or the letter on file.".to_string());
+        }
+
+        Ok(CreateExemptionPayload {
+            student_id: self.student_id,
+            term_id,
+            is_full: self.is_full,
+            amount,
+            reason,
+            notes: Some(notes.to_string()),
+        })
+    }
 }
 
 #[derive(Debug)]
 pub struct UserAccessManagerState {
-    pub users: Vec<User>,
+    pub users: Vec<UserResponse>,
     pub new_user_first_name: String,
     pub new_user_surname: String,
+    pub new_user_other_names: String,
     pub new_user_username: String,
     pub new_user_password: String,
     pub new_user_role: kyefa_models::UserRole,
+    pub new_user_school_ids: Vec<Uuid>,
+    /// Schools the admin can add users to, which are the admin's own
+    pub schools: Vec<School>,
+    /// Ticked by default for new users
+    pub active_school_id: Option<Uuid>,
     pub selected_user_id: Option<String>,
     pub search_query: String,
+    pub show_inactive: bool,
     pub is_loading: bool,
+    pub is_saving: bool,
+    /// Idempotency key for the open dialog's write, renewed when it closes
+    pub request_key: Uuid,
+    /// Temporary password being set, reused if the reset is retried
+    pub pending_temporary_password: Option<String>,
     pub error_message: Option<String>,
+    pub message: Option<String>,
     pub show_add_user_dialog: bool,
     pub show_edit_user_dialog: bool,
-    pub show_delete_confirmation: bool,
+    pub show_deactivate_confirmation: bool,
+    pub show_reset_confirmation: bool,
+    pub password_policy: PasswordPolicy,
+    pub show_login_history: bool,
+    pub login_history: Vec<LoginAttempt>,
+    /// Limits the history to one user when opened from their card
+    pub history_username: Option<String>,
+    pub history_failed_only: bool,
+    pub is_loading_history: bool,
+    /// Username and temporary password from the last reset, shown once so the
+    /// admin can pass them on
+    pub temporary_password: Option<(String, String)>,
 }
 
 #[derive(Debug)]
 pub struct ReportsAnalyticsState {
+    pub currency_symbol: String,
     pub total_students: usize,
     pub total_revenue: f64,
     pub expected_revenue: f64,
@@ -806,37 +3771,106 @@ pub struct ReportsAnalyticsState {
     pub recent_activities: Vec<RecentActivity>,
     pub payment_summary: PaymentSummary,
     pub teacher_earnings: Vec<TeacherEarnings>,
+    pub projected_income: Option<ProjectedIncomeReport>,
+    pub student_payments: Vec<StudentPaymentHistory>,
+    pub exemptions: Vec<ExemptionReportRow>,
+    pub total_exempted: f64,
+    pub overdue_aging: Vec<OverdueAgingRow>,
+    pub collection_trend: Vec<WeeklyCollection>,
+    pub term_comparison: Vec<TermComparison>,
+    pub show_charts: bool,
+    pub teachers: Vec<Teacher>,
+    pub school_name: String,
+    pub term_name: Option<String>,
+    pub export_format: ExportFormat,
+    pub export_message: Option<String>,
     pub selected_report_type: ReportType,
-    pub date_filter_from: Option<String>,
-    pub date_filter_to: Option<String>,
+    pub date_range: DateRangeInput,
     pub selected_teacher_id: Option<String>,
+    /// Filter the selected report was last loaded with. Exports describe this
+    /// rather than the inputs, which may have been edited since.
+    pub applied_filter: Option<ReportFilter>,
     pub is_loading: bool,
     pub error_message: Option<String>,
 }
 
-#[derive(Debug)]
+/// Saved to the settings file on this computer. The currency, date format,
+/// payment method, shares and login rules are set per school on the backend;
+/// `schools` keeps the last copy of each for when it can't be reached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
-    pub admin_share_percentage: f64,
-    pub staff_share_percentage: f64,
-    pub teacher_share_percentage: f64,
-    pub default_payment_method: String,
-    pub currency_symbol: String,
-    pub date_format: String,
+    /// School picked last on this computer, chosen again at the next login
+    pub active_school: Option<Uuid>,
+    pub schools: HashMap<Uuid, SchoolSettings>,
     pub backup_frequency: String,
     pub auto_backup_enabled: bool,
+    /// Where backup archives go, one subfolder per school; can point at a USB drive
+    pub backup_folder: PathBuf,
+    /// Number of archives kept before the oldest are deleted
+    pub backup_retention: usize,
+    pub encrypt_backups: bool,
+    /// Only read from files written before the passphrase moved to the OS
+    /// keychain; `settings::load` moves it there and it is never written back.
+    #[serde(skip_serializing)]
+    pub backup_passphrase: Option<String>,
     pub theme: AppTheme,
+    /// Minutes without input before the screen locks; 0 never locks
+    pub lock_after_minutes: u32,
+    /// Minutes without input before the user is logged out; 0 never logs out
+    pub logout_after_minutes: u32,
 }
 
-#[derive(Debug, Clone)]
+impl AppSettings {
+    /// The active school's settings, or the defaults until the backend has sent them
+    pub fn school(&self) -> SchoolSettings {
+        self.active_school
+            .and_then(|id| self.schools.get(&id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn apply_school(&mut self, school: SchoolSettings) {
+        if let Some(id) = self.active_school {
+            self.schools.insert(id, school);
+        }
+    }
+
+    /// The active school's backups, kept apart so one school's archive can't
+    /// be restored over another's
+    pub fn school_backup_folder(&self) -> PathBuf {
+        match self.active_school {
+            Some(id) => self.backup_folder.join(id.to_string()),
+            None => self.backup_folder.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AppTheme {
     Light,
     Dark,
     System,
 }
 
+impl AppTheme {
+    pub const ALL: [AppTheme; 3] = [AppTheme::Light, AppTheme::Dark, AppTheme::System];
+}
+
+impl std::fmt::Display for AppTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppTheme::Light => write!(f, "Light"),
+            AppTheme::Dark => write!(f, "Dark"),
+            AppTheme::System => write!(f, "Match system"),
+        }
+    }
+}
+
 impl Default for TeachingPeriodManagerState {
     fn default() -> Self {
         Self {
+            currency_symbol: DEFAULT_CURRENCY_SYMBOL.to_string(),
             teaching_periods: Vec::new(),
             teachers: Vec::new(),
             subjects: vec![
@@ -857,7 +3891,7 @@ impl Default for TeachingPeriodManagerState {
             new_period_teacher_id: None,
             new_period_subject: String::new(),
             new_period_class: String::new(),
-            new_period_date: String::new(),
+            new_period_date: DateInput::default(),
             new_period_start_time: String::new(),
             new_period_end_time: String::new(),
             new_period_rate: String::new(),
@@ -868,6 +3902,9 @@ impl Default for TeachingPeriodManagerState {
             show_import_dialog: false,
             import_file_path: None,
             import_preview: None,
+            import_context: None,
+            is_importing: false,
+            import_message: None,
         }
     }
 }
@@ -875,6 +3912,7 @@ impl Default for TeachingPeriodManagerState {
 impl Default for PaymentTrackingState {
     fn default() -> Self {
         Self {
+            currency_symbol: DEFAULT_CURRENCY_SYMBOL.to_string(),
             students: Vec::new(),
             payments: Vec::new(),
             selected_student_id: None,
@@ -883,12 +3921,556 @@ impl Default for PaymentTrackingState {
             payment_description: String::new(),
             search_query: String::new(),
             filter_status: None,
-            filter_date_from: None,
-            filter_date_to: None,
+            date_range: DateRangeInput::default(),
             is_loading: false,
             error_message: None,
             show_payment_dialog: false,
+            is_recording_payment: false,
             selected_payment_id: None,
+            exemption_form: None,
+            installment_form: None,
+            success_message: None,
+        }
+    }
+}
+
+impl TeachingPeriodManagerState {
+    pub fn update(&mut self, message: TeachingPeriodMessage) -> Task<TeachingPeriodMessage> {
+        match message {
+            TeachingPeriodMessage::UpdateSearchQuery(query) => {
+                self.search_query = query;
+                Task::none()
+            },
+            TeachingPeriodMessage::UpdateTeacher(teacher_id) => {
+                self.new_period_teacher_id = teacher_id;
+                Task::none()
+            },
+            TeachingPeriodMessage::UpdateSubject(subject) => {
+                self.new_period_subject = subject;
+                Task::none()
+            },
+            TeachingPeriodMessage::UpdateClass(class) => {
+                self.new_period_class = class;
+                Task::none()
+            },
+            TeachingPeriodMessage::UpdateDate(date) => {
+                self.new_period_date.value = date;
+                Task::none()
+            },
+            TeachingPeriodMessage::UpdateStartTime(time) => {
+                self.new_period_start_time = time;
+                Task::none()
+            },
+            TeachingPeriodMessage::UpdateEndTime(time) => {
+                self.new_period_end_time = time;
+                Task::none()
+            },
+            TeachingPeriodMessage::UpdateRate(rate) => {
+                self.new_period_rate = rate;
+                Task::none()
+            },
+            TeachingPeriodMessage::ShowImportDialog => {
+                self.show_import_dialog = true;
+                self.import_message = None;
+                self.error_message = None;
+                Task::perform(
+                    import::load_period_import_context(self.new_period_date.format.clone()),
+                    TeachingPeriodMessage::ImportContextLoaded,
+                )
+            },
+            TeachingPeriodMessage::ImportContextLoaded(result) => {
+                match result {
+                    Ok(context) => self.import_context = Some(context),
+                    Err(e) => self.error_message = Some(format!("Failed to prepare import: {}", e)),
+                }
+                Task::none()
+            },
+            TeachingPeriodMessage::DownloadImportTemplate => {
+                let Some(context) = self.import_context.clone() else {
+                    return Task::none();
+                };
+                Task::perform(
+                    async move {
+                        routes::pick_path_and_generate_period_template(&context.teachers, &context.subjects, &context.date_format)
+                    },
+                    TeachingPeriodMessage::TemplateGenerated,
+                )
+            },
+            TeachingPeriodMessage::TemplateGenerated(result) => {
+                match result {
+                    Ok(path) => self.import_message = Some(format!("Template saved to {}", path.display())),
+                    Err(e) => self.error_message = Some(format!("Template generation failed: {}", e)),
+                }
+                Task::none()
+            },
+            TeachingPeriodMessage::ChooseImportFile => {
+                Task::perform(
+                    async {
+                        rfd::FileDialog::new()
+                            .add_filter("Excel Files", &["xlsx"])
+                            .pick_file()
+                    },
+                    |path| match path {
+                        Some(path) => TeachingPeriodMessage::PreviewImport(path.display().to_string()),
+                        None => TeachingPeriodMessage::ImportPreviewLoaded(Err(AppError::IoError("No file selected".into()))),
+                    },
+                )
+            },
+            TeachingPeriodMessage::PreviewImport(path) => {
+                let Some(context) = self.import_context.as_ref() else {
+                    return Task::none();
+                };
+                let result = import::preview_periods(std::path::Path::new(&path), context);
+                self.import_file_path = Some(path);
+                self.update(TeachingPeriodMessage::ImportPreviewLoaded(result))
+            },
+            TeachingPeriodMessage::ImportPreviewLoaded(result) => {
+                match result {
+                    Ok(preview) => {
+                        self.error_message = None;
+                        self.import_preview = Some(preview);
+                    },
+                    Err(e) => self.error_message = Some(format!("Import failed: {}", e)),
+                }
+                Task::none()
+            },
+            TeachingPeriodMessage::ImportPreview(preview_msg) => {
+                if let (Some(preview), Some(context)) = (self.import_preview.as_mut(), self.import_context.as_ref()) {
+                    preview.update(preview_msg);
+                    import::validate_period_preview(preview, context);
+                }
+                Task::none()
+            },
+            TeachingPeriodMessage::ConfirmImport => {
+                let (Some(preview), Some(context)) = (self.import_preview.as_ref(), self.import_context.as_ref()) else {
+                    return Task::none();
+                };
+                if !preview.can_commit() {
+                    return Task::none();
+                }
+                self.is_importing = true;
+                Task::perform(
+                    offline::import_periods(import::period_import_payload(preview, context)),
+                    TeachingPeriodMessage::ImportResult,
+                )
+            },
+            TeachingPeriodMessage::CancelImport => {
+                self.show_import_dialog = false;
+                self.import_preview = None;
+                self.import_file_path = None;
+                Task::none()
+            },
+            TeachingPeriodMessage::ImportResult(result) => {
+                self.is_importing = false;
+                match result {
+                    Ok(Saved::Queued(summary)) => {
+                        self.import_message = Some(format!(
+                            "The server can't be reached, so {} periods were saved on this computer and will be sent once it is back.",
+                            summary.created,
+                        ));
+                        self.import_preview = None;
+                        self.show_import_dialog = false;
+                    },
+                    Ok(Saved::Synced(summary)) => {
+                        self.import_message = Some(if summary.rejected.is_empty() {
+                            format!("Imported {} periods.", summary.created)
+                        } else {
+                            format!("Imported {} periods; {} rows were rejected and are shown below.", summary.created, summary.rejected.len())
+                        });
+                        if let Some(preview) = self.import_preview.as_mut() {
+                            preview.keep_rejected(&summary.rejected);
+                        }
+                        if self.import_preview.as_ref().is_some_and(|p| p.rows.is_empty()) {
+                            self.import_preview = None;
+                            self.show_import_dialog = false;
+                        }
+                    },
+                    Err(e) => self.error_message = Some(format!("Import failed: {}", e)),
+                }
+                Task::none()
+            },
+            // Placeholder for other message types
+            _ => Task::none(),
+        }
+    }
+}
+
+impl PaymentTrackingState {
+    pub fn payment_filter(&self) -> Result<PaymentFilter, String> {
+        let (date_from, date_to) = self.date_range.range()?;
+        Ok(PaymentFilter { date_from, date_to })
+    }
+
+    /// Opens the exemption form when `role` may approve exemptions.
+    pub fn open_exemption(&mut self, student_id: Uuid, role: &UserRole, terms: &[Term]) {
+        if !role.can_approve_exemptions() {
+            self.error_message = Some("Only an admin or the headteacher can approve exemptions.".to_string());
+            return;
+        }
+        if let Some(student) = self.students.iter().find(|s| s.id == student_id) {
+            self.error_message = None;
+            self.success_message = None;
+            self.exemption_form = Some(ExemptionForm::new(student, &self.currency_symbol, terms));
+        }
+    }
+
+    /// Opens the installment plan form for the student, offering `terms` to choose from.
+    pub fn open_installment_plan(&mut self, student_id: Uuid, terms: &[Term]) {
+        if let Some(student) = self.students.iter().find(|s| s.id == student_id) {
+            self.success_message = None;
+            self.installment_form = Some(InstallmentPlanForm::new(student, &self.date_range.format, &self.currency_symbol, terms));
+        }
+    }
+
+    /// Saves the schedule being edited for the chosen term
+    pub fn save_installment_plan(&mut self) -> Task<PaymentTrackingMessage> {
+        let Some(form) = self.installment_form.as_mut().filter(|f| !f.is_saving) else {
+            return Task::none();
+        };
+        let payload = form.term.as_ref()
+            .map(|term| term.id)
+            .ok_or_else(|| "Select the term the plan is for.".to_string())
+            .and_then(|term_id| Ok(SaveInstallmentPlanPayload {
+                student_id: form.student_id,
+                term_id,
+                installments: form.installments()?,
+            }));
+        match payload {
+            Ok(payload) => {
+                form.is_saving = true;
+                form.error = None;
+                Task::perform(routes::save_installment_plan(payload, form.request_key), PaymentTrackingMessage::InstallmentPlanSaved)
+            }
+            Err(e) => {
+                form.error = Some(e);
+                Task::none()
+            }
+        }
+    }
+
+    /// Records the payment being entered against `term_id`. The id is generated
+    /// here so a payment queued offline keeps it when replayed.
+    pub fn submit_payment(&mut self, term_id: Option<Uuid>) -> Task<PaymentTrackingMessage> {
+        if self.is_recording_payment {
+            return Task::none();
+        }
+        let student = self.selected_student_id.as_ref()
+            .and_then(|id| self.students.iter().find(|s| s.id.to_string() == *id));
+        let payload = match (student, term_id) {
+            (None, _) => Err("Please select a student.".to_string()),
+            (_, None) => Err("There is no active term to record the payment against.".to_string()),
+            (Some(student), Some(term_id)) => match self.payment_amount.trim().parse::<f64>() {
+                Ok(amount) if amount > 0.0 => Ok((student, RecordPaymentPayload {
+                    id: Uuid::new_v4(),
+                    student_id: student.id,
+                    term_id,
+                    amount_paid: amount,
+                    method: self.payment_method.clone(),
+                    description: self.payment_description.trim().to_string(),
+                })),
+                _ => Err("Enter an amount greater than zero.".to_string()),
+            },
+        };
+        match payload {
+            Ok((student, payload)) => {
+                let total_paid = student.total_paid + payload.amount_paid;
+                let is_overdue = student.installment_plan.as_ref()
+                    .and_then(|plan| plan.days_overdue(total_paid, Local::now().date_naive()))
+                    .is_some();
+                let status = if total_paid >= student.fee_amount {
+                    PaymentStatus::Paid
+                } else if is_overdue {
+                    PaymentStatus::PartialOverdue
+                } else {
+                    PaymentStatus::PartialOnTrack
+                };
+                self.is_recording_payment = true;
+                self.error_message = None;
+                Task::perform(offline::record_payment(payload, status), PaymentTrackingMessage::PaymentRecorded)
+            }
+            Err(e) => {
+                self.error_message = Some(e);
+                Task::none()
+            }
+        }
+    }
+
+    /// Records the exemption against the chosen term, approved by the user logged in
+    pub fn submit_exemption(&mut self) -> Task<PaymentTrackingMessage> {
+        let Some(form) = self.exemption_form.as_mut().filter(|f| !f.is_saving) else {
+            return Task::none();
+        };
+        let payload = form.payload();
+        match payload {
+            Ok(payload) => {
+                form.is_saving = true;
+                form.error = None;
+                Task::perform(routes::create_exemption(payload, form.request_key), PaymentTrackingMessage::ExemptionSaved)
+            }
+            Err(e) => {
+                form.error = Some(e);
+                Task::none()
+            }
+        }
+    }
+
+    pub fn update(&mut self, message: PaymentTrackingMessage) -> Task<PaymentTrackingMessage> {
+        match message {
+            PaymentTrackingMessage::LoadPayments => {
+                let filter = match self.payment_filter() {
+                    Ok(filter) => filter,
+                    Err(e) => {
+                        self.error_message = Some(e);
+                        return Task::none();
+                    }
+                };
+                self.error_message = None;
+                self.is_loading = true;
+
+                Task::batch(vec![
+                    Task::perform(offline::fetch_students(), PaymentTrackingMessage::StudentsLoaded),
+                    Task::perform(routes::fetch_payments(filter), PaymentTrackingMessage::PaymentsLoaded),
+                ])
+            },
+            PaymentTrackingMessage::StudentsLoaded(result) => {
+                match result {
+                    Ok(students) => self.students = students,
+                    Err(e) => self.error_message = Some(format!("Failed to load students: {}", e)),
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::PaymentsLoaded(result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(payments) => self.payments = payments,
+                    Err(e) => self.error_message = Some(format!("Failed to load payments: {}", e)),
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::UpdateSearchQuery(query) => {
+                self.search_query = query;
+                Task::none()
+            },
+            PaymentTrackingMessage::FilterByStatus(status) => {
+                self.filter_status = status;
+                Task::none()
+            },
+            PaymentTrackingMessage::DateRange(_) => {
+                // Handled by DashboardState, which holds the terms the presets need
+                Task::none()
+            },
+            PaymentTrackingMessage::ShowExemptionDialog(_) | PaymentTrackingMessage::SubmitExemption => {
+                // Handled by DashboardState, which knows the approver and the terms
+                Task::none()
+            },
+            PaymentTrackingMessage::ExemptionTermSelected(term) => {
+                if let Some(form) = self.exemption_form.as_mut() {
+                    form.term = Some(term);
+                    form.error = None;
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::ExemptionReasonSelected(reason) => {
+                if let Some(form) = self.exemption_form.as_mut() {
+                    form.reason = Some(reason);
+                    form.error = None;
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::ExemptionFullToggled(is_full) => {
+                if let Some(form) = self.exemption_form.as_mut() {
+                    form.is_full = is_full;
+                    form.error = None;
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::ExemptionAmountChanged(amount) => {
+                if let Some(form) = self.exemption_form.as_mut() {
+                    form.amount = amount;
+                    form.error = None;
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::ExemptionNotesChanged(notes) => {
+                if let Some(form) = self.exemption_form.as_mut() {
+                    form.notes = notes;
+                    form.error = None;
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::ExemptionSaved(result) => {
+                match result {
+                    Ok(exemption) => {
+                        let name = self.exemption_form.take().map(|f| f.student_name).unwrap_or_default();
+                        self.success_message = Some(if exemption.is_full {
+                            format!("{} is fully exempt ({}).", name, exemption.reason)
+                        } else {
+                            format!("{} exempted for {} ({}).", format_money(exemption.amount, &self.currency_symbol), name, exemption.reason)
+                        });
+                        self.update(PaymentTrackingMessage::LoadPayments)
+                    }
+                    Err(e) => {
+                        if let Some(form) = self.exemption_form.as_mut() {
+                            form.is_saving = false;
+                            form.error = Some(format!("Failed to record exemption: {}", e));
+                        }
+                        Task::none()
+                    }
+                }
+            },
+            PaymentTrackingMessage::CancelExemption => {
+                self.exemption_form = None;
+                Task::none()
+            },
+            PaymentTrackingMessage::ShowInstallmentPlan(_) => {
+                // Handled by DashboardState, which knows the terms
+                Task::none()
+            },
+            PaymentTrackingMessage::InstallmentTermSelected(term) => {
+                if let Some(form) = self.installment_form.as_mut() {
+                    form.term = Some(term);
+                    form.error = None;
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::InstallmentDueDateChanged(index, value) => {
+                if let Some(row) = self.installment_form.as_mut().and_then(|f| f.rows.get_mut(index)) {
+                    row.due_date.value = value;
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::InstallmentAmountChanged(index, value) => {
+                if let Some(row) = self.installment_form.as_mut().and_then(|f| f.rows.get_mut(index)) {
+                    row.amount = value;
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::AddInstallment => {
+                if let Some(form) = self.installment_form.as_mut() {
+                    let format = self.date_range.format.clone();
+                    form.rows.push(InstallmentRow {
+                        due_date: DateInput { value: String::new(), format },
+                        amount: String::new(),
+                    });
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::RemoveInstallment(index) => {
+                if let Some(form) = self.installment_form.as_mut().filter(|f| index < f.rows.len()) {
+                    form.rows.remove(index);
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::SplitInstallmentsEvenly => {
+                if let Some(form) = self.installment_form.as_mut() {
+                    form.split_evenly();
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::SaveInstallmentPlan => {
+                // Handled by DashboardState, which knows the active user
+                Task::none()
+            },
+            PaymentTrackingMessage::InstallmentPlanSaved(result) => {
+                match result {
+                    Ok(plan) => {
+                        let form = self.installment_form.take();
+                        let name = form.as_ref().map(|f| f.student_name.clone()).unwrap_or_default();
+                        // A plan for another term doesn't belong on the list being shown
+                        let shown = form.filter(|f| f.knows_fee());
+                        if let Some(student) = shown.and_then(|f| self.students.iter_mut().find(|s| s.id == f.student_id)) {
+                            student.installment_plan = plan.clone();
+                        }
+                        self.success_message = Some(match plan {
+                            Some(plan) => format!("{} will pay in {} installments.", name, plan.installments.len()),
+                            None => format!("Removed the installment plan for {}.", name),
+                        });
+                        self.update(PaymentTrackingMessage::LoadPayments)
+                    }
+                    Err(e) => {
+                        if let Some(form) = self.installment_form.as_mut() {
+                            form.is_saving = false;
+                            form.error = Some(format!("Failed to save installment plan: {}", e));
+                        }
+                        Task::none()
+                    }
+                }
+            },
+            PaymentTrackingMessage::CancelInstallmentPlan => {
+                self.installment_form = None;
+                Task::none()
+            },
+            PaymentTrackingMessage::ShowPaymentDialog => {
+                self.selected_student_id = None;
+                self.payment_amount.clear();
+                self.payment_description.clear();
+                self.error_message = None;
+                self.success_message = None;
+                self.show_payment_dialog = true;
+                Task::none()
+            },
+            PaymentTrackingMessage::RecordPayment(student_id) => {
+                if !self.show_payment_dialog {
+                    self.payment_amount.clear();
+                    self.payment_description.clear();
+                }
+                self.selected_student_id = Some(student_id);
+                self.error_message = None;
+                self.success_message = None;
+                self.show_payment_dialog = true;
+                Task::none()
+            },
+            PaymentTrackingMessage::UpdatePaymentAmount(amount) => {
+                self.payment_amount = amount;
+                self.error_message = None;
+                Task::none()
+            },
+            PaymentTrackingMessage::UpdatePaymentMethod(method) => {
+                self.payment_method = method;
+                Task::none()
+            },
+            PaymentTrackingMessage::UpdatePaymentDescription(description) => {
+                self.payment_description = description;
+                Task::none()
+            },
+            PaymentTrackingMessage::SubmitPayment => {
+                // Handled by DashboardState, which knows the recorder and the current term
+                Task::none()
+            },
+            PaymentTrackingMessage::PaymentRecorded(result) => {
+                self.is_recording_payment = false;
+                match result {
+                    Ok(saved) => {
+                        let (payment, queued) = match saved {
+                            Saved::Synced(payment) => (payment, false),
+                            Saved::Queued(payment) => (payment, true),
+                        };
+                        if let Some(student) = self.students.iter_mut().find(|s| s.id.to_string() == payment.student_id) {
+                            student.total_paid += payment.amount;
+                            student.payment_status = payment.status.clone();
+                            self.success_message = Some(if queued {
+                                format!("The server can't be reached, so the {} payment for {} {} was saved on this computer and will be sent once it is back.",
+                                    format_money(payment.amount, &self.currency_symbol), student.name.first_name, student.name.surname)
+                            } else {
+                                format!("Recorded {} for {} {}.", format_money(payment.amount, &self.currency_symbol), student.name.first_name, student.name.surname)
+                            });
+                        }
+                        self.payments.push(payment);
+                        self.show_payment_dialog = false;
+                        self.selected_student_id = None;
+                    }
+                    Err(e) => self.error_message = Some(format!("Failed to record payment: {}", e)),
+                }
+                Task::none()
+            },
+            PaymentTrackingMessage::CancelPayment => {
+                self.show_payment_dialog = false;
+                self.selected_student_id = None;
+                self.error_message = None;
+                Task::none()
+            },
+            // Placeholder for other message types
+            _ => Task::none(),
         }
     }
 }
@@ -899,16 +4481,411 @@ impl Default for UserAccessManagerState {
             users: Vec::new(),
             new_user_first_name: String::new(),
             new_user_surname: String::new(),
+            new_user_other_names: String::new(),
             new_user_username: String::new(),
             new_user_password: String::new(),
             new_user_role: kyefa_models::UserRole::Teacher,
+            new_user_school_ids: Vec::new(),
+            schools: Vec::new(),
+            active_school_id: None,
             selected_user_id: None,
             search_query: String::new(),
+            show_inactive: false,
             is_loading: false,
+            is_saving: false,
+            request_key: Uuid::new_v4(),
+            pending_temporary_password: None,
             error_message: None,
+            message: None,
             show_add_user_dialog: false,
             show_edit_user_dialog: false,
-            show_delete_confirmation: false,
+            show_deactivate_confirmation: false,
+            show_reset_confirmation: false,
+            password_policy: PasswordPolicy::default(),
+            show_login_history: false,
+            login_history: Vec::new(),
+            history_username: None,
+            history_failed_only: false,
+            is_loading_history: false,
+            temporary_password: None,
+        }
+    }
+}
+
+/// Most login attempts fetched for the login history
+const LOGIN_HISTORY_LIMIT: u32 = 200;
+
+/// Shortest temporary password an admin reset hands out; longer if the
+/// password policy asks for it
+const TEMPORARY_PASSWORD_LEN: usize = 12;
+
+/// Random password for an admin reset, from characters that can't be misread
+/// when copied off the screen. 32 symbols, so every byte maps without bias.
+fn temporary_password(policy: &PasswordPolicy) -> Result<String, String> {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+    let mut bytes = vec![0u8; TEMPORARY_PASSWORD_LEN.max(policy.min_length)];
+    ring::rand::SecureRandom::fill(&ring::rand::SystemRandom::new(), &mut bytes)
+        .map_err(|_| "No secure random source to generate a password.".to_string())?;
+    Ok(bytes.iter().map(|b| ALPHABET[(*b as usize) % ALPHABET.len()] as char).collect())
+}
+
+impl UserAccessManagerState {
+    pub fn selected_user(&self) -> Option<&UserResponse> {
+        let id = self.selected_user_id.as_ref()?;
+        self.users.iter().find(|user| user.id == *id)
+    }
+
+    /// Whether `user` is the only active admin, who must stay an active admin
+    /// so someone can still manage accounts.
+    pub fn is_last_admin(&self, user: &UserResponse) -> bool {
+        user.is_active
+            && user.role == UserRole::Admin
+            && self.users.iter().filter(|u| u.is_active && u.role == UserRole::Admin).count() <= 1
+    }
+
+    fn clear_form(&mut self) {
+        self.new_user_first_name.clear();
+        self.new_user_surname.clear();
+        self.new_user_other_names.clear();
+        self.new_user_username.clear();
+        self.new_user_password.clear();
+        self.new_user_role = UserRole::Teacher;
+        self.new_user_school_ids.clear();
+        self.selected_user_id = None;
+        self.show_add_user_dialog = false;
+        self.show_edit_user_dialog = false;
+        self.show_deactivate_confirmation = false;
+        self.show_reset_confirmation = false;
+        self.request_key = Uuid::new_v4();
+        self.pending_temporary_password = None;
+    }
+
+    fn validate_form(&self) -> Result<(), String> {
+        if self.new_user_first_name.trim().is_empty() || self.new_user_surname.trim().is_empty() {
+            return Err("First name and surname are required.".to_string());
+        }
+        let username = self.new_user_username.trim();
+        if username.is_empty() || username.contains(char::is_whitespace) {
+            return Err("Username is required and can't contain spaces.".to_string());
+        }
+        let taken = self.users.iter().any(|user| {
+            user.username.eq_ignore_ascii_case(username) && Some(&user.id) != self.selected_user_id.as_ref()
+        });
+        if taken {
+            return Err(format!("The username {} is already taken.", username));
+        }
+        if self.new_user_school_ids.is_empty() {
+            return Err("Choose at least one school.".to_string());
+        }
+        Ok(())
+    }
+
+    fn other_names(&self) -> Option<String> {
+        Some(self.new_user_other_names.trim().to_string()).filter(|names| !names.is_empty())
+    }
+
+    fn upsert(&mut self, user: UserResponse) {
+        match self.users.iter_mut().find(|u| u.id == user.id) {
+            Some(existing) => *existing = user,
+            None => self.users.push(user),
+        }
+    }
+
+    pub fn submit_new_user(&mut self) -> Task<UserAccessMessage> {
+        if let Err(e) = self.validate_form() {
+            self.error_message = Some(e);
+            return Task::none();
+        }
+        if let Err(e) = self.password_policy.check(&self.new_user_password) {
+            self.error_message = Some(e);
+            return Task::none();
+        }
+        let payload = CreateUserPayload {
+            username: self.new_user_username.trim().to_string(),
+            password: self.new_user_password.clone(),
+            role: self.new_user_role.clone(),
+            first_name: self.new_user_first_name.trim().to_string(),
+            surname: self.new_user_surname.trim().to_string(),
+            other_names: self.other_names(),
+            school_ids: self.new_user_school_ids.clone(),
+        };
+        self.is_saving = true;
+        self.error_message = None;
+        Task::perform(routes::create_user(payload, self.request_key), UserAccessMessage::UserSaved)
+    }
+
+    pub fn submit_edit_user(&mut self) -> Task<UserAccessMessage> {
+        let Some(user) = self.selected_user().cloned() else {
+            return Task::none();
+        };
+        if let Err(e) = self.validate_form() {
+            self.error_message = Some(e);
+            return Task::none();
+        }
+        if self.is_last_admin(&user) && self.new_user_role != UserRole::Admin {
+            self.error_message = Some(format!("{} is the only admin; make someone else an admin first.", user.username));
+            return Task::none();
+        }
+        // Schools this admin doesn't belong to aren't shown, so they're kept as they are
+        let school_ids = user.schools
+            .iter()
+            .map(|school| school.id)
+            .filter(|id| !self.schools.iter().any(|school| school.id == *id))
+            .chain(self.new_user_school_ids.iter().copied())
+            .collect();
+        let payload = UpdateUserPayload {
+            username: self.new_user_username.trim().to_string(),
+            role: self.new_user_role.clone(),
+            first_name: self.new_user_first_name.trim().to_string(),
+            surname: self.new_user_surname.trim().to_string(),
+            other_names: self.other_names(),
+            is_active: user.is_active,
+            school_ids,
+        };
+        self.save_user(&user, payload)
+    }
+
+    /// Deactivates or reactivates `id`. Admins can't deactivate themselves or
+    /// the last active admin.
+    pub fn set_active(&mut self, id: &str, is_active: bool, acting_user: Uuid) -> Task<UserAccessMessage> {
+        let Some(user) = self.users.iter().find(|user| user.id == id).cloned() else {
+            return Task::none();
+        };
+        if !is_active {
+            if user.id == acting_user.to_string() {
+                self.error_message = Some("You can't deactivate your own account.".to_string());
+                return Task::none();
+            }
+            if self.is_last_admin(&user) {
+                self.error_message = Some(format!("{} is the only active admin and can't be deactivated.", user.username));
+                return Task::none();
+            }
+        }
+        let payload = UpdateUserPayload {
+            username: user.username.clone(),
+            role: user.role.clone(),
+            first_name: user.first_name.clone(),
+            surname: user.surname.clone(),
+            other_names: user.other_names.clone(),
+            is_active,
+            school_ids: user.schools.iter().map(|school| school.id).collect(),
+        };
+        self.save_user(&user, payload)
+    }
+
+    fn save_user(&mut self, user: &UserResponse, payload: UpdateUserPayload) -> Task<UserAccessMessage> {
+        let Ok(id) = Uuid::parse_str(&user.id) else {
+            self.error_message = Some(format!("{} has an invalid id.", user.username));
+            return Task::none();
+        };
+        self.is_saving = true;
+        self.error_message = None;
+        Task::perform(routes::update_user(id, payload, self.request_key), UserAccessMessage::UserSaved)
+    }
+
+    /// Gives the selected user a random temporary password they must change
+    /// at their next login.
+    pub fn reset_password(&mut self) -> Task<UserAccessMessage> {
+        let Some(user) = self.selected_user().cloned() else {
+            return Task::none();
+        };
+        let password = match self.pending_temporary_password.clone() {
+            Some(password) => Ok(password),
+            None => temporary_password(&self.password_policy),
+        };
+        let (Ok(id), Ok(password)) = (Uuid::parse_str(&user.id), password) else {
+            self.error_message = Some(format!("Couldn't reset the password for {}.", user.username));
+            return Task::none();
+        };
+        self.pending_temporary_password = Some(password.clone());
+        self.is_saving = true;
+        self.error_message = None;
+        let payload = ResetPasswordPayload { temporary_password: password.clone() };
+        Task::perform(routes::reset_password(id, payload, self.request_key), move |result| {
+            UserAccessMessage::PasswordReset(result.map(|user| (user, password.clone())))
+        })
+    }
+
+    fn load_login_history(&mut self) -> Task<UserAccessMessage> {
+        self.is_loading_history = true;
+        self.error_message = None;
+        let query = LoginHistoryQuery {
+            username: self.history_username.clone(),
+            failed_only: self.history_failed_only,
+            limit: LOGIN_HISTORY_LIMIT,
+        };
+        Task::perform(routes::fetch_login_history(query), UserAccessMessage::LoginHistoryLoaded)
+    }
+
+    pub fn update(&mut self, message: UserAccessMessage) -> Task<UserAccessMessage> {
+        match message {
+            UserAccessMessage::LoadUsers => {
+                self.is_loading = true;
+                self.error_message = None;
+                Task::perform(routes::fetch_users(), UserAccessMessage::UsersLoaded)
+            }
+            UserAccessMessage::UsersLoaded(result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(users) => self.users = users,
+                    Err(e) => self.error_message = Some(format!("Failed to load users: {}", e)),
+                }
+                Task::none()
+            }
+            UserAccessMessage::UpdateSearchQuery(query) => {
+                self.search_query = query;
+                Task::none()
+            }
+            UserAccessMessage::ToggleShowInactive(show) => {
+                self.show_inactive = show;
+                Task::none()
+            }
+            UserAccessMessage::ShowAddUserDialog => {
+                self.clear_form();
+                self.new_user_school_ids.extend(self.active_school_id);
+                self.error_message = None;
+                self.message = None;
+                self.show_add_user_dialog = true;
+                Task::none()
+            }
+            UserAccessMessage::EditUser(id) => {
+                self.clear_form();
+                if let Some(user) = self.users.iter().find(|user| user.id == id).cloned() {
+                    self.new_user_first_name = user.first_name;
+                    self.new_user_surname = user.surname;
+                    self.new_user_other_names = user.other_names.unwrap_or_default();
+                    self.new_user_username = user.username;
+                    self.new_user_role = user.role;
+                    self.new_user_school_ids = user.schools
+                        .iter()
+                        .map(|school| school.id)
+                        .filter(|id| self.schools.iter().any(|school| school.id == *id))
+                        .collect();
+                    self.selected_user_id = Some(id);
+                    self.error_message = None;
+                    self.message = None;
+                    self.show_edit_user_dialog = true;
+                }
+                Task::none()
+            }
+            UserAccessMessage::DeactivateUser(id) => {
+                self.clear_form();
+                self.error_message = None;
+                self.message = None;
+                self.selected_user_id = Some(id);
+                self.show_deactivate_confirmation = true;
+                Task::none()
+            }
+            UserAccessMessage::ResetUserPassword(id) => {
+                self.clear_form();
+                self.error_message = None;
+                self.message = None;
+                self.selected_user_id = Some(id);
+                self.show_reset_confirmation = true;
+                Task::none()
+            }
+            UserAccessMessage::UpdateNewUserFirstName(value) => {
+                self.new_user_first_name = value;
+                Task::none()
+            }
+            UserAccessMessage::UpdateNewUserSurname(value) => {
+                self.new_user_surname = value;
+                Task::none()
+            }
+            UserAccessMessage::UpdateNewUserOtherNames(value) => {
+                self.new_user_other_names = value;
+                Task::none()
+            }
+            UserAccessMessage::UpdateNewUserUsername(value) => {
+                self.new_user_username = value;
+                Task::none()
+            }
+            UserAccessMessage::UpdateNewUserPassword(value) => {
+                self.new_user_password = value;
+                Task::none()
+            }
+            UserAccessMessage::UpdateNewUserRole(role) => {
+                self.new_user_role = role;
+                Task::none()
+            }
+            UserAccessMessage::ToggleNewUserSchool(school_id, selected) => {
+                self.new_user_school_ids.retain(|id| *id != school_id);
+                if selected {
+                    self.new_user_school_ids.push(school_id);
+                }
+                Task::none()
+            }
+            UserAccessMessage::SubmitNewUser
+            | UserAccessMessage::SubmitEditUser
+            | UserAccessMessage::ConfirmDeactivateUser
+            | UserAccessMessage::ReactivateUser(_)
+            | UserAccessMessage::ConfirmResetPassword => {
+                // Handled by DashboardState, which knows the acting admin
+                Task::none()
+            }
+            UserAccessMessage::CancelUserDialog => {
+                self.clear_form();
+                self.error_message = None;
+                Task::none()
+            }
+            UserAccessMessage::UserSaved(result) => {
+                self.is_saving = false;
+                match result {
+                    Ok(user) => {
+                        self.message = Some(match (self.show_add_user_dialog, user.is_active) {
+                            (true, _) => format!("Added {}. They'll be asked to choose a new password when they first log in.", user.username),
+                            (false, true) => format!("Saved {}.", user.username),
+                            (false, false) => format!("Deactivated {}. They can no longer log in.", user.username),
+                        });
+                        self.upsert(user);
+                        self.clear_form();
+                    }
+                    Err(e) => self.error_message = Some(e.to_string()),
+                }
+                Task::none()
+            }
+            UserAccessMessage::PasswordReset(result) => {
+                self.is_saving = false;
+                match result {
+                    Ok((user, password)) => {
+                        self.temporary_password = Some((user.username.clone(), password));
+                        self.upsert(user);
+                        self.clear_form();
+                    }
+                    Err(e) => self.error_message = Some(format!("Password reset failed: {}", e)),
+                }
+                Task::none()
+            }
+            UserAccessMessage::DismissTemporaryPassword => {
+                self.temporary_password = None;
+                Task::none()
+            }
+            UserAccessMessage::ShowLoginHistory(username) => {
+                self.clear_form();
+                self.message = None;
+                self.show_login_history = true;
+                self.history_username = username;
+                self.login_history.clear();
+                self.load_login_history()
+            }
+            UserAccessMessage::ToggleFailedLoginsOnly(failed_only) => {
+                self.history_failed_only = failed_only;
+                self.load_login_history()
+            }
+            UserAccessMessage::LoginHistoryLoaded(result) => {
+                self.is_loading_history = false;
+                match result {
+                    Ok(attempts) => self.login_history = attempts,
+                    Err(e) => self.error_message = Some(format!("Failed to load login history: {}", e)),
+                }
+                Task::none()
+            }
+            UserAccessMessage::HideLoginHistory => {
+                self.show_login_history = false;
+                self.history_username = None;
+                self.error_message = None;
+                Task::none()
+            }
         }
     }
 }
@@ -916,6 +4893,7 @@ impl Default for UserAccessManagerState {
 impl Default for ReportsAnalyticsState {
     fn default() -> Self {
         Self {
+            currency_symbol: DEFAULT_CURRENCY_SYMBOL.to_string(),
             total_students: 0,
             total_revenue: 0.0,
             expected_revenue: 0.0,
@@ -926,33 +4904,335 @@ impl Default for ReportsAnalyticsState {
                 total_received: 0.0,
                 total_pending: 0.0,
                 paid_count: 0,
-                partial_count: 0,
+                partial_on_track_count: 0,
+                partial_overdue_count: 0,
                 unpaid_count: 0,
                 exempt_count: 0,
+                total_exempted: 0.0,
             },
             teacher_earnings: Vec::new(),
+            projected_income: None,
+            student_payments: Vec::new(),
+            exemptions: Vec::new(),
+            total_exempted: 0.0,
+            overdue_aging: Vec::new(),
+            collection_trend: Vec::new(),
+            term_comparison: Vec::new(),
+            show_charts: false,
+            teachers: Vec::new(),
+            school_name: String::new(),
+            term_name: None,
+            export_format: ExportFormat::Xlsx,
+            export_message: None,
             selected_report_type: ReportType::ProjectedIncome,
-            date_filter_from: None,
-            date_filter_to: None,
+            date_range: DateRangeInput::default(),
             selected_teacher_id: None,
+            applied_filter: None,
             is_loading: false,
             error_message: None,
         }
     }
 }
 
+impl ReportsAnalyticsState {
+    /// Builds the backend query from the filter inputs, rejecting dates that don't parse.
+    pub fn report_filter(&self) -> Result<ReportFilter, String> {
+        let (date_from, date_to) = self.date_range.range()?;
+
+        let teacher_id = match self.selected_teacher_id.as_deref() {
+            Some(id) => Some(Uuid::from_str(id).map_err(|_| "Invalid teacher selected.".to_string())?),
+            None => None,
+        };
+
+        Ok(ReportFilter { date_from, date_to, teacher_id })
+    }
+
+    /// Records `filter` as the one behind the shown report, when `report_type`
+    /// is the report being shown
+    fn mark_applied(&mut self, report_type: ReportType, filter: ReportFilter) {
+        if self.selected_report_type == report_type {
+            self.applied_filter = Some(filter);
+        }
+    }
+
+    fn generate_selected_report(&mut self) -> Task<ReportsAnalyticsMessage> {
+        let message = match self.selected_report_type {
+            ReportType::ProjectedIncome => ReportsAnalyticsMessage::GenerateProjectedIncome,
+            ReportType::CollectionStatus => ReportsAnalyticsMessage::GenerateCollectionStatus,
+            ReportType::TeacherEarnings => ReportsAnalyticsMessage::GenerateTeacherEarnings,
+            ReportType::StudentPayments => ReportsAnalyticsMessage::GenerateStudentPayments,
+            ReportType::Exemptions => ReportsAnalyticsMessage::GenerateExemptions,
+            ReportType::OverdueAging => ReportsAnalyticsMessage::GenerateOverdueAging,
+        };
+        self.update(message)
+    }
+
+    fn refresh(&mut self) -> Task<ReportsAnalyticsMessage> {
+        let filter = match self.report_filter() {
+            Ok(filter) => filter,
+            Err(e) => {
+                self.error_message = Some(e);
+                return Task::none();
+            }
+        };
+        self.error_message = None;
+
+        let mut tasks = vec![
+            Task::perform(routes::fetch_report_overview(filter.clone()), ReportsAnalyticsMessage::OverviewLoaded),
+            self.generate_selected_report(),
+        ];
+        if self.show_charts {
+            tasks.push(self.load_charts(filter));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Fetches the series behind the charts; the teacher and split charts reuse
+    /// the projected income report.
+    fn load_charts(&mut self, filter: ReportFilter) -> Task<ReportsAnalyticsMessage> {
+        Task::batch(vec![
+            Task::perform(routes::fetch_collection_trend_report(filter.clone()), ReportsAnalyticsMessage::CollectionTrendLoaded),
+            Task::perform(routes::fetch_term_comparison_report(filter.clone()), ReportsAnalyticsMessage::TermComparisonLoaded),
+            Task::perform(routes::fetch_projected_income_report(filter.clone()), move |result| {
+                ReportsAnalyticsMessage::ProjectedIncomeLoaded(filter.clone(), result)
+            }),
+        ])
+    }
+
+    /// Renders the selected report and asks where to save it.
+    pub fn export(&mut self, generated_by: &str) -> Task<ReportsAnalyticsMessage> {
+        self.export_message = None;
+        match ReportDocument::from_state(self, generated_by) {
+            Ok(document) => {
+                let format = self.export_format;
+                Task::perform(
+                    async move { export::pick_path_and_export(document, format) },
+                    ReportsAnalyticsMessage::ExportCompleted,
+                )
+            },
+            Err(e) => {
+                self.error_message = Some(e.to_string());
+                Task::none()
+            },
+        }
+    }
+
+    pub fn update(&mut self, message: ReportsAnalyticsMessage) -> Task<ReportsAnalyticsMessage> {
+        match message {
+            ReportsAnalyticsMessage::LoadReports => {
+                Task::batch(vec![
+                    Task::perform(routes::fetch_all_teachers(), ReportsAnalyticsMessage::TeachersLoaded),
+                    self.refresh(),
+                ])
+            },
+            ReportsAnalyticsMessage::RefreshReports | ReportsAnalyticsMessage::ApplyReportFilters => {
+                self.refresh()
+            },
+            ReportsAnalyticsMessage::SelectReportType(report_type) => {
+                self.selected_report_type = report_type;
+                self.generate_selected_report()
+            },
+            ReportsAnalyticsMessage::DateRange(_) => {
+                // Handled by DashboardState, which holds the terms the presets need
+                Task::none()
+            },
+            ReportsAnalyticsMessage::UpdateTeacherFilter(teacher_id) => {
+                self.selected_teacher_id = teacher_id;
+                Task::none()
+            },
+            ReportsAnalyticsMessage::ExportReport => {
+                // Handled by DashboardState, which knows who is generating the export
+                Task::none()
+            },
+            ReportsAnalyticsMessage::SelectExportFormat(format) => {
+                self.export_format = format;
+                Task::none()
+            },
+            ReportsAnalyticsMessage::ToggleCharts => {
+                self.show_charts = !self.show_charts;
+                if !self.show_charts {
+                    return Task::none();
+                }
+                match self.report_filter() {
+                    Ok(filter) => self.load_charts(filter),
+                    Err(e) => {
+                        self.error_message = Some(e);
+                        Task::none()
+                    }
+                }
+            },
+            ReportsAnalyticsMessage::ExportCompleted(result) => {
+                match result {
+                    Ok(path) => self.export_message = Some(format!("Report saved to {}", path.display())),
+                    Err(e) => self.error_message = Some(format!("Export failed: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::GenerateProjectedIncome
+            | ReportsAnalyticsMessage::GenerateCollectionStatus
+            | ReportsAnalyticsMessage::GenerateTeacherEarnings
+            | ReportsAnalyticsMessage::GenerateStudentPayments
+            | ReportsAnalyticsMessage::GenerateExemptions
+            | ReportsAnalyticsMessage::GenerateOverdueAging => {
+                let filter = match self.report_filter() {
+                    Ok(filter) => filter,
+                    Err(e) => {
+                        self.error_message = Some(e);
+                        return Task::none();
+                    }
+                };
+                self.is_loading = true;
+
+                match message {
+                    ReportsAnalyticsMessage::GenerateProjectedIncome => Task::perform(
+                        routes::fetch_projected_income_report(filter.clone()),
+                        move |result| ReportsAnalyticsMessage::ProjectedIncomeLoaded(filter.clone(), result),
+                    ),
+                    ReportsAnalyticsMessage::GenerateCollectionStatus => Task::perform(
+                        routes::fetch_collection_status_report(filter.clone()),
+                        move |result| ReportsAnalyticsMessage::CollectionStatusLoaded(filter.clone(), result),
+                    ),
+                    ReportsAnalyticsMessage::GenerateTeacherEarnings => Task::perform(
+                        routes::fetch_teacher_earnings_report(filter.clone()),
+                        move |result| ReportsAnalyticsMessage::TeacherEarningsLoaded(filter.clone(), result),
+                    ),
+                    ReportsAnalyticsMessage::GenerateExemptions => Task::perform(
+                        routes::fetch_exemptions_report(filter.clone()),
+                        move |result| ReportsAnalyticsMessage::ExemptionsLoaded(filter.clone(), result),
+                    ),
+                    ReportsAnalyticsMessage::GenerateOverdueAging => Task::perform(
+                        routes::fetch_overdue_aging_report(filter.clone()),
+                        move |result| ReportsAnalyticsMessage::OverdueAgingLoaded(filter.clone(), result),
+                    ),
+                    _ => Task::perform(
+                        routes::fetch_student_payments_report(filter.clone()),
+                        move |result| ReportsAnalyticsMessage::StudentPaymentsLoaded(filter.clone(), result),
+                    ),
+                }
+            },
+
+            ReportsAnalyticsMessage::TeachersLoaded(result) => {
+                match result {
+                    Ok(teachers) => self.teachers = teachers,
+                    Err(e) => self.error_message = Some(format!("Failed to load teachers: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::OverviewLoaded(result) => {
+                match result {
+                    Ok(overview) => {
+                        self.school_name = overview.school_name;
+                        self.term_name = overview.term_name;
+                        self.total_students = overview.total_students;
+                        self.expected_revenue = overview.expected_revenue;
+                        self.total_exempted = overview.total_exempted;
+                        self.total_revenue = overview.total_revenue;
+                        self.collection_rate = overview.collection_rate;
+                    },
+                    Err(e) => self.error_message = Some(format!("Failed to load overview: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::ProjectedIncomeLoaded(filter, result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(report) => {
+                        self.teacher_earnings = report.teacher_earnings.clone();
+                        self.projected_income = Some(report);
+                        self.mark_applied(ReportType::ProjectedIncome, filter);
+                    },
+                    Err(e) => self.error_message = Some(format!("Failed to load projected income: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::CollectionStatusLoaded(filter, result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(summary) => {
+                        self.payment_summary = summary;
+                        self.mark_applied(ReportType::CollectionStatus, filter);
+                    },
+                    Err(e) => self.error_message = Some(format!("Failed to load collection status: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::TeacherEarningsLoaded(filter, result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(earnings) => {
+                        self.teacher_earnings = earnings;
+                        self.mark_applied(ReportType::TeacherEarnings, filter);
+                    },
+                    Err(e) => self.error_message = Some(format!("Failed to load teacher earnings: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::StudentPaymentsLoaded(filter, result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(histories) => {
+                        self.student_payments = histories;
+                        self.mark_applied(ReportType::StudentPayments, filter);
+                    },
+                    Err(e) => self.error_message = Some(format!("Failed to load student payments: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::ExemptionsLoaded(filter, result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(exemptions) => {
+                        self.exemptions = exemptions;
+                        self.mark_applied(ReportType::Exemptions, filter);
+                    },
+                    Err(e) => self.error_message = Some(format!("Failed to load exemptions: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::OverdueAgingLoaded(filter, result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(mut rows) => {
+                        rows.sort_by(|a, b| b.days_overdue.cmp(&a.days_overdue));
+                        self.overdue_aging = rows;
+                        self.mark_applied(ReportType::OverdueAging, filter);
+                    },
+                    Err(e) => self.error_message = Some(format!("Failed to load overdue aging: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::CollectionTrendLoaded(result) => {
+                match result {
+                    Ok(trend) => self.collection_trend = trend,
+                    Err(e) => self.error_message = Some(format!("Failed to load collection trend: {}", e)),
+                }
+                Task::none()
+            },
+            ReportsAnalyticsMessage::TermComparisonLoaded(result) => {
+                match result {
+                    Ok(terms) => self.term_comparison = terms,
+                    Err(e) => self.error_message = Some(format!("Failed to load term comparison: {}", e)),
+                }
+                Task::none()
+            },
+        }
+    }
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
-            admin_share_percentage: 30.0,
-            staff_share_percentage: 20.0,
-            teacher_share_percentage: 50.0,
-            default_payment_method: "Cash".to_string(),
-            currency_symbol: "₵".to_string(),
-            date_format: "DD/MM/YYYY".to_string(),
+            active_school: None,
+            schools: HashMap::new(),
             backup_frequency: "Daily".to_string(),
             auto_backup_enabled: true,
+            backup_folder: settings::data_dir().join("backups"),
+            backup_retention: 14,
+            encrypt_backups: true,
+            backup_passphrase: None,
             theme: AppTheme::Light,
+            lock_after_minutes: 5,
+            logout_after_minutes: 30,
         }
     }
 }
@@ -973,10 +5253,16 @@ pub enum TeachingPeriodMessage {
     UpdateStartTime(String),
     UpdateEndTime(String),
     UpdateRate(String),
-    ImportPeriods(String),
+    ImportContextLoaded(Result<PeriodImportContext, AppError>),
+    DownloadImportTemplate,
+    TemplateGenerated(Result<PathBuf, AppError>),
+    ChooseImportFile,
     PreviewImport(String),
+    ImportPreviewLoaded(Result<ImportPreview, AppError>),
+    ImportPreview(ImportPreviewMessage),
     ConfirmImport,
     CancelImport,
+    ImportResult(Result<Saved<ImportSummary>, AppError>),
 }
 
 #[derive(Debug, Clone)]
@@ -990,33 +5276,64 @@ pub enum PaymentTrackingMessage {
     UpdatePaymentAmount(String),
     UpdatePaymentMethod(String),
     UpdatePaymentDescription(String),
-    UpdateDateFilterFrom(String),
-    UpdateDateFilterTo(String),
+    DateRange(DateRangeMessage),
+    StudentsLoaded(Result<Vec<Student>, AppError>),
+    PaymentsLoaded(Result<Vec<Payment>, AppError>),
     SubmitPayment,
+    PaymentRecorded(Result<Saved<Payment>, AppError>),
     CancelPayment,
     DeletePayment(String),
+    ShowExemptionDialog(Uuid),
+    ExemptionTermSelected(Term),
+    ExemptionReasonSelected(ExemptionReason),
+    ExemptionFullToggled(bool),
+    ExemptionAmountChanged(String),
+    ExemptionNotesChanged(String),
+    SubmitExemption,
+    ExemptionSaved(Result<Exemption, AppError>),
+    CancelExemption,
+    ShowInstallmentPlan(Uuid),
+    InstallmentTermSelected(Term),
+    InstallmentDueDateChanged(usize, String),
+    InstallmentAmountChanged(usize, String),
+    AddInstallment,
+    RemoveInstallment(usize),
+    SplitInstallmentsEvenly,
+    SaveInstallmentPlan,
+    InstallmentPlanSaved(Result<Option<InstallmentPlan>, AppError>),
+    CancelInstallmentPlan,
 }
 
 #[derive(Debug, Clone)]
 pub enum UserAccessMessage {
     LoadUsers,
+    UsersLoaded(Result<Vec<UserResponse>, AppError>),
     UpdateSearchQuery(String),
+    ToggleShowInactive(bool),
     ShowAddUserDialog,
-    ShowEditUserDialog(String),
-    ShowDeleteConfirmation(String),
-    AddUser,
     EditUser(String),
-    DeleteUser(String),
+    DeactivateUser(String),
+    ReactivateUser(String),
     ResetUserPassword(String),
     UpdateNewUserFirstName(String),
     UpdateNewUserSurname(String),
+    UpdateNewUserOtherNames(String),
     UpdateNewUserUsername(String),
     UpdateNewUserPassword(String),
     UpdateNewUserRole(UserRole),
+    ToggleNewUserSchool(Uuid, bool),
     SubmitNewUser,
     SubmitEditUser,
     CancelUserDialog,
-    ConfirmDeleteUser,
+    ConfirmDeactivateUser,
+    ConfirmResetPassword,
+    UserSaved(Result<UserResponse, AppError>),
+    PasswordReset(Result<(UserResponse, String), AppError>),
+    DismissTemporaryPassword,
+    ShowLoginHistory(Option<String>),
+    ToggleFailedLoginsOnly(bool),
+    LoginHistoryLoaded(Result<Vec<LoginAttempt>, AppError>),
+    HideLoginHistory,
 }
 
 #[derive(Debug, Clone)]
@@ -1024,13 +5341,27 @@ pub enum ReportsAnalyticsMessage {
     LoadReports,
     RefreshReports,
     SelectReportType(ReportType),
-    UpdateDateFilterFrom(String),
-    UpdateDateFilterTo(String),
+    DateRange(DateRangeMessage),
     UpdateTeacherFilter(Option<String>),
     ApplyReportFilters,
     ExportReport,
+    SelectExportFormat(ExportFormat),
+    ExportCompleted(Result<PathBuf, AppError>),
+    ToggleCharts,
     GenerateProjectedIncome,
     GenerateCollectionStatus,
     GenerateTeacherEarnings,
     GenerateStudentPayments,
+    GenerateExemptions,
+    GenerateOverdueAging,
+    TeachersLoaded(Result<Vec<Teacher>, AppError>),
+    OverviewLoaded(Result<ReportOverview, AppError>),
+    ProjectedIncomeLoaded(ReportFilter, Result<ProjectedIncomeReport, AppError>),
+    CollectionStatusLoaded(ReportFilter, Result<PaymentSummary, AppError>),
+    TeacherEarningsLoaded(ReportFilter, Result<Vec<TeacherEarnings>, AppError>),
+    StudentPaymentsLoaded(ReportFilter, Result<Vec<StudentPaymentHistory>, AppError>),
+    ExemptionsLoaded(ReportFilter, Result<Vec<ExemptionReportRow>, AppError>),
+    OverdueAgingLoaded(ReportFilter, Result<Vec<OverdueAgingRow>, AppError>),
+    CollectionTrendLoaded(Result<Vec<WeeklyCollection>, AppError>),
+    TermComparisonLoaded(Result<Vec<TermComparison>, AppError>),
 }
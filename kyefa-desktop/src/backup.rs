@@ -0,0 +1,347 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::routes;
+
+/// Choices for `AppSettings::backup_frequency`
+pub const BACKUP_FREQUENCIES: [&str; 3] = ["Daily", "Weekly", "Monthly"];
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Shortest passphrase accepted for encrypting backups
+pub const MIN_PASSPHRASE_LEN: usize = 12;
+
+/// Start of every encrypted archive; the trailing byte is the format version.
+const ENCRYPTED_MAGIC: &[u8; 10] = b"KYEFA-ENC\x01";
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// Magic, iteration count, salt and nonce. Authenticated along with the
+/// ciphertext, so none of it can be altered without failing decryption.
+const HEADER_LEN: usize = ENCRYPTED_MAGIC.len() + 4 + SALT_LEN + NONCE_LEN;
+
+/// One archive in the backup folder, as recorded in its manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub file_name: String,
+    pub created_at: DateTime<Utc>,
+    /// SHA-256 of the archive file, hex encoded
+    pub sha256: String,
+    pub size_bytes: u64,
+    /// Whether the archive is encrypted and needs the passphrase to restore
+    #[serde(default)]
+    pub encrypted: bool,
+}
+
+/// Written next to the archives so a backup can be checked before it is
+/// restored, including on another computer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub entries: Vec<BackupEntry>,
+}
+
+/// Time between automatic backups for a `backup_frequency` setting;
+/// anything unrecognised is treated as daily.
+pub fn interval(frequency: &str) -> Duration {
+    match frequency {
+        "Weekly" => Duration::weeks(1),
+        "Monthly" => Duration::days(30),
+        _ => Duration::days(1),
+    }
+}
+
+fn checksum(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: NonZeroU32) -> Result<LessSafeKey, AppError> {
+    let mut key = [0u8; 32];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    UnboundKey::new(&AES_256_GCM, &key)
+        .map(LessSafeKey::new)
+        .map_err(|_| AppError::Configuration("Failed to set up backup encryption.".into()))
+}
+
+/// Encrypts `archive` with AES-256-GCM under a key derived from `passphrase`.
+/// Only the random salt and nonce are stored with it, never the key.
+fn encrypt(archive: Vec<u8>, passphrase: &str) -> Result<Vec<u8>, AppError> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| AppError::Configuration("No secure random source for backup encryption.".into()))?;
+
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).expect("iteration count is non-zero");
+    let key = derive_key(passphrase, &salt, iterations)?;
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(ENCRYPTED_MAGIC);
+    header.extend_from_slice(&PBKDF2_ITERATIONS.to_be_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let mut sealed = archive;
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(&header), &mut sealed)
+        .map_err(|_| AppError::Configuration("Failed to encrypt backup.".into()))?;
+    header.extend_from_slice(&sealed);
+    Ok(header)
+}
+
+fn decrypt(file_name: &str, encrypted: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    if encrypted.len() < HEADER_LEN || !encrypted.starts_with(ENCRYPTED_MAGIC) {
+        return Err(AppError::IoError(format!("{} is not an encrypted Kyefa backup.", file_name)));
+    }
+    let (header, sealed) = encrypted.split_at(HEADER_LEN);
+    let (iterations, rest) = header[ENCRYPTED_MAGIC.len()..].split_at(4);
+    let (salt, nonce) = rest.split_at(SALT_LEN);
+
+    // Version 1 archives are always written with PBKDF2_ITERATIONS. Any other
+    // count is damage or tampering, and honouring it could mean deriving the
+    // key billions of times before the tag check fails.
+    if u32::from_be_bytes(iterations.try_into().expect("four bytes")) != PBKDF2_ITERATIONS {
+        return Err(AppError::IoError(format!("{} has a damaged header.", file_name)));
+    }
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).expect("iteration count is non-zero");
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| AppError::IoError(format!("{} has a damaged header.", file_name)))?;
+    let key = derive_key(passphrase, salt, iterations)?;
+
+    let mut opened = sealed.to_vec();
+    let plain_len = key.open_in_place(nonce, Aad::from(header), &mut opened)
+        .map_err(|_| AppError::IoError(format!("Wrong passphrase for {}.", file_name)))?
+        .len();
+    opened.truncate(plain_len);
+    Ok(opened)
+}
+
+fn read_manifest(folder: &Path) -> Result<BackupManifest, AppError> {
+    match fs::read_to_string(folder.join(MANIFEST_FILE)) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| AppError::SerializationError(format!("Backup manifest is unreadable: {}", e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BackupManifest::default()),
+        Err(e) => Err(AppError::IoError(format!("Failed to open backup manifest: {}", e))),
+    }
+}
+
+fn write_manifest(folder: &Path, manifest: &BackupManifest) -> Result<(), AppError> {
+    let contents = serde_json::to_string_pretty(manifest)
+        .map_err(|e| AppError::SerializationError(format!("Failed to write backup manifest: {}", e)))?;
+    fs::write(folder.join(MANIFEST_FILE), contents)
+        .map_err(|e| AppError::IoError(format!("Failed to write backup manifest: {}", e)))
+}
+
+/// Backups in `folder`, newest first
+pub fn list_backups(folder: &Path) -> Result<Vec<BackupEntry>, AppError> {
+    let mut entries = read_manifest(folder)?.entries;
+    entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(entries)
+}
+
+/// Whether an automatic backup is due, going by the newest one in `folder`
+pub fn is_due(folder: &Path, frequency: &str, now: DateTime<Utc>) -> bool {
+    match list_backups(folder) {
+        Ok(entries) => entries.first().is_none_or(|latest| now - latest.created_at >= interval(frequency)),
+        // Let the backup itself report what is wrong with the folder
+        Err(_) => true,
+    }
+}
+
+/// Downloads a dump of the active school's data and writes it to `folder` as a gzip
+/// archive named for the time it was taken, encrypted when a `passphrase` is
+/// given. Only the newest `retention` archives are kept.
+pub async fn create_backup(folder: PathBuf, retention: usize, passphrase: Option<String>) -> Result<BackupEntry, AppError> {
+    let dump = routes::export_database().await?;
+    if dump.is_empty() {
+        return Err(AppError::BackendError("The server returned an empty database dump.".into()));
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&dump).map_err(|e| AppError::IoError(format!("Failed to compress backup: {}", e)))?;
+    let archive = encoder.finish().map_err(|e| AppError::IoError(format!("Failed to compress backup: {}", e)))?;
+    let archive = match passphrase.as_deref() {
+        Some(passphrase) => encrypt(archive, passphrase)?,
+        None => archive,
+    };
+
+    fs::create_dir_all(&folder)
+        .map_err(|e| AppError::IoError(format!("Backup folder {} is not available: {}", folder.display(), e)))?;
+    let created_at = Utc::now();
+    let file_name = format!(
+        "kyefa-backup-{}.json.gz{}",
+        created_at.with_timezone(&Local).format("%Y%m%d-%H%M%S"),
+        if passphrase.is_some() { ".enc" } else { "" },
+    );
+    fs::write(folder.join(&file_name), &archive)
+        .map_err(|e| AppError::IoError(format!("Failed to write backup: {}", e)))?;
+
+    let entry = BackupEntry {
+        file_name,
+        created_at,
+        sha256: checksum(&archive),
+        size_bytes: archive.len() as u64,
+        encrypted: passphrase.is_some(),
+    };
+    let mut manifest = read_manifest(&folder)?;
+    manifest.entries.push(entry.clone());
+    rotate(&folder, &mut manifest, retention);
+    write_manifest(&folder, &manifest)?;
+    Ok(entry)
+}
+
+/// Deletes the oldest archives beyond `retention`, always keeping at least one.
+fn rotate(folder: &Path, manifest: &mut BackupManifest, retention: usize) {
+    manifest.entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    for expired in manifest.entries.split_off(retention.max(1).min(manifest.entries.len())) {
+        // An archive already removed by hand only needs its manifest entry dropped
+        let _ = fs::remove_file(folder.join(&expired.file_name));
+    }
+}
+
+/// Reads the archive and checks it against its manifest checksum. This
+/// needs no passphrase, so a damaged file is caught before one is asked for.
+pub fn check_file(folder: &Path, entry: &BackupEntry) -> Result<Vec<u8>, AppError> {
+    let archive = fs::read(folder.join(&entry.file_name))
+        .map_err(|e| AppError::IoError(format!("Failed to read {}: {}", entry.file_name, e)))?;
+    if archive.len() as u64 != entry.size_bytes || checksum(&archive) != entry.sha256 {
+        return Err(AppError::IoError(format!(
+            "{} does not match its checksum in the manifest; the file is damaged or was changed.",
+            entry.file_name,
+        )));
+    }
+    Ok(archive)
+}
+
+/// Checks the archive's checksum, decrypts it when encrypted and unpacks it
+/// in full, returning the dump it holds. Since the file already passed its
+/// checksum, a failed decryption means the passphrase is wrong.
+pub fn verify(folder: &Path, entry: &BackupEntry, passphrase: Option<&str>) -> Result<Vec<u8>, AppError> {
+    let mut archive = check_file(folder, entry)?;
+    if entry.encrypted {
+        let passphrase = passphrase
+            .ok_or_else(|| AppError::IoError(format!("{} is encrypted; enter its passphrase.", entry.file_name)))?;
+        archive = decrypt(&entry.file_name, &archive, passphrase)?;
+    }
+
+    let mut dump = Vec::new();
+    GzDecoder::new(&archive[..])
+        .read_to_end(&mut dump)
+        .map_err(|e| AppError::IoError(format!("{} is not a readable backup archive: {}", entry.file_name, e)))?;
+    if dump.is_empty() {
+        return Err(AppError::IoError(format!("{} is empty.", entry.file_name)));
+    }
+    Ok(dump)
+}
+
+/// Verifies the whole archive first, so nothing reaches the backend unless
+/// it decrypted and unpacked cleanly, then replaces the school's data with it.
+pub async fn restore(folder: PathBuf, entry: BackupEntry, passphrase: Option<String>, key: Uuid) -> Result<(), AppError> {
+    let dump = verify(&folder, &entry, passphrase.as_deref())?;
+    routes::restore_database(dump, key).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty folder under the system temp dir, removed again by the test
+    fn scratch_folder() -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("kyefa-backup-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    fn entry(folder: &Path, hours_ago: i64) -> BackupEntry {
+        let file_name = format!("backup-{}.json.gz", hours_ago);
+        fs::write(folder.join(&file_name), b"archive").unwrap();
+        BackupEntry {
+            file_name,
+            created_at: Utc::now() - Duration::hours(hours_ago),
+            sha256: checksum(b"archive"),
+            size_bytes: 7,
+            encrypted: false,
+        }
+    }
+
+    /// Key derivation is deliberately slow, so the tests share one archive
+    fn sealed() -> Vec<u8> {
+        static SEALED: std::sync::OnceLock<Vec<u8>> = std::sync::OnceLock::new();
+        SEALED.get_or_init(|| encrypt(b"dump".to_vec(), "correct horse battery").unwrap()).clone()
+    }
+
+    #[test]
+    fn encrypted_archive_opens_with_its_passphrase() {
+        let sealed = sealed();
+        assert!(sealed.starts_with(ENCRYPTED_MAGIC));
+        assert!(!sealed.windows(4).any(|window| window == b"dump"));
+        assert_eq!(decrypt("backup", &sealed, "correct horse battery").unwrap(), b"dump");
+    }
+
+    #[test]
+    fn wrong_passphrase_is_reported() {
+        let error = decrypt("backup", &sealed(), "wrong horse battery").unwrap_err();
+        assert!(error.to_string().contains("Wrong passphrase"));
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let sealed = sealed();
+
+        let mut body = sealed.clone();
+        *body.last_mut().unwrap() ^= 1;
+        assert!(decrypt("backup", &body, "correct horse battery").is_err());
+
+        // The salt is authenticated along with the ciphertext
+        let mut salt = sealed;
+        salt[ENCRYPTED_MAGIC.len() + 4] ^= 1;
+        assert!(decrypt("backup", &salt, "correct horse battery").is_err());
+    }
+
+    #[test]
+    fn iteration_count_other_than_the_format_is_refused() {
+        let mut sealed = sealed();
+        let count = ENCRYPTED_MAGIC.len()..ENCRYPTED_MAGIC.len() + 4;
+        sealed[count].copy_from_slice(&u32::MAX.to_be_bytes());
+        let error = decrypt("backup", &sealed, "correct horse battery").unwrap_err();
+        assert!(error.to_string().contains("damaged header"));
+    }
+
+    #[test]
+    fn rotation_keeps_the_newest_archives() {
+        let folder = scratch_folder();
+        let mut manifest = BackupManifest { entries: vec![entry(&folder, 3), entry(&folder, 1), entry(&folder, 2)] };
+
+        rotate(&folder, &mut manifest, 2);
+
+        let kept: Vec<_> = manifest.entries.iter().map(|entry| entry.file_name.as_str()).collect();
+        assert_eq!(kept, ["backup-1.json.gz", "backup-2.json.gz"]);
+        assert!(!folder.join("backup-3.json.gz").exists());
+        assert!(folder.join("backup-2.json.gz").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn rotation_always_keeps_one_archive() {
+        let folder = scratch_folder();
+        let mut manifest = BackupManifest { entries: vec![entry(&folder, 1)] };
+
+        rotate(&folder, &mut manifest, 0);
+
+        assert_eq!(manifest.entries.len(), 1);
+        assert!(folder.join("backup-1.json.gz").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,169 @@
+use kyefa_models::{PersonName, Student};
+
+/// Name similarity at or above which two students in the same class are
+/// offered as a likely duplicate. "Kwame Mensah" / "Kwame Mensa" scores ~0.92.
+pub const DUPLICATE_THRESHOLD: f64 = 0.85;
+
+/// Two active students in the same class whose names are close enough to be
+/// the same child entered twice
+#[derive(Debug, Clone)]
+pub struct DuplicateCandidate {
+    pub first: Student,
+    pub second: Student,
+    pub similarity: f64,
+}
+
+fn normalise(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+fn string_similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f64 / longest as f64
+}
+
+/// Similarity of two names from 0.0 to 1.0, on first name and surname. Also
+/// tries the names swapped, since the two fields are often entered in either order.
+pub fn name_similarity(a: &PersonName, b: &PersonName) -> f64 {
+    let a_full = normalise(&format!("{} {}", a.first_name, a.surname));
+    let b_full = normalise(&format!("{} {}", b.first_name, b.surname));
+    let b_swapped = normalise(&format!("{} {}", b.surname, b.first_name));
+
+    string_similarity(&a_full, &b_full).max(string_similarity(&a_full, &b_swapped))
+}
+
+/// Pairs of active, unmerged students in the same `ClassLevel` with similar
+/// names, most similar first.
+pub fn find_duplicates(students: &[Student]) -> Vec<DuplicateCandidate> {
+    let active: Vec<&Student> = students
+        .iter()
+        .filter(|student| student.is_active && student.merged_into.is_none())
+        .collect();
+
+    let mut candidates = Vec::new();
+    for (i, first) in active.iter().enumerate() {
+        for second in &active[i + 1..] {
+            if first.class_level != second.class_level {
+                continue;
+            }
+            let similarity = name_similarity(&first.name, &second.name);
+            if similarity >= DUPLICATE_THRESHOLD {
+                candidates.push(DuplicateCandidate {
+                    first: (*first).clone(),
+                    second: (*second).clone(),
+                    similarity,
+                });
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kyefa_models::{ClassLevel, Gender, StudentRow};
+    use uuid::Uuid;
+
+    fn name(first_name: &str, surname: &str) -> PersonName {
+        PersonName { first_name: first_name.to_string(), surname: surname.to_string(), other_names: None }
+    }
+
+    fn student(first_name: &str, surname: &str, class_level: ClassLevel) -> Student {
+        Student::from(StudentRow {
+            id: Uuid::new_v4(),
+            school_id: Uuid::new_v4(),
+            first_name: first_name.to_string(),
+            surname: surname.to_string(),
+            other_names: None,
+            gender: Gender::Male,
+            class_level,
+            is_active: true,
+            merged_into: None,
+        })
+    }
+
+    #[test]
+    fn levenshtein_counts_edits() {
+        assert_eq!(levenshtein("mensah", "mensa"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+
+    #[test]
+    fn one_letter_off_is_above_the_threshold() {
+        let similarity = name_similarity(&name("Kwame", "Mensah"), &name("Kwame", "Mensa"));
+        assert!(similarity >= DUPLICATE_THRESHOLD, "{}", similarity);
+        assert!((similarity - 11.0 / 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn case_spacing_and_swapped_names_do_not_matter() {
+        assert_eq!(name_similarity(&name("  KWAME ", "mensah"), &name("Kwame", "Mensah")), 1.0);
+        assert_eq!(name_similarity(&name("Kwame", "Mensah"), &name("Mensah", "Kwame")), 1.0);
+    }
+
+    #[test]
+    fn different_children_are_below_the_threshold() {
+        assert!(name_similarity(&name("Kwame", "Mensah"), &name("Kofi", "Mensah")) < DUPLICATE_THRESHOLD);
+        assert!(name_similarity(&name("Ama", "Owusu"), &name("Efua", "Asante")) < DUPLICATE_THRESHOLD);
+    }
+
+    #[test]
+    fn duplicates_are_only_found_within_a_class() {
+        let students = [
+            student("Kwame", "Mensah", ClassLevel::IGCSE1),
+            student("Kwame", "Mensa", ClassLevel::IGCSE1),
+            student("Kwame", "Mensah", ClassLevel::IGCSE2),
+        ];
+        let candidates = find_duplicates(&students);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].first.id, students[0].id);
+        assert_eq!(candidates[0].second.id, students[1].id);
+    }
+
+    #[test]
+    fn merged_and_inactive_students_are_left_out() {
+        let mut merged = student("Kwame", "Mensa", ClassLevel::IGCSE1);
+        merged.merged_into = Some(Uuid::new_v4());
+        let mut inactive = student("Kwame", "Mensah", ClassLevel::IGCSE1);
+        inactive.is_active = false;
+        let students = [student("Kwame", "Mensah", ClassLevel::IGCSE1), merged, inactive];
+        assert!(find_duplicates(&students).is_empty());
+    }
+
+    #[test]
+    fn closest_pairs_come_first() {
+        let students = [
+            student("Kwame", "Mensah", ClassLevel::IGCSE1),
+            student("Kwame", "Mensa", ClassLevel::IGCSE1),
+            student("Kwame", "Mensah", ClassLevel::IGCSE1),
+        ];
+        let candidates = find_duplicates(&students);
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0].similarity, 1.0);
+        assert!(candidates.windows(2).all(|pair| pair[0].similarity >= pair[1].similarity));
+    }
+}
@@ -11,6 +11,9 @@ pub enum AppError {
     Login(LoginError),
     Dashboard(DashboardError),
     Configuration(String),
+    /// The request never got an answer: the connection failed or timed out.
+    /// Offline writes are queued only on this.
+    Unreachable(String),
     NetworkIssue(String),  
     SerializationError(String),
     BackendError(String),  
@@ -19,10 +22,13 @@ pub enum AppError {
 
 #[derive(Debug, Clone)]
 pub enum LoginError {
-    UserNotFound(String),  
+    /// Also covers unknown usernames and locked accounts, which the backend
+    /// doesn't tell apart
     InvalidCredentials(String),
     NetworkIssue(String), 
     ServerError(String),
+    /// The account isn't linked to any active school
+    NoSchool,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +54,7 @@ impl fmt::Display for AppError {
             AppError::Login(e) => write!(f, "Login Error: {}", e),
             AppError::Dashboard(e) => write!(f, "Dashboard Error: {:?}", e), // DashboardError needs Display impl or debug print
             AppError::Configuration(msg) => write!(f, "Configuration Error: {}", msg),
+            AppError::Unreachable(msg) => write!(f, "Backend unreachable: {}", msg),
             AppError::NetworkIssue(msg) => write!(f, "Network Issue: {}", msg),
             AppError::SerializationError(msg) => write!(f, "Serialization Error: {}", msg),
             AppError::BackendError(msg) => write!(f, "Backend API Error: {}", msg),
@@ -59,17 +66,21 @@ impl fmt::Display for AppError {
 impl fmt::Display for LoginError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            LoginError::UserNotFound(msg) => write!(f, "User not found: {}", msg),
             LoginError::InvalidCredentials(msg) => write!(f, "Invalid credentials: {}", msg),
             LoginError::NetworkIssue(msg) => write!(f, "Network problem: {}", msg),
             LoginError::ServerError(msg) => write!(f, "Server error: {}", msg),
+            LoginError::NoSchool => write!(f, "No school: the account isn't linked to an active school"),
         }
     }
 }
 
 impl From<reqwest::Error> for AppError {
     fn from(err: reqwest::Error) -> Self {
-        AppError::NetworkIssue(err.to_string())
+        if err.is_connect() || err.is_timeout() {
+            AppError::Unreachable(err.to_string())
+        } else {
+            AppError::NetworkIssue(err.to_string())
+        }
     }
 }
 
@@ -0,0 +1,652 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+use umya_spreadsheet::{new_file, writer};
+
+use kyefa_models::{ReportType, ExemptionReason, AgingBucket, Payslip};
+use crate::app::ReportsAnalyticsState;
+use crate::error::AppError;
+use crate::ui::date_input::format_date;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Xlsx,
+    Csv,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Xlsx, ExportFormat::Csv, ExportFormat::Pdf];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportFormat::Xlsx => write!(f, "Excel (.xlsx)"),
+            ExportFormat::Csv => write!(f, "CSV (.csv)"),
+            ExportFormat::Pdf => write!(f, "PDF (.pdf)"),
+        }
+    }
+}
+
+/// A single value in an exported table, kept typed so xlsx can format it
+#[derive(Debug, Clone)]
+pub enum ReportCell {
+    Text(String),
+    Money(f64),
+    Count(usize),
+    Percent(f64),
+}
+
+impl ReportCell {
+    fn display(&self) -> String {
+        match self {
+            ReportCell::Text(value) => value.clone(),
+            ReportCell::Money(value) => format!("{:.2}", value),
+            ReportCell::Count(value) => value.to_string(),
+            ReportCell::Percent(value) => format!("{:.1}%", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportTable {
+    pub title: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<ReportCell>>,
+    pub totals: Option<Vec<ReportCell>>,
+}
+
+/// Format-independent view of a report, rendered by the `write_*` functions
+#[derive(Debug, Clone)]
+pub struct ReportDocument {
+    pub title: String,
+    pub header: Vec<(String, String)>,
+    pub tables: Vec<ReportTable>,
+}
+
+fn text(value: impl Into<String>) -> ReportCell {
+    ReportCell::Text(value.into())
+}
+
+/// The current time for a "Generated at" line, with the date in `date_format`
+fn timestamp(date_format: &str) -> String {
+    let now = Local::now();
+    format!("{} {}", format_date(now.date_naive(), date_format), now.format("%H:%M"))
+}
+
+fn columns(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+/// Heading for a column of amounts, e.g. "Amount (₵)"
+fn money_column(label: &str, currency_symbol: &str) -> String {
+    format!("{} ({})", label, currency_symbol)
+}
+
+impl ReportDocument {
+    pub fn from_state(state: &ReportsAnalyticsState, generated_by: &str) -> Result<Self, AppError> {
+        let money = |label: &str| money_column(label, &state.currency_symbol);
+        let (title, tables) = match state.selected_report_type {
+            ReportType::ProjectedIncome => {
+                let report = state.projected_income.as_ref().ok_or_else(|| {
+                    AppError::Configuration("Generate the projected income report before exporting.".to_string())
+                })?;
+                ("Projected Income", vec![
+                    ReportTable {
+                        title: "Revenue Split".to_string(),
+                        columns: columns(&["Share", "Percentage", &money("Amount")]),
+                        rows: vec![
+                            vec![text("Admin"), ReportCell::Percent(report.admin_percentage), ReportCell::Money(report.admin_share)],
+                            vec![text("Support Staff"), ReportCell::Percent(report.support_staff_percentage), ReportCell::Money(report.support_staff_share)],
+                            vec![text("Teachers"), ReportCell::Percent(report.teacher_percentage), ReportCell::Money(report.teacher_share)],
+                        ],
+                        totals: Some(vec![
+                            text("Total Projected Income"),
+                            ReportCell::Percent(report.admin_percentage + report.support_staff_percentage + report.teacher_percentage),
+                            ReportCell::Money(report.expected_revenue),
+                        ]),
+                    },
+                    teacher_earnings_table(state),
+                ])
+            },
+            ReportType::CollectionStatus => {
+                let summary = &state.payment_summary;
+                ("Collection Status", vec![
+                    ReportTable {
+                        title: "Collection Summary".to_string(),
+                        columns: columns(&["Item", &money("Amount")]),
+                        rows: vec![
+                            vec![text("Total Exempted"), ReportCell::Money(summary.total_exempted)],
+                            vec![text("Total Received"), ReportCell::Money(summary.total_received)],
+                            vec![text("Total Pending"), ReportCell::Money(summary.total_pending)],
+                        ],
+                        totals: Some(vec![text("Total Expected"), ReportCell::Money(summary.total_expected)]),
+                    },
+                    ReportTable {
+                        title: "Payment Status Breakdown".to_string(),
+                        columns: columns(&["Status", "Students"]),
+                        rows: vec![
+                            vec![text("Paid"), ReportCell::Count(summary.paid_count)],
+                            vec![text("Partial (On Track)"), ReportCell::Count(summary.partial_on_track_count)],
+                            vec![text("Partial (Overdue)"), ReportCell::Count(summary.partial_overdue_count)],
+                            vec![text("Not Paid"), ReportCell::Count(summary.unpaid_count)],
+                            vec![text("Exempt"), ReportCell::Count(summary.exempt_count)],
+                        ],
+                        totals: Some(vec![
+                            text("Total"),
+                            ReportCell::Count(
+                                summary.paid_count
+                                    + summary.partial_on_track_count
+                                    + summary.partial_overdue_count
+                                    + summary.unpaid_count
+                                    + summary.exempt_count,
+                            ),
+                        ]),
+                    },
+                ])
+            },
+            ReportType::TeacherEarnings => ("Teacher Earnings", vec![teacher_earnings_table(state)]),
+            ReportType::StudentPayments => {
+                let students = &state.student_payments;
+                let summary_rows = students
+                    .iter()
+                    .map(|student| vec![
+                        text(&student.student_name),
+                        text(student.class_level.to_string()),
+                        ReportCell::Money(student.fee_amount),
+                        ReportCell::Money(student.total_paid),
+                        ReportCell::Money(student.balance),
+                        text(student.status.to_string()),
+                    ])
+                    .collect();
+                let ledger_rows = students
+                    .iter()
+                    .flat_map(|student| student.payments.iter().map(move |payment| vec![
+                        text(&student.student_name),
+                        text(format_date(payment.date_paid.date(), &state.date_range.format)),
+                        ReportCell::Money(payment.amount_paid),
+                    ]))
+                    .collect();
+                let total_paid: f64 = students.iter().map(|s| s.total_paid).sum();
+                // The ledger only holds payments inside the filter's dates,
+                // while a student's total covers the whole term
+                let ledger_total: f64 = students
+                    .iter()
+                    .flat_map(|student| student.payments.iter())
+                    .map(|payment| payment.amount_paid)
+                    .sum();
+
+                ("Student Payments", vec![
+                    ReportTable {
+                        title: "Student Balances".to_string(),
+                        columns: columns(&["Student", "Class", &money("Fee"), &money("Paid"), &money("Balance"), "Status"]),
+                        rows: summary_rows,
+                        totals: Some(vec![
+                            text("Total"),
+                            text(""),
+                            ReportCell::Money(students.iter().map(|s| s.fee_amount).sum()),
+                            ReportCell::Money(total_paid),
+                            ReportCell::Money(students.iter().map(|s| s.balance).sum()),
+                            text(""),
+                        ]),
+                    },
+                    ReportTable {
+                        title: "Payment Ledger".to_string(),
+                        columns: columns(&["Student", "Date Paid", &money("Amount")]),
+                        rows: ledger_rows,
+                        totals: Some(vec![text("Total"), text(""), ReportCell::Money(ledger_total)]),
+                    },
+                ])
+            },
+            ReportType::Exemptions => {
+                let exemptions = &state.exemptions;
+                let by_reason = ExemptionReason::ALL
+                    .iter()
+                    .map(|reason| {
+                        let matching = exemptions.iter().filter(|row| &row.exemption.reason == reason);
+                        vec![
+                            text(reason.to_string()),
+                            ReportCell::Count(matching.clone().count()),
+                            ReportCell::Money(matching.map(|row| row.exemption.amount).sum()),
+                        ]
+                    })
+                    .collect();
+                let detail_rows = exemptions
+                    .iter()
+                    .map(|row| vec![
+                        text(&row.student_name),
+                        text(row.class_level.to_string()),
+                        text(&row.term_name),
+                        text(row.exemption.reason.to_string()),
+                        text(if row.exemption.is_full { "Full" } else { "Partial" }),
+                        ReportCell::Money(row.fee_amount),
+                        ReportCell::Money(row.exemption.amount),
+                        text(&row.approved_by_name),
+                        text(format_date(row.exemption.created_at.date(), &state.date_range.format)),
+                        text(row.exemption.notes.clone().unwrap_or_default()),
+                    ])
+                    .collect();
+                let total_exempted: f64 = exemptions.iter().map(|row| row.exemption.amount).sum();
+
+                ("Fee Exemptions", vec![
+                    ReportTable {
+                        title: "Exemptions by Reason".to_string(),
+                        columns: columns(&["Reason", "Students", &money("Exempted")]),
+                        rows: by_reason,
+                        totals: Some(vec![text("Total"), ReportCell::Count(exemptions.len()), ReportCell::Money(total_exempted)]),
+                    },
+                    ReportTable {
+                        title: "Exemption Register".to_string(),
+                        columns: columns(&[
+                            "Student", "Class", "Term", "Reason", "Type", &money("Fee"),
+                            &money("Exempted"), "Approved By", "Approved On", "Notes",
+                        ]),
+                        rows: detail_rows,
+                        totals: Some(vec![
+                            text("Total"), text(""), text(""), text(""), text(""),
+                            ReportCell::Money(exemptions.iter().map(|row| row.fee_amount).sum()),
+                            ReportCell::Money(total_exempted),
+                            text(""), text(""), text(""),
+                        ]),
+                    },
+                ])
+            },
+            ReportType::OverdueAging => {
+                let rows = &state.overdue_aging;
+                let buckets = AgingBucket::ALL
+                    .iter()
+                    .map(|bucket| {
+                        let matching = rows.iter().filter(|row| row.bucket() == *bucket);
+                        vec![
+                            text(bucket.to_string()),
+                            ReportCell::Count(matching.clone().count()),
+                            ReportCell::Money(matching.map(|row| row.amount_overdue).sum()),
+                        ]
+                    })
+                    .collect();
+                let detail_rows = rows
+                    .iter()
+                    .map(|row| vec![
+                        text(&row.student_name),
+                        text(row.class_level.to_string()),
+                        text(format_date(row.oldest_due_date, &state.date_range.format)),
+                        ReportCell::Count(row.days_overdue.max(0) as usize),
+                        text(row.bucket().to_string()),
+                        ReportCell::Money(row.amount_overdue),
+                        text(row.contact_name.clone().unwrap_or_default()),
+                        text(row.contact_phone.clone().unwrap_or_default()),
+                    ])
+                    .collect();
+                let total_overdue: f64 = rows.iter().map(|row| row.amount_overdue).sum();
+
+                ("Overdue Aging", vec![
+                    ReportTable {
+                        title: "Overdue by Age".to_string(),
+                        columns: columns(&["Age", "Students", &money("Overdue")]),
+                        rows: buckets,
+                        totals: Some(vec![text("Total"), ReportCell::Count(rows.len()), ReportCell::Money(total_overdue)]),
+                    },
+                    ReportTable {
+                        title: "Overdue Students".to_string(),
+                        columns: columns(&["Student", "Class", "Oldest Due", "Days Overdue", "Age", &money("Overdue"), "Contact", "Phone"]),
+                        rows: detail_rows,
+                        totals: Some(vec![
+                            text("Total"), text(""), text(""), text(""), text(""),
+                            ReportCell::Money(total_overdue),
+                            text(""), text(""),
+                        ]),
+                    },
+                ])
+            },
+        };
+
+        let header = vec![
+            ("School".to_string(), state.school_name.clone()),
+            ("Term".to_string(), state.term_name.clone().unwrap_or_else(|| "All terms".to_string())),
+            ("Filters".to_string(), describe_filters(state)),
+            ("Generated by".to_string(), generated_by.to_string()),
+            ("Generated at".to_string(), timestamp(&state.date_range.format)),
+        ];
+
+        Ok(Self { title: title.to_string(), header, tables })
+    }
+
+    /// A teacher's payslip for one past payout
+    pub fn payslip(teacher_name: &str, payslip: &Payslip, date_format: &str, currency_symbol: &str) -> Self {
+        let mut rows = vec![
+            vec![text("Base share"), ReportCell::Money(payslip.base_share)],
+            vec![text(format!("Period share ({} periods)", payslip.periods_paid)), ReportCell::Money(payslip.period_share)],
+        ];
+        if payslip.capped {
+            rows.push(vec![text("Period share was limited by the payout cap"), text("")]);
+        }
+        Self {
+            title: format!("Payslip {}", payslip.term_name),
+            header: vec![
+                ("Teacher".to_string(), teacher_name.to_string()),
+                ("Term".to_string(), payslip.term_name.clone()),
+                ("Calculated on".to_string(), format_date(payslip.calculated_on.date(), date_format)),
+                ("Generated at".to_string(), timestamp(date_format)),
+            ],
+            tables: vec![ReportTable {
+                title: "Payout".to_string(),
+                columns: columns(&["Item", &money_column("Amount", currency_symbol)]),
+                rows,
+                totals: Some(vec![text("Total"), ReportCell::Money(payslip.total())]),
+            }],
+        }
+    }
+
+    pub fn default_file_name(&self, format: ExportFormat) -> String {
+        format!(
+            "{}_{}.{}",
+            self.title.to_lowercase().replace(' ', "_"),
+            Local::now().format("%Y-%m-%d"),
+            format.extension(),
+        )
+    }
+}
+
+fn teacher_earnings_table(state: &ReportsAnalyticsState) -> ReportTable {
+    let earnings = &state.teacher_earnings;
+    ReportTable {
+        title: "Teacher Earnings".to_string(),
+        columns: columns(&["Teacher", "Periods", "Share", &money_column("Earnings", &state.currency_symbol)]),
+        rows: earnings
+            .iter()
+            .map(|earning| vec![
+                text(&earning.teacher_name),
+                ReportCell::Count(earning.total_periods),
+                ReportCell::Percent(earning.share_percentage),
+                ReportCell::Money(earning.total_earnings),
+            ])
+            .collect(),
+        totals: Some(vec![
+            text("Total"),
+            ReportCell::Count(earnings.iter().map(|e| e.total_periods).sum()),
+            ReportCell::Percent(earnings.iter().map(|e| e.share_percentage).sum()),
+            ReportCell::Money(earnings.iter().map(|e| e.total_earnings).sum()),
+        ]),
+    }
+}
+
+/// Describes the filter the report was loaded with, not the current inputs
+fn describe_filters(state: &ReportsAnalyticsState) -> String {
+    let Some(filter) = &state.applied_filter else {
+        return "None".to_string();
+    };
+    let mut filters = Vec::new();
+    if let Some(from) = filter.date_from {
+        filters.push(format!("From {}", format_date(from, &state.date_range.format)));
+    }
+    if let Some(to) = filter.date_to {
+        filters.push(format!("To {}", format_date(to, &state.date_range.format)));
+    }
+    if let Some(teacher_id) = filter.teacher_id {
+        let teacher_name = state.teachers
+            .iter()
+            .find(|teacher| teacher.id == teacher_id)
+            .map(|teacher| format!("{} {}", teacher.name.first_name, teacher.name.surname))
+            .unwrap_or_else(|| teacher_id.to_string());
+        filters.push(format!("Teacher: {}", teacher_name));
+    }
+
+    if filters.is_empty() {
+        "None".to_string()
+    } else {
+        filters.join(", ")
+    }
+}
+
+// ============= XLSX ===============
+
+fn column_letter(index: usize) -> String {
+    let mut index = index;
+    let mut letters = String::new();
+    loop {
+        letters.insert(0, (b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters
+}
+
+pub fn write_xlsx(document: &ReportDocument, path: &Path) -> Result<(), AppError> {
+    let mut book = new_file();
+    let sheet = book
+        .get_sheet_by_name_mut("Sheet1")
+        .ok_or_else(|| AppError::Configuration("Main sheet 'Sheet1' not found".to_string()))?;
+    sheet.set_name(document.title.as_str());
+
+    let mut row: u32 = 1;
+    sheet.get_cell_mut((1, row)).set_value(document.title.as_str());
+    sheet.get_style_mut((1, row)).get_font_mut().set_bold(true).set_size(14.0);
+    row += 1;
+
+    for (label, value) in &document.header {
+        sheet.get_cell_mut((1, row)).set_value(label.as_str());
+        sheet.get_style_mut((1, row)).get_font_mut().set_bold(true);
+        sheet.get_cell_mut((2, row)).set_value(value.as_str());
+        row += 1;
+    }
+
+    for table in &document.tables {
+        row += 1;
+        sheet.get_cell_mut((1, row)).set_value(table.title.as_str());
+        sheet.get_style_mut((1, row)).get_font_mut().set_bold(true).set_size(12.0);
+        row += 1;
+
+        for (col, name) in table.columns.iter().enumerate() {
+            let col = col as u32 + 1;
+            sheet.get_cell_mut((col, row)).set_value(name.as_str());
+            sheet.get_style_mut((col, row)).get_font_mut().set_bold(true);
+        }
+        row += 1;
+
+        for cells in &table.rows {
+            write_xlsx_row(sheet, row, cells, false);
+            row += 1;
+        }
+
+        if let Some(totals) = &table.totals {
+            write_xlsx_row(sheet, row, totals, true);
+            row += 1;
+        }
+    }
+
+    let column_count = document.tables.iter().map(|t| t.columns.len()).max().unwrap_or(1).max(2);
+    for col in 0..column_count {
+        sheet.get_column_dimension_mut(&column_letter(col)).set_width(if col == 0 { 28.0 } else { 18.0 });
+    }
+
+    writer::xlsx::write(&book, path)
+        .map_err(|e| AppError::IoError(format!("Failed to write file: {}", e)))
+}
+
+fn write_xlsx_row(sheet: &mut umya_spreadsheet::Worksheet, row: u32, cells: &[ReportCell], bold: bool) {
+    for (col, cell) in cells.iter().enumerate() {
+        let col = col as u32 + 1;
+        let format_code = match cell {
+            ReportCell::Text(value) => {
+                sheet.get_cell_mut((col, row)).set_value(value.as_str());
+                None
+            },
+            ReportCell::Money(value) => {
+                sheet.get_cell_mut((col, row)).set_value_number(*value);
+                Some("#,##0.00")
+            },
+            ReportCell::Count(value) => {
+                sheet.get_cell_mut((col, row)).set_value_number(*value as f64);
+                Some("0")
+            },
+            ReportCell::Percent(value) => {
+                sheet.get_cell_mut((col, row)).set_value_number(*value);
+                Some("0.0\"%\"")
+            },
+        };
+
+        let style = sheet.get_style_mut((col, row));
+        if let Some(code) = format_code {
+            style.get_number_format_mut().set_format_code(code);
+        }
+        if bold {
+            style.get_font_mut().set_bold(true);
+        }
+    }
+}
+
+// ============= CSV ===============
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_line(values: impl IntoIterator<Item = String>) -> String {
+    values.into_iter().map(|v| csv_field(&v)).collect::<Vec<_>>().join(",")
+}
+
+pub fn write_csv(document: &ReportDocument, path: &Path) -> Result<(), AppError> {
+    let mut lines = vec![csv_line([document.title.clone()])];
+    for (label, value) in &document.header {
+        lines.push(csv_line([label.clone(), value.clone()]));
+    }
+
+    for table in &document.tables {
+        lines.push(String::new());
+        lines.push(csv_line([table.title.clone()]));
+        lines.push(csv_line(table.columns.iter().cloned()));
+        for cells in table.rows.iter().chain(table.totals.iter()) {
+            lines.push(csv_line(cells.iter().map(ReportCell::display)));
+        }
+    }
+
+    let mut file = File::create(path)
+        .map_err(|e| AppError::IoError(format!("Failed to create file: {}", e)))?;
+    file.write_all((lines.join("\r\n") + "\r\n").as_bytes())
+        .map_err(|e| AppError::IoError(format!("Failed to write file: {}", e)))
+}
+
+// ============= PDF ===============
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const LINE_HEIGHT: f32 = 6.0;
+
+/// Tracks the current page and write position while laying out a PDF
+struct PdfCursor {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    y: f32,
+}
+
+impl PdfCursor {
+    fn next_line(&mut self, height: f32) {
+        self.y -= height;
+        if self.y < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn write(&self, value: &str, size: f32, x: f32, font: &IndirectFontRef) {
+        self.layer.use_text(value, size, Mm(x), Mm(self.y), font);
+    }
+}
+
+fn truncate(value: &str, width_mm: f32) -> String {
+    // Helvetica at 9pt averages a little under 2mm per character
+    let max_chars = (width_mm / 1.9) as usize;
+    if value.chars().count() > max_chars {
+        let mut truncated: String = value.chars().take(max_chars.saturating_sub(3)).collect();
+        truncated.push_str("...");
+        truncated
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn write_pdf(document: &ReportDocument, path: &Path) -> Result<(), AppError> {
+    let pdf_error = |e: printpdf::Error| AppError::IoError(format!("Failed to build PDF: {}", e));
+
+    let (doc, page, layer) = PdfDocument::new(document.title.as_str(), Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(pdf_error)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(pdf_error)?;
+
+    let layer = doc.get_page(page).get_layer(layer);
+    let mut cursor = PdfCursor { doc, layer, y: PAGE_HEIGHT - MARGIN };
+
+    cursor.write(&document.title, 16.0, MARGIN, &bold);
+    cursor.next_line(LINE_HEIGHT * 1.5);
+
+    for (label, value) in &document.header {
+        cursor.write(&format!("{}:", label), 10.0, MARGIN, &bold);
+        cursor.write(value, 10.0, MARGIN + 30.0, &regular);
+        cursor.next_line(LINE_HEIGHT);
+    }
+
+    let usable_width = PAGE_WIDTH - 2.0 * MARGIN;
+    for table in &document.tables {
+        cursor.next_line(LINE_HEIGHT);
+        cursor.write(&table.title, 12.0, MARGIN, &bold);
+        cursor.next_line(LINE_HEIGHT * 1.2);
+
+        let column_width = usable_width / table.columns.len().max(1) as f32;
+        for (col, name) in table.columns.iter().enumerate() {
+            cursor.write(&truncate(name, column_width), 9.0, MARGIN + col as f32 * column_width, &bold);
+        }
+        cursor.next_line(LINE_HEIGHT);
+
+        for cells in &table.rows {
+            for (col, cell) in cells.iter().enumerate() {
+                cursor.write(&truncate(&cell.display(), column_width), 9.0, MARGIN + col as f32 * column_width, &regular);
+            }
+            cursor.next_line(LINE_HEIGHT);
+        }
+
+        if let Some(totals) = &table.totals {
+            for (col, cell) in totals.iter().enumerate() {
+                cursor.write(&truncate(&cell.display(), column_width), 9.0, MARGIN + col as f32 * column_width, &bold);
+            }
+            cursor.next_line(LINE_HEIGHT);
+        }
+    }
+
+    let file = File::create(path)
+        .map_err(|e| AppError::IoError(format!("Failed to create file: {}", e)))?;
+    cursor.doc.save(&mut BufWriter::new(file)).map_err(pdf_error)
+}
+
+pub fn pick_path_and_export(document: ReportDocument, format: ExportFormat) -> Result<PathBuf, AppError> {
+    let path = rfd::FileDialog::new()
+        .set_file_name(document.default_file_name(format))
+        .add_filter(format.to_string(), &[format.extension()])
+        .save_file()
+        .ok_or_else(|| AppError::IoError("No file path chosen.".into()))?;
+
+    match format {
+        ExportFormat::Xlsx => write_xlsx(&document, &path)?,
+        ExportFormat::Csv => write_csv(&document, &path)?,
+        ExportFormat::Pdf => write_pdf(&document, &path)?,
+    }
+    Ok(path)
+}
@@ -0,0 +1,170 @@
+use kyefa_models::{Guardian, Student};
+
+/// Children who share at least one guardian, with every guardian linked to them
+#[derive(Debug, Clone)]
+pub struct Family<'a> {
+    pub guardians: Vec<&'a Guardian>,
+    pub children: Vec<&'a Student>,
+}
+
+impl Family<'_> {
+    /// What the family still owes across all its children this term
+    pub fn combined_balance(&self) -> f64 {
+        self.children.iter().map(|child| child.balance()).sum()
+    }
+
+    /// Whom to call about the family's fees: the marked primary contact,
+    /// otherwise the first guardian.
+    pub fn primary_contact(&self) -> Option<&Guardian> {
+        self.guardians
+            .iter()
+            .find(|guardian| guardian.is_primary_contact)
+            .or_else(|| self.guardians.first())
+            .copied()
+    }
+
+    /// e.g. "Mensah" or "Mensah / Owusu" when the guardians' surnames differ
+    pub fn surnames(&self) -> String {
+        let mut surnames: Vec<&str> = Vec::new();
+        for guardian in &self.guardians {
+            if !surnames.contains(&guardian.name.surname.as_str()) {
+                surnames.push(&guardian.name.surname);
+            }
+        }
+        surnames.join(" / ")
+    }
+}
+
+fn find(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[index] = root;
+    root
+}
+
+/// Groups guardians who share a child into families, largest balance first.
+/// Merged students are left out.
+pub fn group_families<'a>(guardians: &'a [Guardian], students: &'a [Student]) -> Vec<Family<'a>> {
+    let mut parents: Vec<usize> = (0..guardians.len()).collect();
+    for (i, guardian) in guardians.iter().enumerate() {
+        for (j, other) in guardians.iter().enumerate().skip(i + 1) {
+            if guardian.student_ids.iter().any(|id| other.student_ids.contains(id)) {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                parents[b] = a;
+            }
+        }
+    }
+
+    let mut families: Vec<(usize, Family<'a>)> = Vec::new();
+    for (i, guardian) in guardians.iter().enumerate() {
+        let root = find(&mut parents, i);
+        let position = match families.iter().position(|(r, _)| *r == root) {
+            Some(position) => position,
+            None => {
+                families.push((root, Family { guardians: Vec::new(), children: Vec::new() }));
+                families.len() - 1
+            }
+        };
+        let family = &mut families[position].1;
+        family.guardians.push(guardian);
+        for student in students.iter().filter(|s| s.merged_into.is_none() && guardian.student_ids.contains(&s.id)) {
+            if !family.children.iter().any(|child| child.id == student.id) {
+                family.children.push(student);
+            }
+        }
+    }
+
+    let mut families: Vec<Family<'a>> = families.into_iter().map(|(_, family)| family).collect();
+    families.sort_by(|a, b| b.combined_balance().total_cmp(&a.combined_balance()));
+    families
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kyefa_models::{ClassLevel, Gender, GuardianRelationship, PersonName, StudentRow};
+    use uuid::Uuid;
+
+    fn student(first_name: &str, fee_amount: f64, total_paid: f64) -> Student {
+        let mut student = Student::from(StudentRow {
+            id: Uuid::new_v4(),
+            school_id: Uuid::new_v4(),
+            first_name: first_name.to_string(),
+            surname: "Mensah".to_string(),
+            other_names: None,
+            gender: Gender::Female,
+            class_level: ClassLevel::IGCSE1,
+            is_active: true,
+            merged_into: None,
+        });
+        student.fee_amount = fee_amount;
+        student.total_paid = total_paid;
+        student
+    }
+
+    fn guardian(surname: &str, is_primary_contact: bool, children: &[&Student]) -> Guardian {
+        Guardian {
+            id: Uuid::new_v4(),
+            name: PersonName { first_name: "Abena".to_string(), surname: surname.to_string(), other_names: None },
+            relationship: GuardianRelationship::Mother,
+            phone_number: "0244000000".to_string(),
+            alternate_phone: None,
+            is_primary_contact,
+            student_ids: children.iter().map(|child| child.id).collect(),
+        }
+    }
+
+    #[test]
+    fn guardians_sharing_a_child_are_one_family() {
+        let (ama, kofi, yaw) = (student("Ama", 300.0, 0.0), student("Kofi", 300.0, 100.0), student("Yaw", 300.0, 300.0));
+        let guardians = [
+            guardian("Mensah", false, &[&ama]),
+            guardian("Owusu", false, &[&ama, &kofi]),
+            guardian("Boateng", false, &[&yaw]),
+        ];
+        let students = [ama.clone(), kofi.clone(), yaw.clone()];
+        let families = group_families(&guardians, &students);
+        assert_eq!(families.len(), 2);
+        assert_eq!(families[0].guardians.len(), 2);
+        assert_eq!(families[0].children.len(), 2);
+        assert_eq!(families[0].surnames(), "Mensah / Owusu");
+        assert_eq!(families[1].children[0].id, yaw.id);
+    }
+
+    #[test]
+    fn families_are_ordered_by_what_they_owe() {
+        let (ama, kofi) = (student("Ama", 300.0, 250.0), student("Kofi", 300.0, 0.0));
+        let guardians = [guardian("Mensah", false, &[&ama]), guardian("Owusu", false, &[&kofi])];
+        let students = [ama, kofi.clone()];
+        let families = group_families(&guardians, &students);
+        assert_eq!(families[0].children[0].id, kofi.id);
+        assert_eq!(families[0].combined_balance(), 300.0);
+        assert_eq!(families[1].combined_balance(), 50.0);
+    }
+
+    #[test]
+    fn primary_contact_falls_back_to_the_first_guardian() {
+        let ama = student("Ama", 300.0, 0.0);
+        let students = [ama.clone()];
+        let guardians = [guardian("Mensah", false, &[&ama]), guardian("Owusu", true, &[&ama])];
+        let families = group_families(&guardians, &students);
+        assert_eq!(families[0].primary_contact().map(|g| g.name.surname.as_str()), Some("Owusu"));
+
+        let guardians = [guardian("Mensah", false, &[&ama]), guardian("Owusu", false, &[&ama])];
+        let families = group_families(&guardians, &students);
+        assert_eq!(families[0].primary_contact().map(|g| g.name.surname.as_str()), Some("Mensah"));
+    }
+
+    #[test]
+    fn merged_children_are_left_out() {
+        let (ama, mut duplicate) = (student("Ama", 300.0, 0.0), student("Ama", 300.0, 0.0));
+        duplicate.merged_into = Some(ama.id);
+        let guardians = [guardian("Mensah", false, &[&ama, &duplicate])];
+        let students = [ama.clone(), duplicate];
+        let families = group_families(&guardians, &students);
+        assert_eq!(families[0].children.len(), 1);
+        assert_eq!(families[0].combined_balance(), 300.0);
+    }
+}
@@ -0,0 +1,534 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{Local, NaiveDate, TimeDelta};
+use umya_spreadsheet::reader;
+use uuid::Uuid;
+
+use kyefa_models::{
+    Student, StudentImportRow, Term, Teacher, Subject, ClassLevel,
+    ConductedPeriod, PeriodImportPayload, PeriodImportRow,
+};
+use crate::app::{ImportPreview, ImportPreviewRow, ImportType};
+use crate::error::AppError;
+use crate::routes;
+use crate::ui::date_input::{current_term, format_date, parse_date};
+
+/// Column headers written by `routes::generate_excel_template`
+pub const STUDENT_HEADERS: [&str; 5] = ["FirstName", "Surname", "OtherNames", "Gender", "ClassLevel"];
+
+/// Reads the first sheet of a workbook as (header row, data rows). Each data row
+/// keeps its 1-based sheet row number so errors can point back at the file;
+/// blank rows are dropped.
+pub fn read_sheet(path: &Path) -> Result<(Vec<String>, Vec<(usize, Vec<String>)>), AppError> {
+    let book = reader::xlsx::read(path)
+        .map_err(|e| AppError::IoError(format!("Failed to read workbook: {}", e)))?;
+    let sheet = book
+        .get_sheet(&0)
+        .ok_or_else(|| AppError::Configuration("Workbook has no sheets".to_string()))?;
+
+    let columns = sheet.get_highest_column();
+    let read_row = |row: u32| -> Vec<String> {
+        (1..=columns)
+            .map(|col| sheet.get_value((col, row)).trim().to_string())
+            .collect()
+    };
+
+    let headers = read_row(1);
+    let rows = (2..=sheet.get_highest_row())
+        .map(|row| (row as usize, read_row(row)))
+        .filter(|(_, values)| values.iter().any(|value| !value.is_empty()))
+        .collect();
+
+    Ok((headers, rows))
+}
+
+/// Checks the header row matches `expected`, ignoring case, format hints such as
+/// "Date (DD/MM/YYYY)" and extra trailing columns.
+pub fn check_headers(headers: &[String], expected: &[&str]) -> Result<(), AppError> {
+    let matches = expected.len() <= headers.len()
+        && expected.iter().zip(headers).all(|(want, got)| {
+            want.eq_ignore_ascii_case(got.split(" (").next().unwrap_or_default().trim())
+        });
+    if matches {
+        Ok(())
+    } else {
+        Err(AppError::Configuration(format!(
+            "Unexpected columns. Please use the downloaded template (expected: {}).",
+            expected.join(", ")
+        )))
+    }
+}
+
+pub fn student_import_row(row: &ImportPreviewRow) -> StudentImportRow {
+    let value = |i: usize| row.values.get(i).cloned().unwrap_or_default();
+    StudentImportRow {
+        row_number: row.row_number,
+        first_name: value(0),
+        surname: value(1),
+        other_names: value(2),
+        gender: value(3),
+        class_level: value(4),
+    }
+}
+
+/// Lower-cased first name, surname and class, used to spot the same student
+/// entered twice. Near-misses in spelling are left to the duplicate finder.
+fn duplicate_key(first_name: &str, surname: &str, class_level: &str) -> String {
+    let normalise = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    format!("{}|{}|{}", normalise(first_name), normalise(surname), normalise(class_level))
+}
+
+/// Re-runs row validation and duplicate detection against `existing`.
+/// Called after loading and after every edit in the preview.
+pub fn validate_student_preview(preview: &mut ImportPreview, existing: &[Student]) {
+    let mut seen: HashMap<String, String> = existing
+        .iter()
+        .filter(|student| student.is_active)
+        .map(|student| (
+            duplicate_key(&student.name.first_name, &student.name.surname, &student.class_level.to_string()),
+            format!("existing student {} {} ({})", student.name.first_name, student.name.surname, student.class_level),
+        ))
+        .collect();
+
+    for row in preview.rows.iter_mut() {
+        let import_row = student_import_row(row);
+        row.errors = import_row.validate().err().unwrap_or_default();
+
+        let key = duplicate_key(&import_row.first_name, &import_row.surname, &import_row.class_level);
+        row.duplicate_of = seen.get(&key).cloned();
+        if row.duplicate_of.is_none() && !row.skip {
+            seen.insert(key, format!("row {}", row.row_number));
+        }
+    }
+}
+
+/// Asks for a filled-in template and builds a validated preview. Likely
+/// duplicates start out skipped so they are only imported on purpose.
+pub fn pick_and_preview_students(existing: Vec<Student>) -> Result<ImportPreview, AppError> {
+    let path = rfd::FileDialog::new()
+        .add_filter("Excel Files", &["xlsx"])
+        .pick_file()
+        .ok_or_else(|| AppError::IoError("No file selected".into()))?;
+
+    let (headers, rows) = read_sheet(&path)?;
+    check_headers(&headers, &STUDENT_HEADERS)?;
+
+    let mut preview = ImportPreview {
+        import_type: ImportType::Students,
+        file_name: path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("students.xlsx")
+            .to_string(),
+        headers: STUDENT_HEADERS.iter().map(|h| h.to_string()).collect(),
+        rows: rows
+            .into_iter()
+            .map(|(row_number, mut values)| {
+                values.resize(STUDENT_HEADERS.len(), String::new());
+                ImportPreviewRow {
+                    row_number,
+                    values,
+                    errors: Vec::new(),
+                    duplicate_of: None,
+                    skip: false,
+                }
+            })
+            .collect(),
+    };
+
+    if preview.rows.is_empty() {
+        return Err(AppError::Configuration("The workbook has no student rows.".to_string()));
+    }
+
+    validate_student_preview(&mut preview, &existing);
+    for row in preview.rows.iter_mut() {
+        row.skip = row.duplicate_of.is_some();
+    }
+
+    Ok(preview)
+}
+
+/// Lookups needed to turn register rows into `ConductedPeriod`s
+#[derive(Debug, Clone)]
+pub struct PeriodImportContext {
+    pub term: Term,
+    pub teachers: Vec<Teacher>,
+    pub subjects: Vec<Subject>,
+    pub date_format: String,
+}
+
+/// Fetches the active term, teachers and subjects used by the period template and import.
+pub async fn load_period_import_context(date_format: String) -> Result<PeriodImportContext, AppError> {
+    let (terms, teachers, subjects) = tokio::try_join!(
+        routes::fetch_all_terms(),
+        routes::fetch_all_teachers(),
+        routes::fetch_all_subjects(),
+    )?;
+    let term = current_term(Local::now().date_naive(), &terms)
+        .cloned()
+        .ok_or_else(|| AppError::Configuration("There is no active term to import periods into.".to_string()))?;
+
+    Ok(PeriodImportContext { term, teachers, subjects, date_format })
+}
+
+/// Accepts a date typed in the configured format, or the serial number Excel
+/// stores when a cell was formatted as a date anyway.
+fn parse_register_date(value: &str, format: &str) -> Result<NaiveDate, String> {
+    if let Ok(serial) = value.parse::<f64>() {
+        let excel_epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap_or_default();
+        return (serial.is_finite() && serial.abs() < i32::MAX as f64)
+            .then(|| TimeDelta::try_days(serial.trunc() as i64))
+            .flatten()
+            .and_then(|days| excel_epoch.checked_add_signed(days))
+            .ok_or_else(|| format!("'{}' is not a valid date.", value));
+    }
+    parse_date(value, format)?.ok_or_else(|| "Date cannot be empty.".to_string())
+}
+
+/// Maps one register row onto a period in the context's term.
+pub fn period_from_row(row: &ImportPreviewRow, context: &PeriodImportContext) -> Result<ConductedPeriod, Vec<String>> {
+    let value = |i: usize| row.values.get(i).map(|v| v.trim()).unwrap_or_default();
+    let mut errors = Vec::new();
+
+    let date = match parse_register_date(value(0), &context.date_format) {
+        Ok(date) if date < context.term.start_date.date() || date > context.term.end_date.date() => {
+            errors.push(format!("{} is outside {}.", format_date(date, &context.date_format), context.term.name));
+            None
+        },
+        Ok(date) => Some(date),
+        Err(e) => {
+            errors.push(e);
+            None
+        },
+    };
+
+    let matching_teachers: Vec<&Teacher> = context.teachers
+        .iter()
+        .filter(|teacher| teacher.is_active && routes::teacher_template_label(teacher).eq_ignore_ascii_case(value(1)))
+        .collect();
+    let teacher = match matching_teachers.as_slice() {
+        [teacher] => Some(*teacher),
+        [] if value(1).is_empty() => {
+            errors.push("Teacher cannot be empty.".to_string());
+            None
+        },
+        [] => {
+            errors.push(format!("'{}' is not an active teacher.", value(1)));
+            None
+        },
+        _ => {
+            errors.push(format!("'{}' matches more than one teacher; please enter this period manually.", value(1)));
+            None
+        },
+    };
+
+    let class_level = ClassLevel::from_str(value(3)).ok();
+    if class_level.is_none() {
+        errors.push(format!("'{}' is not a valid class level.", value(3)));
+    }
+
+    let subject = class_level.as_ref().and_then(|class_level| {
+        let stream = class_level.stream();
+        let subject = context.subjects
+            .iter()
+            .find(|subject| subject.stream == stream && subject.name.to_string().eq_ignore_ascii_case(value(2)));
+        if subject.is_none() {
+            errors.push(format!("'{}' is not offered for {} classes.", value(2), stream));
+        }
+        subject
+    });
+
+    let was_conducted = match value(4).to_lowercase().as_str() {
+        "" | "yes" | "y" => true,
+        "no" | "n" => false,
+        other => {
+            errors.push(format!("'{}' should be Yes or No.", other));
+            true
+        },
+    };
+
+    match (date, teacher, class_level, subject) {
+        (Some(date), Some(teacher), Some(class_level), Some(subject)) if errors.is_empty() => {
+            let week_of_term = (date - context.term.start_date.date()).num_days() / 7 + 1;
+            Ok(ConductedPeriod {
+                id: Uuid::new_v4(),
+                date: date.and_hms_opt(0, 0, 0).unwrap_or_default(),
+                class_level,
+                subject_id: subject.id,
+                teacher_id: teacher.id,
+                term_id: context.term.id,
+                week_of_term: week_of_term as u8,
+                was_conducted,
+                notes: Some(value(5).to_string()).filter(|notes| !notes.is_empty()),
+            })
+        },
+        _ => Err(errors),
+    }
+}
+
+/// Re-runs validation over the register, flagging the same teacher, class,
+/// subject and date appearing twice.
+pub fn validate_period_preview(preview: &mut ImportPreview, context: &PeriodImportContext) {
+    let mut seen: HashMap<(Uuid, Uuid, String, NaiveDate), usize> = HashMap::new();
+
+    for row in preview.rows.iter_mut() {
+        row.duplicate_of = None;
+        match period_from_row(row, context) {
+            Ok(period) => {
+                row.errors.clear();
+                let key = (period.teacher_id, period.subject_id, period.class_level.to_string(), period.date.date());
+                match seen.get(&key) {
+                    Some(original) => row.duplicate_of = Some(format!("row {}", original)),
+                    None if !row.skip => {
+                        seen.insert(key, row.row_number);
+                    },
+                    None => {},
+                }
+            },
+            Err(errors) => row.errors = errors,
+        }
+    }
+}
+
+pub fn preview_periods(path: &Path, context: &PeriodImportContext) -> Result<ImportPreview, AppError> {
+    let (headers, rows) = read_sheet(path)?;
+    check_headers(&headers, &routes::PERIOD_TEMPLATE_HEADERS)?;
+
+    let mut preview = ImportPreview {
+        import_type: ImportType::TeachingPeriods,
+        file_name: path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("periods.xlsx")
+            .to_string(),
+        headers: routes::PERIOD_TEMPLATE_HEADERS.iter().map(|h| h.to_string()).collect(),
+        rows: rows
+            .into_iter()
+            .map(|(row_number, mut values)| {
+                values.resize(routes::PERIOD_TEMPLATE_HEADERS.len(), String::new());
+                ImportPreviewRow {
+                    row_number,
+                    values,
+                    errors: Vec::new(),
+                    duplicate_of: None,
+                    skip: false,
+                }
+            })
+            .collect(),
+    };
+
+    if preview.rows.is_empty() {
+        return Err(AppError::Configuration("The register has no period rows.".to_string()));
+    }
+
+    validate_period_preview(&mut preview, context);
+    for row in preview.rows.iter_mut() {
+        row.skip = row.duplicate_of.is_some();
+    }
+
+    Ok(preview)
+}
+
+/// Builds the import payload from the rows the user kept. Only valid once
+/// `ImportPreview::can_commit` holds.
+pub fn period_import_payload(preview: &ImportPreview, context: &PeriodImportContext) -> PeriodImportPayload {
+    PeriodImportPayload {
+        term_id: context.term.id,
+        rows: preview
+            .included_rows()
+            .filter_map(|row| period_from_row(row, context).ok().map(|period| PeriodImportRow {
+                row_number: row.row_number,
+                period,
+            }))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kyefa_models::{AcademicYear, Gender, ParticipationStatus, PersonName, Stream, StudentRow, SubjectName};
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn preview(rows: &[[&str; 5]]) -> ImportPreview {
+        ImportPreview {
+            import_type: ImportType::Students,
+            file_name: "students.xlsx".to_string(),
+            headers: headers(&STUDENT_HEADERS),
+            rows: rows
+                .iter()
+                .enumerate()
+                .map(|(index, values)| ImportPreviewRow {
+                    row_number: index + 2,
+                    values: headers(values),
+                    errors: Vec::new(),
+                    duplicate_of: None,
+                    skip: false,
+                })
+                .collect(),
+        }
+    }
+
+    fn existing(first_name: &str, surname: &str, class_level: ClassLevel) -> Student {
+        Student::from(StudentRow {
+            id: Uuid::new_v4(),
+            school_id: Uuid::new_v4(),
+            first_name: first_name.to_string(),
+            surname: surname.to_string(),
+            other_names: None,
+            gender: Gender::Female,
+            class_level,
+            is_active: true,
+            merged_into: None,
+        })
+    }
+
+    fn period_context() -> PeriodImportContext {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 9, d).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        PeriodImportContext {
+            term: Term {
+                id: Uuid::new_v4(),
+                name: "Term 1".to_string(),
+                academic_year: AcademicYear::starting_in(2025),
+                start_date: day(7),
+                end_date: day(30),
+                is_active: true,
+            },
+            teachers: vec![Teacher {
+                id: Uuid::new_v4(),
+                name: PersonName { first_name: "Ama".to_string(), surname: "Mensah".to_string(), other_names: None },
+                status: ParticipationStatus::Participating,
+                is_active: true,
+            }],
+            subjects: vec![Subject { id: Uuid::new_v4(), name: SubjectName::Mathematics, stream: Stream::CambridgeIGCSE }],
+            date_format: "DD/MM/YYYY".to_string(),
+        }
+    }
+
+    fn register_row(values: [&str; 6]) -> ImportPreviewRow {
+        ImportPreviewRow { row_number: 2, values: headers(&values), errors: Vec::new(), duplicate_of: None, skip: false }
+    }
+
+    #[test]
+    fn register_rows_become_periods_in_the_term() {
+        let context = period_context();
+        let period = period_from_row(&register_row(["15/09/2026", "ama mensah", "Mathematics", "IGCSE1", "No", ""]), &context)
+            .unwrap();
+        assert_eq!(period.week_of_term, 2);
+        assert_eq!(period.teacher_id, context.teachers[0].id);
+        assert_eq!(period.subject_id, context.subjects[0].id);
+        assert!(!period.was_conducted);
+        assert_eq!(period.notes, None);
+    }
+
+    #[test]
+    fn register_rows_report_every_problem() {
+        let errors = period_from_row(&register_row(["01/10/2026", "Kofi Owusu", "Mathematics", "WASSCE1", "Maybe", ""]), &period_context())
+            .unwrap_err();
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors[0].contains("outside Term 1"));
+    }
+
+    #[test]
+    fn repeated_register_rows_are_flagged() {
+        let context = period_context();
+        let row = ["15/09/2026", "Ama Mensah", "Mathematics", "IGCSE1", "Yes", ""];
+        let mut preview = ImportPreview {
+            import_type: ImportType::TeachingPeriods,
+            file_name: "periods.xlsx".to_string(),
+            headers: headers(&routes::PERIOD_TEMPLATE_HEADERS),
+            rows: vec![register_row(row), ImportPreviewRow { row_number: 3, ..register_row(row) }],
+        };
+        validate_period_preview(&mut preview, &context);
+        assert_eq!(preview.rows[0].duplicate_of, None);
+        assert_eq!(preview.rows[1].duplicate_of.as_deref(), Some("row 2"));
+    }
+
+    #[test]
+    fn register_dates_accept_the_pattern_and_excel_serials() {
+        let date = NaiveDate::from_ymd_opt(2026, 9, 14).unwrap();
+        assert_eq!(parse_register_date("14/09/2026", "DD/MM/YYYY"), Ok(date));
+        assert_eq!(parse_register_date("46279", "DD/MM/YYYY"), Ok(date));
+        assert_eq!(parse_register_date("46279.75", "DD/MM/YYYY"), Ok(date));
+        assert!(parse_register_date("", "DD/MM/YYYY").is_err());
+        assert!(parse_register_date("2026-09-14", "DD/MM/YYYY").is_err());
+    }
+
+    #[test]
+    fn register_dates_reject_serials_that_are_not_dates() {
+        for value in ["inf", "-inf", "NaN", "1e300", "-1e300", "2147483647", "99999999"] {
+            let error = parse_register_date(value, "DD/MM/YYYY").unwrap_err();
+            assert!(error.ends_with("is not a valid date."), "{}: {}", value, error);
+        }
+    }
+
+    #[test]
+    fn headers_match_ignoring_case_hints_and_extra_columns() {
+        assert!(check_headers(&headers(&STUDENT_HEADERS), &STUDENT_HEADERS).is_ok());
+        assert!(check_headers(
+            &headers(&["firstname", "SURNAME", "OtherNames", "Gender (Male/Female)", "ClassLevel", "Notes"]),
+            &STUDENT_HEADERS,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn headers_out_of_order_or_missing_are_rejected() {
+        assert!(check_headers(&headers(&["Surname", "FirstName", "OtherNames", "Gender", "ClassLevel"]), &STUDENT_HEADERS).is_err());
+        assert!(check_headers(&headers(&["FirstName", "Surname"]), &STUDENT_HEADERS).is_err());
+    }
+
+    #[test]
+    fn rows_report_every_problem() {
+        let row = StudentImportRow {
+            row_number: 2,
+            first_name: " ".to_string(),
+            surname: "Mensah".to_string(),
+            other_names: String::new(),
+            gender: "Unknown".to_string(),
+            class_level: "Year 20".to_string(),
+        };
+        assert_eq!(row.validate().unwrap_err().len(), 3);
+    }
+
+    #[test]
+    fn valid_rows_are_trimmed() {
+        let mut preview = preview(&[["  Ama ", "Mensah", "", "Female", "IGCSE1"]]);
+        validate_student_preview(&mut preview, &[]);
+        assert!(preview.rows[0].errors.is_empty(), "{:?}", preview.rows[0].errors);
+
+        let student = student_import_row(&preview.rows[0]).validate().unwrap();
+        assert_eq!(student.first_name, "Ama");
+        assert_eq!(student.other_names, None);
+    }
+
+    #[test]
+    fn duplicates_of_existing_students_and_earlier_rows_are_flagged() {
+        let mut preview = preview(&[
+            ["Ama", "Mensah", "", "Female", "IGCSE1"],
+            ["Kofi", "Owusu", "", "Male", "IGCSE1"],
+            ["kofi", " OWUSU ", "", "Male", "IGCSE1"],
+        ]);
+        validate_student_preview(&mut preview, &[existing("Ama", "Mensah", ClassLevel::IGCSE1)]);
+
+        assert!(preview.rows[0].duplicate_of.as_deref().unwrap().starts_with("existing student"));
+        assert_eq!(preview.rows[1].duplicate_of, None);
+        assert_eq!(preview.rows[2].duplicate_of.as_deref(), Some("row 3"));
+    }
+
+    #[test]
+    fn skipped_rows_are_not_duplicated_against() {
+        let mut preview = preview(&[
+            ["Kofi", "Owusu", "", "Male", "IGCSE1"],
+            ["Kofi", "Owusu", "", "Male", "IGCSE1"],
+        ]);
+        preview.rows[0].skip = true;
+        validate_student_preview(&mut preview, &[]);
+        assert_eq!(preview.rows[1].duplicate_of, None);
+    }
+}
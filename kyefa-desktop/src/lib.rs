@@ -2,3 +2,10 @@ pub mod app;
 pub mod routes;
 pub mod ui;
 pub mod error;
+pub mod export;
+pub mod import;
+pub mod duplicates;
+pub mod families;
+pub mod offline;
+pub mod backup;
+pub mod settings;
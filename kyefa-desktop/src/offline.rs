@@ -0,0 +1,419 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use kyefa_models::{
+    format_money, CreateStudentPayload, ImportSummary, Payment, PaymentStatus, PeriodImportPayload,
+    PersonName, RecordPaymentPayload, Student,
+};
+use crate::error::AppError;
+use crate::routes;
+use crate::settings;
+
+static STORE_PATH: Lazy<PathBuf> = Lazy::new(|| settings::data_dir().join("offline-store.json"));
+
+/// Held across each read-modify-write of the store file so a sync and a new
+/// write can't drop each other's changes.
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// A write made while the backend was unreachable, replayed in queue order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QueuedWrite {
+    CreateStudent(CreateStudentPayload),
+    RecordPayment(RecordPaymentPayload),
+    ImportPeriods(PeriodImportPayload),
+}
+
+impl QueuedWrite {
+    /// One line for the sync-status panel
+    pub fn describe(&self, currency_symbol: &str) -> String {
+        match self {
+            QueuedWrite::CreateStudent(payload) => {
+                format!("Add student {} {} ({})", payload.first_name, payload.surname, payload.class_level)
+            }
+            QueuedWrite::RecordPayment(payload) => {
+                format!("Payment of {} by {}", format_money(payload.amount_paid, currency_symbol), payload.method)
+            }
+            QueuedWrite::ImportPeriods(payload) => format!("Import of {} periods", payload.rows.len()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SyncState {
+    Pending,
+    /// The backend rejected the write; it stays queued until retried or discarded.
+    Conflict(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingWrite {
+    /// Client-generated id of the record being written, which the backend
+    /// uses to ignore a replay of a write it has already applied
+    pub id: Uuid,
+    pub write: QueuedWrite,
+    pub queued_at: DateTime<Utc>,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub state: SyncState,
+    /// School that was active when the write was made; it's only replayed
+    /// while that school is active again. Writes queued before schools existed
+    /// have none and go to whichever school is active.
+    #[serde(default)]
+    pub school_id: Option<Uuid>,
+    /// User whose session made the write. It's only replayed under their
+    /// session, so the backend records them as its author; on anyone else's
+    /// it waits for them to log in again. Writes queued before users were
+    /// recorded have none and go under whoever is logged in.
+    #[serde(default)]
+    pub user_id: Option<Uuid>,
+}
+
+impl PendingWrite {
+    pub fn is_for(&self, school_id: Option<Uuid>, user_id: Option<Uuid>) -> bool {
+        (self.school_id.is_none() || self.school_id == school_id) && (self.user_id.is_none() || self.user_id == user_id)
+    }
+
+    pub fn is_for_session(&self) -> bool {
+        self.is_for(routes::active_school(), routes::session_user())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocalStore {
+    pub queue: Vec<PendingWrite>,
+    /// Last student list read from the backend, used while it is unreachable
+    pub students: Vec<Student>,
+    /// School the cached students belong to
+    #[serde(default)]
+    pub students_school: Option<Uuid>,
+    pub last_synced: Option<DateTime<Utc>>,
+}
+
+/// A write that either reached the backend or was queued to replay later.
+/// Both carry the record as the app should show it now.
+#[derive(Debug, Clone)]
+pub enum Saved<T> {
+    Synced(T),
+    Queued(T),
+}
+
+/// Outcome of one pass over the queue
+#[derive(Debug, Clone)]
+pub struct SyncReport {
+    pub applied: usize,
+    pub conflicts: usize,
+    /// The backend could not be reached, so the rest of the queue was left for later.
+    pub offline: bool,
+    pub store: LocalStore,
+}
+
+fn load() -> Result<LocalStore, AppError> {
+    match fs::read_to_string(&*STORE_PATH) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| AppError::SerializationError(format!("Failed to read offline store: {}", e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(LocalStore::default()),
+        Err(e) => Err(AppError::IoError(format!("Failed to open offline store: {}", e))),
+    }
+}
+
+fn save(store: &LocalStore) -> Result<(), AppError> {
+    if let Some(dir) = STORE_PATH.parent() {
+        fs::create_dir_all(dir).map_err(|e| AppError::IoError(format!("Failed to create data folder: {}", e)))?;
+    }
+    let contents = serde_json::to_string_pretty(store)
+        .map_err(|e| AppError::SerializationError(format!("Failed to write offline store: {}", e)))?;
+
+    // Write then rename, so a power cut mid-write leaves the previous store intact
+    let temp_path = STORE_PATH.with_extension("json.tmp");
+    fs::write(&temp_path, contents).map_err(|e| AppError::IoError(format!("Failed to save offline store: {}", e)))?;
+    fs::rename(&temp_path, &*STORE_PATH).map_err(|e| AppError::IoError(format!("Failed to save offline store: {}", e)))
+}
+
+fn with_store<T>(change: impl FnOnce(&mut LocalStore) -> T) -> Result<T, AppError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut store = load()?;
+    let result = change(&mut store);
+    save(&store)?;
+    Ok(result)
+}
+
+/// Whether the error means the backend couldn't be reached, as opposed to
+/// answering with a rejection. Anything it answered is a conflict.
+fn is_unreachable(error: &AppError) -> bool {
+    matches!(error, AppError::Unreachable(_))
+}
+
+fn enqueue(id: Uuid, write: QueuedWrite, school_id: Option<Uuid>, user_id: Option<Uuid>) -> Result<(), AppError> {
+    with_store(|store| {
+        if !store.queue.iter().any(|pending| pending.id == id) {
+            store.queue.push(PendingWrite {
+                id,
+                write,
+                queued_at: Utc::now(),
+                attempts: 0,
+                last_error: None,
+                state: SyncState::Pending,
+                school_id,
+                user_id,
+            });
+        }
+    })
+}
+
+fn has_pending(school_id: Option<Uuid>, user_id: Option<Uuid>) -> Result<bool, AppError> {
+    Ok(snapshot()?
+        .queue
+        .iter()
+        .any(|pending| pending.state == SyncState::Pending && pending.is_for(school_id, user_id)))
+}
+
+/// Sends `write` now, or queues it when the backend is unreachable. Writes go
+/// straight to the queue while older ones are still waiting, so they replay in order.
+/// A queued write belongs to the school and user active when it was
+/// submitted, even if they change while it is being sent.
+async fn submit<T, F>(id: Uuid, write: QueuedWrite, send: F, provisional: T) -> Result<Saved<T>, AppError>
+where
+    F: std::future::Future<Output = Result<T, AppError>>,
+{
+    let school_id = routes::active_school();
+    let user_id = routes::session_user();
+    if !has_pending(school_id, user_id)? {
+        match send.await {
+            Ok(saved) => return Ok(Saved::Synced(saved)),
+            Err(e) if !is_unreachable(&e) => return Err(e),
+            Err(_) => {}
+        }
+    }
+    enqueue(id, write, school_id, user_id)?;
+    Ok(Saved::Queued(provisional))
+}
+
+/// How a student added offline appears until the backend has it
+fn provisional_student(payload: &CreateStudentPayload) -> Student {
+    Student {
+        id: payload.id,
+        name: PersonName {
+            first_name: payload.first_name.clone(),
+            surname: payload.surname.clone(),
+            other_names: payload.other_names.clone(),
+        },
+        gender: payload.gender.clone(),
+        class_level: payload.class_level.clone(),
+        is_active: true,
+        fee_amount: 0.0,
+        payment_status: PaymentStatus::NotEnrolled,
+        enrolment: None,
+        installment_plan: None,
+        total_paid: 0.0,
+        merged_into: None,
+    }
+}
+
+pub async fn create_student(payload: CreateStudentPayload) -> Result<Saved<Student>, AppError> {
+    let provisional = provisional_student(&payload);
+    let send = routes::create_student(payload.clone());
+    submit(payload.id, QueuedWrite::CreateStudent(payload), send, provisional).await
+}
+
+/// `status` is the student's status once this payment is counted.
+pub async fn record_payment(payload: RecordPaymentPayload, status: PaymentStatus) -> Result<Saved<Payment>, AppError> {
+    let provisional = Payment {
+        id: payload.id.to_string(),
+        student_id: payload.student_id.to_string(),
+        amount: payload.amount_paid,
+        method: payload.method.clone(),
+        description: payload.description.clone(),
+        status,
+    };
+    let send = routes::record_payment(payload.clone());
+    submit(payload.id, QueuedWrite::RecordPayment(payload), send, provisional).await
+}
+
+/// Periods carry their own ids, so the import is keyed on the first one.
+pub async fn import_periods(payload: PeriodImportPayload) -> Result<Saved<ImportSummary>, AppError> {
+    let Some(id) = payload.rows.first().map(|row| row.period.id) else {
+        return Ok(Saved::Synced(ImportSummary { created: 0, rejected: Vec::new() }));
+    };
+    let provisional = ImportSummary { created: payload.rows.len(), rejected: Vec::new() };
+    let send = routes::import_periods(payload.clone(), id);
+    submit(id, QueuedWrite::ImportPeriods(payload), send, provisional).await
+}
+
+/// Students from the backend, kept as the offline copy. When the backend is
+/// unreachable the last copy is returned instead, with students still queued
+/// for creation added. The copy is kept for the school active when the fetch
+/// started, which is the one the backend answered for.
+pub async fn fetch_students() -> Result<Vec<Student>, AppError> {
+    let school_id = routes::active_school();
+    let user_id = routes::session_user();
+    match routes::fetch_all_students().await {
+        Ok(students) => {
+            let cached = students.clone();
+            with_store(move |store| {
+                store.students = cached;
+                store.students_school = school_id;
+            })?;
+            Ok(students)
+        }
+        Err(e) if is_unreachable(&e) => {
+            let store = snapshot()?;
+            // Another school's students are never shown in place of this one's
+            let mut students = if store.students_school == school_id {
+                store.students
+            } else {
+                Vec::new()
+            };
+            for pending in store.queue.iter().filter(|pending| pending.is_for(school_id, user_id)) {
+                if let QueuedWrite::CreateStudent(payload) = &pending.write {
+                    if !students.iter().any(|s| s.id == payload.id) {
+                        students.push(provisional_student(payload));
+                    }
+                }
+            }
+            Ok(students)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub fn snapshot() -> Result<LocalStore, AppError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    load()
+}
+
+/// Puts a conflicted write back in line for the next sync.
+pub fn retry(id: Uuid) -> Result<LocalStore, AppError> {
+    with_store(|store| {
+        if let Some(pending) = store.queue.iter_mut().find(|pending| pending.id == id) {
+            pending.state = SyncState::Pending;
+        }
+        store.clone()
+    })
+}
+
+/// Drops a write for good; the record it made locally goes with it on the next refresh.
+pub fn discard(id: Uuid) -> Result<LocalStore, AppError> {
+    with_store(|store| {
+        store.queue.retain(|pending| pending.id != id);
+        store.clone()
+    })
+}
+
+/// Sends the write again under the id it was queued with, which is also its
+/// idempotency key, so a replay the backend already applied isn't applied twice
+async fn replay(pending: &PendingWrite) -> Result<(), AppError> {
+    match &pending.write {
+        QueuedWrite::CreateStudent(payload) => routes::create_student(payload.clone()).await.map(|_| ()),
+        QueuedWrite::RecordPayment(payload) => routes::record_payment(payload.clone()).await.map(|_| ()),
+        QueuedWrite::ImportPeriods(payload) => {
+            let summary = routes::import_periods(payload.clone(), pending.id).await?;
+            match summary.rejected.first() {
+                Some(rejected) => Err(AppError::BackendError(format!(
+                    "{} of {} periods were rejected; row {}: {}",
+                    summary.rejected.len(), payload.rows.len(), rejected.row_number, rejected.message,
+                ))),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+/// Replays the pending writes the logged-in user made for the active school,
+/// oldest first. Applied writes
+/// leave the queue and rejected ones are marked as conflicts. The pass stops at the first write the
+/// backend can't be reached for, since later ones may depend on it, and when
+/// the user switches school or logs out, since the rest would go to the new
+/// school or session.
+pub async fn sync() -> Result<SyncReport, AppError> {
+    let school_id = routes::active_school();
+    let user_id = routes::session_user();
+    let pending: Vec<PendingWrite> = snapshot()?
+        .queue
+        .into_iter()
+        .filter(|pending| pending.state == SyncState::Pending && pending.is_for(school_id, user_id))
+        .collect();
+
+    let mut applied = 0;
+    let mut conflicts = 0;
+    let mut offline = false;
+    for write in pending {
+        if routes::active_school() != school_id || routes::session_user() != user_id {
+            break;
+        }
+        let result = replay(&write).await;
+        with_store(|store| {
+            let position = store.queue.iter().position(|p| p.id == write.id);
+            match (&result, position) {
+                (Ok(()), Some(position)) => {
+                    store.queue.remove(position);
+                }
+                (Err(e), Some(position)) => {
+                    let queued = &mut store.queue[position];
+                    queued.attempts += 1;
+                    queued.last_error = Some(e.to_string());
+                    if !is_unreachable(e) {
+                        queued.state = SyncState::Conflict(e.to_string());
+                    }
+                }
+                // Discarded while it was being sent
+                (_, None) => {}
+            }
+        })?;
+        match result {
+            Ok(()) => applied += 1,
+            Err(e) if is_unreachable(&e) => {
+                offline = true;
+                break;
+            }
+            Err(_) => conflicts += 1,
+        }
+    }
+
+    let store = with_store(|store| {
+        if !offline {
+            store.last_synced = Some(Utc::now());
+        }
+        store.clone()
+    })?;
+    Ok(SyncReport { applied, conflicts, offline, store })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(school_id: Option<Uuid>, user_id: Option<Uuid>) -> PendingWrite {
+        PendingWrite {
+            id: Uuid::new_v4(),
+            write: QueuedWrite::ImportPeriods(PeriodImportPayload { term_id: Uuid::new_v4(), rows: Vec::new() }),
+            queued_at: Utc::now(),
+            attempts: 0,
+            last_error: None,
+            state: SyncState::Pending,
+            school_id,
+            user_id,
+        }
+    }
+
+    #[test]
+    fn writes_replay_only_for_their_school_and_user() {
+        let (school, user) = (Some(Uuid::new_v4()), Some(Uuid::new_v4()));
+        let write = pending(school, user);
+        assert!(write.is_for(school, user));
+        assert!(!write.is_for(school, Some(Uuid::new_v4())));
+        assert!(!write.is_for(Some(Uuid::new_v4()), user));
+        assert!(!write.is_for(school, None));
+    }
+
+    #[test]
+    fn writes_from_before_schools_and_users_go_to_anyone() {
+        let write = pending(None, None);
+        assert!(write.is_for(Some(Uuid::new_v4()), Some(Uuid::new_v4())));
+    }
+}
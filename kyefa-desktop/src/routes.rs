@@ -1,18 +1,31 @@
 use reqwest::{Client, StatusCode};
-use serde_json::json;
 use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
 use reqwest;
 use std::path::PathBuf;
-use reqwest::multipart::{Form, Part};
-use rfd::FileDialog;
+use std::sync::RwLock;
+use std::time::Duration;
+use uuid::Uuid;
 use umya_spreadsheet::*;
 use umya_spreadsheet::writer;
 
 use kyefa_models::{
     UserAccount, UserResponse, Gender, ClassLevel,
     Student, CreateStudentPayload, UpdateStudentPayload,
+    Teacher, ReportFilter, ReportOverview, ProjectedIncomeReport,
+    PaymentSummary, TeacherEarnings, StudentPaymentHistory,
+    Term, Payment, PaymentFilter, RecordPaymentPayload, HomeQuery, HomeSummary, RecentActivity,
+    WeeklyCollection, TermComparison, StudentImportPayload, ImportSummary,
+    Subject, PeriodImportPayload, MergeStudentsPayload, MergeStudentsResult,
+    PromotionPayload, PromotionSummary, Enrolment, EnrolStudentsPayload,
+    WithdrawEnrolmentPayload, Exemption, CreateExemptionPayload, ExemptionReportRow,
+    InstallmentPlan, SaveInstallmentPlanPayload, OverdueAgingRow, Guardian, SaveGuardianPayload,
+    SchoolSettings, UpdateSchoolSettingsPayload, CreateUserPayload, UpdateUserPayload,
+    ResetPasswordPayload, ChangePasswordPayload, LoginPayload, LoginResponse, UnlockPayload, LoginAttempt, LoginHistoryQuery,
+    TeacherPortal, TeacherPeriod, DisputePeriodPayload, PeriodForReview, PeriodReviewQuery, ReviewPeriodPayload,
+    Disbursement, RunPayoutPayload, IDEMPOTENCY_KEY_HEADER, SCHOOL_ID_HEADER,
 };
-use crate::error::{LoginError, AppError, BackendError};
+use crate::error::{LoginError, AppError, BackendError, PasswordChangeError};
 
 use once_cell::sync::Lazy;
 
@@ -22,169 +35,1074 @@ static API_BASE_URL: Lazy<String> = Lazy::new(|| {
         .expect("KYEFA_BACKEND_URL must be set in .env or environment variables")
 });
 
-pub async fn login(username: &str, password: &str) -> Result<UserResponse, LoginError> {
+/// How long a write waits for the backend before it is retried
+const WRITE_TIMEOUT: Duration = Duration::from_secs(15);
+/// Sends of one write, counting the first, before giving up
+const WRITE_ATTEMPTS: u32 = 3;
+/// How long a restore may take on the backend. It is sent once: resending a
+/// large dump while the first is still being applied only gets a 409.
+const RESTORE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// School every request after login is scoped to. The backend only returns
+/// and changes records belonging to it.
+static ACTIVE_SCHOOL: RwLock<Option<Uuid>> = RwLock::new(None);
+
+pub fn set_active_school(school_id: Option<Uuid>) {
+    *ACTIVE_SCHOOL.write().unwrap_or_else(|e| e.into_inner()) = school_id;
+}
+
+pub fn active_school() -> Option<Uuid> {
+    *ACTIVE_SCHOOL.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The last successful login. The backend works out who is making each
+/// request from the token, so it's the only proof of identity sent; the user
+/// id is kept to tell whose offline writes may be replayed.
+struct Session {
+    token: String,
+    user_id: Uuid,
+}
+
+static SESSION: RwLock<Option<Session>> = RwLock::new(None);
+
+fn set_session(session: Option<Session>) {
+    *SESSION.write().unwrap_or_else(|e| e.into_inner()) = session;
+}
+
+/// Forgets the session locally, without telling the backend
+pub fn end_session() {
+    set_session(None);
+}
+
+fn session_token() -> Option<String> {
+    SESSION.read().unwrap_or_else(|e| e.into_inner()).as_ref().map(|session| session.token.clone())
+}
+
+/// The logged-in user, if any
+pub fn session_user() -> Option<Uuid> {
+    SESSION.read().unwrap_or_else(|e| e.into_inner()).as_ref().map(|session| session.user_id)
+}
+
+/// Builder with the session token and the active school's header on every request
+fn scoped_builder() -> reqwest::ClientBuilder {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(token) = session_token() {
+        if let Ok(mut value) = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token)) {
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+    }
+    if let Some(school_id) = active_school() {
+        if let Ok(value) = reqwest::header::HeaderValue::from_str(&school_id.to_string()) {
+            headers.insert(SCHOOL_ID_HEADER, value);
+        }
+    }
+    Client::builder().default_headers(headers)
+}
+
+/// Fails rather than falling back to a plain client, which would send the
+/// request without the session and school headers or the timeout
+fn build(builder: reqwest::ClientBuilder) -> Result<Client, AppError> {
+    builder
+        .build()
+        .map_err(|e| AppError::Configuration(format!("Couldn't set up the connection to the backend: {}", e)))
+}
+
+fn client() -> Result<Client, AppError> {
+    build(scoped_builder())
+}
+
+fn write_client() -> Result<Client, AppError> {
+    build(scoped_builder().timeout(WRITE_TIMEOUT))
+}
+
+fn restore_client() -> Result<Client, AppError> {
+    build(scoped_builder().timeout(RESTORE_TIMEOUT))
+}
+
+/// Sends a POST/PUT with `key` as its idempotency key, retrying on timeouts,
+/// dropped connections, 5xx responses and 409s from an earlier send of the
+/// same write that the backend is still handling. Every retry carries the same key, so
+/// a write the backend applied before the response was lost comes back with
+/// the original result instead of being applied twice. Callers keep `key` for
+/// the form or action until the write succeeds, so resubmitting after a lost
+/// response reuses it too.
+async fn send_write(request: reqwest::RequestBuilder, key: Uuid) -> Result<reqwest::Response, AppError> {
+    let request = request.header(IDEMPOTENCY_KEY_HEADER, key.to_string());
+    let mut attempt = 1;
+    loop {
+        let Some(retry) = request.try_clone() else {
+            // Streaming bodies can't be resent, so they get a single try
+            return request.send().await.map_err(AppError::from);
+        };
+        let result = retry.send().await;
+        let should_retry = match &result {
+            Ok(res) => res.status().is_server_error() || res.status() == StatusCode::CONFLICT,
+            Err(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+        };
+        if !should_retry || attempt == WRITE_ATTEMPTS {
+            return result.map_err(AppError::from);
+        }
+        tokio::time::sleep(Duration::from_millis(500 * 2u64.pow(attempt - 1))).await;
+        attempt += 1;
+    }
+}
+
+/// This computer's name for the login history, or "Unknown" where the OS won't say
+fn machine_name() -> String {
+    whoami::fallible::hostname().unwrap_or_else(|_| "Unknown".to_string())
+}
+
+/// Wrong passwords, unknown usernames and locked accounts all come back as
+/// the same `InvalidCredentials`, so the message gives nothing away. On
+/// success every later request carries the new session's token. `school` is
+/// the school last used on this computer, which files the attempt in that
+/// school's login history.
+pub async fn login(username: &str, password: &str, school: Option<Uuid>) -> Result<UserResponse, LoginError> {
     let client = Client::new();
-    let response = client
-        .post("http://127.0.0.1:3050/login")
-        .json(&json!({
-            "username": username,
-            "password": password,
-        }))
+    let payload = LoginPayload {
+        username: username.to_string(),
+        password: password.to_string(),
+        machine_name: machine_name(),
+    };
+    let mut request = client
+        .post(&format!("{}/login", *API_BASE_URL))
+        .json(&payload);
+    if let Some(school_id) = school {
+        request = request.header(SCHOOL_ID_HEADER, school_id.to_string());
+    }
+    let response = request.send().await;
+
+    match response {
+        Ok(response) => {
+            match response.status() {
+                StatusCode::OK => match response.json::<LoginResponse>().await {
+                    Ok(login) => match Uuid::parse_str(&login.user.id) {
+                        Ok(user_id) => {
+                            set_session(Some(Session { token: login.token, user_id }));
+                            Ok(login.user)
+                        }
+                        Err(_) => Err(LoginError::ServerError("Failed to parse server response.".to_string())),
+                    },
+                    Err(_) => Err(LoginError::ServerError("Failed to parse server response.".to_string())),
+                },
+                StatusCode::UNAUTHORIZED | StatusCode::NOT_FOUND | StatusCode::LOCKED | StatusCode::FORBIDDEN => {
+                    Err(LoginError::InvalidCredentials(
+                        "Incorrect username or password. Repeated failures lock the account for a while.".to_string(),
+                    ))
+                }
+                status => Err(LoginError::ServerError(format!("Server returned an unexpected status: {}", status))),
+            }
+        }
+        Err(e) => Err(LoginError::NetworkIssue(format!("Could not connect to the server: {}", e))),
+    }
+}
+
+/// Forgets the session token and the active school straight away, so nothing
+/// sent afterwards acts for the old user, then asks the backend to end the
+/// session with the token taken out of it. That request is best-effort: a
+/// failure is ignored and the session still runs out by itself.
+pub fn logout() -> impl std::future::Future<Output = ()> {
+    let session = SESSION.write().unwrap_or_else(|e| e.into_inner()).take();
+    set_active_school(None);
+    async move {
+        let Some(session) = session else {
+            return;
+        };
+        let _ = Client::new()
+            .post(&format!("{}/logout", *API_BASE_URL))
+            .bearer_auth(session.token)
+            .timeout(WRITE_TIMEOUT)
+            .send()
+            .await;
+    }
+}
+
+/// Checks the password of the logged-in user to leave the lock screen,
+/// keeping their session
+pub async fn unlock(password: &str) -> Result<(), LoginError> {
+    let payload = UnlockPayload {
+        password: password.to_string(),
+        machine_name: machine_name(),
+    };
+    let res = client()
+        .map_err(|e| LoginError::ServerError(e.to_string()))?
+        .post(&format!("{}/account/unlock", *API_BASE_URL))
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| LoginError::NetworkIssue(format!("Could not connect to the server: {}", e)))?;
+
+    let status = res.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+    let message = serde_json::from_str::<BackendError>(&error_text)
+        .map(|backend_error| backend_error.message)
+        .unwrap_or(error_text);
+    match status {
+        // A wrong password, a locked account or a session that has ended
+        StatusCode::UNAUTHORIZED => Err(LoginError::InvalidCredentials(message)),
+        _ => Err(LoginError::ServerError(message)),
+    }
+}
+
+pub async fn create_student(payload: CreateStudentPayload) -> Result<Student, AppError> {
+    let client = write_client()?;
+    let request = client.post(&format!("{}/students", *API_BASE_URL)) // Use *API_BASE_URL
+        .json(&payload);
+    let res = send_write(request, payload.id).await?;
+
+    if res.status().is_success() {
+        let student: Student = res.json()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse student creation response: {}", e)))?;
+        Ok(student)
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) { // Use BackendError
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to create student: {}", error_text)))
+        }
+    }
+}
+
+pub async fn fetch_all_students() -> Result<Vec<Student>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/students", *API_BASE_URL)) // Use *API_BASE_URL
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        let students: Vec<Student> = res.json()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse students list: {}", e)))?;
+        Ok(students)
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) { // Use BackendError
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch students: {}", error_text)))
+        }
+    }
+}
+
+pub async fn update_student(payload: UpdateStudentPayload, key: Uuid) -> Result<Student, AppError> {
+    let client = write_client()?;
+    let request = client
+        .put(&format!("{}/students", *API_BASE_URL)) // Matches backend route
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<Student>()
+            .await
+            .map_err(|e| AppError::SerializationError(e.to_string()))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(error_text))
+        }
+    }
+}
+
+
+pub async fn fetch_all_teachers() -> Result<Vec<Teacher>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/teachers", *API_BASE_URL))
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Teacher>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse teachers list: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch teachers: {}", error_text)))
+        }
+    }
+}
+
+pub async fn fetch_all_terms() -> Result<Vec<Term>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/terms", *API_BASE_URL))
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Term>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse terms list: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch terms: {}", error_text)))
+        }
+    }
+}
+
+pub async fn fetch_all_subjects() -> Result<Vec<Subject>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/subjects", *API_BASE_URL))
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Subject>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse subjects list: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch subjects: {}", error_text)))
+        }
+    }
+}
+
+pub async fn fetch_payments(filter: PaymentFilter) -> Result<Vec<Payment>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/payments", *API_BASE_URL))
+        .query(&filter)
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Payment>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse payments list: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch payments: {}", error_text)))
+        }
+    }
+}
+
+pub async fn record_payment(payload: RecordPaymentPayload) -> Result<Payment, AppError> {
+    let client = write_client()?;
+    let request = client.post(&format!("{}/payments", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, payload.id).await?;
+
+    if res.status().is_success() {
+        res.json::<Payment>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse recorded payment: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to record payment: {}", error_text)))
+        }
+    }
+}
+
+// ============= USERS ===============
+
+/// Every account, deactivated ones included
+pub async fn fetch_users() -> Result<Vec<UserResponse>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/users", *API_BASE_URL))
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<Vec<UserResponse>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse users: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch users: {}", error_text)))
+        }
+    }
+}
+
+async fn user_response(res: reqwest::Response, action: &str) -> Result<UserResponse, AppError> {
+    if res.status().is_success() {
+        res.json::<UserResponse>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse user: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to {}: {}", action, error_text)))
+        }
+    }
+}
+
+pub async fn create_user(payload: CreateUserPayload, key: Uuid) -> Result<UserResponse, AppError> {
+    let client = write_client()?;
+    let request = client.post(&format!("{}/users", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+    user_response(res, "create user").await
+}
+
+pub async fn update_user(id: Uuid, payload: UpdateUserPayload, key: Uuid) -> Result<UserResponse, AppError> {
+    let client = write_client()?;
+    let request = client.put(&format!("{}/users/{}", *API_BASE_URL, id))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+    user_response(res, "update user").await
+}
+
+pub async fn reset_password(id: Uuid, payload: ResetPasswordPayload, key: Uuid) -> Result<UserResponse, AppError> {
+    let client = write_client()?;
+    let request = client.post(&format!("{}/users/{}/reset-password", *API_BASE_URL, id))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+    user_response(res, "reset password").await
+}
+
+/// Replaces the password of the user logged in. On success the backend has
+/// ended their sessions, so the caller should log them out.
+pub async fn change_password(payload: ChangePasswordPayload, key: Uuid) -> Result<(), PasswordChangeError> {
+    let client = write_client().map_err(|e| PasswordChangeError::ServerError(e.to_string()))?;
+    let request = client.post(&format!("{}/account/change-password", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key)
+        .await
+        .map_err(|e| PasswordChangeError::NetworkError(e.to_string()))?;
+
+    let status = res.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+    let message = serde_json::from_str::<BackendError>(&error_text)
+        .map(|backend_error| backend_error.message)
+        .unwrap_or(error_text);
+    match status {
+        StatusCode::FORBIDDEN => Err(PasswordChangeError::CurrentPasswordIncorrect),
+        // Too short, or one of the recent passwords the policy forbids
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Err(PasswordChangeError::NewPasswordInvalid(message)),
+        _ => Err(PasswordChangeError::ServerError(message)),
+    }
+}
+
+/// Login attempts matching `query`, for admins reviewing who used which computer
+pub async fn fetch_login_history(query: LoginHistoryQuery) -> Result<Vec<LoginAttempt>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/login-history", *API_BASE_URL))
+        .query(&query)
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<Vec<LoginAttempt>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse login history: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch login history: {}", error_text)))
+        }
+    }
+}
+
+// ============= SETTINGS ===============
+
+pub async fn fetch_school_settings() -> Result<SchoolSettings, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/settings", *API_BASE_URL))
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<SchoolSettings>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse school settings: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch school settings: {}", error_text)))
+        }
+    }
+}
+
+pub async fn save_school_settings(payload: UpdateSchoolSettingsPayload, key: Uuid) -> Result<SchoolSettings, AppError> {
+    let client = write_client()?;
+    let request = client.put(&format!("{}/settings", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<SchoolSettings>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse saved settings: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to save school settings: {}", error_text)))
+        }
+    }
+}
+
+// ============= BACKUPS ===============
+
+/// Dump of the active school's data, as produced by the backend's exporter
+pub async fn export_database() -> Result<Vec<u8>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/backups/export", *API_BASE_URL))
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.bytes()
+            .await
+            .map(|bytes| bytes.to_vec())
+            .map_err(AppError::from)
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to export database: {}", error_text)))
+        }
+    }
+}
+
+/// Replaces the active school's data on the backend with the contents of `dump`.
+/// A timeout leaves the outcome unknown, so it is reported as such rather
+/// than retried; trying again with the same `key` picks up the first result.
+pub async fn restore_database(dump: Vec<u8>, key: Uuid) -> Result<(), AppError> {
+    let client = restore_client()?;
+    let res = client.post(&format!("{}/backups/restore", *API_BASE_URL))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(IDEMPOTENCY_KEY_HEADER, key.to_string())
+        .body(dump)
+        .send()
+        .await
+        .map_err(|e| match (e.is_timeout(), e.is_connect()) {
+            (true, _) => AppError::Unreachable(
+                "The server hasn't confirmed the restore. It may still be running; check the data before restoring again.".to_string(),
+            ),
+            (_, true) => AppError::Unreachable("Couldn't reach the server, so nothing was restored.".to_string()),
+            _ => AppError::from(e),
+        })?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to restore database: {}", error_text)))
+        }
+    }
+}
+
+// ============= HOME ===============
+
+pub async fn fetch_home_summary() -> Result<HomeSummary, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/dashboard/summary", *API_BASE_URL))
+        .query(&HomeQuery::default())
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<HomeSummary>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse dashboard summary: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch dashboard summary: {}", error_text)))
+        }
+    }
+}
+
+pub async fn fetch_recent_activity(limit: u32) -> Result<Vec<RecentActivity>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/activity", *API_BASE_URL))
+        .query(&HomeQuery { limit: Some(limit) })
+        .send()
+        .await
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<Vec<RecentActivity>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse recent activity: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch recent activity: {}", error_text)))
+        }
+    }
+}
+
+// ============= TEACHER PORTAL ===============
+
+/// The portal for the teacher linked to the user logged in; the backend
+/// refuses users who aren't linked to a teacher record.
+pub async fn fetch_teacher_portal() -> Result<TeacherPortal, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/portal/teacher", *API_BASE_URL))
         .send()
-        .await;
+        .await
+        .map_err(AppError::from)?;
 
-    match response {
-        Ok(response) => {
-            if response.status() == StatusCode::OK {
-                // let response_text = response.text().await.map_err(|e| 
-                //     LoginError::ServerError(format!("Failed to read response: {}", e))
-                // )?;
-                
-                // println!("Server response: {}", response_text); 
-                
-                // match serde_json::from_str::<UserResponse>(&response_text) {
-                //     Ok(user) => Ok(user),
-                //     Err(e) => {
-                //         println!("JSON parse error: {}", e);
-                //         Err(LoginError::ServerError(format!("Failed to parse server response: {}", e)))
-                //     }
-                // }
-
-                match response.json::<UserResponse>().await {
-                    Ok(user) => Ok(user),
-                    Err(_) => Err(LoginError::ServerError("Failed to parse server response.".to_string())),
-                }
-            } else if response.status() == StatusCode::UNAUTHORIZED {
-                Err(LoginError::InvalidCredentials("Incorrect username or password.".to_string()))
-            } else if response.status() == StatusCode::NOT_FOUND {
-                Err(LoginError::UserNotFound("User with that username does not exist.".to_string()))
-            } else {
-                Err(LoginError::ServerError(format!("Server returned an unexpected status: {}", response.status())))
-            }
+    if res.status().is_success() {
+        res.json::<TeacherPortal>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse teacher portal: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch teacher portal: {}", error_text)))
         }
-        Err(e) => Err(LoginError::NetworkIssue(format!("Could not connect to the server: {}", e))),
     }
 }
 
-pub async fn create_student(payload: CreateStudentPayload) -> Result<Student, AppError> {
-    let client = reqwest::Client::new();
-    let res = client.post(&format!("{}/students", *API_BASE_URL)) // Use *API_BASE_URL
-        .json(&payload)
+pub async fn dispute_period(id: Uuid, payload: DisputePeriodPayload, key: Uuid) -> Result<TeacherPeriod, AppError> {
+    let client = write_client()?;
+    let request = client.post(&format!("{}/periods/{}/dispute", *API_BASE_URL, id))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<TeacherPeriod>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse disputed period: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to dispute period: {}", error_text)))
+        }
+    }
+}
+
+// ============= PERIOD REVIEW ===============
+
+/// Periods matching `query` for the committee to verify or reject
+pub async fn fetch_periods_for_review(query: PeriodReviewQuery) -> Result<Vec<PeriodForReview>, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/periods", *API_BASE_URL))
+        .query(&query)
         .send()
         .await
-        .map_err(|e| AppError::NetworkIssue(e.to_string()))?; // Changed to NetworkIssue
+        .map_err(AppError::from)?;
 
     if res.status().is_success() {
-        let student: Student = res.json()
+        res.json::<Vec<PeriodForReview>>()
             .await
-            .map_err(|e| AppError::SerializationError(format!("Failed to parse student creation response: {}", e)))?;
-        Ok(student)
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse periods: {}", e)))
     } else {
         let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) { // Use BackendError
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
             Err(AppError::BackendError(backend_error.message))
         } else {
-            Err(AppError::NetworkIssue(format!("Failed to create student: {}", error_text))) // Changed to NetworkIssue
+            Err(AppError::BackendError(format!("Failed to fetch periods: {}", error_text)))
         }
     }
 }
 
-pub async fn fetch_all_students() -> Result<Vec<Student>, AppError> {
-    let client = reqwest::Client::new();
-    let res = client.get(&format!("{}/students", *API_BASE_URL)) // Use *API_BASE_URL
+pub async fn review_period(id: Uuid, payload: ReviewPeriodPayload, key: Uuid) -> Result<TeacherPeriod, AppError> {
+    let client = write_client()?;
+    let request = client.put(&format!("{}/periods/{}/verification", *API_BASE_URL, id))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<TeacherPeriod>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse reviewed period: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to review period: {}", error_text)))
+        }
+    }
+}
+
+/// Pays the term out to its teachers, writing the payouts their payslips show
+pub async fn run_payout(payload: RunPayoutPayload, key: Uuid) -> Result<Disbursement, AppError> {
+    let client = write_client()?;
+    let request = client.post(&format!("{}/disbursements", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<Disbursement>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse payout: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to pay out the term: {}", error_text)))
+        }
+    }
+}
+
+// ============= REPORTS ===============
+
+async fn fetch_report<T: DeserializeOwned>(path: &str, filter: &ReportFilter) -> Result<T, AppError> {
+    let client = client()?;
+    let res = client.get(&format!("{}/reports/{}", *API_BASE_URL, path))
+        .query(filter)
         .send()
         .await
-        .map_err(|e| AppError::NetworkIssue(e.to_string()))?; // Changed to NetworkIssue
+        .map_err(AppError::from)?;
 
     if res.status().is_success() {
-        let students: Vec<Student> = res.json()
+        res.json::<T>()
             .await
-            .map_err(|e| AppError::SerializationError(format!("Failed to parse students list: {}", e)))?;
-        Ok(students)
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse {} report: {}", path, e)))
     } else {
         let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) { // Use BackendError
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch {} report: {}", path, error_text)))
+        }
+    }
+}
+
+pub async fn fetch_report_overview(filter: ReportFilter) -> Result<ReportOverview, AppError> {
+    fetch_report("overview", &filter).await
+}
+
+pub async fn fetch_projected_income_report(filter: ReportFilter) -> Result<ProjectedIncomeReport, AppError> {
+    fetch_report("projected-income", &filter).await
+}
+
+pub async fn fetch_collection_status_report(filter: ReportFilter) -> Result<PaymentSummary, AppError> {
+    fetch_report("collection-status", &filter).await
+}
+
+pub async fn fetch_teacher_earnings_report(filter: ReportFilter) -> Result<Vec<TeacherEarnings>, AppError> {
+    fetch_report("teacher-earnings", &filter).await
+}
+
+pub async fn fetch_student_payments_report(filter: ReportFilter) -> Result<Vec<StudentPaymentHistory>, AppError> {
+    fetch_report("student-payments", &filter).await
+}
+
+pub async fn fetch_collection_trend_report(filter: ReportFilter) -> Result<Vec<WeeklyCollection>, AppError> {
+    fetch_report("collection-trend", &filter).await
+}
+
+pub async fn fetch_term_comparison_report(filter: ReportFilter) -> Result<Vec<TermComparison>, AppError> {
+    fetch_report("term-comparison", &filter).await
+}
+
+pub async fn fetch_exemptions_report(filter: ReportFilter) -> Result<Vec<ExemptionReportRow>, AppError> {
+    fetch_report("exemptions", &filter).await
+}
+
+pub async fn fetch_overdue_aging_report(filter: ReportFilter) -> Result<Vec<OverdueAgingRow>, AppError> {
+    fetch_report("overdue-aging", &filter).await
+}
+
+/// Returns the saved plan, or `None` when the plan was removed.
+pub async fn save_installment_plan(payload: SaveInstallmentPlanPayload, key: Uuid) -> Result<Option<InstallmentPlan>, AppError> {
+    let client = write_client()?;
+    let request = client
+        .put(&format!("{}/installment-plans", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<Option<InstallmentPlan>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse installment plan: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to save installment plan: {}", error_text)))
+        }
+    }
+}
+
+pub async fn create_exemption(payload: CreateExemptionPayload, key: Uuid) -> Result<Exemption, AppError> {
+    let client = write_client()?;
+    let request = client
+        .post(&format!("{}/exemptions", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<Exemption>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse exemption: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to record exemption: {}", error_text)))
+        }
+    }
+}
+
+
+pub async fn merge_students(payload: MergeStudentsPayload, key: Uuid) -> Result<MergeStudentsResult, AppError> {
+    let client = write_client()?;
+    let request = client
+        .post(&format!("{}/students/merge", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<MergeStudentsResult>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse merge result: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to merge students: {}", error_text)))
+        }
+    }
+}
+
+pub async fn promote_students(payload: PromotionPayload, key: Uuid) -> Result<PromotionSummary, AppError> {
+    let client = write_client()?;
+    let request = client
+        .post(&format!("{}/students/promote", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<PromotionSummary>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse promotion summary: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
             Err(AppError::BackendError(backend_error.message))
         } else {
-            Err(AppError::NetworkIssue(format!("Failed to fetch students: {}", error_text))) // Changed to NetworkIssue
+            Err(AppError::BackendError(format!("Failed to promote students: {}", error_text)))
         }
     }
 }
 
-pub async fn update_student(payload: UpdateStudentPayload) -> Result<Student, AppError> {
-    let client = reqwest::Client::new();
+pub async fn fetch_guardians() -> Result<Vec<Guardian>, AppError> {
+    let client = client()?;
     let res = client
-        .put(&format!("{}/students", *API_BASE_URL)) // Matches backend route
-        .json(&payload)
+        .get(&format!("{}/guardians", *API_BASE_URL))
         .send()
         .await
-        .map_err(|e| AppError::NetworkIssue(e.to_string()))?;
+        .map_err(AppError::from)?;
 
     if res.status().is_success() {
-        res.json::<Student>()
+        res.json::<Vec<Guardian>>()
             .await
-            .map_err(|e| AppError::SerializationError(e.to_string()))
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse guardians: {}", e)))
     } else {
         let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
         if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
             Err(AppError::BackendError(backend_error.message))
         } else {
-            Err(AppError::NetworkIssue(error_text))
+            Err(AppError::BackendError(format!("Failed to fetch guardians: {}", error_text)))
         }
     }
 }
 
+/// Creates a guardian, or updates `id` when given.
+pub async fn save_guardian(id: Option<Uuid>, payload: SaveGuardianPayload, key: Uuid) -> Result<Guardian, AppError> {
+    let client = write_client()?;
+    let request = match id {
+        Some(id) => client.put(&format!("{}/guardians/{}", *API_BASE_URL, id)),
+        None => client.post(&format!("{}/guardians", *API_BASE_URL)),
+    };
+    let res = send_write(request.json(&payload), key).await?;
 
-pub async fn import_students_from_excel(path: PathBuf) -> Result<(), AppError> {
-    let client = reqwest::Client::new();
+    if res.status().is_success() {
+        res.json::<Guardian>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse guardian: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to save guardian: {}", error_text)))
+        }
+    }
+}
 
-    let file_bytes = tokio::fs::read(&path)
+pub async fn fetch_enrolments(term_id: Uuid) -> Result<Vec<Enrolment>, AppError> {
+    let client = client()?;
+    let res = client
+        .get(&format!("{}/enrolments", *API_BASE_URL))
+        .query(&[("term_id", term_id)])
+        .send()
         .await
-        .map_err(|e| AppError::IoError(format!("Failed to read file: {}", e)))?;
+        .map_err(AppError::from)?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Enrolment>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse enrolments: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to fetch enrolments: {}", error_text)))
+        }
+    }
+}
+
+pub async fn enrol_students(payload: EnrolStudentsPayload, key: Uuid) -> Result<Vec<Enrolment>, AppError> {
+    let client = write_client()?;
+    let request = client
+        .post(&format!("{}/enrolments", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
 
-    let file_name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("students.xlsx");
+    if res.status().is_success() {
+        res.json::<Vec<Enrolment>>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse enrolments: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to enrol students: {}", error_text)))
+        }
+    }
+}
 
-    let part = Part::bytes(file_bytes)
-        .file_name(file_name.to_string())
-        .mime_str("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
-        .map_err(|e| AppError::IoError(format!("Invalid MIME: {}", e)))?;
+pub async fn withdraw_enrolment(payload: WithdrawEnrolmentPayload, key: Uuid) -> Result<Enrolment, AppError> {
+    let client = write_client()?;
+    let request = client
+        .post(&format!("{}/enrolments/withdraw", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
 
-    let form = Form::new().part("file", part);
+    if res.status().is_success() {
+        res.json::<Enrolment>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse enrolment: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Failed to withdraw student: {}", error_text)))
+        }
+    }
+}
 
-    let res = client
+pub async fn import_students(payload: StudentImportPayload, key: Uuid) -> Result<ImportSummary, AppError> {
+    let client = write_client()?;
+    let request = client
         .post(&format!("{}/students/import", *API_BASE_URL))
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| AppError::NetworkIssue(e.to_string()))?;
+        .json(&payload);
+    let res = send_write(request, key).await?;
 
     if res.status().is_success() {
-        Ok(())
+        res.json::<ImportSummary>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse import summary: {}", e)))
     } else {
-        let text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&text) {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
             Err(AppError::BackendError(backend_error.message))
         } else {
-            Err(AppError::NetworkIssue(format!("Upload failed: {}", text)))
+            Err(AppError::BackendError(format!("Import failed: {}", error_text)))
         }
     }
 }
 
-pub async fn pick_and_upload_excel_file() -> Result<(), AppError> {
-    let file = FileDialog::new()
-        .add_filter("Excel Files", &["xlsx"])
-        .pick_file();
 
-    match file {
-        Some(path) => import_students_from_excel(path).await,
-        None => Err(AppError::IoError("No file selected".into())),
+pub async fn import_periods(payload: PeriodImportPayload, key: Uuid) -> Result<ImportSummary, AppError> {
+    let client = write_client()?;
+    let request = client
+        .post(&format!("{}/periods/import", *API_BASE_URL))
+        .json(&payload);
+    let res = send_write(request, key).await?;
+
+    if res.status().is_success() {
+        res.json::<ImportSummary>()
+            .await
+            .map_err(|e| AppError::SerializationError(format!("Failed to parse import summary: {}", e)))
+    } else {
+        let error_text = res.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if let Ok(backend_error) = serde_json::from_str::<BackendError>(&error_text) {
+            Err(AppError::BackendError(backend_error.message))
+        } else {
+            Err(AppError::BackendError(format!("Import failed: {}", error_text)))
+        }
     }
 }
 
 
+/// Dropdown validation over `range`, reading its options from `formula`
+fn list_validation(formula: String, range: &str, title: &str, message: &str) -> DataValidation {
+    let mut validation = DataValidation::default();
+    validation.set_type(DataValidationValues::List);
+    validation.set_formula1(formula);
+    validation.set_allow_blank(true);
+    validation.set_show_error_message(true);
+    validation.set_error_title(title);
+    validation.set_error_message(message);
+    validation.set_show_input_message(false);
+
+    let mut sequence = SequenceOfReferences::default();
+    sequence.set_sqref(range);
+    validation.set_sequence_of_references(sequence);
+    validation
+}
+
 pub fn generate_excel_template(path: &PathBuf) -> Result<(), AppError> {
     let mut book = new_file();
 
@@ -253,25 +1171,8 @@ pub fn generate_excel_template(path: &PathBuf) -> Result<(), AppError> {
     // Create data validations
     let mut data_validations = DataValidations::default();
 
-    // Helper function to create validation
-    let create_validation = |formula: String, range: &str, title: &str, message: &str| {
-        let mut validation = DataValidation::default();
-        validation.set_type(DataValidationValues::List);
-        validation.set_formula1(formula);
-        validation.set_allow_blank(true);
-        validation.set_show_error_message(true);
-        validation.set_error_title(title);
-        validation.set_error_message(message);
-        validation.set_show_input_message(false);
-
-        let mut sequence = SequenceOfReferences::default();
-        sequence.set_sqref(range);
-        validation.set_sequence_of_references(sequence);
-        validation
-    };
-
     // Add gender validation
-    data_validations.add_data_validation_list(create_validation(
+    data_validations.add_data_validation_list(list_validation(
         format!("{}!$A$1:$A${}", validation_sheet_name, gender_options.len()),
         "D2:D1000",
         "Invalid Gender",
@@ -279,7 +1180,7 @@ pub fn generate_excel_template(path: &PathBuf) -> Result<(), AppError> {
     ));
 
     // Add class validation
-    data_validations.add_data_validation_list(create_validation(
+    data_validations.add_data_validation_list(list_validation(
         format!("{}!$B$1:$B${}", validation_sheet_name, class_options.len()),
         "E2:E1000",
         "Invalid Class Level",
@@ -392,6 +1293,111 @@ pub fn generate_excel_template(path: &PathBuf) -> Result<(), AppError> {
 // }
 
 
+/// Column headers of the teaching-period register template
+pub const PERIOD_TEMPLATE_HEADERS: [&str; 6] = ["Date", "Teacher", "Subject", "ClassLevel", "Conducted", "Notes"];
+
+/// Label a teacher is listed under in the period template
+pub fn teacher_template_label(teacher: &Teacher) -> String {
+    format!("{} {}", teacher.name.first_name, teacher.name.surname)
+}
+
+/// Builds the weekly period register template. Teachers and subjects come from
+/// the backend so the dropdowns match what the import will accept.
+pub fn generate_period_template(path: &PathBuf, teachers: &[Teacher], subjects: &[Subject], date_format: &str) -> Result<(), AppError> {
+    let mut book = new_file();
+
+    let teacher_options: Vec<String> = teachers
+        .iter()
+        .filter(|teacher| teacher.is_active)
+        .map(teacher_template_label)
+        .collect();
+    let mut subject_options: Vec<String> = subjects.iter().map(|subject| subject.name.to_string()).collect();
+    subject_options.sort();
+    subject_options.dedup();
+    let class_options: Vec<String> = ClassLevel::ALL.iter().map(|c| c.to_string()).collect();
+    let conducted_options = ["Yes", "No"];
+
+    let validation_sheet_name = "ValidationLists";
+
+    book.new_sheet(validation_sheet_name)
+        .map_err(|e| AppError::Configuration(format!("Failed to create sheet: {}", e)))?;
+
+    {
+        let validation_sheet = book
+            .get_sheet_by_name_mut(validation_sheet_name)
+            .ok_or_else(|| AppError::Configuration("Validation sheet not found".to_string()))?;
+
+        validation_sheet.set_sheet_state(SheetStateValues::Hidden.get_value_string().to_string());
+
+        let columns: [(&str, Vec<&str>); 4] = [
+            ("A", teacher_options.iter().map(String::as_str).collect()),
+            ("B", subject_options.iter().map(String::as_str).collect()),
+            ("C", class_options.iter().map(String::as_str).collect()),
+            ("D", conducted_options.to_vec()),
+        ];
+        for (column, values) in columns {
+            for (i, value) in values.iter().enumerate() {
+                let coord = format!("{}{}", column, i + 1);
+                validation_sheet.get_cell_mut(coord.as_str()).set_value(*value);
+            }
+        }
+    }
+
+    let sheet = book
+        .get_sheet_by_name_mut("Sheet1")
+        .ok_or_else(|| AppError::Configuration("Main sheet 'Sheet1' not found".to_string()))?;
+
+    for (i, header) in PERIOD_TEMPLATE_HEADERS.iter().enumerate() {
+        sheet.get_cell_mut(((i + 1) as u32, 1)).set_value(*header);
+    }
+
+    // Dates are typed as text in the configured format so Excel doesn't reinterpret them
+    for row in 2..=2000u32 {
+        sheet.get_style_mut((1, row)).get_number_format_mut().set_format_code("@");
+    }
+    sheet.get_column_dimension_mut("A").set_width(14.0);
+    sheet.get_column_dimension_mut("B").set_width(24.0);
+    sheet.get_column_dimension_mut("C").set_width(16.0);
+    sheet.get_column_dimension_mut("D").set_width(20.0);
+    sheet.get_column_dimension_mut("F").set_width(30.0);
+
+    // Format hint on the header so the expected date layout is visible
+    sheet.get_cell_mut("A1").set_value(format!("Date ({})", date_format));
+
+    let mut data_validations = DataValidations::default();
+    let validations = [
+        ("A", teacher_options.len(), "B2:B2000", "Invalid Teacher", "Please select a teacher from the dropdown."),
+        ("B", subject_options.len(), "C2:C2000", "Invalid Subject", "Please select a subject from the dropdown."),
+        ("C", class_options.len(), "D2:D2000", "Invalid Class Level", "Please select a valid class level from the dropdown."),
+        ("D", conducted_options.len(), "E2:E2000", "Invalid Value", "Please select Yes or No."),
+    ];
+    for (column, count, range, title, message) in validations {
+        if count == 0 {
+            continue;
+        }
+        data_validations.add_data_validation_list(list_validation(
+            format!("{}!${}$1:${}${}", validation_sheet_name, column, column, count),
+            range,
+            title,
+            message,
+        ));
+    }
+    sheet.set_data_validations(data_validations);
+
+    writer::xlsx::write(&book, path)
+        .map_err(|e| AppError::IoError(format!("Failed to write file: {}", e)))
+}
+
+pub fn pick_path_and_generate_period_template(teachers: &[Teacher], subjects: &[Subject], date_format: &str) -> Result<PathBuf, AppError> {
+    let path = rfd::FileDialog::new()
+        .set_file_name("teaching_period_import_template.xlsx")
+        .add_filter("Excel", &["xlsx"])
+        .save_file()
+        .ok_or_else(|| AppError::IoError("No file path chosen.".into()))?;
+    generate_period_template(&path, teachers, subjects, date_format)?;
+    Ok(path)
+}
+
 pub fn pick_path_and_generate_excel_template() -> Result<(), AppError> {
     if let Some(path) = rfd::FileDialog::new()
         .set_file_name("student_import_template.xlsx")
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::app::AppSettings;
+use crate::error::AppError;
+
+/// Choices for `AppSettings::default_payment_method` and the payment dialog
+pub const PAYMENT_METHODS: [&str; 4] = ["Cash", "Mobile Money", "Bank Transfer", "Cheque"];
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Where the backup passphrase is kept in the OS keychain. It stays out of the
+/// settings file, which anyone at a shared PC can read and which may sit on the
+/// same USB drive as the archives it unlocks.
+const KEYCHAIN_SERVICE: &str = "Kyefa";
+const PASSPHRASE_ENTRY: &str = "backup-passphrase";
+
+/// Folder for everything the app keeps on this computer: `KYEFA_DATA_DIR`,
+/// or `kyefa-data` next to the app. It doesn't follow the working directory,
+/// so launching from a shortcut or a terminal finds the same data.
+pub fn data_dir() -> PathBuf {
+    dotenvy::dotenv().ok();
+    if let Ok(dir) = std::env::var("KYEFA_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("kyefa-data")))
+        .unwrap_or_else(|| PathBuf::from("kyefa-data"))
+}
+
+/// Settings saved on this computer. School-wide values in the file are the
+/// last copy fetched from the backend, used until it is reached again.
+/// A passphrase left in the file by an older version is moved to the keychain
+/// and the file rewritten without it.
+pub fn load() -> Result<AppSettings, AppError> {
+    let mut settings: AppSettings = match fs::read_to_string(data_dir().join(SETTINGS_FILE)) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| AppError::SerializationError(format!("Settings file is unreadable: {}", e)))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(AppSettings::default()),
+        Err(e) => return Err(AppError::IoError(format!("Failed to open settings file: {}", e))),
+    };
+    if let Some(passphrase) = settings.backup_passphrase.take() {
+        // Left in the file to move next time when the keychain can't take it
+        if save_backup_passphrase(&passphrase).is_ok() {
+            save(&settings)?;
+        }
+    }
+    Ok(settings)
+}
+
+pub fn save(settings: &AppSettings) -> Result<(), AppError> {
+    let dir = data_dir();
+    fs::create_dir_all(&dir).map_err(|e| AppError::IoError(format!("Failed to create data folder: {}", e)))?;
+    let contents = serde_json::to_string_pretty(settings)
+        .map_err(|e| AppError::SerializationError(format!("Failed to write settings: {}", e)))?;
+
+    // Write then rename, so a power cut mid-write leaves the previous settings intact
+    let path = dir.join(SETTINGS_FILE);
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, contents).map_err(|e| AppError::IoError(format!("Failed to save settings: {}", e)))?;
+    fs::rename(&temp_path, &path).map_err(|e| AppError::IoError(format!("Failed to save settings: {}", e)))
+}
+
+fn passphrase_entry() -> Result<keyring::Entry, AppError> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, PASSPHRASE_ENTRY)
+        .map_err(|e| AppError::Configuration(format!("The system keychain can't be used: {}", e)))
+}
+
+/// The backup passphrase saved on this computer, if one has been set
+pub fn load_backup_passphrase() -> Result<Option<String>, AppError> {
+    match passphrase_entry()?.get_password() {
+        Ok(passphrase) => Ok(Some(passphrase)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(AppError::Configuration(format!("Couldn't read the backup passphrase from the keychain: {}", e))),
+    }
+}
+
+pub fn save_backup_passphrase(passphrase: &str) -> Result<(), AppError> {
+    passphrase_entry()?
+        .set_password(passphrase)
+        .map_err(|e| AppError::Configuration(format!("Couldn't save the backup passphrase to the keychain: {}", e)))
+}
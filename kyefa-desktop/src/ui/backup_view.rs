@@ -0,0 +1,185 @@
+use iced::{Element, Length, Padding};
+use iced::widget::{column, row, text, text_input, button, container, scrollable, Space};
+use iced::alignment::Vertical;
+use chrono::Local;
+
+use crate::app::{BackupStatus, Message, DashboardMessage};
+use crate::backup::BackupEntry;
+use crate::ui::date_input::format_date;
+use crate::ui::palette;
+
+fn size_label(bytes: u64) -> String {
+    match bytes {
+        0..1_024 => format!("{} B", bytes),
+        1_024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1_024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn created_label(entry: &BackupEntry, date_format: &str) -> String {
+    let local = entry.created_at.with_timezone(&Local);
+    format!("{} {}", format_date(local.date_naive(), date_format), local.format("%H:%M"))
+}
+
+/// Archives in the backup folder, with backing up now and restoring one.
+/// `can_restore` is false for users who may not replace the server's data.
+pub fn backup_view<'a>(backups: &'a BackupStatus, date_format: &str, can_restore: bool) -> Element<'a, Message> {
+    let mut back_up = button(if backups.is_backing_up { "Backing up..." } else { "Back Up Now" })
+        .style(button::primary);
+    if !backups.is_backing_up {
+        back_up = back_up.on_press(Message::Dashboard(DashboardMessage::BackUpNow));
+    }
+
+    let header = row![
+        text("Backups").size(24),
+        Space::with_width(Length::Fill),
+        button("Close")
+            .style(button::text)
+            .on_press(Message::Dashboard(DashboardMessage::CloseBackups)),
+        back_up,
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center);
+
+    let folder = row![
+        text(format!("Folder: {}", backups.folder.display())).size(14),
+        Space::with_width(Length::Fill),
+        button("Change Folder...")
+            .style(button::secondary)
+            .on_press(Message::Dashboard(DashboardMessage::ChooseBackupFolder)),
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center);
+
+    let passphrase: Element<'_, Message> = if backups.encrypt {
+        let status = if backups.passphrase.is_some() {
+            "Backups are encrypted with the passphrase saved in this computer's keychain."
+        } else {
+            "Backups are encrypted, but no passphrase is set yet. Nothing will be backed up until one is."
+        };
+        column![
+            text(status).size(14),
+            row![
+                text_input("New passphrase", &backups.passphrase_input)
+                    .secure(true)
+                    .on_input(|s| Message::Dashboard(DashboardMessage::BackupPassphraseChanged(s)))
+                    .on_submit(Message::Dashboard(DashboardMessage::SaveBackupPassphrase))
+                    .width(Length::Fixed(300.0)),
+                button(if backups.passphrase.is_some() { "Change Passphrase" } else { "Set Passphrase" })
+                    .style(button::secondary)
+                    .on_press(Message::Dashboard(DashboardMessage::SaveBackupPassphrase)),
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center),
+        ]
+        .spacing(5)
+        .into()
+    } else {
+        text("Backups are not encrypted.").size(14).into()
+    };
+
+    let mut feedback = column![
+        text(format!("The newest {} backups are kept; older ones are deleted automatically.", backups.retention))
+            .size(12)
+            .style(|theme| iced::widget::text::secondary(theme)),
+    ]
+    .spacing(5);
+    if let Some(error) = &backups.error {
+        feedback = feedback.push(text(error).size(14).style(palette::error));
+    } else if let Some(message) = &backups.message {
+        feedback = feedback.push(text(message).size(14).style(palette::success));
+    }
+
+    if let Some(entry) = &backups.confirm_restore {
+        let mut restore = button(if backups.is_restoring { "Restoring..." } else { "Replace All Data" })
+            .style(button::danger);
+        if !backups.is_restoring {
+            restore = restore.on_press(Message::Dashboard(DashboardMessage::ConfirmRestore));
+        }
+        let mut details = column![
+            text(format!("Restore the backup from {}?", created_label(entry, date_format))).size(18),
+            text("The archive passed its checksum check. Restoring replaces this school's data on the server with this backup; changes made since it was taken will be lost.")
+                .size(14),
+        ]
+        .spacing(10);
+        if entry.encrypted {
+            details = details.push(
+                text_input("Passphrase this backup was made with", &backups.restore_passphrase)
+                    .secure(true)
+                    .on_input(|s| Message::Dashboard(DashboardMessage::RestorePassphraseChanged(s)))
+                    .width(Length::Fixed(300.0)),
+            );
+        }
+        let confirm = container(
+            details.push(
+                row![
+                    Space::with_width(Length::Fill),
+                    button("Cancel")
+                        .style(button::text)
+                        .on_press(Message::Dashboard(DashboardMessage::CancelRestore)),
+                    restore,
+                ]
+                .spacing(10),
+            ),
+        )
+        .padding(15)
+        .style(container::bordered_box)
+        .width(Length::Fill);
+
+        return container(column![header, folder, passphrase, feedback, confirm].spacing(15))
+            .padding(Padding::from(20))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+    }
+
+    let list: Element<'_, Message> = if backups.entries.is_empty() {
+        container(text("No backups in this folder yet.").size(16))
+            .center_x(Length::Fill)
+            .padding(40)
+            .into()
+    } else {
+        let rows = backups.entries.iter().fold(column![].spacing(10), |rows, entry| {
+            let mut actions = row![].spacing(10);
+            if can_restore {
+                actions = actions.push(
+                    button("Restore...")
+                        .style(button::secondary)
+                        .on_press(Message::Dashboard(DashboardMessage::SelectRestore(entry.clone()))),
+                );
+            }
+            let file_label = format!(
+                "{} ({}{})",
+                entry.file_name,
+                size_label(entry.size_bytes),
+                if entry.encrypted { ", encrypted" } else { "" },
+            );
+            rows.push(
+                container(
+                    row![
+                        column![
+                            text(created_label(entry, date_format)).size(16),
+                            text(file_label)
+                                .size(12)
+                                .style(|theme| iced::widget::text::secondary(theme)),
+                        ]
+                        .spacing(4)
+                        .width(Length::Fill),
+                        actions,
+                    ]
+                    .align_y(Vertical::Center),
+                )
+                .padding(15)
+                .style(container::bordered_box)
+                .width(Length::Fill),
+            )
+        });
+        scrollable(rows).height(Length::Fill).into()
+    };
+
+    container(column![header, folder, passphrase, feedback, list].spacing(15))
+        .padding(Padding::from(20))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
@@ -0,0 +1,87 @@
+use iced::{Element, Length};
+use iced::widget::{column, row, text, text_input, button, container, Space};
+use iced::alignment::Vertical;
+use kyefa_models::PasswordPolicy;
+
+use crate::app::{ChangePasswordForm, Message, DashboardMessage};
+use crate::ui::palette;
+
+/// Current password plus the new one twice. Without `can_cancel` (after an
+/// admin reset) the only way out is changing the password or logging out.
+pub fn change_password_view<'a>(
+    form: &'a ChangePasswordForm,
+    policy: &PasswordPolicy,
+    can_cancel: bool,
+) -> Element<'a, Message> {
+    let field = |label, placeholder: &str, value, on_input: fn(String) -> DashboardMessage| {
+        row![
+            text(label).width(Length::Fixed(160.0)),
+            text_input(placeholder, value)
+                .secure(true)
+                .on_input(move |s| Message::Dashboard(on_input(s)))
+                .on_submit(Message::Dashboard(DashboardMessage::SubmitPasswordChange))
+                .width(Length::Fixed(300.0)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center)
+    };
+
+    let mut rules = format!("Use at least {} characters.", policy.min_length);
+    if policy.history_count > 0 {
+        rules.push_str(&format!(" You can't reuse any of your last {} passwords.", policy.history_count));
+    }
+
+    let mut fields = column![
+        text("Change Password").size(24),
+    ]
+    .spacing(15);
+    if !can_cancel {
+        fields = fields.push(
+            text("Your password was reset by an admin. Choose a new one to carry on.")
+                .size(14)
+                .style(palette::warning),
+        );
+    }
+    fields = fields
+        .push(field("Current Password:", "Current or temporary password", &form.current_password, DashboardMessage::UpdateCurrentPassword))
+        .push(field("New Password:", "New password", &form.new_password, DashboardMessage::UpdateNewPassword))
+        .push(field("Confirm Password:", "New password again", &form.confirm_password, DashboardMessage::UpdateConfirmPassword))
+        .push(text(rules).size(12).style(|theme| iced::widget::text::secondary(theme)))
+        .push(
+            text("You'll be logged out everywhere and asked to log in with the new password.")
+                .size(12)
+                .style(|theme| iced::widget::text::secondary(theme)),
+        );
+
+    if let Some(error) = &form.error {
+        fields = fields.push(text(error.to_string()).size(14).style(palette::error));
+    }
+
+    let mut save = button(if form.is_saving { "Saving..." } else { "Change Password" }).style(button::primary);
+    if !form.is_saving {
+        save = save.on_press(Message::Dashboard(DashboardMessage::SubmitPasswordChange));
+    }
+    let dismiss = if can_cancel {
+        button("Cancel")
+            .style(button::text)
+            .on_press(Message::Dashboard(DashboardMessage::CancelPasswordChange))
+    } else {
+        button("Log Out")
+            .style(button::text)
+            .on_press(Message::Logout)
+    };
+
+    container(
+        fields.push(
+            row![
+                Space::with_width(Length::Fill),
+                dismiss,
+                save,
+            ]
+            .spacing(10),
+        )
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .into()
+}
@@ -0,0 +1,288 @@
+use std::f32::consts::PI;
+
+use iced::{mouse, Color, Pixels, Point, Radians, Rectangle, Renderer, Size, Theme};
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::canvas::{self, path::Arc, Frame, Geometry, Path, Stroke, Text};
+
+use crate::ui::palette::{self, Palette};
+
+const MARGIN_LEFT: f32 = 56.0;
+const MARGIN_RIGHT: f32 = 12.0;
+const MARGIN_TOP: f32 = 28.0;
+const MARGIN_BOTTOM: f32 = 28.0;
+const GRID_LINES: usize = 4;
+
+/// A named run of values drawn in one colour of the theme's palette
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub label: String,
+    pub color: fn(&Palette) -> Color,
+    pub values: Vec<f64>,
+}
+
+/// Series plotted as lines over shared x-axis labels
+#[derive(Debug, Clone)]
+pub struct LineChart {
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+}
+
+/// Bars per category, grouped side by side when there is more than one series
+#[derive(Debug, Clone)]
+pub struct BarChart {
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DonutSegment {
+    pub label: String,
+    pub value: f64,
+    pub color: fn(&Palette) -> Color,
+}
+
+#[derive(Debug, Clone)]
+pub struct DonutChart {
+    pub segments: Vec<DonutSegment>,
+}
+
+/// Short axis label, e.g. 12500.0 -> "12.5k"
+fn compact(value: f64) -> String {
+    if value.abs() >= 1_000_000.0 {
+        format!("{:.1}m", value / 1_000_000.0)
+    } else if value.abs() >= 1_000.0 {
+        format!("{:.1}k", value / 1_000.0)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+fn truncate_label(label: &str, max_chars: usize) -> String {
+    if label.chars().count() > max_chars {
+        let mut short: String = label.chars().take(max_chars.saturating_sub(1)).collect();
+        short.push('…');
+        short
+    } else {
+        label.to_string()
+    }
+}
+
+fn label(frame: &mut Frame, content: String, position: Point, color: Color, horizontal: Horizontal, vertical: Vertical) {
+    frame.fill_text(Text {
+        content,
+        position,
+        color,
+        size: Pixels(11.0),
+        horizontal_alignment: horizontal,
+        vertical_alignment: vertical,
+        ..Text::default()
+    });
+}
+
+fn plot_area(size: Size) -> Rectangle {
+    Rectangle {
+        x: MARGIN_LEFT,
+        y: MARGIN_TOP,
+        width: (size.width - MARGIN_LEFT - MARGIN_RIGHT).max(1.0),
+        height: (size.height - MARGIN_TOP - MARGIN_BOTTOM).max(1.0),
+    }
+}
+
+/// Rounds the top of the y-axis up so gridlines land on readable values.
+fn axis_max(series: &[Series]) -> f64 {
+    let max = series
+        .iter()
+        .flat_map(|s| s.values.iter().copied())
+        .fold(0.0_f64, f64::max);
+    if max <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10_f64.powf(max.log10().floor());
+    (max / magnitude).ceil() * magnitude
+}
+
+fn draw_axes(frame: &mut Frame, area: Rectangle, max: f64, text_color: Color) {
+    let grid_color = Color { a: 0.15, ..text_color };
+    for step in 0..=GRID_LINES {
+        let fraction = step as f32 / GRID_LINES as f32;
+        let y = area.y + area.height * (1.0 - fraction);
+        frame.stroke(
+            &Path::line(Point::new(area.x, y), Point::new(area.x + area.width, y)),
+            Stroke::default().with_color(grid_color).with_width(1.0),
+        );
+        label(
+            frame,
+            compact(max * fraction as f64),
+            Point::new(area.x - 6.0, y),
+            text_color,
+            Horizontal::Right,
+            Vertical::Center,
+        );
+    }
+}
+
+fn draw_legend<'a>(frame: &mut Frame, entries: impl Iterator<Item = (&'a str, Color)>, text_color: Color) {
+    let mut x = MARGIN_LEFT;
+    for (name, color) in entries {
+        frame.fill_rectangle(Point::new(x, 8.0), Size::new(10.0, 10.0), color);
+        label(frame, name.to_string(), Point::new(x + 14.0, 13.0), text_color, Horizontal::Left, Vertical::Center);
+        x += 24.0 + name.chars().count() as f32 * 6.5;
+    }
+}
+
+fn draw_empty(frame: &mut Frame, text_color: Color) {
+    label(
+        frame,
+        "No data for this period".to_string(),
+        frame.center(),
+        Color { a: 0.6, ..text_color },
+        Horizontal::Center,
+        Vertical::Center,
+    );
+}
+
+impl<Message> canvas::Program<Message> for LineChart {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let text_color = theme.palette().text;
+        let palette = palette::of(theme);
+
+        if self.labels.is_empty() {
+            draw_empty(&mut frame, text_color);
+            return vec![frame.into_geometry()];
+        }
+
+        let area = plot_area(bounds.size());
+        let max = axis_max(&self.series);
+        draw_axes(&mut frame, area, max, text_color);
+        draw_legend(&mut frame, self.series.iter().map(|s| (s.label.as_str(), (s.color)(&palette))), text_color);
+
+        let step = if self.labels.len() > 1 { area.width / (self.labels.len() - 1) as f32 } else { 0.0 };
+        let x_at = |i: usize| if self.labels.len() > 1 { area.x + step * i as f32 } else { area.x + area.width / 2.0 };
+        let y_at = |value: f64| area.y + area.height * (1.0 - (value / max) as f32);
+
+        // Skip labels so they don't overlap when a term has many weeks
+        let label_every = ((self.labels.len() as f32 * 40.0) / area.width).ceil().max(1.0) as usize;
+        for (i, name) in self.labels.iter().enumerate().step_by(label_every) {
+            label(&mut frame, name.clone(), Point::new(x_at(i), area.y + area.height + 6.0), text_color, Horizontal::Center, Vertical::Top);
+        }
+
+        for series in &self.series {
+            let points: Vec<Point> = series.values.iter().enumerate().map(|(i, v)| Point::new(x_at(i), y_at(*v))).collect();
+            let line = Path::new(|builder| {
+                if let Some(first) = points.first() {
+                    builder.move_to(*first);
+                    for point in &points[1..] {
+                        builder.line_to(*point);
+                    }
+                }
+            });
+            frame.stroke(&line, Stroke::default().with_color((series.color)(&palette)).with_width(2.0));
+            for point in &points {
+                frame.fill(&Path::circle(*point, 3.0), (series.color)(&palette));
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+impl<Message> canvas::Program<Message> for BarChart {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let text_color = theme.palette().text;
+        let palette = palette::of(theme);
+
+        if self.labels.is_empty() || self.series.is_empty() {
+            draw_empty(&mut frame, text_color);
+            return vec![frame.into_geometry()];
+        }
+
+        let area = plot_area(bounds.size());
+        let max = axis_max(&self.series);
+        draw_axes(&mut frame, area, max, text_color);
+        if self.series.len() > 1 {
+            draw_legend(&mut frame, self.series.iter().map(|s| (s.label.as_str(), (s.color)(&palette))), text_color);
+        }
+
+        let group_width = area.width / self.labels.len() as f32;
+        let bar_width = group_width * 0.7 / self.series.len() as f32;
+        let max_label_chars = ((group_width / 6.5) as usize).max(3);
+
+        for (i, name) in self.labels.iter().enumerate() {
+            let group_x = area.x + group_width * i as f32 + group_width * 0.15;
+            for (j, series) in self.series.iter().enumerate() {
+                let value = series.values.get(i).copied().unwrap_or(0.0);
+                let height = area.height * (value / max) as f32;
+                frame.fill_rectangle(
+                    Point::new(group_x + bar_width * j as f32, area.y + area.height - height),
+                    Size::new(bar_width - 2.0, height),
+                    (series.color)(&palette),
+                );
+            }
+            label(
+                &mut frame,
+                truncate_label(name, max_label_chars),
+                Point::new(area.x + group_width * (i as f32 + 0.5), area.y + area.height + 6.0),
+                text_color,
+                Horizontal::Center,
+                Vertical::Top,
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+impl<Message> canvas::Program<Message> for DonutChart {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let text_color = theme.palette().text;
+        let palette = palette::of(theme);
+
+        let total: f64 = self.segments.iter().map(|s| s.value.max(0.0)).sum();
+        if total <= 0.0 {
+            draw_empty(&mut frame, text_color);
+            return vec![frame.into_geometry()];
+        }
+
+        let legend: Vec<(String, Color)> = self.segments
+            .iter()
+            .map(|s| (format!("{} {:.0}%", s.label, s.value / total * 100.0), (s.color)(&palette)))
+            .collect();
+        draw_legend(&mut frame, legend.iter().map(|(name, color)| (name.as_str(), *color)), text_color);
+
+        let area = plot_area(bounds.size());
+        let center = Point::new(bounds.width / 2.0, area.y + area.height / 2.0);
+        let outer = (area.height.min(bounds.width - 20.0) / 2.0).max(10.0);
+        let thickness = outer * 0.35;
+
+        let mut start = -PI / 2.0;
+        for segment in &self.segments {
+            let sweep = (segment.value.max(0.0) / total) as f32 * 2.0 * PI;
+            if sweep <= 0.0 {
+                continue;
+            }
+            let arc = Path::new(|builder| {
+                builder.arc(Arc {
+                    center,
+                    radius: outer - thickness / 2.0,
+                    start_angle: Radians(start),
+                    end_angle: Radians(start + sweep),
+                });
+            });
+            frame.stroke(&arc, Stroke::default().with_color((segment.color)(&palette)).with_width(thickness));
+            start += sweep;
+        }
+
+        label(&mut frame, compact(total), center, text_color, Horizontal::Center, Vertical::Center);
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -1,7 +1,7 @@
 use iced::{Element, Length, Color, Alignment, Padding};
 use iced::widget::{
     column, row, text, button, container, 
-    Space,
+    pick_list, Space,
 };
 use iced::alignment::{Vertical};
 
@@ -13,11 +13,30 @@ use crate::ui::{
     teaching_period_view, 
     payment_tracking_view, 
     reports_analytics_view, 
-    user_access_view
+    user_access_view,
+    change_password_view,
+    lock_screen_view,
+    teacher_portal_view,
+    period_review_view,
+    sync_status_view,
+    backup_view,
+    settings_view,
 };
 use kyefa_models::UserRole;
 
 pub fn dashboard_view(state: &DashboardState) -> Element<'_, Message> {
+    if let Some(lock) = &state.lock {
+        let user_name = format!("{} {}", state.active_user.name.first_name, state.active_user.name.surname);
+        return lock_screen_view::lock_screen_view(lock, user_name);
+    }
+    if let (true, Some(form)) = (state.must_change_password, &state.change_password) {
+        return container(change_password_view::change_password_view(form, &state.password_policy, false))
+            .max_width(640)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into();
+    }
+
     let logout_button = row![
         button("Logout") 
             .style(move |theme, status| {
@@ -32,11 +51,14 @@ pub fn dashboard_view(state: &DashboardState) -> Element<'_, Message> {
 
     let menu_items_data = vec![
         ("Dashboard", DashboardMessage::NavigateToHome, DashboardView::Home),
+        ("My Teaching", DashboardMessage::NavigateToTeacherPortal, DashboardView::TeacherPortal),
         ("Student Management", DashboardMessage::NavigateToStudentManager, DashboardView::StudentManager),
         ("Teaching Periods", DashboardMessage::NavigateToTeachingPeriodManager, DashboardView::TeachingPeriodManager),
+        ("Period Review", DashboardMessage::NavigateToPeriodReview, DashboardView::PeriodReview),
         ("Payment Tracking", DashboardMessage::NavigateToPaymentTracking, DashboardView::PaymentTrackingManager),
         ("Reports & Analytics", DashboardMessage::NavigateToReportsAnalytics, DashboardView::ReportsAnalytics),
         ("User Access", DashboardMessage::NavigateToUserAccessManager, DashboardView::UserAccessManager),
+        ("Settings", DashboardMessage::NavigateToSettings, DashboardView::Settings),
     ];
 
     let menu_items = menu_items_data
@@ -44,6 +66,12 @@ pub fn dashboard_view(state: &DashboardState) -> Element<'_, Message> {
         .filter(|(label, _, _)| {
             if *label == "User Access" {
                 matches!(state.active_user.role, UserRole::Admin)
+            } else if *label == "My Teaching" {
+                matches!(state.active_user.role, UserRole::Teacher)
+            } else if *label == "Period Review" {
+                state.active_user.role.can_review_periods()
+            } else if *label == "Reports & Analytics" {
+                state.active_user.role.can_view_reports()
             } else {
                 true
             }
@@ -64,6 +92,9 @@ pub fn dashboard_view(state: &DashboardState) -> Element<'_, Message> {
                 DashboardView::PaymentTrackingManager => ui::helper::payment(20.0, 20.0, icon_color),
                 DashboardView::ReportsAnalytics => ui::helper::report(20.0, 20.0, icon_color),
                 DashboardView::UserAccessManager => ui::helper::access(20.0, 20.0, icon_color),
+                DashboardView::TeacherPortal => ui::helper::period(20.0, 20.0, icon_color),
+                DashboardView::PeriodReview => ui::helper::period(20.0, 20.0, icon_color),
+                DashboardView::Settings => ui::helper::settings(20.0, 20.0, icon_color),
             };
             
             let menu_item = button(
@@ -101,15 +132,40 @@ pub fn dashboard_view(state: &DashboardState) -> Element<'_, Message> {
     ]
     .spacing(5);
 
+    // Switching starts the dashboard afresh for the chosen school
+    let school: Element<'_, Message> = if state.schools.len() > 1 {
+        pick_list(
+            state.schools.as_slice(),
+            state.active_school.clone(),
+            |school| Message::Dashboard(DashboardMessage::SwitchSchool(school)),
+        )
+        .placeholder("Choose a school")
+        .width(Length::Fill)
+        .into()
+    } else {
+        text(state.active_school.as_ref().map(|school| school.name.clone()).unwrap_or_default())
+            .size(12)
+            .style(|theme| iced::widget::text::secondary(theme))
+            .into()
+    };
 
-    let user_menu = column![
+    let mut user_menu = column![
+        school,
         user_info,
-        change_password_button,
-        logout_button
-    ]
-    .spacing(10)
-    .padding(10)
-    .align_x(Alignment::Start);
+        sync_status_view::sync_indicator(&state.sync),
+    ];
+    if matches!(state.active_user.role, UserRole::Admin) {
+        user_menu = user_menu.push(
+            button("Backups").on_press(Message::Dashboard(DashboardMessage::ShowBackups))
+        );
+    }
+    let user_menu = user_menu
+        .push(button("Lock Screen").on_press(Message::Dashboard(DashboardMessage::LockScreen)))
+        .push(change_password_button)
+        .push(logout_button)
+        .spacing(10)
+        .padding(10)
+        .align_x(Alignment::Start);
 
     let app_brand = container(
         row![text("Kyefa").size(25),]
@@ -131,24 +187,43 @@ pub fn dashboard_view(state: &DashboardState) -> Element<'_, Message> {
     .style(container::rounded_box)
     .center_y(Length::Fill);
 
-    let main_content: Element<'_, Message> = match state.current_view {
-        DashboardView::Home => home_view::home_view(state).into(),
-        DashboardView::StudentManager => {
-            student_manager_view::student_manager_view(&state.student_manager)
-                .map(|msg| Message::Dashboard(msg))
-        },
-        DashboardView::TeachingPeriodManager => {
-            teaching_period_view::teaching_period_view(&state.teaching_period_manager)
-        },
-        DashboardView::PaymentTrackingManager => {
-            payment_tracking_view::payment_tracking_view(&state.payment_tracking)
-        },
-        DashboardView::ReportsAnalytics => {
-            reports_analytics_view::reports_analytics_view(&state.reports_analytics)
-        },
-        DashboardView::UserAccessManager => {
-            user_access_view::user_access_view(&state.user_access_manager)
-        },
+    let main_content: Element<'_, Message> = if let Some(form) = &state.change_password {
+        change_password_view::change_password_view(form, &state.password_policy, true)
+    } else if state.sync.show_panel {
+        sync_status_view::sync_status_view(&state.sync, &state.student_manager.date_format, &state.currency_symbol)
+    } else if state.backups.show_panel {
+        let can_restore = matches!(state.active_user.role, UserRole::Admin);
+        backup_view::backup_view(&state.backups, &state.student_manager.date_format, can_restore)
+    } else {
+        match state.current_view {
+            DashboardView::Home => home_view::home_view(state).into(),
+            DashboardView::StudentManager => {
+                student_manager_view::student_manager_view(&state.student_manager)
+                    .map(|msg| Message::Dashboard(msg))
+            },
+            DashboardView::TeachingPeriodManager => {
+                teaching_period_view::teaching_period_view(&state.teaching_period_manager)
+            },
+            DashboardView::PaymentTrackingManager => {
+                payment_tracking_view::payment_tracking_view(&state.payment_tracking)
+            },
+            DashboardView::ReportsAnalytics => {
+                reports_analytics_view::reports_analytics_view(&state.reports_analytics)
+            },
+            DashboardView::UserAccessManager => {
+                user_access_view::user_access_view(&state.user_access_manager, &state.student_manager.date_format)
+            },
+            DashboardView::TeacherPortal => {
+                teacher_portal_view::teacher_portal_view(&state.teacher_portal)
+            },
+            DashboardView::PeriodReview => {
+                period_review_view::period_review_view(&state.period_review)
+            },
+            DashboardView::Settings => match &state.settings_form {
+                Some(form) => settings_view::settings_view(form),
+                None => text("Loading settings...").into(),
+            },
+        }
     };
 
     row![sidebar, main_content]
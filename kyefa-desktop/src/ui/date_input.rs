@@ -0,0 +1,316 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use iced::{Element, Length};
+use iced::widget::{column, row, text, text_input, pick_list, button};
+use iced::alignment::Vertical;
+use kyefa_models::Term;
+use crate::ui::palette;
+
+/// Date pattern used until the user's settings are applied
+pub const DEFAULT_DATE_FORMAT: &str = "DD/MM/YYYY";
+
+/// Patterns offered in Settings
+pub const DATE_FORMATS: [&str; 3] = ["DD/MM/YYYY", "MM/DD/YYYY", "YYYY-MM-DD"];
+
+// Shared with the backend, which checks a school's date format before storing it
+pub use kyefa_models::chrono_date_format as chrono_format;
+
+/// Formats `date` with `pattern`, or with the default pattern when `pattern`
+/// isn't a valid one.
+pub fn format_date(date: NaiveDate, pattern: &str) -> String {
+    let format = chrono_format(pattern)
+        .or_else(|_| chrono_format(DEFAULT_DATE_FORMAT))
+        .unwrap_or_default();
+    date.format(&format).to_string()
+}
+
+/// Parses `value` against `pattern`; an empty value means no date was entered.
+pub fn parse_date(value: &str, pattern: &str) -> Result<Option<NaiveDate>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(value, &chrono_format(pattern)?)
+        .map(Some)
+        .map_err(|_| format!("'{}' is not a valid date in {} format.", value, pattern))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatePreset {
+    Today,
+    ThisWeek,
+    ThisMonth,
+    ThisTerm,
+    LastTerm,
+}
+
+impl DatePreset {
+    pub const ALL: [DatePreset; 5] = [
+        DatePreset::Today,
+        DatePreset::ThisWeek,
+        DatePreset::ThisMonth,
+        DatePreset::ThisTerm,
+        DatePreset::LastTerm,
+    ];
+
+    /// Resolves the preset to an inclusive date range, or `None` when the term it
+    /// refers to isn't known.
+    pub fn range(&self, today: NaiveDate, terms: &[Term]) -> Option<(NaiveDate, NaiveDate)> {
+        match self {
+            DatePreset::Today => Some((today, today)),
+            DatePreset::ThisWeek => {
+                let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+                Some((monday, monday + Duration::days(6)))
+            },
+            DatePreset::ThisMonth => {
+                let first = today.with_day(1)?;
+                let next_month = if first.month() == 12 {
+                    NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)?
+                } else {
+                    NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)?
+                };
+                Some((first, next_month - Duration::days(1)))
+            },
+            DatePreset::ThisTerm => {
+                let term = current_term(today, terms)?;
+                Some((term.start_date.date(), term.end_date.date()))
+            },
+            DatePreset::LastTerm => {
+                let current_start = current_term(today, terms)
+                    .map(|term| term.start_date)
+                    .unwrap_or_else(|| today.and_hms_opt(0, 0, 0).unwrap_or_default());
+                let term = terms
+                    .iter()
+                    .filter(|term| term.end_date < current_start)
+                    .max_by_key(|term| term.end_date)?;
+                Some((term.start_date.date(), term.end_date.date()))
+            },
+        }
+    }
+}
+
+/// The active term, falling back to whichever term contains `today`.
+pub fn current_term(today: NaiveDate, terms: &[Term]) -> Option<&Term> {
+    terms
+        .iter()
+        .find(|term| term.is_active)
+        .or_else(|| terms.iter().find(|term| {
+            term.start_date.date() <= today && today <= term.end_date.date()
+        }))
+}
+
+impl std::fmt::Display for DatePreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatePreset::Today => write!(f, "Today"),
+            DatePreset::ThisWeek => write!(f, "This Week"),
+            DatePreset::ThisMonth => write!(f, "This Month"),
+            DatePreset::ThisTerm => write!(f, "This Term"),
+            DatePreset::LastTerm => write!(f, "Last Term"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum DateRangeMessage {
+    FromChanged(String),
+    ToChanged(String),
+    PresetSelected(DatePreset),
+    Clear,
+}
+
+/// From/to date inputs shared by the filter bars
+#[derive(Debug, Clone)]
+pub struct DateRangeInput {
+    pub from: String,
+    pub to: String,
+    pub preset: Option<DatePreset>,
+    pub format: String,
+}
+
+impl Default for DateRangeInput {
+    fn default() -> Self {
+        Self {
+            from: String::new(),
+            to: String::new(),
+            preset: None,
+            format: DEFAULT_DATE_FORMAT.to_string(),
+        }
+    }
+}
+
+impl DateRangeInput {
+    /// Switches the display format, rewriting any dates already entered.
+    pub fn set_format(&mut self, format: &str) {
+        if let Ok((from, to)) = self.range() {
+            self.from = from.map(|d| format_date(d, format)).unwrap_or_default();
+            self.to = to.map(|d| format_date(d, format)).unwrap_or_default();
+        }
+        self.format = format.to_string();
+    }
+
+    pub fn update(&mut self, message: DateRangeMessage, terms: &[Term]) {
+        match message {
+            DateRangeMessage::FromChanged(value) => {
+                self.from = value;
+                self.preset = None;
+            },
+            DateRangeMessage::ToChanged(value) => {
+                self.to = value;
+                self.preset = None;
+            },
+            DateRangeMessage::PresetSelected(preset) => {
+                self.preset = Some(preset);
+                match preset.range(Local::now().date_naive(), terms) {
+                    Some((from, to)) => {
+                        self.from = format_date(from, &self.format);
+                        self.to = format_date(to, &self.format);
+                    },
+                    None => {
+                        self.from.clear();
+                        self.to.clear();
+                    },
+                }
+            },
+            DateRangeMessage::Clear => {
+                self.from.clear();
+                self.to.clear();
+                self.preset = None;
+            },
+        }
+    }
+
+    /// The parsed range, rejecting malformed dates and a start after the end.
+    pub fn range(&self) -> Result<(Option<NaiveDate>, Option<NaiveDate>), String> {
+        let from = parse_date(&self.from, &self.format)?;
+        let to = parse_date(&self.to, &self.format)?;
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err("From date must be on or before the To date.".to_string());
+            }
+        }
+        Ok((from, to))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.from.trim().is_empty() && self.to.trim().is_empty()
+    }
+}
+
+pub fn date_range_input<'a, Message: Clone + 'a>(
+    input: &'a DateRangeInput,
+    on_message: impl Fn(DateRangeMessage) -> Message + Copy + 'a,
+) -> Element<'a, Message> {
+    let inputs = row![
+        text_input(&format!("From ({})", input.format), &input.from)
+            .on_input(move |s| on_message(DateRangeMessage::FromChanged(s)))
+            .width(Length::Fixed(150.0)),
+        text_input(&format!("To ({})", input.format), &input.to)
+            .on_input(move |s| on_message(DateRangeMessage::ToChanged(s)))
+            .width(Length::Fixed(150.0)),
+        pick_list(
+            DatePreset::ALL.to_vec(),
+            input.preset,
+            move |preset| on_message(DateRangeMessage::PresetSelected(preset)),
+        )
+        .placeholder("Preset...")
+        .width(Length::Fixed(130.0)),
+        button("Clear")
+            .style(button::text)
+            .on_press(on_message(DateRangeMessage::Clear)),
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center);
+
+    match input.range() {
+        Err(error) => column![
+            inputs,
+            text(error).size(12).style(palette::error),
+        ]
+        .spacing(4)
+        .into(),
+        Ok(_) => inputs.into(),
+    }
+}
+
+/// A single date field validated against the configured format
+#[derive(Debug, Clone)]
+pub struct DateInput {
+    pub value: String,
+    pub format: String,
+}
+
+impl Default for DateInput {
+    fn default() -> Self {
+        Self {
+            value: String::new(),
+            format: DEFAULT_DATE_FORMAT.to_string(),
+        }
+    }
+}
+
+impl DateInput {
+    pub fn set_format(&mut self, format: &str) {
+        if let Ok(Some(date)) = self.date() {
+            self.value = format_date(date, format);
+        }
+        self.format = format.to_string();
+    }
+
+    pub fn date(&self) -> Result<Option<NaiveDate>, String> {
+        parse_date(&self.value, &self.format)
+    }
+}
+
+pub fn date_input<'a, Message: Clone + 'a>(
+    input: &'a DateInput,
+    on_change: impl Fn(String) -> Message + 'a,
+) -> Element<'a, Message> {
+    let field = text_input(&input.format, &input.value)
+        .on_input(on_change)
+        .width(Length::Fixed(150.0));
+
+    match input.date() {
+        Err(error) => column![
+            field,
+            text(error).size(12).style(palette::error),
+        ]
+        .spacing(4)
+        .into(),
+        Ok(_) => field.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_the_offered_patterns() {
+        for pattern in DATE_FORMATS {
+            assert!(chrono_format(pattern).is_ok(), "{}", pattern);
+        }
+        assert_eq!(chrono_format("YYYY-MM-DD").as_deref(), Ok("%Y-%m-%d"));
+    }
+
+    #[test]
+    fn rejects_patterns_that_are_not_dates() {
+        for pattern in ["", "DD/MM", "DD/MM/YY", "DD/DD/YYYY", "%H:%M DD/MM/YYYY", "DD/MM/YYYY%"] {
+            assert!(chrono_format(pattern).is_err(), "{}", pattern);
+        }
+    }
+
+    #[test]
+    fn formats_with_the_default_when_the_pattern_is_invalid() {
+        let date = NaiveDate::from_ymd_opt(2026, 9, 7).unwrap();
+        assert_eq!(format_date(date, "MM/DD/YYYY"), "09/07/2026");
+        assert_eq!(format_date(date, "%Y"), "07/09/2026");
+    }
+
+    #[test]
+    fn parses_in_the_given_pattern() {
+        let date = NaiveDate::from_ymd_opt(2026, 9, 7);
+        assert_eq!(parse_date("07/09/2026", "DD/MM/YYYY"), Ok(date));
+        assert_eq!(parse_date("  ", "DD/MM/YYYY"), Ok(None));
+        assert!(parse_date("2026-09-07", "DD/MM/YYYY").is_err());
+    }
+}
@@ -121,3 +121,20 @@ pub fn report<'a>(w: f32, h: f32, color: Option<Color>) -> Svg<'a> {
     
     svg_widget
 }
+
+pub fn settings<'a>(w: f32, h: f32, color: Option<Color>) -> Svg<'a> {
+    let mut svg_widget = svg(svg::Handle::from_path(format!(
+        "{}/assets/icons/settings_24dp_1F1F1F_FILL1_wght400_GRAD0_opsz24.svg",
+        env!("CARGO_MANIFEST_DIR")
+    )))
+    .height(h)
+    .width(w);
+    
+    if let Some(color) = color {
+        svg_widget = svg_widget.style(move |_theme, _status| svg::Style {
+            color: Some(color),
+        });
+    }
+    
+    svg_widget
+}
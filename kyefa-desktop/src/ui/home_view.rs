@@ -1,27 +1,179 @@
-use iced::{Element, Length};
-use iced::widget::{column, text, Space};
+use iced::{Element, Length, Alignment, Padding};
+use iced::widget::{column, row, text, button, container, scrollable, Space, Row};
+use iced::alignment::Vertical;
+use chrono::Local;
 
-use crate::app::{Message, DashboardState};
+use crate::app::{Message, DashboardState, DashboardMessage, HomeMessage};
+use crate::ui::palette;
+use crate::ui::date_input::format_date;
+use kyefa_models::{format_money, UserRole, HomeSummary, ActivityType, RecentActivity};
 
 pub fn home_view(state: &DashboardState) -> Element<'_, Message> {
+    let home = &state.home;
+
+    let header = row![
+        column![
+            text(format!("Welcome, {} {}",
+                state.active_user.name.first_name,
+                state.active_user.name.surname))
+                .size(40),
+            text(match home.summary.as_ref().and_then(|s| s.active_term_name.as_deref()) {
+                Some(term) => format!("Active term: {}", term),
+                None => "No active term".to_string(),
+            })
+            .size(16)
+            .style(|theme| iced::widget::text::secondary(theme)),
+        ]
+        .spacing(5),
+        Space::with_width(Length::Fill),
+        button("Refresh")
+            .style(button::secondary)
+            .on_press(Message::Dashboard(DashboardMessage::Home(HomeMessage::LoadHome))),
+    ]
+    .align_y(Vertical::Center);
+
+    let error_banner = match &home.error_message {
+        Some(error) => row![
+            text(error).size(14).style(palette::error)
+        ],
+        None => row![],
+    };
+
+    let cards: Element<'_, Message> = match &home.summary {
+        Some(summary) => role_cards(&state.active_user.role, summary, &state.currency_symbol),
+        None if home.is_loading => text("Loading dashboard...").size(16).into(),
+        None => text("Dashboard figures are unavailable").size(16)
+            .style(|theme| iced::widget::text::secondary(theme))
+            .into(),
+    };
+
     column![
-        text(format!("Welcome, {} {}", 
-            state.active_user.name.first_name, 
-            state.active_user.name.surname))
-            .size(40),
-        Space::with_height(Length::Fixed(20.0)),
-        text("Here's a summary of your recent activities and key metrics:").size(20),
+        header,
+        error_banner,
         Space::with_height(Length::Fixed(20.0)),
-        text("- Total Students: 150").size(18),
-        text("- Active Teachers: 12").size(18),
-        text("- Upcoming Payouts: GHS 5,000.00").size(18),
+        cards,
         Space::with_height(Length::Fixed(30.0)),
-        text("Recent Changes:").size(20),
-        Space::with_height(Length::Fixed(10.0)),
-        text("- Added new student: John Doe").size(18),
-        text("- Updated Mr. Kobi's period share").size(18),
+        text("Recent Activity").size(20),
+        activity_feed(&home.recent_activities, &state.date_format),
     ]
     .padding(20)
     .spacing(10)
     .into()
 }
+
+fn role_cards<'a>(role: &UserRole, summary: &'a HomeSummary, currency_symbol: &str) -> Element<'a, Message> {
+    let periods_card = kpi_card(
+        "Periods Conducted This Week",
+        summary.periods_this_week.to_string(),
+        Some(format!("of {} logged", summary.logged_periods_this_week)),
+    );
+
+    let cards: Row<'a, Message> = match role {
+        UserRole::Teacher => match &summary.teacher_summary {
+            Some(mine) => row![
+                kpi_card("My Periods This Week", mine.periods_this_week.to_string(), None),
+                kpi_card("My Periods This Term", mine.periods_this_term.to_string(), None),
+                kpi_card("My Projected Earnings", format_money(mine.projected_earnings, currency_symbol), Some("under the active share rules".to_string())),
+            ],
+            None => row![
+                kpi_card("My Periods This Term", "-".to_string(), Some("Account is not linked to a teacher record".to_string())),
+            ],
+        },
+        UserRole::Admin | UserRole::CommitteeMember | UserRole::Headteacher => row![
+            kpi_card("Total Students", summary.total_students.to_string(), None),
+            kpi_card("Active Teachers", summary.active_teachers.to_string(), None),
+            kpi_card(
+                "Collection Rate",
+                format!("{:.1}%", summary.collection_rate),
+                Some(format!("{} of {}", format_money(summary.total_revenue, currency_symbol), format_money(summary.expected_revenue, currency_symbol))),
+            ),
+            periods_card,
+        ],
+        UserRole::DataEntry | UserRole::Staff => row![
+            kpi_card("Total Students", summary.total_students.to_string(), None),
+            kpi_card("Active Teachers", summary.active_teachers.to_string(), None),
+            periods_card,
+        ],
+    };
+
+    cards.spacing(15).into()
+}
+
+fn kpi_card<'a>(title: &'a str, value: String, detail: Option<String>) -> Element<'a, Message> {
+    let mut content = column![
+        text(title).size(12)
+            .style(|theme| iced::widget::text::secondary(theme)),
+        text(value).size(24),
+    ]
+    .spacing(8)
+    .align_x(Alignment::Center);
+
+    if let Some(detail) = detail {
+        content = content.push(
+            text(detail).size(12)
+                .style(|theme| iced::widget::text::secondary(theme))
+        );
+    }
+
+    container(content)
+        .padding(20)
+        .style(container::bordered_box)
+        .width(Length::Fill)
+        .into()
+}
+
+fn activity_label(activity_type: &ActivityType) -> &'static str {
+    match activity_type {
+        ActivityType::PaymentReceived => "Payment",
+        ActivityType::StudentAdded => "Student",
+        ActivityType::TeachingPeriodCreated => "Period",
+        ActivityType::UserCreated => "User",
+        ActivityType::StudentsMerged => "Merge",
+        ActivityType::ExemptionGranted => "Exemption",
+        ActivityType::StudentsPromoted => "Promotion",
+        ActivityType::PasswordChanged | ActivityType::PasswordChangeFailed => "Password",
+        ActivityType::PeriodReviewed | ActivityType::PeriodDisputed => "Period",
+        ActivityType::PayoutRun => "Payout",
+    }
+}
+
+/// When an activity happened, in local time with the date in the school's pattern
+fn activity_time(activity: &RecentActivity, date_format: &str) -> String {
+    let local = activity.timestamp.with_timezone(&Local);
+    format!("{} {}", format_date(local.date_naive(), date_format), local.format("%H:%M"))
+}
+
+fn activity_feed<'a>(activities: &'a [RecentActivity], date_format: &str) -> Element<'a, Message> {
+    if activities.is_empty() {
+        return text("No recent activity").size(14)
+            .style(|theme| iced::widget::text::secondary(theme))
+            .into();
+    }
+
+    let entries = activities
+        .iter()
+        .fold(column![], |col, activity| {
+            let entry = container(
+                row![
+                    container(text(activity_label(&activity.activity_type)).size(12))
+                        .width(Length::Fixed(70.0)),
+                    column![
+                        text(&activity.description).size(14),
+                        text(format!("{} · {}", activity.user_name, activity_time(activity, date_format)))
+                            .size(12)
+                            .style(|theme| iced::widget::text::secondary(theme)),
+                    ]
+                    .spacing(2),
+                ]
+                .align_y(Vertical::Center)
+            )
+            .padding(Padding::from([8, 12]))
+            .style(container::bordered_box)
+            .width(Length::Fill);
+
+            col.push(entry)
+        })
+        .spacing(6);
+
+    scrollable(entries).height(Length::Fill).into()
+}
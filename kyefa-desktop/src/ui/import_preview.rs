@@ -0,0 +1,117 @@
+use iced::{Element, Length, Font, font::Weight, Padding};
+use iced::widget::{column, row, text, text_input, button, checkbox, container, scrollable, Space};
+use iced::alignment::Vertical;
+
+use crate::app::ImportPreview;
+use crate::ui::palette;
+
+#[derive(Debug, Clone)]
+pub enum ImportPreviewMessage {
+    /// (row index, column index, new value)
+    CellEdited(usize, usize, String),
+    ToggleSkip(usize),
+    SkipInvalid,
+}
+
+/// Review table for a parsed spreadsheet. Cells are editable so bad rows can be
+/// fixed in place; `on_confirm` is only wired up once the preview can be committed.
+pub fn import_preview_view<'a, Message: Clone + 'a>(
+    preview: &'a ImportPreview,
+    is_importing: bool,
+    on_message: impl Fn(ImportPreviewMessage) -> Message + Copy + 'a,
+    on_confirm: Message,
+    on_cancel: Message,
+) -> Element<'a, Message> {
+    let included = preview.included_rows().count();
+    let duplicates = preview.rows.iter().filter(|r| r.duplicate_of.is_some()).count();
+
+    let summary = text(format!(
+        "{}: {} rows read, {} to import, {} with errors, {} likely duplicates",
+        preview.file_name,
+        preview.rows.len(),
+        included,
+        preview.error_count(),
+        duplicates,
+    ))
+    .size(14);
+
+    let bold = Font { weight: Weight::Bold, ..Font::default() };
+    let header = preview.headers.iter().fold(
+        row![text("Row").font(bold).width(Length::Fixed(50.0))],
+        |header, title| header.push(text(title).font(bold).width(Length::FillPortion(2))),
+    )
+    .push(text("Skip").font(bold).width(Length::Fixed(50.0)))
+    .spacing(8);
+
+    let rows = preview.rows.iter().enumerate().fold(column![].spacing(6), |rows, (index, preview_row)| {
+        let cells = preview_row.values.iter().enumerate().fold(
+            row![text(preview_row.row_number.to_string()).width(Length::Fixed(50.0))],
+            |cells, (column, value)| cells.push(
+                text_input("", value)
+                    .on_input(move |s| on_message(ImportPreviewMessage::CellEdited(index, column, s)))
+                    .size(13)
+                    .width(Length::FillPortion(2)),
+            ),
+        )
+        .push(
+            container(
+                checkbox("", preview_row.skip)
+                    .on_toggle(move |_| on_message(ImportPreviewMessage::ToggleSkip(index))),
+            )
+            .width(Length::Fixed(50.0)),
+        )
+        .spacing(8)
+        .align_y(Vertical::Center);
+
+        let mut entry = column![cells].spacing(2);
+        if !preview_row.skip {
+            for error in &preview_row.errors {
+                entry = entry.push(text(error).size(12).style(palette::error));
+            }
+        }
+        if let Some(original) = &preview_row.duplicate_of {
+            entry = entry.push(text(format!("Possible duplicate of {}", original)).size(12).style(palette::warning));
+        }
+
+        rows.push(entry)
+    });
+
+    let mut confirm = button(text(if is_importing {
+        "Importing...".to_string()
+    } else {
+        format!("Import {} rows", included)
+    }))
+    .style(button::primary);
+    if preview.can_commit() && !is_importing {
+        confirm = confirm.on_press(on_confirm);
+    }
+
+    let actions = row![
+        Space::with_width(Length::Fill),
+        button("Skip rows with errors")
+            .style(button::secondary)
+            .on_press(on_message(ImportPreviewMessage::SkipInvalid)),
+        button("Cancel")
+            .style(button::text)
+            .on_press(on_cancel),
+        confirm,
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center);
+
+    container(
+        column![
+            text("Import Preview").size(20),
+            summary,
+            header,
+            scrollable(rows).height(Length::Fill),
+            actions,
+        ]
+        .spacing(10),
+    )
+    .padding(Padding::from(15))
+    .style(container::bordered_box)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
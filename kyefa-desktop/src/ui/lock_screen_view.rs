@@ -0,0 +1,49 @@
+use iced::{Element, Length};
+use iced::widget::{column, row, text, text_input, button, container, Space};
+use iced::alignment::Horizontal;
+
+use crate::app::{LockScreen, Message, DashboardMessage};
+use crate::ui::palette;
+
+/// Covers the dashboard until the logged-in user's password is entered.
+/// Logging out from here discards whatever was left unsaved.
+pub fn lock_screen_view<'a>(lock: &'a LockScreen, user_name: String) -> Element<'a, Message> {
+    let mut unlock = button(if lock.is_verifying { "Checking..." } else { "Unlock" })
+        .style(button::primary);
+    if !lock.is_verifying {
+        unlock = unlock.on_press(Message::Dashboard(DashboardMessage::Unlock));
+    }
+
+    let mut content = column![
+        text("Kyefa is locked").size(24),
+        text(format!("Enter the password for {} to carry on.", user_name))
+            .size(14)
+            .style(|theme| iced::widget::text::secondary(theme)),
+        text_input("Password", &lock.password)
+            .secure(true)
+            .on_input(|s| Message::Dashboard(DashboardMessage::UpdateUnlockPassword(s)))
+            .on_submit(Message::Dashboard(DashboardMessage::Unlock))
+            .width(Length::Fixed(300.0)),
+    ]
+    .spacing(15)
+    .align_x(Horizontal::Center);
+
+    if let Some(error) = &lock.error {
+        content = content.push(text(error).size(14).style(palette::error));
+    }
+
+    content = content.push(
+        row![
+            button("Log Out")
+                .style(button::text)
+                .on_press(Message::Logout),
+            Space::with_width(Length::Fixed(20.0)),
+            unlock,
+        ]
+    );
+
+    container(content)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}
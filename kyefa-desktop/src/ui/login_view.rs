@@ -1,4 +1,4 @@
-use iced::{Element, Length, Theme, Background, Border, Color, border};
+use iced::{Element, Length, Theme, Background, Border, border};
 use iced::widget::{column, text, text_input, button, container, row, Space};
 use iced::alignment::{Horizontal, Vertical};
 use iced::widget::Column;
@@ -6,6 +6,7 @@ use iced::widget::Column;
 use crate::{
     error::LoginError,
     app::{Message, LoginMessage, LoginState},
+    ui::palette,
 };
 
 pub fn login_view(state: &LoginState) -> Element<'_, Message> {
@@ -47,17 +48,21 @@ pub fn login_view(state: &LoginState) -> Element<'_, Message> {
 
     if let Some(error) = &state.error {
         let error_message = match error {
-            LoginError::UserNotFound(msg) => format!("User not found: {}", msg),
-            LoginError::InvalidCredentials(msg) => format!("Invalid credentials: {}", msg),
+            LoginError::InvalidCredentials(msg) => msg.clone(),
             LoginError::NetworkIssue(msg) => format!("Network issue: {}", msg),
             LoginError::ServerError(msg) => format!("Server error: {}", msg),
+            LoginError::NoSchool => "Your account isn't linked to any school yet. Ask an admin to add you to one.".to_string(),
         };
-        let display_message = row![text(error_message).color(iced::Color::from_rgb(1.0, 0.0, 0.0))];
+        let display_message = row![text(error_message).style(palette::error)];
         fields_with_button = fields_with_button.push(display_message);
     }
 
+    if let Some(notice) = &state.notice {
+        fields_with_button = fields_with_button.push(row![text(notice).style(palette::success)]);
+    }
+
     if state.is_authenticating {
-        let authenticating_message = row![text("Authenticating...").size(18).color(iced::Color::from_rgb(0.0, 0.5, 1.0))];
+        let authenticating_message = row![text("Authenticating...").size(18).style(palette::info)];
         fields_with_button = fields_with_button.push(authenticating_message);
     }
 
@@ -90,9 +95,9 @@ fn login_box_style(theme: &Theme) -> container::Style {
     let palette = theme.extended_palette();
 
     container::Style {
-        background: Some(Background::Color(Color::from_rgb(0.95, 0.95, 0.95))),
+        background: Some(Background::Color(palette.background.weak.color)),
         border: Border {
-            color: Color::from_rgb(0.95, 0.95, 0.95),
+            color: palette.background.weak.color,
             width: 1.0,
             radius: border::Radius::new(8.0),
         },
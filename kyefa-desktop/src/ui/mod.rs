@@ -1,9 +1,20 @@
+pub mod backup_view;
+pub mod change_password_view;
+pub mod charts;
 pub mod dashboard_view;
+pub mod date_input;
 pub mod helper;
 pub mod home_view;
+pub mod import_preview;
+pub mod lock_screen_view;
 pub mod login_view;
+pub mod palette;
+pub mod period_review_view;
 pub mod student_manager_view;
+pub mod sync_status_view;
+pub mod teacher_portal_view;
 pub mod teaching_period_view;
 pub mod payment_tracking_view;
 pub mod reports_analytics_view;
+pub mod settings_view;
 pub mod user_access_view;
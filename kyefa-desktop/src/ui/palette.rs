@@ -0,0 +1,101 @@
+use iced::{Color, Theme};
+use iced::widget::text;
+use kyefa_models::{PaymentStatus, UserRole};
+
+/// Colours that carry a meaning (errors, payment status, roles), with a
+/// lighter variant for dark themes so they stay readable on dark backgrounds.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub error: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub overdue: Color,
+    pub info: Color,
+    pub highlight: Color,
+    pub muted: Color,
+    /// Weaker than `muted`, for things that don't apply
+    pub faint: Color,
+    /// Chart series for fees expected, fees collected and periods taught
+    pub expected: Color,
+    pub collected: Color,
+    pub periods: Color,
+}
+
+const LIGHT: Palette = Palette {
+    error: Color::from_rgb(0.8, 0.2, 0.2),
+    success: Color::from_rgb(0.2, 0.6, 0.2),
+    warning: Color::from_rgb(0.85, 0.55, 0.1),
+    overdue: Color::from_rgb(0.9, 0.35, 0.1),
+    info: Color::from_rgb(0.2, 0.6, 0.9),
+    highlight: Color::from_rgb(0.8, 0.2, 0.8),
+    muted: Color::from_rgb(0.5, 0.5, 0.5),
+    faint: Color::from_rgb(0.7, 0.7, 0.7),
+    expected: Color::from_rgb(0.9, 0.6, 0.2),
+    collected: Color::from_rgb(0.2, 0.7, 0.2),
+    periods: Color::from_rgb(0.2, 0.6, 0.9),
+};
+
+const DARK: Palette = Palette {
+    error: Color::from_rgb(0.95, 0.45, 0.45),
+    success: Color::from_rgb(0.45, 0.8, 0.45),
+    warning: Color::from_rgb(1.0, 0.7, 0.3),
+    overdue: Color::from_rgb(1.0, 0.55, 0.35),
+    info: Color::from_rgb(0.45, 0.7, 1.0),
+    highlight: Color::from_rgb(0.9, 0.5, 0.9),
+    muted: Color::from_rgb(0.65, 0.65, 0.65),
+    faint: Color::from_rgb(0.45, 0.45, 0.45),
+    expected: Color::from_rgb(1.0, 0.72, 0.4),
+    collected: Color::from_rgb(0.45, 0.85, 0.45),
+    periods: Color::from_rgb(0.45, 0.7, 1.0),
+};
+
+impl Palette {
+    pub fn payment_status(&self, status: &PaymentStatus) -> Color {
+        match status {
+            PaymentStatus::Paid => self.success,
+            PaymentStatus::PartialOnTrack => self.warning,
+            PaymentStatus::PartialOverdue => self.overdue,
+            PaymentStatus::NotPaid => self.error,
+            PaymentStatus::Exempt => self.muted,
+            PaymentStatus::NotEnrolled => self.faint,
+        }
+    }
+
+    pub fn role(&self, role: &UserRole) -> Color {
+        match role {
+            UserRole::Admin => self.error,
+            UserRole::Staff => self.info,
+            UserRole::Teacher => self.success,
+            _ => self.muted,
+        }
+    }
+}
+
+/// The palette matching `theme`
+pub fn of(theme: &Theme) -> Palette {
+    if theme.extended_palette().is_dark { DARK } else { LIGHT }
+}
+
+/// The operating system's light or dark preference, or light where it can't be read
+pub fn system_theme() -> Theme {
+    match dark_light::detect() {
+        dark_light::Mode::Dark => Theme::Dark,
+        dark_light::Mode::Light | dark_light::Mode::Default => Theme::Light,
+    }
+}
+
+pub fn error(theme: &Theme) -> text::Style {
+    text::Style { color: Some(of(theme).error) }
+}
+
+pub fn success(theme: &Theme) -> text::Style {
+    text::Style { color: Some(of(theme).success) }
+}
+
+pub fn warning(theme: &Theme) -> text::Style {
+    text::Style { color: Some(of(theme).warning) }
+}
+
+pub fn info(theme: &Theme) -> text::Style {
+    text::Style { color: Some(of(theme).info) }
+}
@@ -1,13 +1,27 @@
-use iced::{Element, Length, Color, Alignment, Padding};
+use iced::{Element, Length, Alignment, Padding};
 use iced::widget::{
     column, row, text, button, container, 
-    text_input, scrollable, pick_list, Space
+    text_input, scrollable, pick_list, checkbox, Space
 };
 use iced::alignment::{Horizontal, Vertical};
-use crate::app::{PaymentTrackingState, Message, DashboardMessage, PaymentTrackingMessage};
-use kyefa_models::PaymentStatus;
+use crate::app::{PaymentTrackingState, ExemptionForm, InstallmentPlanForm, Message, DashboardMessage, PaymentTrackingMessage};
+use crate::settings::PAYMENT_METHODS;
+use crate::ui::date_input::{date_range_input, date_input, format_date};
+use crate::ui::palette;
+use chrono::Local;
+use kyefa_models::{format_money, PaymentStatus, ExemptionReason, Student};
 
 pub fn payment_tracking_view(state: &PaymentTrackingState) -> Element<'_, Message> {
+    if let Some(form) = &state.exemption_form {
+        return exemption_form_view(form);
+    }
+    if let Some(form) = &state.installment_form {
+        return installment_plan_view(form);
+    }
+    if state.show_payment_dialog {
+        return payment_dialog_view(state);
+    }
+
     let header = row![
         text("Payment Tracking").size(24),
         Space::with_width(Length::Fill),
@@ -26,27 +40,48 @@ pub fn payment_tracking_view(state: &PaymentTrackingState) -> Element<'_, Messag
         pick_list(
             vec![
                 PaymentStatus::Paid,
-                PaymentStatus::Partial,
+                PaymentStatus::PartialOnTrack,
+                PaymentStatus::PartialOverdue,
                 PaymentStatus::NotPaid,
                 PaymentStatus::Exempt,
+                PaymentStatus::NotEnrolled,
             ],
             state.filter_status.clone(),
             |status| Message::Dashboard(DashboardMessage::PaymentTracking(PaymentTrackingMessage::FilterByStatus(Some(status))))
         )
         .placeholder("Filter by status..."),
+        Space::with_width(Length::Fixed(20.0)),
+        date_range_input(&state.date_range, |msg| {
+            Message::Dashboard(DashboardMessage::PaymentTracking(PaymentTrackingMessage::DateRange(msg)))
+        }),
         Space::with_width(Length::Fill),
+        button("Apply")
+            .style(button::secondary)
+            .on_press(Message::Dashboard(DashboardMessage::PaymentTracking(PaymentTrackingMessage::LoadPayments))),
     ]
     .spacing(10)
-    .align_y(Vertical::Center);
+    .align_y(Vertical::Top);
+
+    let error_banner = match &state.error_message {
+        Some(error) => row![
+            text(error).size(14).style(palette::error)
+        ],
+        None => match &state.success_message {
+            Some(message) => row![
+                text(message).size(14).style(palette::success)
+            ],
+            None => row![],
+        },
+    };
 
     let payment_summary = row![
-        summary_card("Total Expected", format!("₵{:.2}", 
-            state.students.iter().map(|s| s.fee_amount).sum::<f64>())),
-        summary_card("Total Received", format!("₵{:.2}", 
-            state.payments.iter().map(|p| p.amount).sum::<f64>())),
-        summary_card("Outstanding", format!("₵{:.2}", 
+        summary_card("Total Expected", format_money(
+            state.students.iter().map(|s| s.fee_amount).sum::<f64>(), &state.currency_symbol)),
+        summary_card("Total Received", format_money(
+            state.payments.iter().map(|p| p.amount).sum::<f64>(), &state.currency_symbol)),
+        summary_card("Outstanding", format_money(
             state.students.iter().map(|s| s.fee_amount).sum::<f64>() - 
-            state.payments.iter().map(|p| p.amount).sum::<f64>())),
+            state.payments.iter().map(|p| p.amount).sum::<f64>(), &state.currency_symbol)),
         summary_card("Collection Rate", format!("{:.1}%", 
             if state.students.is_empty() { 0.0 } else {
                 (state.payments.len() as f64 / state.students.len() as f64) * 100.0
@@ -88,12 +123,8 @@ pub fn payment_tracking_view(state: &PaymentTrackingState) -> Element<'_, Messag
                 search_match && status_match
             })
             .fold(column![], |col, student| {
-                let status_color = match student.payment_status {
-                    PaymentStatus::Paid => Color::from_rgb(0.2, 0.8, 0.2),
-                    PaymentStatus::Partial => Color::from_rgb(1.0, 0.6, 0.0),
-                    PaymentStatus::NotPaid => Color::from_rgb(0.8, 0.2, 0.2),
-                    PaymentStatus::Exempt => Color::from_rgb(0.5, 0.5, 0.5),
-                };
+                let status = student.payment_status.clone();
+                let badge_status = status.clone();
 
                 let student_card = container(
                     column![
@@ -101,15 +132,15 @@ pub fn payment_tracking_view(state: &PaymentTrackingState) -> Element<'_, Messag
                             text(format!("{} {}", student.name.first_name, student.name.surname)).size(16),
                             Space::with_width(Length::Fill),
                             container(
-                                text(format!("{:?}", student.payment_status)).size(12)
-                                    .style(move |_| iced::widget::text::Style {
-                                        color: Some(status_color),
+                                text(student.payment_status.to_string()).size(12)
+                                    .style(move |theme| iced::widget::text::Style {
+                                        color: Some(palette::of(theme).payment_status(&status)),
                                     })
                             )
                             .padding(Padding::from([4, 8]))
                             .style(move |theme| container::Style {
                                 border: iced::Border {
-                                    color: status_color,
+                                    color: palette::of(theme).payment_status(&badge_status),
                                     width: 1.0,
                                     radius: 4.0.into(),
                                 },
@@ -119,8 +150,17 @@ pub fn payment_tracking_view(state: &PaymentTrackingState) -> Element<'_, Messag
                         row![
                             text(format!("Class: {:?}", student.class_level)).size(12),
                             Space::with_width(Length::Fixed(20.0)),
-                            text(format!("Fee: ₵{:.2}", student.fee_amount)).size(12),
+                            text(format!("Fee: {}", format_money(student.fee_amount, &state.currency_symbol))).size(12),
+                            Space::with_width(Length::Fixed(20.0)),
+                            text(installment_summary(student, &state.date_range.format, &state.currency_symbol)).size(12)
+                                .style(|theme| iced::widget::text::secondary(theme)),
                             Space::with_width(Length::Fill),
+                            button("Installments")
+                                .style(button::text)
+                                .on_press(Message::Dashboard(DashboardMessage::PaymentTracking(PaymentTrackingMessage::ShowInstallmentPlan(student.id)))),
+                            button("Grant Exemption")
+                                .style(button::text)
+                                .on_press(Message::Dashboard(DashboardMessage::PaymentTracking(PaymentTrackingMessage::ShowExemptionDialog(student.id)))),
                             button("View Payments")
                                 .style(button::text)
                                 .on_press(Message::Dashboard(DashboardMessage::PaymentTracking(PaymentTrackingMessage::ViewStudentPayments(student.id.to_string())))),
@@ -145,6 +185,7 @@ pub fn payment_tracking_view(state: &PaymentTrackingState) -> Element<'_, Messag
 
     let content = column![
         header,
+        error_banner,
         Space::with_height(Length::Fixed(20.0)),
         payment_summary,
         Space::with_height(Length::Fixed(20.0)),
@@ -183,3 +224,311 @@ fn summary_card(title: &str, value: String) -> Element<'_, Message> {
     .width(Length::Fill)
     .into()
 }
+
+/// Reason, amount and supporting notes for a fee exemption
+fn exemption_form_view(form: &ExemptionForm) -> Element<'_, Message> {
+    let payment_msg = |msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg));
+
+    let mut fields = column![
+        text(format!("Exempt {} from fees", form.student_name)).size(24),
+        text(format!("Current term fee: {}. The exemption is recorded against the term you choose with you as the approver.", format_money(form.fee_amount, &form.currency_symbol)))
+            .size(14)
+            .style(|theme| iced::widget::text::secondary(theme)),
+        row![
+            text("Term:").width(Length::Fixed(120.0)),
+            pick_list(
+                form.terms.clone(),
+                form.term.clone(),
+                move |term| payment_msg(PaymentTrackingMessage::ExemptionTermSelected(term)),
+            )
+            .placeholder("Select term...")
+            .width(Length::Fixed(250.0)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+        row![
+            text("Reason:").width(Length::Fixed(120.0)),
+            pick_list(
+                ExemptionReason::ALL.to_vec(),
+                form.reason.clone(),
+                move |reason| payment_msg(PaymentTrackingMessage::ExemptionReasonSelected(reason)),
+            )
+            .placeholder("Select reason...")
+            .width(Length::Fixed(250.0)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+        checkbox("Full exemption (waive the whole fee)", form.is_full)
+            .on_toggle(move |is_full| payment_msg(PaymentTrackingMessage::ExemptionFullToggled(is_full))),
+    ]
+    .spacing(15);
+
+    if !form.is_full {
+        fields = fields.push(
+            row![
+                text(format!("Amount ({}):", form.currency_symbol)).width(Length::Fixed(120.0)),
+                text_input("0.00", &form.amount)
+                    .on_input(move |s| payment_msg(PaymentTrackingMessage::ExemptionAmountChanged(s)))
+                    .width(Length::Fixed(250.0)),
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center),
+        );
+    }
+
+    fields = fields.push(
+        row![
+            text("Notes:").width(Length::Fixed(120.0)),
+            text_input("Supporting notes for the auditors", &form.notes)
+                .on_input(move |s| payment_msg(PaymentTrackingMessage::ExemptionNotesChanged(s)))
+                .width(Length::Fill),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+    );
+
+    if let Some(error) = &form.error {
+        fields = fields.push(text(error).size(14).style(palette::error));
+    }
+
+    let mut approve = button(if form.is_saving { "Saving..." } else { "Approve Exemption" })
+        .style(button::primary);
+    if !form.is_saving {
+        approve = approve.on_press(payment_msg(PaymentTrackingMessage::SubmitExemption));
+    }
+
+    container(
+        fields.push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(payment_msg(PaymentTrackingMessage::CancelExemption)),
+                approve,
+            ]
+            .spacing(10),
+        )
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Entry in the student picker of the payment dialog
+#[derive(Debug, Clone, PartialEq)]
+struct StudentOption {
+    id: String,
+    name: String,
+}
+
+impl std::fmt::Display for StudentOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Amount, method and a note for one payment against the current term
+fn payment_dialog_view(state: &PaymentTrackingState) -> Element<'_, Message> {
+    let payment_msg = |msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg));
+
+    let options: Vec<StudentOption> = state.students
+        .iter()
+        .filter(|student| student.is_active && student.merged_into.is_none())
+        .map(|student| StudentOption {
+            id: student.id.to_string(),
+            name: format!("{} {} ({})", student.name.first_name, student.name.surname, student.class_level),
+        })
+        .collect();
+    let selected = state.selected_student_id.as_ref()
+        .and_then(|id| options.iter().find(|option| option.id == *id).cloned());
+    let balance = state.selected_student_id.as_ref()
+        .and_then(|id| state.students.iter().find(|s| s.id.to_string() == *id))
+        .map(|student| format!("Outstanding this term: {}", format_money(student.balance(), &state.currency_symbol)))
+        .unwrap_or_default();
+
+    let mut fields = column![
+        text("Record Payment").size(24),
+        row![
+            text("Student:").width(Length::Fixed(120.0)),
+            pick_list(options, selected, move |option| payment_msg(PaymentTrackingMessage::RecordPayment(option.id)))
+                .placeholder("Select student...")
+                .width(Length::Fixed(350.0)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+        text(balance).size(14).style(|theme| iced::widget::text::secondary(theme)),
+        row![
+            text(format!("Amount ({}):", state.currency_symbol)).width(Length::Fixed(120.0)),
+            text_input("0.00", &state.payment_amount)
+                .on_input(move |s| payment_msg(PaymentTrackingMessage::UpdatePaymentAmount(s)))
+                .width(Length::Fixed(250.0)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+        row![
+            text("Method:").width(Length::Fixed(120.0)),
+            pick_list(
+                PAYMENT_METHODS,
+                PAYMENT_METHODS.iter().copied().find(|method| *method == state.payment_method),
+                move |method| payment_msg(PaymentTrackingMessage::UpdatePaymentMethod(method.to_string())),
+            )
+            .width(Length::Fixed(250.0)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+        row![
+            text("Description:").width(Length::Fixed(120.0)),
+            text_input("e.g. receipt number", &state.payment_description)
+                .on_input(move |s| payment_msg(PaymentTrackingMessage::UpdatePaymentDescription(s)))
+                .width(Length::Fill),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+    ]
+    .spacing(15);
+
+    if let Some(error) = &state.error_message {
+        fields = fields.push(text(error).size(14).style(palette::error));
+    }
+
+    let mut save = button(if state.is_recording_payment { "Saving..." } else { "Record Payment" })
+        .style(button::primary);
+    if !state.is_recording_payment {
+        save = save.on_press(payment_msg(PaymentTrackingMessage::SubmitPayment));
+    }
+
+    container(
+        fields.push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(payment_msg(PaymentTrackingMessage::CancelPayment)),
+                save,
+            ]
+            .spacing(10),
+        )
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Where the student stands against their installment plan, if they have one
+fn installment_summary(student: &Student, date_format: &str, currency_symbol: &str) -> String {
+    let Some(plan) = &student.installment_plan else {
+        return String::new();
+    };
+    let today = Local::now().date_naive();
+    match (plan.days_overdue(student.total_paid, today), plan.next_unpaid(student.total_paid)) {
+        (Some(days), Some(installment)) => format!(
+            "Installment of {} overdue by {} days",
+            format_money(installment.amount, currency_symbol),
+            days,
+        ),
+        (None, Some(installment)) => format!(
+            "Next installment {} due {}",
+            format_money(installment.amount, currency_symbol),
+            format_date(installment.due_date, date_format),
+        ),
+        _ => format!("{} installments, all paid", plan.installments.len()),
+    }
+}
+
+/// Due dates and amounts for paying a term's fee in parts
+fn installment_plan_view(form: &InstallmentPlanForm) -> Element<'_, Message> {
+    let payment_msg = |msg| Message::Dashboard(DashboardMessage::PaymentTracking(msg));
+
+    let rows = form.rows.iter().enumerate().fold(column![].spacing(10), |rows, (index, installment)| {
+        rows.push(
+            row![
+                text(format!("Installment {}", index + 1)).width(Length::Fixed(120.0)),
+                date_input(&installment.due_date, move |s| payment_msg(PaymentTrackingMessage::InstallmentDueDateChanged(index, s))),
+                text_input(&format!("Amount ({})", form.currency_symbol), &installment.amount)
+                    .on_input(move |s| payment_msg(PaymentTrackingMessage::InstallmentAmountChanged(index, s)))
+                    .width(Length::Fixed(150.0)),
+                button("Remove")
+                    .style(button::text)
+                    .on_press(payment_msg(PaymentTrackingMessage::RemoveInstallment(index))),
+            ]
+            .spacing(10)
+            .align_y(Vertical::Top),
+        )
+    });
+
+    let total = form.total();
+    let is_balanced = !form.knows_fee() || (total - form.fee_amount).abs() <= 0.005;
+    let fee_note = if form.knows_fee() {
+        format!("Term fee: {}.", format_money(form.fee_amount, &form.currency_symbol))
+    } else {
+        "The installments must add up to the student's fee for the chosen term.".to_string()
+    };
+
+    let mut content = column![
+        text(format!("Installment plan for {}", form.student_name)).size(24),
+        text(format!(
+            "{} Payments are applied to installments in date order; a student is overdue once an installment's due date passes without it being covered. Remove every installment to cancel the plan.",
+            fee_note,
+        ))
+        .size(14)
+        .style(|theme| iced::widget::text::secondary(theme)),
+        row![
+            text("Term:").width(Length::Fixed(120.0)),
+            pick_list(
+                form.terms.clone(),
+                form.term.clone(),
+                move |term| payment_msg(PaymentTrackingMessage::InstallmentTermSelected(term)),
+            )
+            .placeholder("Select term...")
+            .width(Length::Fixed(250.0)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+        rows,
+        row![
+            button("Add Installment")
+                .style(button::secondary)
+                .on_press(payment_msg(PaymentTrackingMessage::AddInstallment)),
+            button("Split Fee Evenly")
+                .style(button::secondary)
+                .on_press_maybe(form.knows_fee().then_some(payment_msg(PaymentTrackingMessage::SplitInstallmentsEvenly))),
+            Space::with_width(Length::Fill),
+            text(if form.knows_fee() {
+                format!("Total {} of {}", format_money(total, &form.currency_symbol), format_money(form.fee_amount, &form.currency_symbol))
+            } else {
+                format!("Total {}", format_money(total, &form.currency_symbol))
+            })
+            .size(14)
+            .style(move |theme| if is_balanced { palette::success(theme) } else { palette::error(theme) }),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+    ]
+    .spacing(15);
+
+    if let Some(error) = &form.error {
+        content = content.push(text(error).size(14).style(palette::error));
+    }
+
+    let mut save = button(if form.is_saving { "Saving..." } else { "Save Plan" })
+        .style(button::primary);
+    if !form.is_saving {
+        save = save.on_press(payment_msg(PaymentTrackingMessage::SaveInstallmentPlan));
+    }
+
+    container(
+        content.push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(payment_msg(PaymentTrackingMessage::CancelInstallmentPlan)),
+                save,
+            ]
+            .spacing(10),
+        )
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .into()
+}
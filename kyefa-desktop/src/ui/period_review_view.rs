@@ -0,0 +1,216 @@
+use iced::{Element, Length, Padding};
+use iced::widget::{column, row, text, button, container, scrollable, text_input, pick_list, Space};
+use iced::alignment::Vertical;
+use kyefa_models::{PeriodForReview, PeriodVerification};
+
+use crate::app::{PeriodReviewState, PeriodReviewMessage, Message, DashboardMessage};
+use crate::ui::date_input::format_date;
+use crate::ui::palette;
+use crate::ui::teacher_portal_view::verification_style;
+
+fn review_msg(message: PeriodReviewMessage) -> Message {
+    Message::Dashboard(DashboardMessage::PeriodReview(message))
+}
+
+/// The committee's queue: periods in the chosen state for the chosen term,
+/// each verified or rejected from here, and the term's payout once every
+/// period has been reviewed
+pub fn period_review_view(state: &PeriodReviewState) -> Element<'_, Message> {
+    if state.rejecting.is_some() {
+        return rejection_dialog_view(state);
+    }
+
+    let mut header = row![
+        text("Period Review").size(24),
+        Space::with_width(Length::Fill),
+        pick_list(
+            state.terms.as_slice(),
+            state.term.clone(),
+            |term| review_msg(PeriodReviewMessage::TermSelected(term)),
+        )
+        .placeholder("Active term")
+        .width(Length::Fixed(220.0)),
+        pick_list(
+            PeriodVerification::ALL,
+            Some(state.verification.clone()),
+            |verification| review_msg(PeriodReviewMessage::VerificationSelected(verification)),
+        )
+        .width(Length::Fixed(200.0)),
+        button("Refresh")
+            .style(button::secondary)
+            .on_press(review_msg(PeriodReviewMessage::LoadPeriods)),
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center);
+    if state.can_run_payouts {
+        header = header.push(
+            button("Pay Out Term")
+                .style(button::primary)
+                .on_press_maybe(state.term.is_some().then_some(review_msg(PeriodReviewMessage::ConfirmPayout))),
+        );
+    }
+
+    let mut content = column![header].spacing(15).padding(20);
+    if let Some(error) = &state.error_message {
+        content = content.push(text(error).size(14).style(palette::error));
+    } else if let Some(message) = &state.message {
+        content = content.push(text(message).size(14).style(palette::success));
+    }
+    if state.confirming_payout {
+        content = content.push(payout_confirmation(state));
+    }
+
+    let periods: Element<'_, Message> = if state.periods.is_empty() {
+        let placeholder = if state.is_loading {
+            "Loading periods...".to_string()
+        } else {
+            format!("No periods are {}", state.verification.to_string().to_lowercase())
+        };
+        text(placeholder).size(14)
+            .style(|theme| iced::widget::text::secondary(theme))
+            .into()
+    } else {
+        state.periods
+            .iter()
+            .fold(column![], |col, row| col.push(period_row(row, state)))
+            .spacing(8)
+            .into()
+    };
+
+    content
+        .push(text(format!("{} periods", state.periods.len())).size(14)
+            .style(|theme| iced::widget::text::secondary(theme)))
+        .push(scrollable(periods).height(Length::Fill))
+        .into()
+}
+
+fn payout_confirmation(state: &PeriodReviewState) -> Element<'_, Message> {
+    let term = state.term.as_ref().map(|term| term.to_string()).unwrap_or_default();
+    let mut run = button(if state.is_saving { "Paying out..." } else { "Pay Out" }).style(button::danger);
+    if !state.is_saving {
+        run = run.on_press(review_msg(PeriodReviewMessage::RunPayout));
+    }
+    container(
+        row![
+            text(format!(
+                "Pay out {} from the fees collected for it? Every period must be reviewed first, and verified periods can't be changed afterwards.",
+                term,
+            ))
+            .size(14)
+            .width(Length::Fill),
+            button("Cancel")
+                .style(button::text)
+                .on_press(review_msg(PeriodReviewMessage::CancelPayout)),
+            run,
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center)
+    )
+    .padding(Padding::from([10, 12]))
+    .style(container::bordered_box)
+    .width(Length::Fill)
+    .into()
+}
+
+fn period_row<'a>(row_data: &'a PeriodForReview, state: &'a PeriodReviewState) -> Element<'a, Message> {
+    let period = &row_data.period;
+    let mut details = column![
+        row![
+            text(format_date(period.date, &state.date_format)).size(14).width(Length::Fixed(110.0)),
+            text(&row_data.teacher_name).size(14).width(Length::Fixed(180.0)),
+            text(format!("{} - {}", period.subject, period.class_level)).size(14),
+            Space::with_width(Length::Fill),
+            text(format!("Week {}", period.week_of_term)).size(12)
+                .style(|theme| iced::widget::text::secondary(theme)),
+            text(period.verification.to_string()).size(14).style(verification_style(&period.verification)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+    ]
+    .spacing(4);
+    if let Some(reason) = &period.rejection_reason {
+        details = details.push(text(format!("Rejected: {}", reason)).size(12).style(palette::error));
+    }
+    if let Some(reason) = &period.dispute_reason {
+        details = details.push(text(format!("Teacher's dispute: {}", reason)).size(12).style(palette::warning));
+    }
+
+    // Disputed periods get a second decision; verified and rejected ones can
+    // still be changed until the term is paid out
+    let can_verify = period.verification != PeriodVerification::Verified;
+    let can_reject = period.verification != PeriodVerification::Rejected;
+    let saving = state.is_saving;
+    details = details.push(
+        row![
+            Space::with_width(Length::Fill),
+            button("Reject")
+                .style(button::secondary)
+                .on_press_maybe((can_reject && !saving).then_some(review_msg(PeriodReviewMessage::ShowRejection(period.id)))),
+            button("Verify")
+                .style(button::success)
+                .on_press_maybe((can_verify && !saving).then_some(review_msg(PeriodReviewMessage::Verify(period.id)))),
+        ]
+        .spacing(10)
+    );
+
+    container(details)
+        .padding(Padding::from([8, 12]))
+        .style(container::bordered_box)
+        .width(Length::Fill)
+        .into()
+}
+
+/// Sends the period back to its teacher with the reason
+fn rejection_dialog_view(state: &PeriodReviewState) -> Element<'_, Message> {
+    let period = state.rejected_period();
+    let summary = period
+        .map(|row| format!(
+            "{}: {} - {} on {}",
+            row.teacher_name,
+            row.period.subject,
+            row.period.class_level,
+            format_date(row.period.date, &state.date_format),
+        ))
+        .unwrap_or_default();
+
+    let mut fields = column![
+        text("Reject Period").size(24),
+        text(summary).size(16),
+        row![
+            text("Reason:").width(Length::Fixed(120.0)),
+            text_input("e.g. not signed in the class register", &state.rejection_reason)
+                .on_input(|s| review_msg(PeriodReviewMessage::UpdateRejectionReason(s)))
+                .width(Length::Fill),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+    ]
+    .spacing(15);
+    if let Some(reason) = period.and_then(|row| row.period.dispute_reason.as_ref()) {
+        fields = fields.push(text(format!("Teacher's dispute: {}", reason)).size(14).style(palette::warning));
+    }
+    if let Some(error) = &state.error_message {
+        fields = fields.push(text(error).size(14).style(palette::error));
+    }
+
+    let mut send = button(if state.is_saving { "Sending..." } else { "Reject Period" }).style(button::danger);
+    if !state.is_saving {
+        send = send.on_press(review_msg(PeriodReviewMessage::SubmitRejection));
+    }
+
+    container(
+        fields.push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(review_msg(PeriodReviewMessage::CancelRejection)),
+                send,
+            ]
+            .spacing(10),
+        )
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .into()
+}
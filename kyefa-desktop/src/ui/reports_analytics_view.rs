@@ -1,16 +1,51 @@
 use iced::{Element, Length, Color, Alignment, Padding};
 use iced::widget::{
     column, row, text, button, container, 
-    text_input, scrollable, pick_list, Space
+    scrollable, pick_list, Space, canvas
 };
 use iced::alignment::{Horizontal, Vertical};
 use crate::app::{ReportsAnalyticsState, Message, DashboardMessage, ReportsAnalyticsMessage};
-use kyefa_models::{ReportType, TeacherEarnings};
+use crate::export::ExportFormat;
+use crate::ui::date_input::{date_range_input, format_date};
+use crate::ui::charts::{LineChart, BarChart, DonutChart, DonutSegment, Series};
+use crate::ui::palette::{self, Palette};
+use kyefa_models::{format_money, ReportType, TeacherEarnings, Teacher, PaymentStatus, ExemptionReason, AgingBucket};
+
+/// Entry in the teacher filter; `id: None` means all teachers.
+#[derive(Debug, Clone, PartialEq)]
+struct TeacherFilterOption {
+    id: Option<String>,
+    name: String,
+}
+
+impl std::fmt::Display for TeacherFilterOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+fn teacher_filter_options(teachers: &[Teacher]) -> Vec<TeacherFilterOption> {
+    std::iter::once(TeacherFilterOption { id: None, name: "All Teachers".to_string() })
+        .chain(teachers.iter().map(|teacher| TeacherFilterOption {
+            id: Some(teacher.id.to_string()),
+            name: format!("{} {}", teacher.name.first_name, teacher.name.surname),
+        }))
+        .collect()
+}
 
 pub fn reports_analytics_view(state: &ReportsAnalyticsState) -> Element<'_, Message> {
     let header = row![
         text("Reports & Analytics").size(24),
         Space::with_width(Length::Fill),
+        pick_list(
+            ExportFormat::ALL.to_vec(),
+            Some(state.export_format),
+            |format| Message::Dashboard(DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::SelectExportFormat(format)))
+        )
+        .width(Length::Fixed(150.0)),
+        button(if state.show_charts { "Show Reports" } else { "Show Charts" })
+            .style(button::secondary)
+            .on_press(Message::Dashboard(DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::ToggleCharts))),
         button("Export Report")
             .style(button::secondary)
             .on_press(Message::Dashboard(DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::ExportReport))),
@@ -26,31 +61,42 @@ pub fn reports_analytics_view(state: &ReportsAnalyticsState) -> Element<'_, Mess
         tab_button("Collection Status", ReportType::CollectionStatus, &state.selected_report_type),
         tab_button("Teacher Earnings", ReportType::TeacherEarnings, &state.selected_report_type),
         tab_button("Student Payments", ReportType::StudentPayments, &state.selected_report_type),
+        tab_button("Exemptions", ReportType::Exemptions, &state.selected_report_type),
+        tab_button("Overdue Aging", ReportType::OverdueAging, &state.selected_report_type),
     ]
     .spacing(5);
 
+    let teacher_options = teacher_filter_options(&state.teachers);
+    let selected_teacher = teacher_options
+        .iter()
+        .find(|option| option.id == state.selected_teacher_id)
+        .cloned();
+
     let filters = row![
-        text_input("From Date (DD/MM/YYYY)", 
-            state.date_filter_from.as_deref().unwrap_or(""))
-            .on_input(|s| Message::Dashboard(DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::UpdateDateFilterFrom(s))))
-            .width(Length::Fixed(150.0)),
-        text_input("To Date (DD/MM/YYYY)", 
-            state.date_filter_to.as_deref().unwrap_or(""))
-            .on_input(|s| Message::Dashboard(DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::UpdateDateFilterTo(s))))
-            .width(Length::Fixed(150.0)),
+        date_range_input(&state.date_range, |msg| {
+            Message::Dashboard(DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::DateRange(msg)))
+        }),
+        pick_list(
+            teacher_options,
+            selected_teacher,
+            |option| Message::Dashboard(DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::UpdateTeacherFilter(option.id)))
+        )
+        .placeholder("All Teachers")
+        .width(Length::Fixed(200.0)),
         Space::with_width(Length::Fill),
         button("Apply Filters")
             .style(button::secondary)
             .on_press(Message::Dashboard(DashboardMessage::ReportsAnalytics(ReportsAnalyticsMessage::ApplyReportFilters))),
     ]
     .spacing(10)
-    .align_y(Vertical::Center);
+    .align_y(Vertical::Top);
 
     let overview_cards = row![
-        metric_card("Total Students", state.total_students.to_string(), Color::from_rgb(0.2, 0.6, 0.9)),
-        metric_card("Expected Revenue", format!("₵{:.2}", state.expected_revenue), Color::from_rgb(0.9, 0.6, 0.2)),
-        metric_card("Actual Revenue", format!("₵{:.2}", state.total_revenue), Color::from_rgb(0.2, 0.8, 0.2)),
-        metric_card("Collection Rate", format!("{:.1}%", state.collection_rate), Color::from_rgb(0.8, 0.2, 0.8)),
+        metric_card("Total Students", state.total_students.to_string(), |p| p.info),
+        metric_card("Expected Revenue", format_money(state.expected_revenue, &state.currency_symbol), |p| p.warning),
+        metric_card("Exempted", format_money(state.total_exempted, &state.currency_symbol), |p| p.muted),
+        metric_card("Actual Revenue", format_money(state.total_revenue, &state.currency_symbol), |p| p.success),
+        metric_card("Collection Rate", format!("{:.1}%", state.collection_rate), |p| p.highlight),
     ]
     .spacing(15);
 
@@ -59,10 +105,34 @@ pub fn reports_analytics_view(state: &ReportsAnalyticsState) -> Element<'_, Mess
         ReportType::CollectionStatus => collection_status_report(state),
         ReportType::TeacherEarnings => teacher_earnings_report(state),
         ReportType::StudentPayments => student_payments_report(state),
+        ReportType::Exemptions => exemptions_report(state),
+        ReportType::OverdueAging => overdue_aging_report(state),
+    };
+
+    let (report_tabs, report_content) = if state.show_charts {
+        (row![text("Charts").size(18)], charts_grid(state))
+    } else {
+        (report_tabs, report_content)
+    };
+
+    let error_banner = match &state.error_message {
+        Some(error) => row![
+            text(error).size(14).style(palette::error)
+        ],
+        None => row![],
+    };
+
+    let export_banner = match &state.export_message {
+        Some(message) => row![
+            text(message).size(14).style(palette::success)
+        ],
+        None => row![],
     };
 
     let content = column![
         header,
+        error_banner,
+        export_banner,
         Space::with_height(Length::Fixed(20.0)),
         overview_cards,
         Space::with_height(Length::Fixed(30.0)),
@@ -103,14 +173,14 @@ fn tab_button<'a>(label: &'a str, report_type: ReportType, current: &'a ReportTy
         .into()
 }
 
-fn metric_card(title: &str, value: String, color: Color) -> Element<'_, Message> {
+fn metric_card(title: &str, value: String, color: impl Fn(&Palette) -> Color + 'static) -> Element<'_, Message> {
     container(
         column![
             text(title).size(12)
                 .style(|theme| iced::widget::text::secondary(theme)),
             text(value).size(20)
-                .style(move |_| iced::widget::text::Style {
-                    color: Some(color),
+                .style(move |theme| iced::widget::text::Style {
+                    color: Some(color(&palette::of(theme))),
                 }),
         ]
         .spacing(8)
@@ -122,23 +192,108 @@ fn metric_card(title: &str, value: String, color: Color) -> Element<'_, Message>
     .into()
 }
 
+fn charts_grid(state: &ReportsAnalyticsState) -> Element<'_, Message> {
+    let collection_trend = LineChart {
+        labels: state.collection_trend.iter().map(|week| format!("W{}", week.week_of_term)).collect(),
+        series: vec![
+            Series {
+                label: "Expected".to_string(),
+                color: |palette| palette.expected,
+                values: state.collection_trend.iter().map(|week| week.cumulative_expected).collect(),
+            },
+            Series {
+                label: "Collected".to_string(),
+                color: |palette| palette.collected,
+                values: state.collection_trend.iter().map(|week| week.cumulative_collected).collect(),
+            },
+        ],
+    };
+
+    let periods_per_teacher = BarChart {
+        labels: state.teacher_earnings.iter().map(|teacher| teacher.teacher_name.clone()).collect(),
+        series: vec![Series {
+            label: "Periods".to_string(),
+            color: |palette| palette.periods,
+            values: state.teacher_earnings.iter().map(|teacher| teacher.total_periods as f64).collect(),
+        }],
+    };
+
+    let revenue_split = DonutChart {
+        segments: match &state.projected_income {
+            Some(report) => vec![
+                DonutSegment { label: "Admin".to_string(), value: report.admin_share, color: |palette| palette.highlight },
+                DonutSegment { label: "Staff".to_string(), value: report.support_staff_share, color: |palette| palette.expected },
+                DonutSegment { label: "Teachers".to_string(), value: report.teacher_share, color: |palette| palette.periods },
+            ],
+            None => Vec::new(),
+        },
+    };
+
+    let term_comparison = BarChart {
+        labels: state.term_comparison.iter().map(|term| term.term_name.clone()).collect(),
+        series: vec![
+            Series {
+                label: "Expected".to_string(),
+                color: |palette| palette.expected,
+                values: state.term_comparison.iter().map(|term| term.expected_revenue).collect(),
+            },
+            Series {
+                label: "Collected".to_string(),
+                color: |palette| palette.collected,
+                values: state.term_comparison.iter().map(|term| term.total_revenue).collect(),
+            },
+        ],
+    };
+
+    column![
+        row![
+            chart_card(format!("Cumulative Collections vs Expected ({})", state.currency_symbol), canvas(collection_trend)),
+            chart_card("Periods Taught per Teacher", canvas(periods_per_teacher)),
+        ]
+        .spacing(15),
+        row![
+            chart_card(format!("Revenue Split ({})", state.currency_symbol), canvas(revenue_split)),
+            chart_card(format!("Term-over-Term Revenue ({})", state.currency_symbol), canvas(term_comparison)),
+        ]
+        .spacing(15),
+    ]
+    .spacing(15)
+    .into()
+}
+
+fn chart_card<'a, P>(title: impl ToString, chart: canvas::Canvas<P, Message>) -> Element<'a, Message>
+where
+    P: canvas::Program<Message> + 'a,
+{
+    container(
+        column![
+            text(title.to_string()).size(14),
+            chart.width(Length::Fill).height(Length::Fixed(260.0)),
+        ]
+        .spacing(8)
+    )
+    .padding(15)
+    .style(container::bordered_box)
+    .width(Length::Fill)
+    .into()
+}
+
 fn projected_income_report(state: &ReportsAnalyticsState) -> Element<'_, Message> {
-    let total_projected = state.expected_revenue;
-    let admin_share = total_projected * (30.0 / 100.0);
-    let staff_share = total_projected * (20.0 / 100.0);
-    let teacher_share = total_projected * (50.0 / 100.0);
+    let Some(report) = &state.projected_income else {
+        return empty_report("No projected income data available");
+    };
 
     column![
         text("Projected Income Breakdown").size(18),
         Space::with_height(Length::Fixed(15.0)),
-        income_row("Total Projected Income", format!("₵{:.2}", total_projected)),
-        income_row("Admin Share (30%)", format!("₵{:.2}", admin_share)),
-        income_row("Staff Share (20%)", format!("₵{:.2}", staff_share)),
-        income_row("Teachers Share (50%)", format!("₵{:.2}", teacher_share)),
+        income_row("Total Projected Income", format_money(report.expected_revenue, &state.currency_symbol)),
+        income_row(format!("Admin Share ({:.0}%)", report.admin_percentage), format_money(report.admin_share, &state.currency_symbol)),
+        income_row(format!("Staff Share ({:.0}%)", report.support_staff_percentage), format_money(report.support_staff_share, &state.currency_symbol)),
+        income_row(format!("Teachers Share ({:.0}%)", report.teacher_percentage), format_money(report.teacher_share, &state.currency_symbol)),
         Space::with_height(Length::Fixed(20.0)),
         text("Teacher Individual Earnings").size(16),
         Space::with_height(Length::Fixed(10.0)),
-        teacher_earnings_list(&state.teacher_earnings),
+        teacher_earnings_list(&state.teacher_earnings, &state.currency_symbol),
     ]
     .spacing(5)
     .into()
@@ -150,16 +305,18 @@ fn collection_status_report(state: &ReportsAnalyticsState) -> Element<'_, Messag
     column![
         text("Collection Status Summary").size(18),
         Space::with_height(Length::Fixed(15.0)),
-        collection_row("Total Expected", format!("₵{:.2}", summary.total_expected), Color::from_rgb(0.5, 0.5, 0.5)),
-        collection_row("Total Received", format!("₵{:.2}", summary.total_received), Color::from_rgb(0.2, 0.8, 0.2)),
-        collection_row("Total Pending", format!("₵{:.2}", summary.total_pending), Color::from_rgb(0.8, 0.2, 0.2)),
+        collection_row("Total Expected", format_money(summary.total_expected, &state.currency_symbol), |p| p.muted),
+        collection_row("Total Exempted", format_money(summary.total_exempted, &state.currency_symbol), |p| p.muted),
+        collection_row("Total Received", format_money(summary.total_received, &state.currency_symbol), |p| p.success),
+        collection_row("Total Pending", format_money(summary.total_pending, &state.currency_symbol), |p| p.error),
         Space::with_height(Length::Fixed(20.0)),
         text("Payment Status Breakdown").size(16),
         Space::with_height(Length::Fixed(10.0)),
-        status_row("Paid", summary.paid_count, Color::from_rgb(0.2, 0.8, 0.2)),
-        status_row("Partial", summary.partial_count, Color::from_rgb(1.0, 0.6, 0.0)),
-        status_row("Not Paid", summary.unpaid_count, Color::from_rgb(0.8, 0.2, 0.2)),
-        status_row("Exempt", summary.exempt_count, Color::from_rgb(0.5, 0.5, 0.5)),
+        status_row("Paid", summary.paid_count, |p| p.payment_status(&PaymentStatus::Paid)),
+        status_row("Partial (On Track)", summary.partial_on_track_count, |p| p.payment_status(&PaymentStatus::PartialOnTrack)),
+        status_row("Partial (Overdue)", summary.partial_overdue_count, |p| p.payment_status(&PaymentStatus::PartialOverdue)),
+        status_row("Not Paid", summary.unpaid_count, |p| p.payment_status(&PaymentStatus::NotPaid)),
+        status_row("Exempt", summary.exempt_count, |p| p.payment_status(&PaymentStatus::Exempt)),
     ]
     .spacing(5)
     .into()
@@ -169,27 +326,261 @@ fn teacher_earnings_report(state: &ReportsAnalyticsState) -> Element<'_, Message
     column![
         text("Teacher Earnings Report").size(18),
         Space::with_height(Length::Fixed(15.0)),
-        teacher_earnings_list(&state.teacher_earnings),
+        teacher_earnings_list(&state.teacher_earnings, &state.currency_symbol),
     ]
     .spacing(5)
     .into()
 }
 
 fn student_payments_report(state: &ReportsAnalyticsState) -> Element<'_, Message> {
+    let history: Element<'_, Message> = if state.student_payments.is_empty() {
+        empty_report("No student payments recorded for this period")
+    } else {
+        let cards = state.student_payments
+            .iter()
+            .fold(column![], |col, student| {
+                let status = student.status.clone();
+
+                let payments = student.payments
+                    .iter()
+                    .fold(column![], |col, payment| {
+                        col.push(
+                            row![
+                                text(format_date(payment.date_paid.date(), &state.date_range.format)).size(12),
+                                Space::with_width(Length::Fill),
+                                text(format_money(payment.amount_paid, &state.currency_symbol)).size(12),
+                            ]
+                        )
+                    })
+                    .spacing(2);
+
+                let card = container(
+                    column![
+                        row![
+                            column![
+                                text(&student.student_name).size(14),
+                                text(format!("Class: {}", student.class_level)).size(12)
+                                    .style(|theme| iced::widget::text::secondary(theme)),
+                            ]
+                            .spacing(2),
+                            Space::with_width(Length::Fill),
+                            column![
+                                text(student.status.to_string()).size(12)
+                                    .style(move |theme| iced::widget::text::Style {
+                                        color: Some(palette::of(theme).payment_status(&status)),
+                                    }),
+                                text(format!("Paid {} of {}", format_money(student.total_paid, &state.currency_symbol), format_money(student.fee_amount, &state.currency_symbol))).size(12),
+                                text(format!("Balance {}", format_money(student.balance, &state.currency_symbol))).size(12)
+                                    .style(|theme| iced::widget::text::secondary(theme)),
+                            ]
+                            .spacing(2)
+                            .align_x(Alignment::End),
+                        ]
+                        .align_y(Vertical::Center),
+                        payments,
+                    ]
+                    .spacing(8)
+                )
+                .padding(Padding::from([10, 15]))
+                .style(container::bordered_box)
+                .width(Length::Fill);
+
+                col.push(card)
+            })
+            .spacing(8);
+
+        scrollable(cards)
+            .height(Length::Fixed(400.0))
+            .into()
+    };
+
     column![
         text("Student Payments Report").size(18),
         Space::with_height(Length::Fixed(15.0)),
-        text("Detailed payment history will be displayed here").size(14)
-            .style(|theme| iced::widget::text::secondary(theme)),
+        history,
+    ]
+    .spacing(5)
+    .into()
+}
+
+fn exemptions_report(state: &ReportsAnalyticsState) -> Element<'_, Message> {
+    if state.exemptions.is_empty() {
+        return column![
+            text("Fee Exemptions").size(18),
+            empty_report("No exemptions approved for this period"),
+        ]
+        .spacing(5)
+        .into();
+    }
+
+    let by_reason = ExemptionReason::ALL.iter().fold(column![].spacing(5), |col, reason| {
+        let matching: Vec<_> = state.exemptions.iter().filter(|row| &row.exemption.reason == reason).collect();
+        col.push(income_row(
+            format!("{} ({} students)", reason, matching.len()),
+            format_money(matching.iter().map(|row| row.exemption.amount).sum::<f64>(), &state.currency_symbol),
+        ))
+    });
+
+    let register = state.exemptions.iter().fold(column![].spacing(8), |col, row| {
+        let exemption = &row.exemption;
+        col.push(
+            container(
+                column![
+                    row![
+                        column![
+                            text(&row.student_name).size(14),
+                            text(format!("Class: {} · {}", row.class_level, row.term_name)).size(12)
+                                .style(|theme| iced::widget::text::secondary(theme)),
+                        ]
+                        .spacing(2),
+                        Space::with_width(Length::Fill),
+                        column![
+                            text(format!(
+                                "{} · {}",
+                                exemption.reason,
+                                if exemption.is_full { "Full" } else { "Partial" },
+                            ))
+                            .size(12),
+                            text(format!("{} of {}", format_money(exemption.amount, &state.currency_symbol), format_money(row.fee_amount, &state.currency_symbol))).size(12),
+                        ]
+                        .spacing(2)
+                        .align_x(Alignment::End),
+                    ]
+                    .align_y(Vertical::Center),
+                    text(format!(
+                        "Approved by {} on {}{}",
+                        row.approved_by_name,
+                        format_date(exemption.created_at.date(), &state.date_range.format),
+                        exemption.notes.as_deref().map(|notes| format!(" · {}", notes)).unwrap_or_default(),
+                    ))
+                    .size(12)
+                    .style(|theme| iced::widget::text::secondary(theme)),
+                ]
+                .spacing(6)
+            )
+            .padding(Padding::from([10, 15]))
+            .style(container::bordered_box)
+            .width(Length::Fill)
+        )
+    });
+
+    column![
+        text("Fee Exemptions").size(18),
+        Space::with_height(Length::Fixed(15.0)),
+        by_reason,
+        income_row(
+            "Total Exempted",
+            format_money(state.exemptions.iter().map(|row| row.exemption.amount).sum::<f64>(), &state.currency_symbol),
+        ),
+        Space::with_height(Length::Fixed(20.0)),
+        text("Exemption Register").size(16),
+        scrollable(register).height(Length::Fixed(400.0)),
     ]
     .spacing(5)
     .into()
 }
 
-fn income_row(label: &str, value: String) -> Element<'_, Message> {
+fn overdue_aging_report(state: &ReportsAnalyticsState) -> Element<'_, Message> {
+    if state.overdue_aging.is_empty() {
+        return column![
+            text("Overdue Aging").size(18),
+            empty_report("No students are behind on their installment plans"),
+        ]
+        .spacing(5)
+        .into();
+    }
+
+    let buckets = AgingBucket::ALL.iter().fold(row![].spacing(15), |buckets, bucket| {
+        let matching: Vec<_> = state.overdue_aging.iter().filter(|row| row.bucket() == *bucket).collect();
+        buckets.push(metric_card(
+            bucket_title(*bucket),
+            format!("{} · {} students", format_money(matching.iter().map(|row| row.amount_overdue).sum::<f64>(), &state.currency_symbol), matching.len()),
+            move |p| aging_color(p, *bucket),
+        ))
+    });
+
+    let students = state.overdue_aging.iter().fold(column![].spacing(6), |col, row| {
+        let bucket = row.bucket();
+        col.push(
+            container(
+                row![
+                    column![
+                        text(&row.student_name).size(14),
+                        text(match (&row.contact_name, &row.contact_phone) {
+                            (Some(name), Some(phone)) => format!("Class: {} · Call {} on {}", row.class_level, name, phone),
+                            _ => format!("Class: {} · No guardian on record", row.class_level),
+                        })
+                        .size(12)
+                        .style(|theme| iced::widget::text::secondary(theme)),
+                    ]
+                    .spacing(2),
+                    Space::with_width(Length::Fill),
+                    column![
+                        text(format!("{} overdue", format_money(row.amount_overdue, &state.currency_symbol))).size(12)
+                            .style(move |theme| iced::widget::text::Style {
+                                color: Some(aging_color(&palette::of(theme), bucket)),
+                            }),
+                        text(format!(
+                            "{} days since {}",
+                            row.days_overdue,
+                            format_date(row.oldest_due_date, &state.date_range.format),
+                        ))
+                        .size(12)
+                        .style(|theme| iced::widget::text::secondary(theme)),
+                    ]
+                    .spacing(2)
+                    .align_x(Alignment::End),
+                ]
+                .align_y(Vertical::Center)
+            )
+            .padding(Padding::from([10, 15]))
+            .style(container::bordered_box)
+            .width(Length::Fill)
+        )
+    });
+
+    column![
+        text("Overdue Aging").size(18),
+        Space::with_height(Length::Fixed(15.0)),
+        buckets,
+        Space::with_height(Length::Fixed(20.0)),
+        text("Students Behind on Installments").size(16),
+        scrollable(students).height(Length::Fixed(400.0)),
+    ]
+    .spacing(5)
+    .into()
+}
+
+fn bucket_title(bucket: AgingBucket) -> &'static str {
+    match bucket {
+        AgingBucket::UpToThirtyDays => "Overdue 0-30 days",
+        AgingBucket::ThirtyOneToSixtyDays => "Overdue 31-60 days",
+        AgingBucket::OverSixtyDays => "Overdue 60+ days",
+    }
+}
+
+fn aging_color(palette: &Palette, bucket: AgingBucket) -> Color {
+    match bucket {
+        AgingBucket::UpToThirtyDays => palette.warning,
+        AgingBucket::ThirtyOneToSixtyDays => palette.overdue,
+        AgingBucket::OverSixtyDays => palette.error,
+    }
+}
+
+fn empty_report(message: &str) -> Element<'_, Message> {
+    container(
+        text(message).size(14)
+            .style(|theme| iced::widget::text::secondary(theme))
+    )
+    .padding(20)
+    .center_x(Length::Fill)
+    .into()
+}
+
+fn income_row<'a>(label: impl ToString, value: String) -> Element<'a, Message> {
     container(
         row![
-            text(label).size(14),
+            text(label.to_string()).size(14),
             Space::with_width(Length::Fill),
             text(value).size(14),
         ]
@@ -201,14 +592,14 @@ fn income_row(label: &str, value: String) -> Element<'_, Message> {
     .into()
 }
 
-fn collection_row(label: &str, value: String, color: Color) -> Element<'_, Message> {
+fn collection_row(label: &str, value: String, color: impl Fn(&Palette) -> Color + 'static) -> Element<'_, Message> {
     container(
         row![
             text(label).size(14),
             Space::with_width(Length::Fill),
             text(value).size(14)
-                .style(move |_| iced::widget::text::Style {
-                    color: Some(color),
+                .style(move |theme| iced::widget::text::Style {
+                    color: Some(color(&palette::of(theme))),
                 }),
         ]
         .align_y(Vertical::Center)
@@ -219,14 +610,14 @@ fn collection_row(label: &str, value: String, color: Color) -> Element<'_, Messa
     .into()
 }
 
-fn status_row(label: &str, count: usize, color: Color) -> Element<'_, Message> {
+fn status_row(label: &str, count: usize, color: impl Fn(&Palette) -> Color + 'static) -> Element<'_, Message> {
     container(
         row![
             text(label).size(14),
             Space::with_width(Length::Fill),
             text(count.to_string()).size(14)
-                .style(move |_| iced::widget::text::Style {
-                    color: Some(color),
+                .style(move |theme| iced::widget::text::Style {
+                    color: Some(color(&palette::of(theme))),
                 }),
         ]
         .align_y(Vertical::Center)
@@ -237,7 +628,7 @@ fn status_row(label: &str, count: usize, color: Color) -> Element<'_, Message> {
     .into()
 }
 
-fn teacher_earnings_list(earnings: &[TeacherEarnings]) -> Element<'_, Message> {
+fn teacher_earnings_list<'a>(earnings: &'a [TeacherEarnings], currency_symbol: &str) -> Element<'a, Message> {
     if earnings.is_empty() {
         container(
             text("No teacher earnings data available").size(14)
@@ -260,7 +651,7 @@ fn teacher_earnings_list(earnings: &[TeacherEarnings]) -> Element<'_, Message> {
                         .spacing(2),
                         Space::with_width(Length::Fill),
                         column![
-                            text(format!("₵{:.2}", earning.total_earnings)).size(14),
+                            text(format_money(earning.total_earnings, currency_symbol)).size(14),
                             text(format!("{:.1}% share", earning.share_percentage)).size(12)
                                 .style(|theme| iced::widget::text::secondary(theme)),
                         ]
@@ -0,0 +1,207 @@
+use iced::{Element, Length, Padding};
+use iced::widget::{column, row, text, text_input, button, container, scrollable, pick_list, checkbox, Space};
+use iced::alignment::Vertical;
+
+use crate::app::{SettingsForm, SettingsMessage, AppTheme, Message, DashboardMessage};
+use crate::backup::BACKUP_FREQUENCIES;
+use crate::settings::PAYMENT_METHODS;
+use crate::ui::date_input::DATE_FORMATS;
+use crate::ui::palette;
+use kyefa_models::DEFAULT_CURRENCY_SYMBOL;
+
+fn settings_msg(message: SettingsMessage) -> Message {
+    Message::Dashboard(DashboardMessage::Settings(message))
+}
+
+fn field<'a>(label: &'a str, control: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    row![
+        text(label).size(14).width(Length::Fixed(220.0)),
+        control.into(),
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center)
+    .into()
+}
+
+fn section<'a>(title: &'a str, note: &'a str, fields: Vec<Element<'a, Message>>) -> Element<'a, Message> {
+    let header = column![
+        text(title).size(18),
+        text(note).size(12).style(|theme| iced::widget::text::secondary(theme)),
+    ]
+    .spacing(4);
+    container(
+        fields
+            .into_iter()
+            .fold(column![header].spacing(12), |fields, field| fields.push(field)),
+    )
+    .padding(15)
+    .style(container::bordered_box)
+    .width(Length::Fill)
+    .into()
+}
+
+fn share_input<'a>(value: &'a str, can_edit: bool, on_input: fn(String) -> SettingsMessage) -> Element<'a, Message> {
+    let mut input = text_input("0", value).width(Length::Fixed(100.0));
+    if can_edit {
+        input = input.on_input(move |s| settings_msg(on_input(s)));
+    }
+    row![input, text("%").size(14)].spacing(5).align_y(Vertical::Center).into()
+}
+
+fn number_input<'a>(value: &'a str, can_edit: bool, on_input: fn(String) -> SettingsMessage) -> Element<'a, Message> {
+    let mut input = text_input("0", value).width(Length::Fixed(100.0));
+    if can_edit {
+        input = input.on_input(move |s| settings_msg(on_input(s)));
+    }
+    input.into()
+}
+
+/// School-wide settings, editable by admins and shown read-only to everyone
+/// else, followed by the settings kept on this computer.
+pub fn settings_view(form: &SettingsForm) -> Element<'_, Message> {
+    let can_edit = form.can_edit_school;
+
+    let mut save = button(if form.is_saving { "Saving..." } else { "Save" }).style(button::primary);
+    if !form.is_saving {
+        save = save.on_press(settings_msg(SettingsMessage::Save));
+    }
+    let header = row![
+        text("Settings").size(24),
+        Space::with_width(Length::Fill),
+        button("Discard Changes")
+            .style(button::text)
+            .on_press(settings_msg(SettingsMessage::Discard)),
+        save,
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center);
+
+    let mut currency = text_input(DEFAULT_CURRENCY_SYMBOL, &form.currency_symbol).width(Length::Fixed(100.0));
+    if can_edit {
+        currency = currency.on_input(|s| settings_msg(SettingsMessage::CurrencySymbolChanged(s)));
+    }
+    let date_format: Element<'_, Message> = if can_edit {
+        pick_list(
+            DATE_FORMATS,
+            DATE_FORMATS.iter().copied().find(|format| *format == form.date_format),
+            |format| settings_msg(SettingsMessage::DateFormatSelected(format.to_string())),
+        )
+        .width(Length::Fixed(200.0))
+        .into()
+    } else {
+        text(&form.date_format).size(14).into()
+    };
+    let payment_method: Element<'_, Message> = if can_edit {
+        pick_list(
+            PAYMENT_METHODS,
+            PAYMENT_METHODS.iter().copied().find(|method| *method == form.default_payment_method),
+            |method| settings_msg(SettingsMessage::PaymentMethodSelected(method.to_string())),
+        )
+        .width(Length::Fixed(200.0))
+        .into()
+    } else {
+        text(&form.default_payment_method).size(14).into()
+    };
+
+    let school = section(
+        "School",
+        if can_edit {
+            "Shared by every computer in the school. Revenue shares must add up to 100%. The sibling discount comes off the fee of each child enrolled after a brother or sister."
+        } else {
+            "Shared by every computer in the school. Only an admin can change these."
+        },
+        vec![
+            field("Currency symbol", currency),
+            field("Date format", date_format),
+            field("Default payment method", payment_method),
+            field("Admin share", share_input(&form.admin_share, can_edit, SettingsMessage::AdminShareChanged)),
+            field("Staff share", share_input(&form.staff_share, can_edit, SettingsMessage::StaffShareChanged)),
+            field("Teacher share", share_input(&form.teacher_share, can_edit, SettingsMessage::TeacherShareChanged)),
+            field("Sibling discount", share_input(&form.sibling_discount, can_edit, SettingsMessage::SiblingDiscountChanged)),
+            field("Minimum password length", number_input(&form.password_min_length, can_edit, SettingsMessage::PasswordMinLengthChanged)),
+            field("Previous passwords blocked", number_input(&form.password_history, can_edit, SettingsMessage::PasswordHistoryChanged)),
+            field("Failed logins before lockout", number_input(&form.max_failed_logins, can_edit, SettingsMessage::MaxFailedLoginsChanged)),
+            field("Lockout length (minutes)", number_input(&form.lockout_minutes, can_edit, SettingsMessage::LockoutMinutesChanged)),
+        ],
+    );
+
+    let this_computer = section(
+        "This Computer",
+        "Saved on this computer only.",
+        vec![
+            field(
+                "Theme",
+                pick_list(AppTheme::ALL.to_vec(), Some(form.theme.clone()), |theme| {
+                    settings_msg(SettingsMessage::ThemeSelected(theme))
+                })
+                .width(Length::Fixed(200.0)),
+            ),
+            field(
+                "Lock screen after idle",
+                row![
+                    text_input("5", &form.lock_after)
+                        .on_input(|s| settings_msg(SettingsMessage::LockAfterChanged(s)))
+                        .width(Length::Fixed(100.0)),
+                    text("minutes (0 never locks)").size(14),
+                ]
+                .spacing(5)
+                .align_y(Vertical::Center),
+            ),
+            field(
+                "Log out after idle",
+                row![
+                    text_input("30", &form.logout_after)
+                        .on_input(|s| settings_msg(SettingsMessage::LogoutAfterChanged(s)))
+                        .width(Length::Fixed(100.0)),
+                    text("minutes (0 never logs out)").size(14),
+                ]
+                .spacing(5)
+                .align_y(Vertical::Center),
+            ),
+            field(
+                "Automatic backups",
+                checkbox("Back up automatically", form.auto_backup_enabled)
+                    .on_toggle(|enabled| settings_msg(SettingsMessage::AutoBackupToggled(enabled))),
+            ),
+            field(
+                "Backup frequency",
+                pick_list(
+                    BACKUP_FREQUENCIES,
+                    BACKUP_FREQUENCIES.iter().copied().find(|frequency| *frequency == form.backup_frequency),
+                    |frequency| settings_msg(SettingsMessage::BackupFrequencySelected(frequency.to_string())),
+                )
+                .width(Length::Fixed(200.0)),
+            ),
+            field(
+                "Backups to keep",
+                text_input("14", &form.backup_retention)
+                    .on_input(|s| settings_msg(SettingsMessage::BackupRetentionChanged(s)))
+                    .width(Length::Fixed(100.0)),
+            ),
+            field(
+                "Encryption",
+                checkbox("Encrypt backups with a passphrase", form.encrypt_backups)
+                    .on_toggle(|encrypt| settings_msg(SettingsMessage::EncryptBackupsToggled(encrypt))),
+            ),
+            field(
+                "Backup folder and passphrase",
+                button("Manage Backups...")
+                    .style(button::secondary)
+                    .on_press(Message::Dashboard(DashboardMessage::ShowBackups)),
+            ),
+        ],
+    );
+
+    let mut content = column![header].spacing(15);
+    if let Some(error) = &form.error {
+        content = content.push(text(error).size(14).style(palette::error));
+    } else if let Some(message) = &form.message {
+        content = content.push(text(message).size(14).style(palette::success));
+    }
+
+    container(content.push(scrollable(column![school, this_computer].spacing(15)).height(Length::Fill)))
+        .padding(Padding::from(20))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
@@ -1,16 +1,51 @@
-use iced::{Element, Length, alignment, Font, font::Weight, Color};
+use iced::{Element, Length, alignment, Font, font::Weight};
 use iced::widget::{
     Column, Row, Text, TextInput, Button, PickList, Container, Scrollable, Space,
-    column, row, text, text_input, button, pick_list, container, scrollable,
+    column, row, text, text_input, button, pick_list, container, scrollable, checkbox,
 };
 use crate::app::{DashboardMessage, StudentManagerMessage};
-use crate::app::StudentManagerState;
-use kyefa_models::{Gender, ClassLevel, Student};
+use crate::app::{StudentManagerState, PromotionWizard, EnrolmentPanel, FamilyPanel, GuardianForm};
+use crate::families::{self, Family};
+use crate::ui::import_preview::import_preview_view;
+use crate::ui::date_input::{date_input, format_date};
+use crate::ui::palette;
+use kyefa_models::{format_money, Gender, ClassLevel, Student, PromotionOutcome, GuardianRelationship};
 
 pub fn student_manager_view<'a>(state: &'a StudentManagerState) -> Element<'a, DashboardMessage> {
+    if let Some(preview) = &state.import_preview {
+        let mut content = column![].spacing(10).padding(20);
+        if let Some(error_msg) = &state.form_error_message {
+            content = content.push(text(error_msg).style(palette::error));
+        }
+        if let Some(message) = &state.import_message {
+            content = content.push(text(message).style(palette::success));
+        }
+        return content
+            .push(import_preview_view(
+                preview,
+                state.is_importing,
+                |msg| DashboardMessage::StudentManager(StudentManagerMessage::ImportPreview(msg)),
+                DashboardMessage::StudentManager(StudentManagerMessage::ConfirmImport),
+                DashboardMessage::StudentManager(StudentManagerMessage::CancelImport),
+            ))
+            .into();
+    }
+
     let header = row![
         text("Student Management").size(24),
         Space::with_width(Length::Fill),
+        button("Families")
+            .style(button::secondary)
+            .on_press(DashboardMessage::StudentManager(StudentManagerMessage::ShowFamilies)),
+        button("Term Enrolment")
+            .style(button::secondary)
+            .on_press(DashboardMessage::StudentManager(StudentManagerMessage::ShowEnrolment)),
+        button("Year-End Promotion")
+            .style(button::secondary)
+            .on_press(DashboardMessage::StudentManager(StudentManagerMessage::ShowPromotionWizard)),
+        button("Find Duplicates")
+            .style(button::secondary)
+            .on_press(DashboardMessage::StudentManager(StudentManagerMessage::FindDuplicates)),
         button("Download Excel Template") 
             .style(button::secondary)
             .on_press(DashboardMessage::StudentManager(StudentManagerMessage::GenerateExcelTemplate)),
@@ -19,6 +54,22 @@ pub fn student_manager_view<'a>(state: &'a StudentManagerState) -> Element<'a, D
             .on_press(DashboardMessage::StudentManager(StudentManagerMessage::ImportStudentsFromExcel)),
     ];
 
+    if let Some(wizard) = &state.promotion {
+        return promotion_panel(state, wizard);
+    }
+    if let Some(panel) = &state.enrolment {
+        return enrolment_panel(state, panel);
+    }
+    if let Some(panel) = &state.families {
+        return match &panel.form {
+            Some(form) => guardian_form_view(state, panel, form),
+            None => families_panel(state, panel),
+        };
+    }
+    if state.show_duplicates {
+        return duplicates_panel(state);
+    }
+
     let add_student_section = column![
         header,
         text("Add New Student").size(20),
@@ -67,18 +118,23 @@ pub fn student_manager_view<'a>(state: &'a StudentManagerState) -> Element<'a, D
             text(error_msg).style(|theme| {
                 // Use a custom style function for error text
                 iced::widget::text::Style {
-                    color: Some(Color::from_rgb(1.0, 0.0, 0.0)),
+                    color: Some(palette::of(theme).error),
                     ..iced::widget::text::default(theme)
                 }
             })
         );
     }
+    if let Some(message) = &state.import_message {
+        form_feedback = form_feedback.push(
+            text(message).style(palette::success)
+        );
+    }
     if state.show_success_message {
         form_feedback = form_feedback.push(
             text("Student Added Successfully!").style(|theme| {
                 // Use a custom style function for success text
                 iced::widget::text::Style {
-                    color: Some(Color::from_rgb(0.0, 0.6, 0.0)),
+                    color: Some(palette::of(theme).success),
                     ..iced::widget::text::default(theme)
                 }
             })
@@ -123,7 +179,7 @@ pub fn student_manager_view<'a>(state: &'a StudentManagerState) -> Element<'a, D
     if state.students.is_empty() {
         students_rows = students_rows.push(text("No students found."));
     } else {
-        for student in &state.students {
+        for student in state.students.iter().filter(|s| s.merged_into.is_none()) {
             students_rows = students_rows.push(
                 row![
                     text(&student.name.surname).width(Length::FillPortion(2)),
@@ -156,3 +212,623 @@ pub fn student_manager_view<'a>(state: &'a StudentManagerState) -> Element<'a, D
         .height(Length::Fill)
         .into()
 }
+
+fn student_summary(student: &Student) -> Element<'_, DashboardMessage> {
+    column![
+        text(format!("{} {}", student.name.first_name, student.name.surname)).size(16),
+        text(format!(
+            "{} · {} · {}",
+            student.name.other_names.as_deref().unwrap_or("-"),
+            student.gender,
+            student.class_level,
+        ))
+        .size(12)
+        .style(|theme| iced::widget::text::secondary(theme)),
+    ]
+    .spacing(2)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Likely duplicate pairs, each with a choice of which record survives the merge
+fn duplicates_panel(state: &StudentManagerState) -> Element<'_, DashboardMessage> {
+    let student_msg = DashboardMessage::StudentManager;
+
+    let mut content = column![
+        row![
+            text("Possible Duplicate Students").size(24),
+            Space::with_width(Length::Fill),
+            button("Close")
+                .style(button::text)
+                .on_press(student_msg(StudentManagerMessage::CloseDuplicates)),
+        ]
+        .align_y(alignment::Vertical::Center),
+        text("Students in the same class with very similar names. Merging moves all payments and exemptions to the record you keep; the other is kept as merged and inactive.")
+            .size(14)
+            .style(|theme| iced::widget::text::secondary(theme)),
+    ]
+    .spacing(10)
+    .padding(20);
+
+    if let Some(error_msg) = &state.form_error_message {
+        content = content.push(text(error_msg).style(palette::error));
+    }
+    if let Some(message) = &state.import_message {
+        content = content.push(text(message).style(palette::success));
+    }
+
+    if state.duplicate_candidates.is_empty() {
+        return content.push(text("No likely duplicates found.")).into();
+    }
+
+    let pairs = state.duplicate_candidates.iter().fold(column![].spacing(10), |pairs, candidate| {
+        let (first, second) = (&candidate.first, &candidate.second);
+        let pending = state.pending_merge.filter(|(surviving, duplicate)| {
+            (*surviving == first.id && *duplicate == second.id) || (*surviving == second.id && *duplicate == first.id)
+        });
+
+        let actions: Element<'_, DashboardMessage> = match pending {
+            Some((surviving, _)) => {
+                let (keep, remove) = if surviving == first.id { (first, second) } else { (second, first) };
+                let mut confirm = button(if state.is_merging { "Merging..." } else { "Confirm Merge" })
+                    .style(button::danger);
+                if !state.is_merging {
+                    confirm = confirm.on_press(student_msg(StudentManagerMessage::ConfirmMerge));
+                }
+                row![
+                    text(format!(
+                        "Merge {} {} into {} {}?",
+                        remove.name.first_name, remove.name.surname, keep.name.first_name, keep.name.surname,
+                    ))
+                    .size(14),
+                    Space::with_width(Length::Fill),
+                    button("Cancel")
+                        .style(button::text)
+                        .on_press(student_msg(StudentManagerMessage::CancelMerge)),
+                    confirm,
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center)
+                .into()
+            }
+            None => row![
+                text(format!("{:.0}% similar", candidate.similarity * 100.0)).size(12),
+                Space::with_width(Length::Fill),
+                button("Not a Duplicate")
+                    .style(button::text)
+                    .on_press(student_msg(StudentManagerMessage::DismissDuplicate(first.id, second.id))),
+                button("Keep First")
+                    .style(button::secondary)
+                    .on_press(student_msg(StudentManagerMessage::SelectMerge(first.id, second.id))),
+                button("Keep Second")
+                    .style(button::secondary)
+                    .on_press(student_msg(StudentManagerMessage::SelectMerge(second.id, first.id))),
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center)
+            .into(),
+        };
+
+        pairs.push(
+            container(
+                column![
+                    row![student_summary(first), student_summary(second)].spacing(20),
+                    actions,
+                ]
+                .spacing(10)
+            )
+            .padding(15)
+            .style(container::bordered_box)
+            .width(Length::Fill)
+        )
+    });
+
+    content.push(scrollable(pairs).height(Length::Fill)).into()
+}
+
+/// Year-end promotion: one proposed outcome per active student, then a confirmation step
+fn promotion_panel<'a>(state: &'a StudentManagerState, wizard: &'a PromotionWizard) -> Element<'a, DashboardMessage> {
+    let student_msg = DashboardMessage::StudentManager;
+
+    let mut content = column![
+        row![
+            text(format!("Close {} and move to {}", wizard.academic_year, wizard.next_academic_year)).size(24),
+            Space::with_width(Length::Fill),
+            button("Cancel")
+                .style(button::text)
+                .on_press(student_msg(StudentManagerMessage::CancelPromotion)),
+        ]
+        .align_y(alignment::Vertical::Center),
+        text(format!(
+            "{} promoted · {} repeating · {} graduating · {} leaving",
+            wizard.count(&PromotionOutcome::Promoted),
+            wizard.count(&PromotionOutcome::Repeated),
+            wizard.count(&PromotionOutcome::Graduated),
+            wizard.count(&PromotionOutcome::Left),
+        ))
+        .size(14),
+    ]
+    .spacing(10)
+    .padding(20);
+
+    if let Some(error_msg) = &state.form_error_message {
+        content = content.push(text(error_msg).style(palette::error));
+    }
+
+    let bold = Font { weight: Weight::Bold, ..Font::default() };
+    let header = row![
+        text("Student").font(bold).width(Length::FillPortion(3)),
+        text("Current Class").font(bold).width(Length::FillPortion(2)),
+        text("Outcome").font(bold).width(Length::FillPortion(2)),
+        text("Next Year").font(bold).width(Length::FillPortion(2)),
+    ]
+    .spacing(10);
+
+    let rows = wizard.rows.iter()
+        .filter(|row| !wizard.reviewing || row.outcome != PromotionOutcome::Promoted)
+        .fold(column![].spacing(4), |rows, promotion_row| {
+            let student = &promotion_row.student;
+            let outcome: Element<'_, DashboardMessage> = if wizard.reviewing {
+                text(promotion_row.outcome.to_string()).into()
+            } else {
+                let student_id = student.id;
+                pick_list(
+                    promotion_row.options(),
+                    Some(promotion_row.outcome.clone()),
+                    move |outcome| DashboardMessage::StudentManager(StudentManagerMessage::PromotionOutcomeChanged(student_id, outcome)),
+                )
+                .into()
+            };
+            let next_year = match promotion_row.next_class_level() {
+                Some(class_level) => class_level.to_string(),
+                None => "Deactivated".to_string(),
+            };
+
+            rows.push(
+                row![
+                    text(format!("{}, {}", student.name.surname, student.name.first_name)).width(Length::FillPortion(3)),
+                    text(student.class_level.to_string()).width(Length::FillPortion(2)),
+                    container(outcome).width(Length::FillPortion(2)),
+                    text(next_year).width(Length::FillPortion(2)),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center)
+            )
+        });
+
+    let actions = if wizard.reviewing {
+        let mut apply = button(if wizard.is_submitting { "Applying..." } else { "Apply Promotion" })
+            .style(button::danger);
+        if !wizard.is_submitting {
+            apply = apply.on_press(student_msg(StudentManagerMessage::SubmitPromotion));
+        }
+        row![
+            text("Students moving up one class are not listed. Graduates and leavers will be deactivated.")
+                .size(14),
+            Space::with_width(Length::Fill),
+            button("Back")
+                .style(button::secondary)
+                .on_press(student_msg(StudentManagerMessage::EditPromotion)),
+            apply,
+        ]
+    } else {
+        row![
+            text("Mark repeaters and leavers, then review.").size(14),
+            Space::with_width(Length::Fill),
+            button("Review")
+                .style(button::primary)
+                .on_press(student_msg(StudentManagerMessage::ReviewPromotion)),
+        ]
+    }
+    .spacing(10)
+    .align_y(alignment::Vertical::Center);
+
+    content
+        .push(header)
+        .push(scrollable(rows).height(Length::Fill))
+        .push(actions)
+        .into()
+}
+
+/// Enrolment for one term: enrolled students with their dates, and the rest to pick from
+fn enrolment_panel<'a>(state: &'a StudentManagerState, panel: &'a EnrolmentPanel) -> Element<'a, DashboardMessage> {
+    let student_msg = DashboardMessage::StudentManager;
+    let format = &panel.join_date.format;
+
+    let mut content = column![
+        row![
+            text("Term Enrolment").size(24),
+            Space::with_width(Length::Fill),
+            pick_list(
+                panel.terms.clone(),
+                panel.selected_term.clone(),
+                |term| DashboardMessage::StudentManager(StudentManagerMessage::EnrolmentTermSelected(term)),
+            )
+            .placeholder("Select term..."),
+            button("Close")
+                .style(button::text)
+                .on_press(student_msg(StudentManagerMessage::CloseEnrolment)),
+        ]
+        .spacing(10)
+        .align_y(alignment::Vertical::Center),
+    ]
+    .spacing(10)
+    .padding(20);
+
+    if let Some(error_msg) = panel.error.as_ref().or(state.form_error_message.as_ref()) {
+        content = content.push(text(error_msg).style(palette::error));
+    }
+    if let Some(message) = &state.import_message {
+        content = content.push(text(message).style(palette::success));
+    }
+
+    if panel.selected_term.is_none() {
+        return content.push(text("Select a term to see who is enrolled.")).into();
+    }
+    if panel.is_loading {
+        return content.push(text("Loading enrolments...")).into();
+    }
+
+    let (enrolled, not_enrolled): (Vec<&Student>, Vec<&Student>) = state.students
+        .iter()
+        .filter(|student| student.is_active && student.merged_into.is_none())
+        .partition(|student| panel.is_enrolled(student.id));
+
+    let left = panel.enrolments.iter().filter(|e| e.left_on.is_some()).count();
+    content = content.push(
+        text(format!(
+            "{} enrolled · {} left during the term · {} not enrolled · Expected fees {}",
+            enrolled.len(),
+            left,
+            not_enrolled.len(),
+            format_money(panel.expected_fees(), &state.currency_symbol),
+        ))
+        .size(14),
+    );
+
+    let enrolled_rows = enrolled.iter().fold(column![].spacing(6), |rows, student| {
+        let Some(enrolment) = panel.enrolment_for(student.id) else {
+            return rows;
+        };
+        let action: Element<'_, DashboardMessage> = if panel.withdrawing == Some(enrolment.id) {
+            let mut confirm = button("Confirm").style(button::danger);
+            if !panel.is_saving {
+                confirm = confirm.on_press(student_msg(StudentManagerMessage::ConfirmWithdrawal));
+            }
+            row![
+                text("Left on").size(12),
+                date_input(&panel.leave_date, |s| DashboardMessage::StudentManager(StudentManagerMessage::EnrolmentLeaveDateChanged(s))),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(student_msg(StudentManagerMessage::CancelWithdrawal)),
+                confirm,
+            ]
+            .spacing(6)
+            .align_y(alignment::Vertical::Center)
+            .into()
+        } else {
+            button("Withdraw")
+                .style(button::text)
+                .on_press(student_msg(StudentManagerMessage::StartWithdrawal(enrolment.id)))
+                .into()
+        };
+
+        rows.push(
+            row![
+                column![
+                    text(format!("{}, {} ({})", student.name.surname, student.name.first_name, student.class_level)).size(14),
+                    text(format!("Joined {} · {}", format_date(enrolment.joined_on, format), format_money(enrolment.fee_amount, &state.currency_symbol)))
+                        .size(12)
+                        .style(|theme| iced::widget::text::secondary(theme)),
+                ]
+                .spacing(2)
+                .width(Length::Fill),
+                action,
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center)
+        )
+    });
+
+    // Students who left keep their enrolment for the part of the term they attended
+    let withdrawn_rows = panel.enrolments
+        .iter()
+        .filter(|e| e.left_on.is_some() && !panel.is_enrolled(e.student_id))
+        .filter_map(|e| state.students.iter().find(|s| s.id == e.student_id).map(|s| (s, e)))
+        .fold(column![].spacing(6), |rows, (student, enrolment)| {
+            rows.push(
+                text(format!(
+                    "{}, {}: {} to {} · {}",
+                    student.name.surname,
+                    student.name.first_name,
+                    format_date(enrolment.joined_on, format),
+                    enrolment.left_on.map(|d| format_date(d, format)).unwrap_or_default(),
+                    format_money(enrolment.fee_amount, &state.currency_symbol),
+                ))
+                .size(12)
+                .style(|theme| iced::widget::text::secondary(theme)),
+            )
+        });
+
+    let not_enrolled_rows = not_enrolled.iter().fold(column![].spacing(6), |rows, student| {
+        let student_id = student.id;
+        rows.push(
+            checkbox(
+                format!("{}, {} ({})", student.name.surname, student.name.first_name, student.class_level),
+                panel.selected_students.contains(&student_id),
+            )
+            .on_toggle(move |_| DashboardMessage::StudentManager(StudentManagerMessage::ToggleEnrolmentSelection(student_id)))
+            .text_size(14),
+        )
+    });
+
+    let mut enrol = button(text(if panel.is_saving {
+        "Saving...".to_string()
+    } else {
+        format!("Enrol {} Selected", panel.selected_students.len())
+    }))
+    .style(button::primary);
+    if !panel.is_saving && !panel.selected_students.is_empty() {
+        enrol = enrol.on_press(student_msg(StudentManagerMessage::EnrolSelected));
+    }
+
+    let lists = row![
+        container(
+            column![
+                text(format!("Enrolled ({})", enrolled.len())).size(18),
+                scrollable(column![enrolled_rows, withdrawn_rows].spacing(12)).height(Length::Fill),
+            ]
+            .spacing(10)
+        )
+        .padding(15)
+        .style(container::bordered_box)
+        .width(Length::FillPortion(1)),
+        container(
+            column![
+                text(format!("Not Enrolled ({})", not_enrolled.len())).size(18),
+                scrollable(not_enrolled_rows).height(Length::Fill),
+                row![
+                    text("Joining on").size(14),
+                    date_input(&panel.join_date, |s| DashboardMessage::StudentManager(StudentManagerMessage::EnrolmentJoinDateChanged(s))),
+                    Space::with_width(Length::Fill),
+                    enrol,
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            ]
+            .spacing(10)
+        )
+        .padding(15)
+        .style(container::bordered_box)
+        .width(Length::FillPortion(1)),
+    ]
+    .spacing(20)
+    .height(Length::Fill);
+
+    content.push(lists).into()
+}
+
+/// Families with their guardians' numbers and what they owe, largest balance first
+fn families_panel<'a>(state: &'a StudentManagerState, panel: &'a FamilyPanel) -> Element<'a, DashboardMessage> {
+    let student_msg = DashboardMessage::StudentManager;
+
+    let mut content = column![
+        row![
+            text("Families").size(24),
+            Space::with_width(Length::Fill),
+            text_input("Search guardians or children...", &panel.search_query)
+                .on_input(|s| DashboardMessage::StudentManager(StudentManagerMessage::FamilySearchChanged(s)))
+                .width(Length::Fixed(250.0)),
+            button("Add Guardian")
+                .style(button::primary)
+                .on_press(student_msg(StudentManagerMessage::NewGuardian)),
+            button("Close")
+                .style(button::text)
+                .on_press(student_msg(StudentManagerMessage::CloseFamilies)),
+        ]
+        .spacing(10)
+        .align_y(alignment::Vertical::Center),
+    ]
+    .spacing(10)
+    .padding(20);
+
+    if let Some(error_msg) = &panel.error {
+        content = content.push(text(error_msg).style(palette::error));
+    }
+    if panel.is_loading {
+        return content.push(text("Loading guardians...")).into();
+    }
+
+    let query = panel.search_query.trim().to_lowercase();
+    let families: Vec<Family<'_>> = families::group_families(&panel.guardians, &state.students)
+        .into_iter()
+        .filter(|family| {
+            query.is_empty()
+                || family.guardians.iter().any(|g| format!("{} {} {}", g.name.first_name, g.name.surname, g.phone_number).to_lowercase().contains(&query))
+                || family.children.iter().any(|c| format!("{} {}", c.name.first_name, c.name.surname).to_lowercase().contains(&query))
+        })
+        .collect();
+
+    let linked = panel.guardians.iter().flat_map(|g| g.student_ids.iter()).collect::<std::collections::HashSet<_>>();
+    let without_guardian = state.students
+        .iter()
+        .filter(|s| s.is_active && s.merged_into.is_none() && !linked.contains(&s.id))
+        .count();
+    content = content.push(
+        text(format!(
+            "{} families · {} outstanding · {} active students have no guardian on record",
+            families.len(),
+            format_money(families.iter().map(|f| f.combined_balance()).sum::<f64>(), &state.currency_symbol),
+            without_guardian,
+        ))
+        .size(14),
+    );
+
+    if families.is_empty() {
+        return content.push(text("No families match. Add a guardian and link their children to start one.")).into();
+    }
+
+    let cards = families.iter().fold(column![].spacing(10), |cards, family| {
+        let balance = family.combined_balance();
+        let primary_id = family.primary_contact().map(|g| g.id);
+
+        let guardians = family.guardians.iter().fold(column![].spacing(4), |col, guardian| {
+            let phones = match &guardian.alternate_phone {
+                Some(alternate) => format!("{} / {}", guardian.phone_number, alternate),
+                None => guardian.phone_number.clone(),
+            };
+            col.push(
+                row![
+                    text(format!(
+                        "{} {} ({}){}",
+                        guardian.name.first_name,
+                        guardian.name.surname,
+                        guardian.relationship,
+                        if Some(guardian.id) == primary_id { " · call first" } else { "" },
+                    ))
+                    .size(14),
+                    Space::with_width(Length::Fill),
+                    text(phones).size(14),
+                    button("Edit")
+                        .style(button::text)
+                        .on_press(DashboardMessage::StudentManager(StudentManagerMessage::EditGuardian(guardian.id))),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            )
+        });
+
+        let children = family.children.iter().fold(column![].spacing(2), |col, child| {
+            col.push(
+                row![
+                    text(format!("{} {} · {}", child.name.first_name, child.name.surname, child.class_level)).size(12),
+                    Space::with_width(Length::Fill),
+                    text(format!("{} · {} owed", child.payment_status, format_money(child.balance(), &state.currency_symbol))).size(12),
+                ]
+            )
+        });
+
+        cards.push(
+            container(
+                column![
+                    row![
+                        text(format!("{} family", family.surnames())).size(16),
+                        Space::with_width(Length::Fill),
+                        text(format!("{} children · {} owed", family.children.len(), format_money(balance, &state.currency_symbol)))
+                            .size(14)
+                            .style(move |theme| if balance > 0.0 { palette::error(theme) } else { palette::success(theme) }),
+                    ]
+                    .align_y(alignment::Vertical::Center),
+                    guardians,
+                    children,
+                ]
+                .spacing(8)
+            )
+            .padding(15)
+            .style(container::bordered_box)
+            .width(Length::Fill)
+        )
+    });
+
+    content.push(scrollable(cards).height(Length::Fill)).into()
+}
+
+/// Guardian details and the children they are responsible for
+fn guardian_form_view<'a>(state: &'a StudentManagerState, panel: &'a FamilyPanel, form: &'a GuardianForm) -> Element<'a, DashboardMessage> {
+    let student_msg = DashboardMessage::StudentManager;
+
+    let mut content = column![
+        text(if form.editing_id.is_some() { "Edit Guardian" } else { "Add Guardian" }).size(24),
+        row![
+            text_input("First Name", &form.first_name)
+                .on_input(|s| DashboardMessage::StudentManager(StudentManagerMessage::GuardianFirstNameChanged(s)))
+                .padding(10),
+            text_input("Surname", &form.surname)
+                .on_input(|s| DashboardMessage::StudentManager(StudentManagerMessage::GuardianSurnameChanged(s)))
+                .padding(10),
+        ]
+        .spacing(10),
+        row![
+            text("Relationship:"),
+            pick_list(
+                GuardianRelationship::ALL.to_vec(),
+                form.relationship.clone(),
+                |relationship| DashboardMessage::StudentManager(StudentManagerMessage::GuardianRelationshipSelected(relationship)),
+            )
+            .placeholder("Select...")
+            .width(Length::Fixed(200.0)),
+            checkbox("Primary contact for fees", form.is_primary_contact)
+                .on_toggle(|checked| DashboardMessage::StudentManager(StudentManagerMessage::GuardianPrimaryToggled(checked))),
+        ]
+        .spacing(10)
+        .align_y(alignment::Vertical::Center),
+        row![
+            text_input("Phone Number", &form.phone_number)
+                .on_input(|s| DashboardMessage::StudentManager(StudentManagerMessage::GuardianPhoneChanged(s)))
+                .padding(10),
+            text_input("Alternate Phone (Optional)", &form.alternate_phone)
+                .on_input(|s| DashboardMessage::StudentManager(StudentManagerMessage::GuardianAlternatePhoneChanged(s)))
+                .padding(10),
+        ]
+        .spacing(10),
+        row![
+            text(format!("Children ({} linked)", form.student_ids.len())).size(18),
+            Space::with_width(Length::Fill),
+            text_input("Search students...", &form.student_search)
+                .on_input(|s| DashboardMessage::StudentManager(StudentManagerMessage::GuardianStudentSearchChanged(s)))
+                .width(Length::Fixed(250.0)),
+        ]
+        .align_y(alignment::Vertical::Center),
+    ]
+    .spacing(10)
+    .padding(20);
+
+    // Linked children first, so they stay visible while searching for siblings
+    let query = form.student_search.trim().to_lowercase();
+    let mut students: Vec<&Student> = state.students
+        .iter()
+        .filter(|s| s.merged_into.is_none())
+        .filter(|s| {
+            form.student_ids.contains(&s.id)
+                || query.is_empty()
+                || format!("{} {}", s.name.first_name, s.name.surname).to_lowercase().contains(&query)
+        })
+        .collect();
+    students.sort_by_key(|s| !form.student_ids.contains(&s.id));
+
+    let student_list = students.iter().fold(column![].spacing(6), |col, student| {
+        let student_id = student.id;
+        col.push(
+            checkbox(
+                format!("{}, {} ({})", student.name.surname, student.name.first_name, student.class_level),
+                form.student_ids.contains(&student_id),
+            )
+            .on_toggle(move |_| DashboardMessage::StudentManager(StudentManagerMessage::ToggleGuardianChild(student_id)))
+            .text_size(14),
+        )
+    });
+    content = content.push(scrollable(student_list).height(Length::Fill));
+
+    if let Some(error_msg) = &panel.error {
+        content = content.push(text(error_msg).style(palette::error));
+    }
+
+    let mut save = button(if form.is_saving { "Saving..." } else { "Save Guardian" })
+        .style(button::primary);
+    if !form.is_saving {
+        save = save.on_press(student_msg(StudentManagerMessage::SaveGuardian));
+    }
+
+    content
+        .push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(student_msg(StudentManagerMessage::CancelGuardianForm)),
+                save,
+            ]
+            .spacing(10),
+        )
+        .into()
+}
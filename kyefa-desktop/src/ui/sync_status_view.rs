@@ -0,0 +1,138 @@
+use iced::{Element, Length, Padding, Theme};
+use iced::widget::{column, row, text, button, container, scrollable, Space};
+use iced::alignment::Vertical;
+use chrono::{DateTime, Local, Utc};
+
+use crate::app::{SyncStatus, Message, DashboardMessage};
+use crate::offline::SyncState;
+use crate::ui::date_input::format_date;
+use crate::ui::palette;
+
+fn local_time(time: DateTime<Utc>, date_format: &str) -> String {
+    let local = time.with_timezone(&Local);
+    format!("{} {}", format_date(local.date_naive(), date_format), local.format("%H:%M"))
+}
+
+/// Sidebar line showing whether anything is waiting to reach the server
+pub fn sync_indicator(sync: &SyncStatus) -> Element<'_, Message> {
+    let (label, style): (String, fn(&Theme) -> text::Style) = match (sync.conflict_count(), sync.pending_count()) {
+        (0, 0) if sync.is_offline => ("Offline".to_string(), palette::warning),
+        (0, 0) => ("All changes synced".to_string(), text::default),
+        (0, pending) if sync.is_syncing => (format!("Syncing {} changes...", pending), palette::warning),
+        (0, pending) => (format!("{} changes waiting to sync", pending), palette::warning),
+        (conflicts, _) => (format!("{} changes need attention", conflicts), palette::error),
+    };
+
+    button(text(label).size(12).style(style))
+        .style(button::text)
+        .padding(0)
+        .on_press(Message::Dashboard(DashboardMessage::ShowSyncStatus))
+        .into()
+}
+
+/// Writes saved on this computer that haven't reached the server, with the
+/// server's reason for any it turned down
+pub fn sync_status_view<'a>(sync: &'a SyncStatus, date_format: &str, currency_symbol: &str) -> Element<'a, Message> {
+    let last_synced = match sync.store.last_synced {
+        Some(time) => format!("Last synced {}", local_time(time, date_format)),
+        None => "Not synced yet".to_string(),
+    };
+
+    let mut sync_now = button(if sync.is_syncing { "Syncing..." } else { "Sync Now" })
+        .style(button::primary);
+    if !sync.is_syncing {
+        sync_now = sync_now.on_press(Message::Dashboard(DashboardMessage::SyncNow));
+    }
+
+    let header = row![
+        text("Sync Status").size(24),
+        Space::with_width(Length::Fill),
+        button("Close")
+            .style(button::text)
+            .on_press(Message::Dashboard(DashboardMessage::CloseSyncStatus)),
+        sync_now,
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center);
+
+    let mut summary = column![
+        text(format!(
+            "{} waiting, {} turned down by the server. {}.",
+            sync.pending_count(),
+            sync.conflict_count(),
+            last_synced,
+        ))
+        .size(14),
+    ]
+    .spacing(5);
+    if sync.is_offline {
+        summary = summary.push(
+            text("The server can't be reached. Changes are kept on this computer and sent when it is back.")
+                .size(14)
+                .style(palette::warning),
+        );
+    }
+    if let Some(message) = &sync.message {
+        summary = summary.push(text(message).size(14));
+    }
+
+    let writes: Element<'_, Message> = if sync.store.queue.is_empty() {
+        container(text("Nothing is waiting to sync.").size(16))
+            .center_x(Length::Fill)
+            .padding(40)
+            .into()
+    } else {
+        let rows = sync.store.queue.iter().fold(column![].spacing(10), |rows, pending| {
+            let mut details = column![
+                text(pending.write.describe(currency_symbol)).size(16),
+                text(format!("Saved {}, {} attempts", local_time(pending.queued_at, date_format), pending.attempts))
+                    .size(12)
+                    .style(|theme| iced::widget::text::secondary(theme)),
+            ]
+            .spacing(4)
+            .width(Length::Fill);
+
+            let mut actions = row![].spacing(10).align_y(Vertical::Center);
+            match &pending.state {
+                SyncState::Pending => {
+                    if let Some(error) = &pending.last_error {
+                        details = details.push(text(format!("Last try: {}", error)).size(12).style(palette::warning));
+                    }
+                    let waiting = match pending.is_for_session() {
+                        true => "Waiting",
+                        false => "Held for the user and school it was made in",
+                    };
+                    actions = actions.push(text(waiting).size(12).style(palette::warning));
+                }
+                SyncState::Conflict(reason) => {
+                    details = details.push(text(reason).size(12).style(palette::error));
+                    actions = actions
+                        .push(
+                            button("Retry")
+                                .style(button::secondary)
+                                .on_press(Message::Dashboard(DashboardMessage::RetryWrite(pending.id))),
+                        )
+                        .push(
+                            button("Discard")
+                                .style(button::danger)
+                                .on_press(Message::Dashboard(DashboardMessage::DiscardWrite(pending.id))),
+                        );
+                }
+            }
+
+            rows.push(
+                container(row![details, actions].spacing(10).align_y(Vertical::Center))
+                    .padding(15)
+                    .style(container::bordered_box)
+                    .width(Length::Fill),
+            )
+        });
+        scrollable(rows).height(Length::Fill).into()
+    };
+
+    container(column![header, summary, writes].spacing(15))
+        .padding(Padding::from(20))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
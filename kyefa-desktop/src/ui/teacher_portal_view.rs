@@ -0,0 +1,266 @@
+use iced::{Element, Length, Alignment, Padding};
+use iced::widget::{column, row, text, button, container, scrollable, text_input, Space};
+use iced::alignment::Vertical;
+use kyefa_models::{format_money, PeriodVerification, Payslip, ProjectedShare, TeacherPeriod};
+
+use crate::app::{TeacherPortalState, TeacherPortalMessage, Message, DashboardMessage};
+use crate::ui::date_input::format_date;
+use crate::ui::palette;
+
+fn portal_msg(message: TeacherPortalMessage) -> Message {
+    Message::Dashboard(DashboardMessage::TeacherPortal(message))
+}
+
+/// What a teacher sees of their own work: periods and their verification,
+/// the projected share for the term and past payslips
+pub fn teacher_portal_view(state: &TeacherPortalState) -> Element<'_, Message> {
+    if state.disputing.is_some() {
+        return dispute_dialog_view(state);
+    }
+
+    let term = state.portal.as_ref()
+        .and_then(|portal| portal.term_name.as_deref())
+        .map(|term| format!("Current term: {}", term))
+        .unwrap_or_else(|| "No active term".to_string());
+    let header = row![
+        column![
+            text("My Teaching").size(24),
+            text(term).size(14).style(|theme| iced::widget::text::secondary(theme)),
+        ]
+        .spacing(5),
+        Space::with_width(Length::Fill),
+        button("Refresh")
+            .style(button::secondary)
+            .on_press(portal_msg(TeacherPortalMessage::LoadPortal)),
+    ]
+    .align_y(Vertical::Center);
+
+    let mut content = column![header].spacing(15).padding(20);
+    if let Some(error) = &state.error_message {
+        content = content.push(text(error).size(14).style(palette::error));
+    } else if let Some(message) = &state.message {
+        content = content.push(text(message).size(14).style(palette::success));
+    }
+
+    let Some(portal) = &state.portal else {
+        let placeholder = if state.is_loading { "Loading your periods..." } else { "Your periods are unavailable" };
+        return content.push(text(placeholder).size(16)).into();
+    };
+
+    let share: Element<'_, Message> = match &portal.projected_share {
+        Some(share) => share_cards(share, &state.currency_symbol),
+        None => text("No projection yet: there is no active term or share configuration.")
+            .size(14)
+            .style(|theme| iced::widget::text::secondary(theme))
+            .into(),
+    };
+
+    let periods: Element<'_, Message> = if portal.periods.is_empty() {
+        text("No periods logged this term").size(14)
+            .style(|theme| iced::widget::text::secondary(theme))
+            .into()
+    } else {
+        portal.periods
+            .iter()
+            .fold(column![], |col, period| col.push(period_row(period, &state.date_format)))
+            .spacing(8)
+            .into()
+    };
+
+    let payslips: Element<'_, Message> = if portal.payslips.is_empty() {
+        text("No payouts yet").size(14)
+            .style(|theme| iced::widget::text::secondary(theme))
+            .into()
+    } else {
+        portal.payslips
+            .iter()
+            .fold(column![], |col, payslip| col.push(payslip_row(payslip, &state.date_format, &state.currency_symbol)))
+            .spacing(8)
+            .into()
+    };
+
+    content
+        .push(share)
+        .push(scrollable(
+            column![
+                text("Periods This Term").size(20),
+                periods,
+                Space::with_height(Length::Fixed(10.0)),
+                text("Payslips").size(20),
+                payslips,
+            ]
+            .spacing(10),
+        ).height(Length::Fill))
+        .into()
+}
+
+fn share_card<'a>(title: &'a str, value: String, detail: Option<String>) -> Element<'a, Message> {
+    let mut content = column![
+        text(title).size(12).style(|theme| iced::widget::text::secondary(theme)),
+        text(value).size(24),
+    ]
+    .spacing(8)
+    .align_x(Alignment::Center);
+    if let Some(detail) = detail {
+        content = content.push(text(detail).size(12).style(|theme| iced::widget::text::secondary(theme)));
+    }
+    container(content)
+        .padding(20)
+        .style(container::bordered_box)
+        .width(Length::Fill)
+        .into()
+}
+
+fn share_cards<'a>(share: &'a ProjectedShare, currency_symbol: &str) -> Element<'a, Message> {
+    let periods_detail = match share.max_periods_paid {
+        Some(max) => format!("paid up to {} periods", max),
+        None => "verified periods only".to_string(),
+    };
+    let period_detail = if share.capped {
+        "reduced by the payout cap".to_string()
+    } else {
+        "for verified periods".to_string()
+    };
+    row![
+        share_card("Projected Share", format_money(share.total(), currency_symbol), Some(format!("under {}", share.constraint_name))),
+        share_card("Base Share", format_money(share.base_share, currency_symbol), None),
+        share_card("Period Share", format_money(share.period_share, currency_symbol), Some(period_detail)),
+        share_card("Verified Periods", share.verified_periods.to_string(), Some(periods_detail)),
+    ]
+    .spacing(15)
+    .into()
+}
+
+pub(crate) fn verification_style(verification: &PeriodVerification) -> fn(&iced::Theme) -> iced::widget::text::Style {
+    match verification {
+        PeriodVerification::Verified => palette::success,
+        PeriodVerification::Pending => palette::info,
+        PeriodVerification::Rejected => palette::error,
+        PeriodVerification::Disputed => palette::warning,
+    }
+}
+
+fn period_row<'a>(period: &'a TeacherPeriod, date_format: &str) -> Element<'a, Message> {
+    let mut details = column![
+        row![
+            text(format_date(period.date, date_format)).size(14).width(Length::Fixed(110.0)),
+            text(format!("{} - {}", period.subject, period.class_level)).size(14),
+            Space::with_width(Length::Fill),
+            text(format!("Week {}", period.week_of_term)).size(12)
+                .style(|theme| iced::widget::text::secondary(theme)),
+            text(period.verification.to_string()).size(14).style(verification_style(&period.verification)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+    ]
+    .spacing(4);
+    if let Some(reason) = &period.rejection_reason {
+        details = details.push(text(format!("Rejected: {}", reason)).size(12).style(palette::error));
+    }
+    if let Some(reason) = &period.dispute_reason {
+        details = details.push(text(format!("Your dispute: {}", reason)).size(12)
+            .style(|theme| iced::widget::text::secondary(theme)));
+    }
+    if period.verification == PeriodVerification::Rejected {
+        details = details.push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Dispute")
+                    .style(button::secondary)
+                    .on_press(portal_msg(TeacherPortalMessage::ShowDispute(period.id))),
+            ]
+        );
+    }
+
+    container(details)
+        .padding(Padding::from([8, 12]))
+        .style(container::bordered_box)
+        .width(Length::Fill)
+        .into()
+}
+
+fn payslip_row<'a>(payslip: &'a Payslip, date_format: &str, currency_symbol: &str) -> Element<'a, Message> {
+    container(
+        row![
+            column![
+                text(&payslip.term_name).size(14),
+                text(format!(
+                    "Calculated {} · {} periods{}",
+                    format_date(payslip.calculated_on.date(), date_format),
+                    payslip.periods_paid,
+                    if payslip.capped { " · capped" } else { "" },
+                ))
+                .size(12)
+                .style(|theme| iced::widget::text::secondary(theme)),
+            ]
+            .spacing(4),
+            Space::with_width(Length::Fill),
+            text(format_money(payslip.total(), currency_symbol)).size(16),
+            button("Download Payslip")
+                .style(button::text)
+                .on_press(portal_msg(TeacherPortalMessage::ExportPayslip(payslip.payout_id))),
+        ]
+        .spacing(15)
+        .align_y(Vertical::Center)
+    )
+    .padding(Padding::from([8, 12]))
+    .style(container::bordered_box)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Asks the committee to look at a rejected period again
+fn dispute_dialog_view(state: &TeacherPortalState) -> Element<'_, Message> {
+    let period = state.disputed_period();
+    let summary = period
+        .map(|period| format!(
+            "{} - {} on {}",
+            period.subject,
+            period.class_level,
+            format_date(period.date, &state.date_format),
+        ))
+        .unwrap_or_default();
+    let rejection = period
+        .and_then(|period| period.rejection_reason.clone())
+        .unwrap_or_else(|| "No reason given".to_string());
+
+    let mut fields = column![
+        text("Dispute Period").size(24),
+        text(summary).size(16),
+        text(format!("Rejected: {}", rejection)).size(14).style(palette::error),
+        row![
+            text("Reason:").width(Length::Fixed(120.0)),
+            text_input("e.g. the register was signed by the class prefect", &state.dispute_reason)
+                .on_input(|s| portal_msg(TeacherPortalMessage::UpdateDisputeReason(s)))
+                .width(Length::Fill),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center),
+    ]
+    .spacing(15);
+
+    if let Some(error) = &state.error_message {
+        fields = fields.push(text(error).size(14).style(palette::error));
+    }
+
+    let mut send = button(if state.is_saving { "Sending..." } else { "Send Dispute" }).style(button::primary);
+    if !state.is_saving {
+        send = send.on_press(portal_msg(TeacherPortalMessage::SubmitDispute));
+    }
+
+    container(
+        fields.push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(portal_msg(TeacherPortalMessage::CancelDispute)),
+                send,
+            ]
+            .spacing(10),
+        )
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .into()
+}
@@ -1,12 +1,24 @@
-use iced::{Element, Length, Color, Alignment, Padding};
+use iced::{Element, Length, Alignment, Padding};
 use iced::widget::{
     column, row, text, button, container, 
     text_input, scrollable, pick_list, Space
 };
 use iced::alignment::{Horizontal, Vertical};
 use crate::app::{TeachingPeriodManagerState, Message, DashboardMessage, TeachingPeriodMessage};
+use crate::ui::import_preview::import_preview_view;
+use crate::ui::palette;
+use crate::ui::date_input::format_date;
+use kyefa_models::format_money;
+
+fn period_message(message: TeachingPeriodMessage) -> Message {
+    Message::Dashboard(DashboardMessage::TeachingPeriod(message))
+}
 
 pub fn teaching_period_view(state: &TeachingPeriodManagerState) -> Element<'_, Message> {
+    if state.show_import_dialog {
+        return import_panel(state);
+    }
+
     let header = row![
         text("Teaching Period Management").size(24),
         Space::with_width(Length::Fill),
@@ -59,11 +71,11 @@ pub fn teaching_period_view(state: &TeachingPeriodManagerState) -> Element<'_, M
                         row![
                             text(format!("{} - {}", period.subject, period.class)).size(16),
                             Space::with_width(Length::Fill),
-                            text(format!("₵{:.2}", period.rate)).size(14)
+                            text(format_money(period.rate, &state.currency_symbol)).size(14)
                                 .style(|theme| iced::widget::text::secondary(theme)),
                         ],
                         row![
-                            text(format!("Date: {}", period.date.format("%d/%m/%Y"))).size(12),
+                            text(format!("Date: {}", format_date(period.date, &state.new_period_date.format))).size(12),
                             Space::with_width(Length::Fixed(20.0)),
                             text(format!("Time: {}:00 - {}:00", period.start_time, period.end_time)).size(12),
                         ],
@@ -115,3 +127,73 @@ pub fn teaching_period_view(state: &TeachingPeriodManagerState) -> Element<'_, M
         content.into()
     }
 }
+
+/// Register import: template download, file choice, then the row preview
+fn import_panel(state: &TeachingPeriodManagerState) -> Element<'_, Message> {
+    let mut content = column![
+        row![
+            text("Import Teaching Periods").size(24),
+            Space::with_width(Length::Fill),
+            button("Close")
+                .style(button::text)
+                .on_press(period_message(TeachingPeriodMessage::CancelImport)),
+        ]
+        .align_y(Vertical::Center),
+    ]
+    .spacing(10)
+    .padding(20);
+
+    if let Some(error) = &state.error_message {
+        content = content.push(text(error).size(14).style(palette::error));
+    }
+    if let Some(message) = &state.import_message {
+        content = content.push(text(message).size(14).style(palette::success));
+    }
+
+    let Some(context) = &state.import_context else {
+        return content.push(text("Loading teachers, subjects and the active term...").size(14)).into();
+    };
+
+    if let Some(preview) = &state.import_preview {
+        return content
+            .push(import_preview_view(
+                preview,
+                state.is_importing,
+                |msg| period_message(TeachingPeriodMessage::ImportPreview(msg)),
+                period_message(TeachingPeriodMessage::ConfirmImport),
+                period_message(TeachingPeriodMessage::CancelImport),
+            ))
+            .into();
+    }
+
+    content
+        .push(
+            container(
+                column![
+                    text(format!("Periods will be recorded against {}.", context.term.name)).size(16),
+                    text(format!(
+                        "Dates must fall between {} and {} and be entered as {}.",
+                        format_date(context.term.start_date.date(), &context.date_format),
+                        format_date(context.term.end_date.date(), &context.date_format),
+                        context.date_format,
+                    ))
+                    .size(14)
+                    .style(|theme| iced::widget::text::secondary(theme)),
+                    row![
+                        button("Download Register Template")
+                            .style(button::secondary)
+                            .on_press(period_message(TeachingPeriodMessage::DownloadImportTemplate)),
+                        button("Choose Filled Register...")
+                            .style(button::primary)
+                            .on_press(period_message(TeachingPeriodMessage::ChooseImportFile)),
+                    ]
+                    .spacing(10),
+                ]
+                .spacing(10)
+                .padding(Padding::from(15)),
+            )
+            .style(container::bordered_box)
+            .width(Length::Fill),
+        )
+        .into()
+}
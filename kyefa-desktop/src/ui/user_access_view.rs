@@ -1,33 +1,92 @@
-use iced::{Element, Length, Color, Alignment, Padding};
+use iced::{Element, Length, Alignment, Padding};
 use iced::widget::{
-    column, row, text, button, container, 
-    text_input, scrollable, pick_list, Space
+    column, row, text, button, container,
+    text_input, scrollable, pick_list, checkbox, Space
 };
-use iced::alignment::{Horizontal, Vertical};
+use iced::alignment::Vertical;
+use chrono::{DateTime, Local, Utc};
+use kyefa_models::{LoginAttempt, UserResponse, UserRole};
 use crate::app::{UserAccessManagerState, Message, DashboardMessage, UserAccessMessage};
-use kyefa_models::UserRole;
+use crate::ui::date_input::format_date;
+use crate::ui::palette;
+
+fn user_access_msg(message: UserAccessMessage) -> Message {
+    Message::Dashboard(DashboardMessage::UserAccess(message))
+}
+
+fn badge<'a>(label: String, color: impl Fn(&palette::Palette) -> iced::Color + Clone + 'static) -> Element<'a, Message> {
+    let border_color = color.clone();
+    container(
+        text(label).size(12)
+            .style(move |theme| iced::widget::text::Style {
+                color: Some(color(&palette::of(theme))),
+            })
+    )
+    .padding(Padding::from([4, 8]))
+    .style(move |theme| container::Style {
+        border: iced::Border {
+            color: border_color(&palette::of(theme)),
+            width: 1.0,
+            radius: 4.0.into(),
+        },
+        ..container::transparent(theme)
+    })
+    .into()
+}
+
+fn format_time(time: DateTime<Utc>, date_format: &str) -> String {
+    let local = time.with_timezone(&Local);
+    format!("{} {}", format_date(local.date_naive(), date_format), local.format("%H:%M"))
+}
+
+pub fn user_access_view<'a>(state: &'a UserAccessManagerState, date_format: &str) -> Element<'a, Message> {
+    if state.show_login_history {
+        return login_history_view(state, date_format);
+    }
+    if state.show_add_user_dialog || state.show_edit_user_dialog {
+        return user_dialog_view(state);
+    }
+    if state.show_deactivate_confirmation || state.show_reset_confirmation {
+        return confirmation_view(state);
+    }
 
-pub fn user_access_view(state: &UserAccessManagerState) -> Element<'_, Message> {
     let header = row![
         text("User Access Management").size(24),
         Space::with_width(Length::Fill),
+        button("Login History")
+            .style(button::secondary)
+            .on_press(user_access_msg(UserAccessMessage::ShowLoginHistory(None))),
         button("Add New User")
             .style(button::primary)
-            .on_press(Message::Dashboard(DashboardMessage::UserAccess(UserAccessMessage::ShowAddUserDialog))),
+            .on_press(user_access_msg(UserAccessMessage::ShowAddUserDialog)),
     ]
     .spacing(10)
     .align_y(Vertical::Center);
 
     let search_bar = row![
         text_input("Search users...", &state.search_query)
-            .on_input(|s| Message::Dashboard(DashboardMessage::UserAccess(UserAccessMessage::UpdateSearchQuery(s))))
+            .on_input(|s| user_access_msg(UserAccessMessage::UpdateSearchQuery(s)))
             .width(Length::Fixed(300.0)),
         Space::with_width(Length::Fill),
+        checkbox("Show deactivated", state.show_inactive)
+            .on_toggle(|show| user_access_msg(UserAccessMessage::ToggleShowInactive(show))),
     ]
     .spacing(10)
     .align_y(Vertical::Center);
 
-    let users_list: Element<'_, Message> = if state.users.is_empty() {
+    let query = state.search_query.to_lowercase();
+    let visible: Vec<&UserResponse> = state.users
+        .iter()
+        .filter(|user| state.show_inactive || user.is_active)
+        .filter(|user| {
+            query.is_empty()
+                || format!("{} {} {}", user.first_name, user.surname, user.username)
+                    .to_lowercase()
+                    .contains(&query)
+        })
+        .collect();
+
+    let users_list: Element<'_, Message> = if visible.is_empty() {
         container(
             column![
                 text("No users found").size(18),
@@ -41,84 +100,23 @@ pub fn user_access_view(state: &UserAccessManagerState) -> Element<'_, Message>
         .center_y(Length::Fill)
         .into()
     } else {
-        let users = state.users
-            .iter()
-            .filter(|user| {
-                if state.search_query.is_empty() {
-                    true
-                } else {
-                    format!("{} {} {}", user.name.first_name, user.name.surname, user.username)
-                        .to_lowercase()
-                        .contains(&state.search_query.to_lowercase())
-                }
-            })
-            .fold(column![], |col, user| {
-                let role_color = match user.role {
-                    UserRole::Admin => Color::from_rgb(0.8, 0.2, 0.2),
-                    UserRole::Staff => Color::from_rgb(0.2, 0.6, 0.9),
-                    UserRole::Teacher => Color::from_rgb(0.2, 0.8, 0.2),
-                    _ => Color::from_rgb(0.5, 0.5, 0.5),
-                };
-
-                let user_card = container(
-                    column![
-                        row![
-                            text(format!("{} {}", user.name.first_name, user.name.surname)).size(16),
-                            Space::with_width(Length::Fill),
-                            container(
-                                text(format!("{:?}", user.role)).size(12)
-                                    .style(move |_| iced::widget::text::Style {
-                                        color: Some(role_color),
-                                    })
-                            )
-                            .padding(Padding::from([4, 8]))
-                            .style(move |theme| container::Style {
-                                border: iced::Border {
-                                    color: role_color,
-                                    width: 1.0,
-                                    radius: 4.0.into(),
-                                },
-                                ..container::transparent(theme)
-                            }),
-                        ],
-                        row![
-                            text(format!("Username: {}", user.username)).size(12)
-                                .style(|theme| iced::widget::text::secondary(theme)),
-                            Space::with_width(Length::Fill),
-                            button("Edit")
-                                .style(button::text)
-                                .on_press(Message::Dashboard(DashboardMessage::UserAccess(UserAccessMessage::EditUser(user.id.to_string())))),
-                            button("Reset Password")
-                                .style(button::secondary)
-                                .on_press(Message::Dashboard(DashboardMessage::UserAccess(UserAccessMessage::ResetUserPassword(user.id.to_string())))),
-                            button("Delete")
-                                .style(button::danger)
-                                .on_press(Message::Dashboard(DashboardMessage::UserAccess(UserAccessMessage::DeleteUser(user.id.to_string())))),
-                        ]
-                        .spacing(10),
-                    ]
-                    .spacing(8)
-                    .padding(15)
-                )
-                .style(container::bordered_box)
-                .width(Length::Fill);
-
-                col.push(user_card)
-            })
+        let users = visible
+            .into_iter()
+            .fold(column![], |col, user| col.push(user_card(state, user, date_format)))
             .spacing(10);
 
         scrollable(users).height(Length::Fill).into()
     };
 
-    let content = column![
-        header,
-        Space::with_height(Length::Fixed(20.0)),
-        search_bar,
-        Space::with_height(Length::Fixed(20.0)),
-        users_list,
-    ]
-    .spacing(10)
-    .padding(20);
+    let mut content = column![header, Space::with_height(Length::Fixed(20.0))].spacing(10).padding(20);
+    if let Some((username, password)) = &state.temporary_password {
+        content = content.push(temporary_password_notice(username, password));
+    }
+    if let Some(error) = &state.error_message {
+        content = content.push(text(error).size(14).style(palette::error));
+    } else if let Some(message) = &state.message {
+        content = content.push(text(message).size(14).style(palette::success));
+    }
 
     if state.is_loading {
         container(
@@ -129,6 +127,329 @@ pub fn user_access_view(state: &UserAccessManagerState) -> Element<'_, Message>
         .center_y(Length::Fill)
         .into()
     } else {
-        content.into()
+        content
+            .push(search_bar)
+            .push(Space::with_height(Length::Fixed(20.0)))
+            .push(users_list)
+            .into()
+    }
+}
+
+fn user_card<'a>(state: &UserAccessManagerState, user: &'a UserResponse, date_format: &str) -> Element<'a, Message> {
+    let role = user.role.clone();
+    let mut title = row![
+        text(format!("{} {}", user.first_name, user.surname)).size(16),
+        Space::with_width(Length::Fill),
+    ]
+    .spacing(8);
+    if !user.is_active {
+        title = title.push(badge("Deactivated".to_string(), |palette| palette.faint));
+    }
+    if let Some(until) = user.locked_until.filter(|until| *until > Utc::now()) {
+        title = title.push(badge(format!("Locked until {}", format_time(until, date_format)), |palette| palette.error));
+    }
+    if user.must_change_password {
+        title = title.push(badge("Must change password".to_string(), |palette| palette.warning));
+    }
+    title = title.push(badge(role.to_string(), move |palette| palette.role(&role)));
+
+    let id = user.id.to_string();
+    let mut actions = row![
+        text(format!("Username: {}", user.username)).size(12)
+            .style(|theme| iced::widget::text::secondary(theme)),
+        Space::with_width(Length::Fill),
+    ]
+    .spacing(10);
+    if user.is_active {
+        let mut deactivate = button("Deactivate").style(button::danger);
+        if !state.is_last_admin(user) {
+            deactivate = deactivate.on_press(user_access_msg(UserAccessMessage::DeactivateUser(id.clone())));
+        }
+        actions = actions.push(
+            button("History")
+                .style(button::text)
+                .on_press(user_access_msg(UserAccessMessage::ShowLoginHistory(Some(user.username.clone())))),
+        )
+        .push(
+            button("Edit")
+                .style(button::text)
+                .on_press(user_access_msg(UserAccessMessage::EditUser(id.clone()))),
+        )
+        .push(
+            button("Reset Password")
+                .style(button::secondary)
+                .on_press(user_access_msg(UserAccessMessage::ResetUserPassword(id))),
+        )
+        .push(deactivate);
+    } else {
+        let mut reactivate = button("Reactivate").style(button::secondary);
+        if !state.is_saving {
+            reactivate = reactivate.on_press(user_access_msg(UserAccessMessage::ReactivateUser(id)));
+        }
+        actions = actions.push(reactivate);
     }
+
+    container(
+        column![title, actions]
+            .spacing(8)
+            .padding(15)
+    )
+    .style(container::bordered_box)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Shown once after a reset; the password isn't kept anywhere else
+fn temporary_password_notice<'a>(username: &'a str, password: &'a str) -> Element<'a, Message> {
+    container(
+        row![
+            column![
+                text(format!("Temporary password for {}", username)).size(16),
+                text(password).size(20).font(iced::Font::MONOSPACE),
+                text("Pass this on to them now; it won't be shown again. They'll choose a new password when they next log in.")
+                    .size(12)
+                    .style(|theme| iced::widget::text::secondary(theme)),
+            ]
+            .spacing(6),
+            Space::with_width(Length::Fill),
+            button("Done")
+                .style(button::primary)
+                .on_press(user_access_msg(UserAccessMessage::DismissTemporaryPassword)),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center)
+    )
+    .padding(15)
+    .style(container::bordered_box)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Names, username and role of a new or existing user, plus a first password
+/// for new ones
+fn user_dialog_view(state: &UserAccessManagerState) -> Element<'_, Message> {
+    let is_new = state.show_add_user_dialog;
+    let editing_last_admin = state.selected_user().is_some_and(|user| state.is_last_admin(user));
+
+    let field = |label, control: Element<'static, Message>| {
+        row![text(label).width(Length::Fixed(120.0)), control]
+            .spacing(10)
+            .align_y(Vertical::Center)
+    };
+
+    let mut fields = column![
+        text(if is_new { "Add User" } else { "Edit User" }).size(24),
+        field("First Name:", text_input("First name", &state.new_user_first_name)
+            .on_input(|s| user_access_msg(UserAccessMessage::UpdateNewUserFirstName(s)))
+            .width(Length::Fixed(300.0))
+            .into()),
+        field("Surname:", text_input("Surname", &state.new_user_surname)
+            .on_input(|s| user_access_msg(UserAccessMessage::UpdateNewUserSurname(s)))
+            .width(Length::Fixed(300.0))
+            .into()),
+        field("Other Names:", text_input("Optional", &state.new_user_other_names)
+            .on_input(|s| user_access_msg(UserAccessMessage::UpdateNewUserOtherNames(s)))
+            .width(Length::Fixed(300.0))
+            .into()),
+        field("Username:", text_input("Username", &state.new_user_username)
+            .on_input(|s| user_access_msg(UserAccessMessage::UpdateNewUserUsername(s)))
+            .width(Length::Fixed(300.0))
+            .into()),
+        field("Role:", pick_list(
+            UserRole::ALL,
+            Some(state.new_user_role.clone()),
+            |role| user_access_msg(UserAccessMessage::UpdateNewUserRole(role)),
+        )
+        .width(Length::Fixed(300.0))
+        .into()),
+    ]
+    .spacing(15);
+
+    // Only worth asking when the admin works in more than one school
+    if state.schools.len() > 1 {
+        let schools = state.schools.iter().fold(column![].spacing(6), |schools, school| {
+            let school_id = school.id;
+            schools.push(
+                checkbox(&school.name, state.new_user_school_ids.contains(&school_id))
+                    .on_toggle(move |selected| user_access_msg(UserAccessMessage::ToggleNewUserSchool(school_id, selected))),
+            )
+        });
+        fields = fields.push(
+            row![text("Schools:").width(Length::Fixed(120.0)), schools]
+                .spacing(10),
+        );
+    }
+
+    if is_new {
+        fields = fields
+            .push(field("Password:", text_input(&format!("At least {} characters", state.password_policy.min_length), &state.new_user_password)
+                .on_input(|s| user_access_msg(UserAccessMessage::UpdateNewUserPassword(s)))
+                .secure(true)
+                .width(Length::Fixed(300.0))
+                .into()))
+            .push(
+                text("They'll be asked to choose their own password when they first log in.")
+                    .size(12)
+                    .style(|theme| iced::widget::text::secondary(theme)),
+            );
+    } else if editing_last_admin {
+        fields = fields.push(
+            text("This is the only active admin, so the role must stay Admin.")
+                .size(12)
+                .style(palette::warning),
+        );
+    }
+
+    if let Some(error) = &state.error_message {
+        fields = fields.push(text(error).size(14).style(palette::error));
+    }
+
+    let mut save = button(if state.is_saving { "Saving..." } else { "Save" }).style(button::primary);
+    if !state.is_saving {
+        save = save.on_press(user_access_msg(if is_new {
+            UserAccessMessage::SubmitNewUser
+        } else {
+            UserAccessMessage::SubmitEditUser
+        }));
+    }
+
+    container(
+        fields.push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(user_access_msg(UserAccessMessage::CancelUserDialog)),
+                save,
+            ]
+            .spacing(10),
+        )
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Asks before deactivating a user or resetting their password
+fn confirmation_view(state: &UserAccessManagerState) -> Element<'_, Message> {
+    let name = state.selected_user()
+        .map(|user| format!("{} {} ({})", user.first_name, user.surname, user.username))
+        .unwrap_or_default();
+    let (title, explanation, action, confirm) = if state.show_deactivate_confirmation {
+        (
+            "Deactivate User",
+            "They won't be able to log in. Their payments and history are kept, and you can reactivate them later.",
+            "Deactivate",
+            UserAccessMessage::ConfirmDeactivateUser,
+        )
+    } else {
+        (
+            "Reset Password",
+            "Their current password stops working. You'll be shown a temporary password to give them, which they must change when they next log in.",
+            "Reset Password",
+            UserAccessMessage::ConfirmResetPassword,
+        )
+    };
+
+    let mut fields = column![
+        text(title).size(24),
+        text(name).size(16),
+        text(explanation).size(14).style(|theme| iced::widget::text::secondary(theme)),
+    ]
+    .spacing(15);
+    if let Some(error) = &state.error_message {
+        fields = fields.push(text(error).size(14).style(palette::error));
+    }
+
+    let mut confirm_button = button(if state.is_saving { "Working..." } else { action }).style(button::danger);
+    if !state.is_saving {
+        confirm_button = confirm_button.on_press(user_access_msg(confirm));
+    }
+
+    container(
+        fields.push(
+            row![
+                Space::with_width(Length::Fill),
+                button("Cancel")
+                    .style(button::text)
+                    .on_press(user_access_msg(UserAccessMessage::CancelUserDialog)),
+                confirm_button,
+            ]
+            .spacing(10),
+        )
+    )
+    .padding(20)
+    .width(Length::Fill)
+    .into()
+}
+
+fn attempt_row<'a>(attempt: &'a LoginAttempt, date_format: &str) -> Element<'a, Message> {
+    let (result, style): (&str, fn(&iced::Theme) -> iced::widget::text::Style) = if attempt.succeeded {
+        ("Succeeded", palette::success)
+    } else {
+        ("Failed", palette::error)
+    };
+    row![
+        text(format_time(attempt.attempted_at, date_format)).size(14).width(Length::Fixed(160.0)),
+        text(&attempt.username).size(14).width(Length::Fixed(180.0)),
+        text(&attempt.machine_name).size(14).width(Length::Fill),
+        text(result).size(14).style(style).width(Length::Fixed(100.0)),
+    ]
+    .spacing(10)
+    .padding(Padding::from([6, 10]))
+    .into()
+}
+
+/// Every login attempt, newest first, with the computer it came from
+fn login_history_view<'a>(state: &'a UserAccessManagerState, date_format: &str) -> Element<'a, Message> {
+    let title = match &state.history_username {
+        Some(username) => format!("Login History: {}", username),
+        None => "Login History".to_string(),
+    };
+    let header = row![
+        text(title).size(24),
+        Space::with_width(Length::Fill),
+        checkbox("Failed attempts only", state.history_failed_only)
+            .on_toggle(|failed_only| user_access_msg(UserAccessMessage::ToggleFailedLoginsOnly(failed_only))),
+        button("Back to Users")
+            .style(button::secondary)
+            .on_press(user_access_msg(UserAccessMessage::HideLoginHistory)),
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center);
+
+    let mut content = column![header].spacing(10).padding(20);
+    if let Some(error) = &state.error_message {
+        content = content.push(text(error).size(14).style(palette::error));
+    }
+
+    let body: Element<'_, Message> = if state.is_loading_history {
+        text("Loading login history...").size(16).into()
+    } else if state.login_history.is_empty() {
+        text("No login attempts recorded").size(14)
+            .style(|theme| iced::widget::text::secondary(theme))
+            .into()
+    } else {
+        let columns = row![
+            text("Time").size(12).width(Length::Fixed(160.0)),
+            text("Username").size(12).width(Length::Fixed(180.0)),
+            text("Computer").size(12).width(Length::Fill),
+            text("Result").size(12).width(Length::Fixed(100.0)),
+        ]
+        .spacing(10)
+        .padding(Padding::from([0, 10]));
+        let rows = state.login_history
+            .iter()
+            .fold(column![], |col, attempt| col.push(attempt_row(attempt, date_format)));
+        column![
+            columns,
+            container(scrollable(rows).height(Length::Fill))
+                .style(container::bordered_box)
+                .width(Length::Fill),
+        ]
+        .spacing(6)
+        .into()
+    };
+
+    content.push(body).into()
 }
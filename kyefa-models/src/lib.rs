@@ -4,14 +4,15 @@ use serde::{Serialize, Deserialize};
 use std::str::FromStr;
 
 #[cfg(feature = "database")]
-use sqlx::{FromRow, Type};
+use sqlx::FromRow;
 
 // ============= SYSTEM USER MANAGEMENT ===============
 
 /// Role of a system user
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(sqlx::Type))]
-#[cfg_attr(feature = "database", sqlx(rename_all = "lowercase"))]
+#[cfg_attr(feature = "database", sqlx(type_name = "user_role"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
 pub enum UserRole {
     Admin,
     CommitteeMember,
@@ -21,6 +22,66 @@ pub enum UserRole {
     Teacher,
 }
 
+impl UserRole {
+    pub const ALL: [UserRole; 6] = [
+        UserRole::Admin,
+        UserRole::CommitteeMember,
+        UserRole::Headteacher,
+        UserRole::DataEntry,
+        UserRole::Staff,
+        UserRole::Teacher,
+    ];
+
+    /// Roles that may approve fee exemptions
+    pub fn can_approve_exemptions(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::Headteacher)
+    }
+
+    /// Roles that may merge duplicate student records, which folds one
+    /// student's payments into another's
+    pub fn can_merge_students(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::Headteacher | UserRole::DataEntry)
+    }
+
+    /// Roles that may close an academic year and promote the school's students
+    pub fn can_promote_students(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::Headteacher)
+    }
+
+    /// Roles that may read the financial reports: teachers' earnings and
+    /// every family's balance
+    pub fn can_view_reports(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::CommitteeMember | UserRole::Headteacher)
+    }
+
+    /// Roles that may log periods for any teacher; everyone else can only log
+    /// their own
+    pub fn can_log_any_periods(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::CommitteeMember | UserRole::Headteacher | UserRole::DataEntry)
+    }
+
+    /// Roles that may verify or reject teachers' logged periods
+    pub fn can_review_periods(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::CommitteeMember | UserRole::Headteacher)
+    }
+
+    /// Roles that may pay a term out to its teachers, which fixes every
+    /// verified period's pay for good
+    pub fn can_run_payouts(&self) -> bool {
+        matches!(self, UserRole::Admin | UserRole::Headteacher)
+    }
+}
+
+impl std::fmt::Display for UserRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UserRole::CommitteeMember => write!(f, "Committee Member"),
+            UserRole::DataEntry => write!(f, "Data Entry"),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
 /// Represents a person's full name
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersonName {
@@ -37,10 +98,19 @@ pub struct UserAccount {
     pub username: String,
     pub password_hash: String,
     pub role: UserRole,
+    /// Deactivated accounts are kept for the audit trail but can't log in.
     pub is_active: bool,
     pub first_name: String,
     pub surname: String,
     pub other_names: Option<String>,
+    /// Set on new accounts and by an admin's password reset; the user must
+    /// choose a new password before doing anything else.
+    pub must_change_password: bool,
+    /// Consecutive failed logins since the last successful one
+    pub failed_login_count: i32,
+    /// Set once `failed_login_count` reaches the lockout threshold. An admin's
+    /// password reset clears it early.
+    pub locked_until: Option<DateTime<Utc>>,
 }
 
 /// User information for UI display
@@ -95,6 +165,88 @@ pub struct UserResponse {
     pub first_name: String,
     pub surname: String,
     pub other_names: Option<String>,
+    #[serde(default)]
+    pub must_change_password: bool,
+    #[serde(default)]
+    pub locked_until: Option<DateTime<Utc>>,
+    /// Schools the user can work in
+    #[serde(default)]
+    pub schools: Vec<School>,
+}
+
+/// Answer to a successful `POST /login`. The desktop sends `token` as a bearer
+/// token with every later request; the backend works out who is acting from it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LoginResponse {
+    pub token: String,
+    pub user: UserResponse,
+}
+
+/// Body of `POST /login`. `machine_name` identifies the computer in the login
+/// history, which matters on shared office PCs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginPayload {
+    pub username: String,
+    pub password: String,
+    pub machine_name: String,
+}
+
+/// Body of `POST /account/unlock`, which checks the password of the user
+/// whose session is locked behind the lock screen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockPayload {
+    pub password: String,
+    pub machine_name: String,
+}
+
+/// One row of the login history, successful or not. `user_id` is unset when
+/// the username didn't match an account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(FromRow))]
+pub struct LoginAttempt {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub username: String,
+    pub attempted_at: DateTime<Utc>,
+    pub machine_name: String,
+    pub succeeded: bool,
+}
+
+/// Query parameters accepted by `GET /login-history`, newest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoginHistoryQuery {
+    pub username: Option<String>,
+    #[serde(default)]
+    pub failed_only: bool,
+    #[serde(default)]
+    pub limit: u32,
+}
+
+// ============= SCHOOLS ===============
+
+/// A school running the programme. Every record belongs to one school, and the
+/// backend scopes each request to the school named in `SCHOOL_ID_HEADER`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(FromRow))]
+pub struct School {
+    pub id: Uuid,
+    pub name: String,
+    pub is_active: bool,
+}
+
+impl std::fmt::Display for School {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Row from the `user_schools` table. A user can work in every school they
+/// have a row for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(FromRow))]
+pub struct UserSchoolRow {
+    pub user_id: Uuid,
+    pub school_id: Uuid,
 }
 
 // ============= BASIC ENUMS ===============
@@ -102,7 +254,8 @@ pub struct UserResponse {
 /// Gender enum
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(sqlx::Type))]
-#[cfg_attr(feature = "database", sqlx(rename_all = "lowercase"))]
+#[cfg_attr(feature = "database", sqlx(type_name = "gender"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
 pub enum Gender {
     Male,
     Female,
@@ -122,6 +275,18 @@ impl std::fmt::Display for Gender {
     }
 }
 
+impl FromStr for Gender {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Male" => Ok(Gender::Male),
+            "Female" => Ok(Gender::Female),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Academic class/level
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(sqlx::Type))]
@@ -155,6 +320,33 @@ impl ClassLevel {
     pub fn excel_options() -> &'static str {
         "LowerSecondaryYear8,LowerSecondaryYear9,IGCSE1,IGCSE2,WASSCE1,WASSCE2,WASSCE3,ALevel1,ALevel2"
     }
+
+    /// The class a student normally moves up to at year end; `None` means the
+    /// student completes the programme. IGCSE2 continues to A-Level by default,
+    /// and leavers are marked as exceptions in the promotion wizard.
+    pub fn next(&self) -> Option<ClassLevel> {
+        match self {
+            ClassLevel::LowerSecondaryYear8 => Some(ClassLevel::LowerSecondaryYear9),
+            ClassLevel::LowerSecondaryYear9 => Some(ClassLevel::IGCSE1),
+            ClassLevel::IGCSE1 => Some(ClassLevel::IGCSE2),
+            ClassLevel::IGCSE2 => Some(ClassLevel::ALevel1),
+            ClassLevel::WASSCE1 => Some(ClassLevel::WASSCE2),
+            ClassLevel::WASSCE2 => Some(ClassLevel::WASSCE3),
+            ClassLevel::WASSCE3 => None,
+            ClassLevel::ALevel1 => Some(ClassLevel::ALevel2),
+            ClassLevel::ALevel2 => None,
+        }
+    }
+
+    /// The syllabus a class follows, which decides which subjects it can take
+    pub fn stream(&self) -> Stream {
+        match self {
+            ClassLevel::LowerSecondaryYear8 | ClassLevel::LowerSecondaryYear9 => Stream::CambridgeLowerSecondary,
+            ClassLevel::IGCSE1 | ClassLevel::IGCSE2 => Stream::CambridgeIGCSE,
+            ClassLevel::WASSCE1 | ClassLevel::WASSCE2 | ClassLevel::WASSCE3 => Stream::WASSCE,
+            ClassLevel::ALevel1 | ClassLevel::ALevel2 => Stream::CambridgeALevel,
+        }
+    }
 }
 
 impl std::fmt::Display for ClassLevel {
@@ -185,7 +377,8 @@ impl FromStr for ClassLevel {
 /// Education stream (syllabus)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(sqlx::Type))]
-#[cfg_attr(feature = "database", sqlx(rename_all = "PascalCase"))]
+#[cfg_attr(feature = "database", sqlx(type_name = "stream"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
 pub enum Stream {
     CambridgeIGCSE,
     CambridgeLowerSecondary,
@@ -193,10 +386,17 @@ pub enum Stream {
     WASSCE,
 }
 
+impl std::fmt::Display for Stream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// Status of teacher's participation in the tuition program
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(sqlx::Type))]
-#[cfg_attr(feature = "database", sqlx(rename_all = "PascalCase"))]
+#[cfg_attr(feature = "database", sqlx(type_name = "participation_status"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
 pub enum ParticipationStatus {
     Participating,
     NotParticipating,
@@ -204,7 +404,8 @@ pub enum ParticipationStatus {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(sqlx::Type))]
-#[cfg_attr(feature = "database", sqlx(rename_all = "PascalCase"))]
+#[cfg_attr(feature = "database", sqlx(type_name = "subject_name"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
 pub enum SubjectName {
     English,
     Mathematics,
@@ -223,18 +424,39 @@ pub enum SubjectName {
     FurtherMath,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+impl std::fmt::Display for SubjectName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A school year, named by the calendar year it starts in and shown as
+/// "2025/2026". Terms carry the year they belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(sqlx::Type))]
-#[cfg_attr(feature = "database", sqlx(rename_all = "lowercase"))]
-pub enum AcademicYear {
-    Year2024_2025,
-    Year2025_2026,
-    Year2026_2027,
+#[cfg_attr(feature = "database", sqlx(transparent))]
+pub struct AcademicYear(pub i32);
+
+impl AcademicYear {
+    pub fn starting_in(year: i32) -> Self {
+        AcademicYear(year)
+    }
+
+    pub fn next(&self) -> AcademicYear {
+        AcademicYear(self.0 + 1)
+    }
+}
+
+impl std::fmt::Display for AcademicYear {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.0, self.0 + 1)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "database", derive(sqlx::Type))]
-#[cfg_attr(feature = "database", sqlx(rename_all = "lowercase"))]
+#[cfg_attr(feature = "database", sqlx(type_name = "support_staff_role"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
 pub enum SupportStaffRole {
     Janitor,
     Administrator,
@@ -248,12 +470,14 @@ pub enum SupportStaffRole {
 #[cfg_attr(feature = "database", derive(FromRow))]
 pub struct StudentRow {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub first_name: String,
     pub surname: String,
     pub other_names: Option<String>,
     pub gender: Gender,
     pub class_level: ClassLevel, 
     pub is_active: bool,
+    pub merged_into: Option<Uuid>,
 }
 
 /// Row from the `subjects` table
@@ -261,6 +485,7 @@ pub struct StudentRow {
 #[cfg_attr(feature = "database", derive(FromRow))]
 pub struct SubjectRow {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub name: SubjectName,
     pub stream: Stream,
 }
@@ -270,6 +495,7 @@ pub struct SubjectRow {
 #[cfg_attr(feature = "database", derive(FromRow))]
 pub struct ConductedPeriodRow {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub date: NaiveDateTime,
     pub class_level: ClassLevel,
     pub subject_id: Uuid,
@@ -285,6 +511,7 @@ pub struct ConductedPeriodRow {
 #[cfg_attr(feature = "database", derive(FromRow))]
 pub struct TermRow {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub name: String,
     pub academic_year: AcademicYear,
     pub start_date: NaiveDateTime,
@@ -297,6 +524,7 @@ pub struct TermRow {
 #[cfg_attr(feature = "database", derive(FromRow))]
 pub struct TeacherRow {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub first_name: String,
     pub surname: String,
     pub other_names: Option<String>,
@@ -308,6 +536,7 @@ pub struct TeacherRow {
 #[cfg_attr(feature = "database", derive(FromRow))]
 pub struct SupportStaffRow {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub first_name: String,
     pub surname: String,
     pub other_names: Option<String>,
@@ -327,6 +556,26 @@ pub struct Student {
     pub is_active: bool,
     pub fee_amount: f64,
     pub payment_status: PaymentStatus,
+    /// Enrolment in the term the list was fetched for. `fee_amount` and
+    /// `payment_status` only count when this is set.
+    #[serde(default)]
+    pub enrolment: Option<Enrolment>,
+    /// Installment plan for the same term, when the fee is being paid in parts
+    #[serde(default)]
+    pub installment_plan: Option<InstallmentPlan>,
+    #[serde(default)]
+    pub total_paid: f64,
+    /// Set when this record was merged into another as a duplicate; such
+    /// students stay inactive rather than being deleted.
+    #[serde(default)]
+    pub merged_into: Option<Uuid>,
+}
+
+impl Student {
+    /// Fee still owed for the term; never negative
+    pub fn balance(&self) -> f64 {
+        (self.fee_amount - self.total_paid).max(0.0)
+    }
 }
 
 impl From<StudentRow> for Student {
@@ -344,10 +593,153 @@ impl From<StudentRow> for Student {
             // Default values for new fields
             fee_amount: 0.0, 
             payment_status: PaymentStatus::NotPaid,
+            enrolment: None,
+            installment_plan: None,
+            total_paid: 0.0,
+            merged_into: row.merged_into,
+        }
+    }
+}
+
+/// A student's place in the tuition programme for one term. Expected revenue
+/// is the sum of `fee_amount` over a term's enrolments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(FromRow))]
+pub struct Enrolment {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: Uuid,
+    pub joined_on: NaiveDate,
+    pub left_on: Option<NaiveDate>,
+    pub fee_amount: f64,
+}
+
+impl Enrolment {
+    pub fn is_current(&self, today: NaiveDate) -> bool {
+        self.joined_on <= today && self.left_on.is_none_or(|left| today <= left)
+    }
+}
+
+/// How a guardian is related to the children linked to them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(sqlx::Type))]
+#[cfg_attr(feature = "database", sqlx(type_name = "guardian_relationship"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
+pub enum GuardianRelationship {
+    Mother,
+    Father,
+    Guardian,
+    Grandparent,
+    Sibling,
+    Other,
+}
+
+impl GuardianRelationship {
+    pub const ALL: [GuardianRelationship; 6] = [
+        GuardianRelationship::Mother,
+        GuardianRelationship::Father,
+        GuardianRelationship::Guardian,
+        GuardianRelationship::Grandparent,
+        GuardianRelationship::Sibling,
+        GuardianRelationship::Other,
+    ];
+}
+
+impl std::fmt::Display for GuardianRelationship {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A parent or guardian to call about one or more students. Students who
+/// share a guardian are treated as one family.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Guardian {
+    pub id: Uuid,
+    pub name: PersonName,
+    pub relationship: GuardianRelationship,
+    pub phone_number: String,
+    pub alternate_phone: Option<String>,
+    /// The person called first about the family's fees
+    pub is_primary_contact: bool,
+    pub student_ids: Vec<Uuid>,
+}
+
+/// Why a student is excused some or all of a term's fee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(sqlx::Type))]
+#[cfg_attr(feature = "database", sqlx(type_name = "exemption_reason"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
+pub enum ExemptionReason {
+    StaffChild,
+    Hardship,
+    Scholarship,
+}
+
+impl ExemptionReason {
+    pub const ALL: [ExemptionReason; 3] = [
+        ExemptionReason::StaffChild,
+        ExemptionReason::Hardship,
+        ExemptionReason::Scholarship,
+    ];
+}
+
+impl std::fmt::Display for ExemptionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExemptionReason::StaffChild => write!(f, "Staff Child"),
+            ExemptionReason::Hardship => write!(f, "Hardship"),
+            ExemptionReason::Scholarship => write!(f, "Scholarship"),
         }
     }
 }
 
+/// An approved reduction of a student's fee for one term. A full exemption
+/// waives the whole fee and `amount` is the fee at the time of approval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(FromRow))]
+pub struct Exemption {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: Uuid,
+    pub is_full: bool,
+    pub amount: f64,
+    pub reason: ExemptionReason,
+    pub approved_by: Uuid,
+    pub notes: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// What happens to a student at the end of the academic year
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(sqlx::Type))]
+#[cfg_attr(feature = "database", sqlx(type_name = "promotion_outcome"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
+pub enum PromotionOutcome {
+    Promoted,
+    Repeated,
+    Graduated,
+    Left,
+}
+
+impl std::fmt::Display for PromotionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The class a student was in for one academic year, so reports can show the
+/// class at the time rather than the current one. `outcome` is unset while
+/// the year is still open.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(FromRow))]
+pub struct ClassHistoryEntry {
+    pub student_id: Uuid,
+    pub academic_year: AcademicYear,
+    pub class_level: ClassLevel,
+    pub outcome: Option<PromotionOutcome>,
+}
+
 /// Represents a teacher
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Teacher {
@@ -415,7 +807,7 @@ impl From<SubjectRow> for Subject {
 }
 
 /// Term information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Term {
     pub id: Uuid,
     pub name: String,
@@ -425,6 +817,12 @@ pub struct Term {
     pub is_active: bool,
 }
 
+impl std::fmt::Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.academic_year)
+    }
+}
+
 impl From<TermRow> for Term {
     fn from(row: TermRow) -> Self {
         Self {
@@ -473,14 +871,106 @@ impl From<ConductedPeriodRow> for ConductedPeriod {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PaymentStatus {
     Paid,
-    Partial,
+    /// Part paid and keeping up with the installment plan, or part paid with no plan
+    PartialOnTrack,
+    /// Part paid but behind an installment that has fallen due
+    PartialOverdue,
     NotPaid,
     Exempt,
+    /// Not enrolled in the term, so nothing is expected
+    NotEnrolled,
 }
 
 impl std::fmt::Display for PaymentStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            PaymentStatus::PartialOnTrack => write!(f, "Partial (On Track)"),
+            PaymentStatus::PartialOverdue => write!(f, "Partial (Overdue)"),
+            PaymentStatus::NotPaid => write!(f, "Not Paid"),
+            PaymentStatus::NotEnrolled => write!(f, "Not Enrolled"),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+/// One agreed part-payment of a term's fee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Installment {
+    pub due_date: NaiveDate,
+    pub amount: f64,
+}
+
+/// An agreed schedule for paying a student's fee for one term in parts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallmentPlan {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: Uuid,
+    /// Ordered by due date
+    pub installments: Vec<Installment>,
+    pub created_by: Uuid,
+    pub created_at: NaiveDateTime,
+}
+
+impl InstallmentPlan {
+    /// Total of the installments due on or before `date`
+    pub fn amount_due_by(&self, date: NaiveDate) -> f64 {
+        self.installments
+            .iter()
+            .filter(|installment| installment.due_date <= date)
+            .map(|installment| installment.amount)
+            .sum()
+    }
+
+    /// The first installment not yet covered by `total_paid`
+    pub fn next_unpaid(&self, total_paid: f64) -> Option<&Installment> {
+        let mut cumulative = 0.0;
+        self.installments.iter().find(|installment| {
+            cumulative += installment.amount;
+            cumulative > total_paid + 0.005
+        })
+    }
+
+    /// Days since the earliest installment `total_paid` doesn't cover fell due,
+    /// or `None` when payments are up to date.
+    pub fn days_overdue(&self, total_paid: f64, today: NaiveDate) -> Option<i64> {
+        self.next_unpaid(total_paid)
+            .filter(|installment| installment.due_date < today)
+            .map(|installment| (today - installment.due_date).num_days())
+    }
+}
+
+/// How long an overdue balance has been outstanding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AgingBucket {
+    UpToThirtyDays,
+    ThirtyOneToSixtyDays,
+    OverSixtyDays,
+}
+
+impl AgingBucket {
+    pub const ALL: [AgingBucket; 3] = [
+        AgingBucket::UpToThirtyDays,
+        AgingBucket::ThirtyOneToSixtyDays,
+        AgingBucket::OverSixtyDays,
+    ];
+
+    pub fn for_days(days_overdue: i64) -> Self {
+        match days_overdue {
+            ..=30 => AgingBucket::UpToThirtyDays,
+            31..=60 => AgingBucket::ThirtyOneToSixtyDays,
+            _ => AgingBucket::OverSixtyDays,
+        }
+    }
+}
+
+impl std::fmt::Display for AgingBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgingBucket::UpToThirtyDays => write!(f, "0-30 days"),
+            AgingBucket::ThirtyOneToSixtyDays => write!(f, "31-60 days"),
+            AgingBucket::OverSixtyDays => write!(f, "60+ days"),
+        }
     }
 }
 
@@ -508,7 +998,8 @@ pub struct TeachingPeriod {
     pub teacher_name: String,
 }
 
-#[derive(Debug, Clone)]
+/// An entry from the audit log, as shown in the activity feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecentActivity {
     pub id: String,
     pub activity_type: ActivityType,
@@ -517,26 +1008,185 @@ pub struct RecentActivity {
     pub user_name: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(sqlx::Type))]
+#[cfg_attr(feature = "database", sqlx(type_name = "activity_type"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
 pub enum ActivityType {
     PaymentReceived,
     StudentAdded,
     TeachingPeriodCreated,
     UserCreated,
+    StudentsMerged,
+    ExemptionGranted,
+    StudentsPromoted,
+    PasswordChanged,
+    /// A password change refused because the current password was wrong
+    PasswordChangeFailed,
+    /// A period verified or rejected by the committee
+    PeriodReviewed,
+    PeriodDisputed,
+    /// A term's revenue split and paid out to its teachers
+    PayoutRun,
+}
+
+/// Where a logged period stands with the committee. Only verified periods
+/// count towards a payout.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(sqlx::Type))]
+#[cfg_attr(feature = "database", sqlx(type_name = "period_verification"))]
+#[cfg_attr(feature = "database", sqlx(rename_all = "snake_case"))]
+pub enum PeriodVerification {
+    Pending,
+    Verified,
+    Rejected,
+    /// Rejected, then disputed by the teacher and awaiting another look
+    Disputed,
+}
+
+impl PeriodVerification {
+    pub const ALL: [PeriodVerification; 4] = [
+        PeriodVerification::Pending,
+        PeriodVerification::Disputed,
+        PeriodVerification::Rejected,
+        PeriodVerification::Verified,
+    ];
+}
+
+impl std::fmt::Display for PeriodVerification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeriodVerification::Pending => write!(f, "Awaiting verification"),
+            PeriodVerification::Verified => write!(f, "Verified"),
+            PeriodVerification::Rejected => write!(f, "Rejected"),
+            PeriodVerification::Disputed => write!(f, "Disputed"),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+/// One of a teacher's own logged periods, as shown in their portal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeacherPeriod {
+    pub id: Uuid,
+    pub date: NaiveDate,
+    pub class_level: ClassLevel,
+    pub subject: String,
+    pub week_of_term: u8,
+    pub verification: PeriodVerification,
+    pub rejection_reason: Option<String>,
+    pub dispute_reason: Option<String>,
+}
+
+/// A period on the committee's review screen, with whose it is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodForReview {
+    pub teacher_id: Uuid,
+    pub teacher_name: String,
+    pub period: TeacherPeriod,
+}
+
+/// Query parameters accepted by `GET /periods`. The term defaults to the
+/// active one and the verification to every state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeriodReviewQuery {
+    pub term_id: Option<Uuid>,
+    pub verification: Option<PeriodVerification>,
+}
+
+/// The teacher's share of the current term's expected revenue under the
+/// active `ConstraintConfig`, before any fees still to come in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectedShare {
+    pub constraint_name: String,
+    pub base_share: f64,
+    pub period_share: f64,
+    pub verified_periods: usize,
+    /// `ConstraintConfig::max_periods_paid`, when there is a cap
+    pub max_periods_paid: Option<u32>,
+    /// Whether the cap or ratio limit reduced the period share
+    pub capped: bool,
+}
+
+impl ProjectedShare {
+    pub fn total(&self) -> f64 {
+        self.base_share + self.period_share
+    }
+}
+
+/// A past `TeacherPayout` with the term it was for, enough for a payslip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payslip {
+    pub payout_id: Uuid,
+    pub term_name: String,
+    pub calculated_on: NaiveDateTime,
+    pub periods_paid: usize,
+    pub base_share: f64,
+    pub period_share: f64,
+    pub capped: bool,
+}
+
+impl Payslip {
+    pub fn total(&self) -> f64 {
+        self.base_share + self.period_share
+    }
+}
+
+/// Everything the teacher portal shows for the requesting teacher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeacherPortal {
+    pub teacher_id: Uuid,
+    pub teacher_name: String,
+    pub term_name: Option<String>,
+    /// The active term's periods, newest first
+    pub periods: Vec<TeacherPeriod>,
+    /// Absent when there is no active term
+    pub projected_share: Option<ProjectedShare>,
+    /// Newest first
+    pub payslips: Vec<Payslip>,
+}
+
+/// Key figures for the home dashboard, scoped to the active term
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HomeSummary {
+    pub active_term_name: Option<String>,
+    pub total_students: usize,
+    pub active_teachers: usize,
+    pub expected_revenue: f64,
+    pub total_revenue: f64,
+    pub collection_rate: f64,
+    /// Periods marked conducted this week
+    pub periods_this_week: usize,
+    /// Every period logged this week, conducted or not
+    pub logged_periods_this_week: usize,
+    /// Present only when the requesting user is linked to a teacher record
+    pub teacher_summary: Option<TeacherHomeSummary>,
+}
+
+/// The requesting teacher's own figures for the active term
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeacherHomeSummary {
+    pub teacher_id: Uuid,
+    pub periods_this_term: usize,
+    pub periods_this_week: usize,
+    pub projected_earnings: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentSummary {
+    /// Fees due after exemptions
     pub total_expected: f64,
+    #[serde(default)]
+    pub total_exempted: f64,
     pub total_received: f64,
     pub total_pending: f64,
     pub paid_count: usize,
-    pub partial_count: usize,
+    pub partial_on_track_count: usize,
+    pub partial_overdue_count: usize,
     pub unpaid_count: usize,
     pub exempt_count: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeacherEarnings {
     pub teacher_id: String,
     pub teacher_name: String,
@@ -551,14 +1201,285 @@ pub enum ReportType {
     CollectionStatus,
     TeacherEarnings,
     StudentPayments,
+    Exemptions,
+    OverdueAging,
+}
+
+/// Headline figures shown above every report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportOverview {
+    pub school_name: String,
+    pub term_name: Option<String>,
+    pub total_students: usize,
+    /// Fees due after exemptions
+    pub expected_revenue: f64,
+    #[serde(default)]
+    pub total_exempted: f64,
+    pub total_revenue: f64,
+    pub collection_rate: f64,
+}
+
+/// Expected revenue split under the active `ConstraintConfig`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectedIncomeReport {
+    pub expected_revenue: f64,
+    pub admin_percentage: f64,
+    pub support_staff_percentage: f64,
+    pub teacher_percentage: f64,
+    pub admin_share: f64,
+    pub support_staff_share: f64,
+    pub teacher_share: f64,
+    pub teacher_earnings: Vec<TeacherEarnings>,
+}
+
+/// Collections for one week of the term, with running totals for charting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyCollection {
+    pub week_of_term: u8,
+    pub collected: f64,
+    pub cumulative_collected: f64,
+    pub cumulative_expected: f64,
+}
+
+/// Headline figures for one term, for term-over-term comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TermComparison {
+    pub term_id: Uuid,
+    pub term_name: String,
+    pub expected_revenue: f64,
+    pub total_revenue: f64,
+    pub total_periods: usize,
+}
+
+/// A student behind on their installment plan, for the overdue-aging report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverdueAgingRow {
+    pub student_id: Uuid,
+    pub student_name: String,
+    pub class_level: ClassLevel,
+    /// Installments fallen due, less what has been paid
+    pub amount_overdue: f64,
+    /// Counted from the earliest installment still unpaid
+    pub days_overdue: i64,
+    pub oldest_due_date: NaiveDate,
+    /// The family's primary contact, when a guardian is on record
+    #[serde(default)]
+    pub contact_name: Option<String>,
+    #[serde(default)]
+    pub contact_phone: Option<String>,
+}
+
+impl OverdueAgingRow {
+    pub fn bucket(&self) -> AgingBucket {
+        AgingBucket::for_days(self.days_overdue)
+    }
+}
+
+/// One approved exemption with the names an auditor needs alongside it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExemptionReportRow {
+    pub exemption: Exemption,
+    pub student_name: String,
+    pub class_level: ClassLevel,
+    pub term_name: String,
+    pub approved_by_name: String,
+    /// The student's fee before the exemption
+    pub fee_amount: f64,
+}
+
+/// Settings shared by every desktop in one school, held by the backend per
+/// school. The share percentages split teaching revenue and must add up to 100.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchoolSettings {
+    pub currency_symbol: String,
+    pub date_format: String,
+    pub default_payment_method: String,
+    pub admin_share_percentage: f64,
+    pub staff_share_percentage: f64,
+    pub teacher_share_percentage: f64,
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+    #[serde(default)]
+    pub lockout_policy: LockoutPolicy,
+    /// Taken off the fee of each child enrolled in a term after a sibling
+    /// already is; 0 turns the discount off
+    #[serde(default)]
+    pub sibling_discount_percentage: f64,
+}
+
+/// Pattern tokens and the chrono specifiers they stand for
+const DATE_TOKENS: [(&str, &str); 3] = [("DD", "%d"), ("MM", "%m"), ("YYYY", "%Y")];
+
+/// Converts a display pattern such as "DD/MM/YYYY" into a chrono format string.
+/// A pattern needs DD, MM and YYYY once each, with only '/', '-', '.' or spaces
+/// between them.
+pub fn chrono_date_format(pattern: &str) -> Result<String, String> {
+    let invalid = || format!("'{}' is not a date format. Use DD, MM and YYYY separated by /, - or .", pattern);
+    let mut format = String::new();
+    let mut counts = [0; 3];
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        match DATE_TOKENS.iter().position(|(token, _)| rest.starts_with(token)) {
+            Some(index) => {
+                let (token, specifier) = DATE_TOKENS[index];
+                counts[index] += 1;
+                format.push_str(specifier);
+                rest = &rest[token.len()..];
+            }
+            None => {
+                let separator = rest.chars().next().filter(|c| matches!(c, '/' | '-' | '.' | ' ')).ok_or_else(invalid)?;
+                format.push(separator);
+                rest = &rest[separator.len_utf8()..];
+            }
+        }
+    }
+    if counts != [1, 1, 1] {
+        return Err(invalid());
+    }
+    Ok(format)
+}
+
+impl SchoolSettings {
+    /// Checks every value is in range. The desktop runs this before saving
+    /// and the backend again before storing.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.currency_symbol.trim().is_empty() {
+            return Err("Currency symbol cannot be empty.".to_string());
+        }
+        chrono_date_format(&self.date_format)?;
+        if self.default_payment_method.trim().is_empty() {
+            return Err("Choose a default payment method.".to_string());
+        }
+        let shares = [
+            ("Admin", self.admin_share_percentage),
+            ("Staff", self.staff_share_percentage),
+            ("Teacher", self.teacher_share_percentage),
+        ];
+        if let Some((label, _)) = shares.iter().find(|(_, share)| !(0.0..=100.0).contains(share)) {
+            return Err(format!("{} share must be a percentage between 0 and 100.", label));
+        }
+        let total: f64 = shares.iter().map(|(_, share)| share).sum();
+        if (total - 100.0).abs() > 0.01 {
+            return Err(format!("The shares must add up to 100%; they add up to {:.1}%.", total));
+        }
+        if !(PasswordPolicy::MIN_LENGTH_FLOOR..=64).contains(&self.password_policy.min_length) {
+            return Err(format!("Minimum password length must be between {} and 64.", PasswordPolicy::MIN_LENGTH_FLOOR));
+        }
+        if self.password_policy.history_count > PasswordPolicy::MAX_HISTORY_COUNT {
+            return Err(format!(
+                "Blocked previous passwords must be a number from 0 to {}.",
+                PasswordPolicy::MAX_HISTORY_COUNT
+            ));
+        }
+        if !(3..=20).contains(&self.lockout_policy.max_failed_attempts) {
+            return Err("Failed logins before a lockout must be between 3 and 20.".to_string());
+        }
+        if !(1..=1440).contains(&self.lockout_policy.lockout_minutes) {
+            return Err("Lockout must last between 1 and 1440 minutes.".to_string());
+        }
+        if !(0.0..100.0).contains(&self.sibling_discount_percentage) {
+            return Err("Sibling discount must be a percentage from 0 up to, but not including, 100.".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// When repeated failed logins lock an account. The backend answers a locked
+/// account, a wrong password and an unknown username alike, so the login
+/// screen can't be used to find out which usernames exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockoutPolicy {
+    pub max_failed_attempts: u32,
+    pub lockout_minutes: u32,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self { max_failed_attempts: 5, lockout_minutes: 15 }
+    }
+}
+
+/// Symbol amounts are shown with until a school chooses its own
+pub const DEFAULT_CURRENCY_SYMBOL: &str = "₵";
+
+/// An amount as shown to people, after the school's currency symbol. Every
+/// screen, export and message formats money through this.
+pub fn format_money(amount: f64, currency_symbol: &str) -> String {
+    format!("{}{:.2}", currency_symbol, amount)
+}
+
+impl Default for SchoolSettings {
+    fn default() -> Self {
+        Self {
+            currency_symbol: DEFAULT_CURRENCY_SYMBOL.to_string(),
+            date_format: "DD/MM/YYYY".to_string(),
+            default_payment_method: "Cash".to_string(),
+            admin_share_percentage: 30.0,
+            staff_share_percentage: 20.0,
+            teacher_share_percentage: 50.0,
+            password_policy: PasswordPolicy::default(),
+            lockout_policy: LockoutPolicy::default(),
+            sibling_discount_percentage: 0.0,
+        }
+    }
+}
+
+/// Rules for passwords users choose themselves. The backend enforces both;
+/// only it can see the password history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    /// How many of the user's previous passwords can't be chosen again
+    pub history_count: usize,
+}
+
+impl PasswordPolicy {
+    /// Lowest minimum length an admin can configure
+    pub const MIN_LENGTH_FLOOR: usize = 8;
+    /// Most previous passwords an admin can block, and so the most the
+    /// backend keeps for each user
+    pub const MAX_HISTORY_COUNT: usize = 24;
+
+    /// The checks that can be made before the password leaves this computer
+    pub fn check(&self, password: &str) -> Result<(), String> {
+        if password.chars().count() < self.min_length {
+            return Err(format!("Passwords must be at least {} characters.", self.min_length));
+        }
+        if password.trim().is_empty() {
+            return Err("Passwords can't be only spaces.".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self { min_length: 8, history_count: 5 }
+    }
+}
+
+/// A student's fee position and the payments that make it up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudentPaymentHistory {
+    pub student_id: Uuid,
+    pub student_name: String,
+    /// Class the student was in during the reported period, from their class history
+    pub class_level: ClassLevel,
+    pub fee_amount: f64,
+    pub total_paid: f64,
+    pub balance: f64,
+    pub status: PaymentStatus,
+    pub payments: Vec<DbPayment>,
 }
 
 
 // ============= ORIGINAL FINANCIAL MODELS ===============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "database", derive(FromRow))]
 pub struct DbPayment {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub student_id: Uuid,
     pub term_id: Uuid,
     pub amount_paid: f64,
@@ -569,6 +1490,7 @@ pub struct DbPayment {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConstraintConfig {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub name: String,         
     pub base_percentage: f64,
     pub admin_percentage: f64,
@@ -581,13 +1503,16 @@ pub struct ConstraintConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Disbursement {
     pub id: Uuid,
+    pub school_id: Uuid,
     pub term_id: Uuid,
     pub total_revenue: f64,
     pub admin_share: f64,
     pub support_staff_share: f64,
     pub teacher_base_share: f64,
     pub teacher_period_share: f64,
-    pub constraint_id: Uuid,
+    /// The `ConstraintConfig` in force, unset when the school has none and
+    /// the shares came from its settings alone
+    pub constraint_id: Option<Uuid>,
     pub calculated_on: NaiveDateTime,
 }
 
@@ -612,8 +1537,19 @@ pub struct TeacherPayoutItem {
 
 // ============= HTTP/API COMMS ===============
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Request header carrying the client's key for a POST/PUT. The backend
+/// answers a repeated key with the result it gave the first time.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Request header naming the school a request is for. The backend rejects
+/// schools the user isn't bound to and never returns another school's records.
+pub const SCHOOL_ID_HEADER: &str = "School-Id";
+
+/// Body of `POST /students`. `id` is generated on the client, so a create
+/// replayed from the offline queue is recognised rather than duplicated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateStudentPayload {
+    pub id: Uuid,
     pub first_name: String,
     pub surname: String,
     pub other_names: Option<String>,
@@ -621,6 +1557,180 @@ pub struct CreateStudentPayload {
     pub class_level: ClassLevel,
 }
 
+/// Body of `POST /guardians` and `PUT /guardians/{id}`; `student_ids` replaces
+/// the guardian's links on update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveGuardianPayload {
+    pub first_name: String,
+    pub surname: String,
+    pub other_names: Option<String>,
+    pub relationship: GuardianRelationship,
+    pub phone_number: String,
+    pub alternate_phone: Option<String>,
+    pub is_primary_contact: bool,
+    pub student_ids: Vec<Uuid>,
+}
+
+/// One data row of the student import template, as typed in the sheet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudentImportRow {
+    pub row_number: usize,
+    pub first_name: String,
+    pub surname: String,
+    pub other_names: String,
+    pub gender: String,
+    pub class_level: String,
+}
+
+impl StudentImportRow {
+    /// Checks the row against the same rules as the Add Student form. Shared so the
+    /// backend rejects exactly what the desktop preview flags.
+    pub fn validate(&self) -> Result<CreateStudentPayload, Vec<String>> {
+        let mut errors = Vec::new();
+
+        if self.first_name.trim().is_empty() {
+            errors.push("First name cannot be empty.".to_string());
+        }
+        if self.surname.trim().is_empty() {
+            errors.push("Surname cannot be empty.".to_string());
+        }
+        let gender = Gender::from_str(self.gender.trim());
+        if gender.is_err() {
+            errors.push(format!("'{}' is not a valid gender (expected Male or Female).", self.gender.trim()));
+        }
+        let class_level = ClassLevel::from_str(self.class_level.trim());
+        if class_level.is_err() {
+            errors.push(format!("'{}' is not a valid class level.", self.class_level.trim()));
+        }
+
+        match (gender, class_level) {
+            (Ok(gender), Ok(class_level)) if errors.is_empty() => Ok(CreateStudentPayload {
+                id: Uuid::new_v4(),
+                first_name: self.first_name.trim().to_string(),
+                surname: self.surname.trim().to_string(),
+                other_names: Some(self.other_names.trim().to_string()).filter(|s| !s.is_empty()),
+                gender,
+                class_level,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+/// Body of `POST /students/import`; only the rows the user chose to keep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudentImportPayload {
+    pub rows: Vec<StudentImportRow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRowError {
+    pub row_number: usize,
+    pub message: String,
+}
+
+/// Result of an import: rows that failed server-side validation are reported
+/// back and nothing is written for them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub created: usize,
+    pub rejected: Vec<ImportRowError>,
+}
+
+/// A period parsed from an imported register, tagged with its sheet row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodImportRow {
+    pub row_number: usize,
+    pub period: ConductedPeriod,
+}
+
+/// Body of `POST /periods/import`; every period belongs to `term_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodImportPayload {
+    pub term_id: Uuid,
+    pub rows: Vec<PeriodImportRow>,
+}
+
+/// Body of `POST /students/merge`. The backend moves every payment and exemption
+/// from `duplicate_id` onto `surviving_id`, marks the duplicate merged-inactive
+/// and writes an audit entry for the user making the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeStudentsPayload {
+    pub surviving_id: Uuid,
+    pub duplicate_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeStudentsResult {
+    pub surviving: Student,
+    pub duplicate: Student,
+    pub payments_moved: usize,
+    pub exemptions_moved: usize,
+}
+
+/// One student's year-end move. `next_class_level` is only set for `Promoted`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromotionDecision {
+    pub student_id: Uuid,
+    pub current_class_level: ClassLevel,
+    pub outcome: PromotionOutcome,
+    pub next_class_level: Option<ClassLevel>,
+}
+
+/// Body of `POST /students/promote`. The backend closes `academic_year` in each
+/// student's class history, opens `next_academic_year` at the new class and
+/// deactivates graduates and leavers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromotionPayload {
+    pub academic_year: AcademicYear,
+    pub next_academic_year: AcademicYear,
+    pub decisions: Vec<PromotionDecision>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromotionSummary {
+    pub promoted: usize,
+    pub repeated: usize,
+    pub graduated: usize,
+    pub left: usize,
+}
+
+/// Body of `POST /enrolments`; the backend sets each fee from the class fee schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrolStudentsPayload {
+    pub term_id: Uuid,
+    pub student_ids: Vec<Uuid>,
+    pub joined_on: NaiveDate,
+}
+
+/// Body of `POST /exemptions`. `amount` is ignored for a full exemption. The
+/// user making the request is the approver, so they must be able to approve.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateExemptionPayload {
+    pub student_id: Uuid,
+    pub term_id: Uuid,
+    pub is_full: bool,
+    pub amount: Option<f64>,
+    pub reason: ExemptionReason,
+    pub notes: Option<String>,
+}
+
+/// Body of `PUT /installment-plans`; replaces any plan the student already has
+/// for the term. An empty `installments` removes the plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveInstallmentPlanPayload {
+    pub student_id: Uuid,
+    pub term_id: Uuid,
+    pub installments: Vec<Installment>,
+}
+
+/// Body of `POST /enrolments/withdraw`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawEnrolmentPayload {
+    pub enrolment_id: Uuid,
+    pub left_on: NaiveDate,
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct UpdateStudentPayload {
     pub id: Uuid,
@@ -630,3 +1740,184 @@ pub struct UpdateStudentPayload {
     pub gender: Gender,
     pub class_level: ClassLevel,
 }
+
+/// Query parameters accepted by the `/reports/*` endpoints
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportFilter {
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+    pub teacher_id: Option<Uuid>,
+}
+
+/// Query parameters accepted by `GET /dashboard/summary` and `GET /activity`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HomeQuery {
+    pub limit: Option<u32>,
+}
+
+/// Body of `POST /payments`; `id` is client-generated for the same reason as
+/// `CreateStudentPayload::id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordPaymentPayload {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: Uuid,
+    pub amount_paid: f64,
+    pub method: String,
+    pub description: String,
+}
+
+/// Query parameters accepted by `GET /payments`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaymentFilter {
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+}
+
+/// Body of `PUT /settings`; only admins may change school-wide settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSchoolSettingsPayload {
+    pub settings: SchoolSettings,
+}
+
+/// Body of `POST /users`. The password is temporary: the account is created
+/// with `must_change_password` set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUserPayload {
+    pub username: String,
+    pub password: String,
+    pub role: UserRole,
+    pub first_name: String,
+    pub surname: String,
+    pub other_names: Option<String>,
+    pub school_ids: Vec<Uuid>,
+}
+
+/// Body of `PUT /users/{id}`. Accounts are deactivated through `is_active`
+/// rather than deleted; the backend refuses to leave no active admin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserPayload {
+    pub username: String,
+    pub role: UserRole,
+    pub first_name: String,
+    pub surname: String,
+    pub other_names: Option<String>,
+    pub is_active: bool,
+    pub school_ids: Vec<Uuid>,
+}
+
+/// Body of `POST /users/{id}/reset-password`; the user has to replace the
+/// temporary password at their next login.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetPasswordPayload {
+    pub temporary_password: String,
+}
+
+/// Body of `POST /account/change-password`. The backend checks the current
+/// password and the school's `PasswordPolicy`, records the change in the audit
+/// log and ends the user's sessions, so they log in again with the new one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangePasswordPayload {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Body of `POST /periods/{id}/dispute`. Only the period's own teacher can
+/// dispute it, and only once it has been rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputePeriodPayload {
+    pub reason: String,
+}
+
+/// Body of `POST /disbursements`, paying a term out to its teachers. A term
+/// is paid out once, after the committee has reviewed all its periods.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunPayoutPayload {
+    pub term_id: Uuid,
+}
+
+/// Body of `PUT /periods/{id}/verification`, the committee's decision on a
+/// pending or disputed period. A rejection needs a reason the teacher can read.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewPeriodPayload {
+    pub verified: bool,
+    pub rejection_reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+    }
+
+    fn plan() -> InstallmentPlan {
+        InstallmentPlan {
+            id: Uuid::nil(),
+            student_id: Uuid::nil(),
+            term_id: Uuid::nil(),
+            installments: vec![
+                Installment { due_date: day(1), amount: 100.0 },
+                Installment { due_date: day(15), amount: 200.0 },
+            ],
+            created_by: Uuid::nil(),
+            created_at: day(1).and_hms_opt(0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn overdue_counts_from_the_first_uncovered_installment() {
+        let plan = plan();
+        assert_eq!(plan.days_overdue(0.0, day(11)), Some(10));
+        assert_eq!(plan.days_overdue(100.0, day(20)), Some(5));
+        assert_eq!(plan.days_overdue(99.0, day(20)), Some(19));
+    }
+
+    #[test]
+    fn not_overdue_on_the_due_date_or_when_covered() {
+        let plan = plan();
+        assert_eq!(plan.days_overdue(100.0, day(15)), None);
+        assert_eq!(plan.days_overdue(300.0, day(31)), None);
+        // Half a pesewa short is rounding
+        assert_eq!(plan.days_overdue(99.996, day(10)), None);
+    }
+
+    #[test]
+    fn aging_buckets_split_at_thirty_and_sixty_days() {
+        assert_eq!(AgingBucket::for_days(1), AgingBucket::UpToThirtyDays);
+        assert_eq!(AgingBucket::for_days(30), AgingBucket::UpToThirtyDays);
+        assert_eq!(AgingBucket::for_days(31), AgingBucket::ThirtyOneToSixtyDays);
+        assert_eq!(AgingBucket::for_days(60), AgingBucket::ThirtyOneToSixtyDays);
+        assert_eq!(AgingBucket::for_days(61), AgingBucket::OverSixtyDays);
+    }
+
+    #[test]
+    fn money_follows_the_currency_symbol() {
+        assert_eq!(format_money(1250.5, "₵"), "₵1250.50");
+        assert_eq!(format_money(0.0, "GH₵ "), "GH₵ 0.00");
+    }
+
+    #[test]
+    fn default_school_settings_are_valid() {
+        assert_eq!(SchoolSettings::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn shares_must_add_up_to_a_hundred() {
+        let settings = SchoolSettings { teacher_share_percentage: 40.0, ..SchoolSettings::default() };
+        assert!(settings.validate().unwrap_err().contains("add up to 90.0%"));
+        let settings = SchoolSettings { admin_share_percentage: f64::NAN, ..SchoolSettings::default() };
+        assert!(settings.validate().unwrap_err().starts_with("Admin share"));
+    }
+
+    #[test]
+    fn sibling_discount_must_leave_something_to_pay() {
+        let settings = SchoolSettings { sibling_discount_percentage: 10.0, ..SchoolSettings::default() };
+        assert_eq!(settings.validate(), Ok(()));
+        for discount in [100.0, -5.0, f64::NAN] {
+            let settings = SchoolSettings { sibling_discount_percentage: discount, ..SchoolSettings::default() };
+            assert!(settings.validate().is_err(), "{}", discount);
+        }
+    }
+}